@@ -0,0 +1,48 @@
+//! Build command - Write transformed game data to a snapshot file
+//!
+//! Usage:
+//!   wowlab snapshot build --patch 12.0.0 --data-dir ./data --output ./data/game.snapshot
+
+use std::time::Instant;
+
+use anyhow::Result;
+use wowlab_common::parsers::DbcData;
+use wowlab_common::snapshot::{DataSnapshot, SnapshotData, SnapshotSection};
+
+use super::sync::timed;
+use super::BuildArgs;
+
+pub fn run_build(args: BuildArgs) -> Result<()> {
+    let total_start = Instant::now();
+    tracing::info!("Building snapshot for patch {}", args.patch);
+
+    let dbc = timed("Loading DBC data", || DbcData::load_all(&args.data_dir))?;
+    let data = timed("Transforming data", || {
+        Ok::<_, anyhow::Error>(SnapshotData::from_dbc(&dbc))
+    })?;
+    timed("Writing snapshot", || data.write(&args.patch, &args.output))?;
+
+    // Re-open to validate the file and report what it holds
+    let snapshot = timed("Verifying snapshot", || {
+        let snapshot = DataSnapshot::open(&args.output)?;
+        snapshot.verify()?;
+        Ok::<_, anyhow::Error>(snapshot)
+    })?;
+    for section in [
+        SnapshotSection::Spells,
+        SnapshotSection::Auras,
+        SnapshotSection::Items,
+        SnapshotSection::TraitTrees,
+    ] {
+        tracing::info!("  {}: {}", section, snapshot.count(section));
+    }
+
+    let size = std::fs::metadata(&args.output)?.len();
+    tracing::info!(
+        "Wrote {} ({:.1} MB) in {:.2}s",
+        args.output.display(),
+        size as f64 / 1_048_576.0,
+        total_start.elapsed().as_secs_f64()
+    );
+    Ok(())
+}
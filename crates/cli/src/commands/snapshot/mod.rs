@@ -4,10 +4,12 @@
 //!
 //! Structure:
 //! - `sync.rs` - Main sync command orchestration
+//! - `build.rs` - Write a snapshot file for fast local loading
 //! - `db.rs` - Database operations (bulk insert, cleanup)
 //! - `dump_spell.rs` - Debug utility for single spell
 //! - `dump_trait.rs` - Debug utility for single trait tree
 
+mod build;
 mod db;
 mod dump_spell;
 mod dump_trait;
@@ -21,6 +23,8 @@ use std::path::PathBuf;
 pub enum SnapshotCommand {
     /// Sync transformed data to Supabase Postgres
     Sync(SyncArgs),
+    /// Write transformed data to a snapshot file
+    Build(BuildArgs),
     /// Dump a single spell to JSON (for debugging)
     DumpSpell(DumpSpellArgs),
     /// Dump a trait tree to JSON (for debugging)
@@ -31,6 +35,7 @@ impl SnapshotCommand {
     pub async fn run(self) -> Result<()> {
         match self {
             Self::Sync(args) => sync::run_sync(args).await,
+            Self::Build(args) => build::run_build(args),
             Self::DumpSpell(args) => dump_spell::run_dump_spell(args),
             Self::DumpTrait(args) => dump_trait::run_dump_trait(args),
         }
@@ -97,6 +102,21 @@ pub struct SyncArgs {
     pub tables: Vec<SyncTable>,
 }
 
+#[derive(clap::Args)]
+pub struct BuildArgs {
+    /// Patch version to label the snapshot with (e.g., "11.2.0")
+    #[arg(long)]
+    pub patch: String,
+
+    /// Directory containing DBC CSV files
+    #[arg(long, default_value = "./data")]
+    pub data_dir: PathBuf,
+
+    /// Snapshot file to write
+    #[arg(long, default_value = "./data/game.snapshot")]
+    pub output: PathBuf,
+}
+
 #[derive(clap::Args)]
 pub struct DumpSpellArgs {
     /// Spell ID to dump
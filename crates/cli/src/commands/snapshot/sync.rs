@@ -231,7 +231,7 @@ where
     Ok(())
 }
 
-fn timed<T, E, F>(label: &str, f: F) -> Result<T, E>
+pub(super) fn timed<T, E, F>(label: &str, f: F) -> Result<T, E>
 where
     F: FnOnce() -> Result<T, E>,
 {
@@ -2,6 +2,7 @@
 //!
 //! Usage:
 //!   wowlab snapshot sync --patch 11.2.0 --data-dir ~/Source/wowlab-data
+//!   wowlab snapshot build --patch 11.2.0 --data-dir ~/Source/wowlab-data
 //!   wowlab snapshot dump-spell 53351 --data-dir ~/Source/wowlab-data
 //!   wowlab snapshot dump-talent 253 --data-dir ~/Source/wowlab-data
 
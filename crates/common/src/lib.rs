@@ -1,4 +1,6 @@
 pub mod parsers;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
 pub mod stats;
 pub mod types;
 
@@ -22,11 +24,11 @@ pub use types::spell_desc::{
 
 pub use types::data::{
     argb_to_hex, rgb_to_hex, AppliedBonus, AuraDataFlat, ClassDataFlat, CurveFlat, CurvePointFlat,
-    EmpowerStage, GlobalColorFlat, GlobalStringFlat, ItemBonusFlat, ItemClassification,
-    ItemDataFlat, ItemDropSource, ItemEffect, ItemQuality, ItemScalingData, ItemSetBonus,
-    ItemSetInfo, ItemStat, ItemSummary, KnowledgeSource, LearnSpell, PeriodicType, PointLimits,
-    RandPropPointsFlat, RefreshBehavior, ScaledItemStats, ScaledStat, SpecDataFlat, SpellCost,
-    SpellDamage, SpellDataFlat, SpellEffect, SpellRange, SpellSummary, SpellTiming,
+    EmpowerStage, GlobalColorFlat, GlobalStringFlat, ItemArmorData, ItemBonusFlat,
+    ItemClassification, ItemDataFlat, ItemDropSource, ItemEffect, ItemQuality, ItemScalingData,
+    ItemSetBonus, ItemSetInfo, ItemStat, ItemSummary, KnowledgeSource, LearnSpell, PeriodicType,
+    PointLimits, RandPropPointsFlat, RefreshBehavior, ScaledItemStats, ScaledStat, SpecDataFlat,
+    SpellCost, SpellDamage, SpellDataFlat, SpellEffect, SpellRange, SpellSummary, SpellTiming,
     TalentNodeSummary, TraitEdge, TraitNode, TraitNodeEntry, TraitSelection, TraitSubTree,
     TraitTreeFlat, TraitTreeWithSelections,
 };
@@ -39,7 +41,7 @@ pub use parsers::{
 pub use parsers::DbcData;
 
 #[cfg(feature = "dbc")]
-pub use parsers::DbcError;
+pub use parsers::{Db2Error, DbcError};
 pub use parsers::{TraitError, TransformError};
 
 pub use parsers::SpellKnowledgeContext;
@@ -49,12 +51,14 @@ pub use parsers::{
     transform_all_global_colors, transform_all_global_strings, transform_all_item_bonuses,
     transform_all_items, transform_all_rand_prop_points, transform_all_specs, transform_all_spells,
     transform_all_trait_trees, transform_aura, transform_class, transform_global_color,
-    transform_global_string, transform_item, transform_spec, transform_spell, transform_trait_tree,
+    transform_global_string, transform_item, transform_item_scaling, transform_spec,
+    transform_spell, transform_trait_tree,
 };
 
 pub use parsers::{
     apply_decoded_traits, decode_trait_loadout, encode_minimal_loadout, encode_trait_loadout,
-    DecodedTraitLoadout, DecodedTraitNode,
+    trait_tree_hash, validate_trait_loadout, DecodedTraitLoadout, DecodedTraitNode,
+    LoadoutViolation, TraitCurrency,
 };
 
 pub use parsers::{
@@ -64,7 +68,8 @@ pub use parsers::{
 };
 
 pub use parsers::{
-    apply_item_bonuses, get_bonus_description, get_stat_budget, get_stat_name, interpolate_curve,
+    apply_item_bonuses, apply_item_bonuses_for_slot, get_bonus_description, get_item_armor,
+    get_stat_budget, get_stat_name, interpolate_curve, slot_budget_index,
 };
 
 #[cfg(feature = "crypto")]
@@ -74,5 +79,6 @@ pub use parsers::{
 };
 
 pub use stats::{
-    correlation, covariance, ema, ema_span, linear_regression, sma, LinearRegression, Summary,
+    correlation, covariance, ema, ema_span, linear_regression, sma, LinearRegression, Streaming,
+    Summary, Z_95,
 };
@@ -174,10 +174,17 @@ pub fn sha256_hex(data: &[u8]) -> String {
 
 /// Build the message to sign for a node request.
 ///
-/// Format: `timestamp\0method\0path\0body_hash`
-pub fn build_sign_message(timestamp: u64, method: &str, path: &str, body: &[u8]) -> String {
+/// Format: `timestamp\0nonce\0method\0path\0body_hash`. The nonce is unique
+/// per request, so a captured request can't be replayed.
+pub fn build_sign_message(
+    timestamp: u64,
+    nonce: &str,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> String {
     let body_hash = sha256_hex(body);
-    format!("{timestamp}\0{method}\0{path}\0{body_hash}")
+    format!("{timestamp}\0{nonce}\0{method}\0{path}\0{body_hash}")
 }
 
 #[cfg(test)]
@@ -324,15 +331,16 @@ mod tests {
         let path = "/functions/v1/node-heartbeat";
         let body = b"{}";
 
-        let message = build_sign_message(timestamp, method, path, body);
+        let message = build_sign_message(timestamp, "a1b2c3", method, path, body);
 
         // Should contain all parts separated by null bytes
         let parts: Vec<&str> = message.split('\0').collect();
-        assert_eq!(parts.len(), 4);
+        assert_eq!(parts.len(), 5);
         assert_eq!(parts[0], "1234567890");
-        assert_eq!(parts[1], "POST");
-        assert_eq!(parts[2], "/functions/v1/node-heartbeat");
-        assert_eq!(parts[3], sha256_hex(body));
+        assert_eq!(parts[1], "a1b2c3");
+        assert_eq!(parts[2], "POST");
+        assert_eq!(parts[3], "/functions/v1/node-heartbeat");
+        assert_eq!(parts[4], sha256_hex(body));
     }
 
     #[test]
@@ -345,13 +353,20 @@ mod tests {
         let path = "/functions/v1/chunk-claim";
         let body = br#"{"batchSize":5}"#;
 
-        let message = build_sign_message(timestamp, method, path, body);
+        let message = build_sign_message(timestamp, "a1b2c3", method, path, body);
 
         // Sign
         let signature = kp.sign_base64(message.as_bytes());
 
         // Verify
         verify_signature_base64(&kp.public_key_base64(), message.as_bytes(), &signature).unwrap();
+
+        // A different nonce must not verify with the same signature
+        let replayed = build_sign_message(timestamp, "d4e5f6", method, path, body);
+        assert!(
+            verify_signature_base64(&kp.public_key_base64(), replayed.as_bytes(), &signature)
+                .is_err()
+        );
     }
 
     #[test]
@@ -0,0 +1,680 @@
+//! Native DB2 reader (WDC3, WDC4 and WDC5)
+//!
+//! Records are decoded with a [`DbdLayout`] and come out as string records
+//! under the same headers a CSV export uses, so the `*Row` structs
+//! deserialize from either source. Handles sparse (offset map) tables, copy
+//! tables, pallet and common data, and relationship maps. Encrypted sections
+//! whose key isn't known are stored zeroed and are skipped.
+
+use std::collections::HashMap;
+
+use csv::StringRecord;
+use serde::de::DeserializeOwned;
+
+use super::super::errors::Db2Error;
+use super::dbd::{DbdField, DbdLayout, DbdType};
+
+/// Records are variable length and located through an offset map
+const FLAG_SPARSE: u16 = 0x1;
+
+/// Schema string that follows the version number in WDC5 headers
+const WDC5_SCHEMA_SIZE: usize = 128;
+
+/// Size of one field storage info entry
+const FIELD_STORAGE_SIZE: usize = 24;
+
+/// Size of one section header
+const SECTION_HEADER_SIZE: usize = 40;
+
+/// DB2 file format version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Db2Format {
+    Wdc3,
+    Wdc4,
+    Wdc5,
+}
+
+/// How a field's values are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Storage {
+    /// Plain values in the record
+    None,
+    Bitpacked,
+    /// Not in the record: a default, with per-record exceptions
+    Common,
+    /// Record holds an index into the pallet
+    Pallet,
+    /// Record holds an index into the pallet, which stores whole arrays
+    PalletArray,
+    BitpackedSigned,
+}
+
+#[derive(Debug, Clone)]
+struct FieldStorage {
+    offset_bits: usize,
+    size_bits: usize,
+    storage: Storage,
+    /// Size of its pallet or common data block
+    additional_data_size: usize,
+    /// Bitpacking flags, common data default or pallet array length
+    values: [u32; 3],
+    /// First pallet value of this field
+    pallet_start: usize,
+    /// Common data exceptions by record ID
+    common: HashMap<u32, u32>,
+}
+
+#[derive(Debug, Clone)]
+struct Section {
+    tact_key_hash: u64,
+    file_offset: usize,
+    record_count: usize,
+    string_table_size: usize,
+    offset_records_end: usize,
+    id_list_size: usize,
+    relationship_data_size: usize,
+    offset_map_id_count: usize,
+    copy_table_count: usize,
+}
+
+/// A DB2 file, ready to be decoded with a layout
+#[derive(Debug, Clone)]
+pub struct Db2File {
+    data: Vec<u8>,
+    pub format: Db2Format,
+    pub table_hash: u32,
+    pub layout_hash: u32,
+    record_count: usize,
+    record_size: usize,
+    flags: u16,
+    id_index: usize,
+    sections: Vec<Section>,
+    fields: Vec<FieldStorage>,
+    pallet: Vec<u32>,
+}
+
+/// Decoded records under CSV-style headers
+#[derive(Debug, Clone)]
+pub struct Db2Table {
+    pub headers: StringRecord,
+    pub rows: Vec<StringRecord>,
+}
+
+impl Db2Table {
+    /// Deserialize every row, by header name
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<Vec<T>, csv::Error> {
+        self.rows
+            .iter()
+            .map(|row| row.deserialize(Some(&self.headers)))
+            .collect()
+    }
+}
+
+impl Db2File {
+    /// Parse the headers of a DB2 file.
+    pub fn parse(data: Vec<u8>) -> Result<Self, Db2Error> {
+        let mut cursor = Cursor::new(&data, 0);
+        let magic = cursor.bytes(4)?;
+        let format = match magic {
+            b"WDC3" => Db2Format::Wdc3,
+            b"WDC4" => Db2Format::Wdc4,
+            b"WDC5" => Db2Format::Wdc5,
+            other => {
+                return Err(Db2Error::UnsupportedFormat(
+                    String::from_utf8_lossy(other).into_owned(),
+                ))
+            }
+        };
+        if format == Db2Format::Wdc5 {
+            // Version number and schema string
+            cursor.skip(4 + WDC5_SCHEMA_SIZE)?;
+        }
+
+        let record_count = cursor.u32()? as usize;
+        let _field_count = cursor.u32()?;
+        let record_size = cursor.u32()? as usize;
+        let _string_table_size = cursor.u32()?;
+        let table_hash = cursor.u32()?;
+        let layout_hash = cursor.u32()?;
+        let _min_id = cursor.u32()?;
+        let _max_id = cursor.u32()?;
+        let _locale = cursor.u32()?;
+        let flags = cursor.u16()?;
+        let id_index = cursor.u16()? as usize;
+        let total_field_count = cursor.u32()? as usize;
+        let _bitpacked_data_offset = cursor.u32()?;
+        let _lookup_column_count = cursor.u32()?;
+        let field_storage_info_size = cursor.u32()? as usize;
+        let common_data_size = cursor.u32()? as usize;
+        let pallet_data_size = cursor.u32()? as usize;
+        let section_count = cursor.u32()? as usize;
+
+        // Capacities are capped by what the file could hold, so a corrupt
+        // count fails on reading instead of allocating
+        let mut sections = Vec::with_capacity(section_count.min(data.len() / SECTION_HEADER_SIZE));
+        for _ in 0..section_count {
+            sections.push(Section {
+                tact_key_hash: cursor.u64()?,
+                file_offset: cursor.u32()? as usize,
+                record_count: cursor.u32()? as usize,
+                string_table_size: cursor.u32()? as usize,
+                offset_records_end: cursor.u32()? as usize,
+                id_list_size: cursor.u32()? as usize,
+                relationship_data_size: cursor.u32()? as usize,
+                offset_map_id_count: cursor.u32()? as usize,
+                copy_table_count: cursor.u32()? as usize,
+            });
+        }
+
+        // Field structures (size and byte offset) are superseded by the
+        // storage info below
+        cursor.skip(
+            total_field_count
+                .checked_mul(4)
+                .ok_or(Db2Error::Overflow("field structures"))?,
+        )?;
+
+        let mut fields = Vec::new();
+        for _ in 0..field_storage_info_size / FIELD_STORAGE_SIZE {
+            let offset_bits = cursor.u16()? as usize;
+            let size_bits = cursor.u16()? as usize;
+            let additional_data_size = cursor.u32()? as usize;
+            let storage = match cursor.u32()? {
+                0 => Storage::None,
+                1 => Storage::Bitpacked,
+                2 => Storage::Common,
+                3 => Storage::Pallet,
+                4 => Storage::PalletArray,
+                5 => Storage::BitpackedSigned,
+                other => return Err(Db2Error::UnknownStorage(other)),
+            };
+            let values = [cursor.u32()?, cursor.u32()?, cursor.u32()?];
+            fields.push(FieldStorage {
+                offset_bits,
+                size_bits,
+                storage,
+                additional_data_size,
+                values,
+                pallet_start: 0,
+                common: HashMap::new(),
+            });
+        }
+
+        // Pallet and common data blocks, one per field that uses them
+        let pallet_end = cursor
+            .pos
+            .checked_add(pallet_data_size)
+            .ok_or(Db2Error::Overflow("pallet data"))?;
+        let mut pallet = Vec::with_capacity(pallet_data_size.min(data.len()) / 4);
+        while cursor.pos < pallet_end {
+            pallet.push(cursor.u32()?);
+        }
+        let mut pallet_start = 0;
+        for field in &mut fields {
+            if matches!(field.storage, Storage::Pallet | Storage::PalletArray) {
+                field.pallet_start = pallet_start;
+                pallet_start = pallet_start.saturating_add(field.additional_data_size / 4);
+            }
+        }
+
+        let common_end = cursor
+            .pos
+            .checked_add(common_data_size)
+            .ok_or(Db2Error::Overflow("common data"))?;
+        for field in &mut fields {
+            if field.storage != Storage::Common {
+                continue;
+            }
+            for _ in 0..field.additional_data_size / 8 {
+                let id = cursor.u32()?;
+                let value = cursor.u32()?;
+                field.common.insert(id, value);
+            }
+        }
+        if cursor.pos != common_end {
+            return Err(Db2Error::UnexpectedEof(common_end));
+        }
+
+        Ok(Self {
+            data,
+            format,
+            table_hash,
+            layout_hash,
+            record_count,
+            record_size,
+            flags,
+            id_index,
+            sections,
+            fields,
+            pallet,
+        })
+    }
+
+    /// Whether records are variable length
+    pub fn is_sparse(&self) -> bool {
+        self.flags & FLAG_SPARSE != 0
+    }
+
+    /// Decode every record with `layout`.
+    pub fn read(&self, layout: &DbdLayout) -> Result<Db2Table, Db2Error> {
+        let headers: StringRecord = layout.headers().into_iter().collect();
+        let id_column = column_of(layout, |f| f.is_id);
+        let mut rows = Vec::new();
+        let mut row_by_id: HashMap<u32, usize> = HashMap::new();
+        let mut copies = Vec::new();
+
+        // Strings of every section, in the order string offsets assume
+        let mut strings = Vec::new();
+        for section in &self.sections {
+            if !self.is_sparse() {
+                let start = section
+                    .record_count
+                    .checked_mul(self.record_size)
+                    .and_then(|size| size.checked_add(section.file_offset))
+                    .ok_or(Db2Error::Overflow("section records"))?;
+                strings.extend_from_slice(self.slice(start, section.string_table_size)?);
+            }
+        }
+
+        let mut records_before = 0;
+        for section in &self.sections {
+            let decoded = self.read_section(section, layout, records_before, &strings)?;
+            records_before += section.record_count;
+            for (id, row) in decoded.rows {
+                row_by_id.insert(id, rows.len());
+                rows.push(row);
+            }
+            copies.extend(decoded.copies);
+        }
+
+        for (new_id, source_id) in copies {
+            let Some(&index) = row_by_id.get(&source_id) else {
+                continue;
+            };
+            let mut row: Vec<String> = rows[index].iter().map(str::to_string).collect();
+            if let Some(column) = id_column {
+                row[column] = new_id.to_string();
+            }
+            rows.push(row.into_iter().collect());
+        }
+
+        Ok(Db2Table { headers, rows })
+    }
+
+    fn read_section(
+        &self,
+        section: &Section,
+        layout: &DbdLayout,
+        records_before: usize,
+        strings: &[u8],
+    ) -> Result<DecodedSection, Db2Error> {
+        // Offset maps replace the record block in sparse tables
+        let records_size = if self.is_sparse() {
+            section
+                .offset_records_end
+                .saturating_sub(section.file_offset)
+        } else {
+            section
+                .record_count
+                .checked_mul(self.record_size)
+                .and_then(|size| size.checked_add(section.string_table_size))
+                .ok_or(Db2Error::Overflow("section records"))?
+        };
+        let data_start = section
+            .file_offset
+            .checked_add(records_size)
+            .ok_or(Db2Error::Overflow("section records"))?;
+        let mut cursor = Cursor::new(&self.data, data_start);
+
+        let id_list = (0..section.id_list_size / 4)
+            .map(|_| cursor.u32())
+            .collect::<Result<Vec<_>, _>>()?;
+        let len = self.data.len();
+        let mut copies = Vec::with_capacity(section.copy_table_count.min(len / 8));
+        for _ in 0..section.copy_table_count {
+            copies.push((cursor.u32()?, cursor.u32()?));
+        }
+        let mut offset_map = Vec::with_capacity(section.offset_map_id_count.min(len / 6));
+        for _ in 0..section.offset_map_id_count {
+            offset_map.push((cursor.u32()? as usize, cursor.u16()? as usize));
+        }
+        let mut relations: HashMap<usize, u32> = HashMap::new();
+        if section.relationship_data_size > 0 {
+            let entries = cursor.u32()? as usize;
+            let _min_id = cursor.u32()?;
+            let _max_id = cursor.u32()?;
+            for _ in 0..entries {
+                let foreign_id = cursor.u32()?;
+                let record = cursor.u32()? as usize;
+                relations.insert(record, foreign_id);
+            }
+        }
+        let offset_map_ids = (0..section.offset_map_id_count)
+            .map(|_| cursor.u32())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut rows = Vec::with_capacity(section.record_count.min(len / self.record_size.max(1)));
+        if self.is_sparse() {
+            for (index, &(offset, size)) in offset_map.iter().enumerate() {
+                let record = self.slice(offset, size)?;
+                let id = id_list
+                    .get(index)
+                    .or_else(|| offset_map_ids.get(index))
+                    .copied();
+                let (values, inline_id) = self.decode_sparse(record, layout)?;
+                let id = id.or(inline_id).unwrap_or_default();
+                rows.push((id, assemble(layout, values, id, relations.get(&index))));
+            }
+        } else {
+            let block = self.slice(
+                section.file_offset,
+                section.record_count.saturating_mul(self.record_size),
+            )?;
+            if section.tact_key_hash != 0 && block.iter().all(|&b| b == 0) {
+                return Ok(DecodedSection {
+                    rows,
+                    copies: Vec::new(),
+                });
+            }
+            // Size of every section's records, which come before the strings
+            let records_total = self
+                .record_count
+                .checked_mul(self.record_size)
+                .ok_or(Db2Error::Overflow("records"))?;
+            for index in 0..section.record_count {
+                let record = &block[index * self.record_size..(index + 1) * self.record_size];
+                // Position in the virtual file where every section's records
+                // come before every section's strings
+                let position = (records_before + index) * self.record_size;
+                let string_base = position as i64 - records_total as i64;
+                let id = self.record_id(record, id_list.get(index).copied())?;
+                let values = self.decode_dense(record, layout, id, string_base, strings)?;
+                rows.push((id, assemble(layout, values, id, relations.get(&index))));
+            }
+        }
+
+        Ok(DecodedSection { rows, copies })
+    }
+
+    /// ID of a fixed-size record: from the ID list, or its ID field
+    fn record_id(&self, record: &[u8], listed: Option<u32>) -> Result<u32, Db2Error> {
+        if let Some(id) = listed {
+            return Ok(id);
+        }
+        let field = self
+            .fields
+            .get(self.id_index)
+            .ok_or(Db2Error::MissingField(self.id_index))?;
+        let values = self.field_values(field, record, 0, 1, 32)?;
+        Ok(values[0] as u32)
+    }
+
+    /// Decode the inline fields of a fixed-size record
+    fn decode_dense(
+        &self,
+        record: &[u8],
+        layout: &DbdLayout,
+        id: u32,
+        string_base: i64,
+        strings: &[u8],
+    ) -> Result<Vec<Vec<String>>, Db2Error> {
+        let mut values = Vec::new();
+        for (index, field) in layout.inline_fields().enumerate() {
+            let storage = self
+                .fields
+                .get(index)
+                .ok_or(Db2Error::MissingField(index))?;
+            let raw = self.field_values(storage, record, id, field.array, field.size)?;
+            let element_bytes = field.size as usize / 8;
+            let formatted = raw
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| {
+                    if field.ty.is_string() {
+                        let at = string_base
+                            + (storage.offset_bits / 8 + i * element_bytes) as i64
+                            + value as u32 as i64;
+                        read_string(strings, at)
+                    } else {
+                        format_value(field, value)
+                    }
+                })
+                .collect();
+            values.push(formatted);
+        }
+        Ok(values)
+    }
+
+    /// Raw values of one field of a fixed-size record
+    fn field_values(
+        &self,
+        storage: &FieldStorage,
+        record: &[u8],
+        id: u32,
+        array: usize,
+        element_bits: u8,
+    ) -> Result<Vec<u64>, Db2Error> {
+        let values = match storage.storage {
+            Storage::None => {
+                let bits = element_bits as usize;
+                (0..array)
+                    .map(|i| read_bits(record, storage.offset_bits + i * bits, bits))
+                    .collect::<Result<_, _>>()?
+            }
+            Storage::Bitpacked => vec![read_bits(record, storage.offset_bits, storage.size_bits)?],
+            Storage::BitpackedSigned => {
+                let value = read_bits(record, storage.offset_bits, storage.size_bits)?;
+                vec![sign_extend(value, storage.size_bits) as u64]
+            }
+            Storage::Common => {
+                let value = storage.common.get(&id).copied();
+                vec![value.unwrap_or(storage.values[0]) as u64]
+            }
+            Storage::Pallet | Storage::PalletArray => {
+                let count = if storage.storage == Storage::PalletArray {
+                    storage.values[2] as usize
+                } else {
+                    1
+                };
+                let index = read_bits(record, storage.offset_bits, storage.size_bits)? as usize;
+                let start = index
+                    .checked_mul(count)
+                    .and_then(|offset| offset.checked_add(storage.pallet_start))
+                    .ok_or(Db2Error::Overflow("pallet index"))?;
+                let end = start
+                    .checked_add(count)
+                    .ok_or(Db2Error::Overflow("pallet index"))?;
+                (start..end)
+                    .map(|i| {
+                        self.pallet
+                            .get(i)
+                            .map(|&v| v as u64)
+                            .ok_or(Db2Error::UnexpectedEof(i * 4))
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        Ok(values)
+    }
+
+    /// Decode the inline fields of a variable-length record, where fields
+    /// follow each other and strings are stored in place.
+    fn decode_sparse(
+        &self,
+        record: &[u8],
+        layout: &DbdLayout,
+    ) -> Result<(Vec<Vec<String>>, Option<u32>), Db2Error> {
+        let mut cursor = Cursor::new(record, 0);
+        let mut values = Vec::new();
+        let mut id = None;
+        for field in layout.inline_fields() {
+            let mut formatted = Vec::with_capacity(field.array);
+            for _ in 0..field.array {
+                if field.ty.is_string() {
+                    formatted.push(cursor.c_string()?);
+                    continue;
+                }
+                let value = match field.size {
+                    8 => cursor.bytes(1)?[0] as u64,
+                    16 => cursor.u16()? as u64,
+                    64 => cursor.u64()?,
+                    _ => cursor.u32()? as u64,
+                };
+                if field.is_id && id.is_none() {
+                    id = Some(value as u32);
+                }
+                formatted.push(format_value(field, value));
+            }
+            values.push(formatted);
+        }
+        Ok((values, id))
+    }
+
+    fn slice(&self, start: usize, len: usize) -> Result<&[u8], Db2Error> {
+        let end = start.saturating_add(len);
+        self.data
+            .get(start..end)
+            .ok_or(Db2Error::UnexpectedEof(end))
+    }
+}
+
+struct DecodedSection {
+    rows: Vec<(u32, StringRecord)>,
+    /// (new ID, ID of the row it copies)
+    copies: Vec<(u32, u32)>,
+}
+
+/// Put inline values and the non-inline ID and foreign key in column order.
+fn assemble(
+    layout: &DbdLayout,
+    inline: Vec<Vec<String>>,
+    id: u32,
+    relation: Option<&u32>,
+) -> StringRecord {
+    let mut inline = inline.into_iter();
+    let mut row = StringRecord::new();
+    for field in &layout.fields {
+        if field.inline {
+            for value in inline.next().unwrap_or_default() {
+                row.push_field(&value);
+            }
+        } else if field.is_id {
+            row.push_field(&id.to_string());
+        } else if field.is_relation {
+            row.push_field(&relation.copied().unwrap_or_default().to_string());
+        } else {
+            for _ in 0..field.array {
+                row.push_field("0");
+            }
+        }
+    }
+    row
+}
+
+/// Column index of the first field matching `pred`
+fn column_of(layout: &DbdLayout, pred: impl Fn(&DbdField) -> bool) -> Option<usize> {
+    let mut column = 0;
+    for field in &layout.fields {
+        if pred(field) {
+            return Some(column);
+        }
+        column += field.array;
+    }
+    None
+}
+
+fn format_value(field: &DbdField, value: u64) -> String {
+    match field.ty {
+        DbdType::Float => f32::from_bits(value as u32).to_string(),
+        _ if field.signed => sign_extend(value, field.size as usize).to_string(),
+        _ if field.size < 64 => (value & ((1u64 << field.size) - 1)).to_string(),
+        _ => value.to_string(),
+    }
+}
+
+fn sign_extend(value: u64, bits: usize) -> i64 {
+    if bits == 0 || bits >= 64 {
+        return value as i64;
+    }
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+/// Read `bits` bits (little-endian) starting `offset` bits into `data`
+fn read_bits(data: &[u8], offset: usize, bits: usize) -> Result<u64, Db2Error> {
+    if bits == 0 {
+        return Ok(0);
+    }
+    let start = offset / 8;
+    let end = (offset + bits).div_ceil(8);
+    let bytes = data.get(start..end).ok_or(Db2Error::UnexpectedEof(end))?;
+    let mut buf = [0u8; 16];
+    let available = bytes.len().min(16);
+    buf[..available].copy_from_slice(&bytes[..available]);
+    let value = u128::from_le_bytes(buf) >> (offset % 8);
+    Ok(if bits >= 64 {
+        value as u64
+    } else {
+        (value as u64) & ((1u64 << bits) - 1)
+    })
+}
+
+fn read_string(strings: &[u8], at: i64) -> String {
+    let Ok(start) = usize::try_from(at) else {
+        return String::new();
+    };
+    let Some(tail) = strings.get(start..) else {
+        return String::new();
+    };
+    let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+    String::from_utf8_lossy(&tail[..end]).into_owned()
+}
+
+/// Little-endian reader over a byte slice
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Db2Error> {
+        let end = self.pos.saturating_add(len);
+        let slice = self
+            .data
+            .get(self.pos..end)
+            .ok_or(Db2Error::UnexpectedEof(end))?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), Db2Error> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u16(&mut self) -> Result<u16, Db2Error> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, Db2Error> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, Db2Error> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn c_string(&mut self) -> Result<String, Db2Error> {
+        let tail = &self.data[self.pos.min(self.data.len())..];
+        let end = tail
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(Db2Error::UnexpectedEof(self.data.len()))?;
+        let value = String::from_utf8_lossy(&tail[..end]).into_owned();
+        self.pos += end + 1;
+        Ok(value)
+    }
+}
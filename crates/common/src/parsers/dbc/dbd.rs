@@ -0,0 +1,236 @@
+//! WoWDBDefs table definitions (`.dbd`)
+//!
+//! DB2 files don't name their columns. A definition lists the columns of a
+//! table and, per layout hash, the order, size and array length of the fields
+//! a file with that layout stores.
+
+use std::collections::HashMap;
+
+use super::super::errors::Db2Error;
+
+/// Column type from the `COLUMNS` block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbdType {
+    Int,
+    Float,
+    String,
+    LocString,
+}
+
+impl DbdType {
+    pub fn is_string(self) -> bool {
+        matches!(self, Self::String | Self::LocString)
+    }
+}
+
+/// One field of a layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbdField {
+    pub name: String,
+    pub ty: DbdType,
+    /// Size in bits (32 for floats and strings)
+    pub size: u8,
+    pub signed: bool,
+    /// Array length (1 for plain fields)
+    pub array: usize,
+    /// Holds the record ID
+    pub is_id: bool,
+    /// Holds the foreign key from the relationship map
+    pub is_relation: bool,
+    /// Stored in the record data (not in the ID list or relationship map)
+    pub inline: bool,
+}
+
+impl DbdField {
+    /// Column headers, as a CSV export names them (`Name`, or `Name_0`,
+    /// `Name_1`... for arrays)
+    pub fn headers(&self) -> Vec<String> {
+        if self.array > 1 {
+            (0..self.array)
+                .map(|i| format!("{}_{}", self.name, i))
+                .collect()
+        } else {
+            vec![self.name.clone()]
+        }
+    }
+}
+
+/// Fields of the files with one of `hashes` as their layout hash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbdLayout {
+    pub hashes: Vec<u32>,
+    pub fields: Vec<DbdField>,
+}
+
+impl DbdLayout {
+    /// Fields stored in the record data, in file order
+    pub fn inline_fields(&self) -> impl Iterator<Item = &DbdField> {
+        self.fields.iter().filter(|f| f.inline)
+    }
+
+    /// Column headers of every field
+    pub fn headers(&self) -> Vec<String> {
+        self.fields.iter().flat_map(|f| f.headers()).collect()
+    }
+}
+
+/// A parsed `.dbd` file
+#[derive(Debug, Clone, Default)]
+pub struct DbdDefinition {
+    pub layouts: Vec<DbdLayout>,
+}
+
+impl DbdDefinition {
+    /// Parse a definition. Version blocks without a `LAYOUT` line can't be
+    /// matched to a file and are skipped.
+    pub fn parse(text: &str) -> Result<Self, Db2Error> {
+        let mut columns: HashMap<String, DbdType> = HashMap::new();
+        let mut layouts = Vec::new();
+        let mut in_columns = false;
+        let mut current: Option<DbdLayout> = None;
+
+        for (index, raw) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let line = strip_comment(raw).trim();
+
+            if line.is_empty() {
+                in_columns = false;
+                if let Some(layout) = current.take() {
+                    layouts.push(layout);
+                }
+                continue;
+            }
+            if line == "COLUMNS" {
+                in_columns = true;
+                continue;
+            }
+            if in_columns {
+                let (name, ty) = parse_column(line).ok_or_else(|| Db2Error::Definition {
+                    line: line_no,
+                    reason: format!("invalid column '{}'", line),
+                })?;
+                columns.insert(name, ty);
+                continue;
+            }
+
+            if let Some(hashes) = line.strip_prefix("LAYOUT ") {
+                let hashes = hashes
+                    .split(',')
+                    .map(|h| u32::from_str_radix(h.trim(), 16))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| Db2Error::Definition {
+                        line: line_no,
+                        reason: format!("invalid layout hash in '{}'", line),
+                    })?;
+                current
+                    .get_or_insert_with(|| DbdLayout {
+                        hashes: Vec::new(),
+                        fields: Vec::new(),
+                    })
+                    .hashes
+                    .extend(hashes);
+                continue;
+            }
+            if line.starts_with("BUILD ") || line.starts_with("COMMENT ") {
+                continue;
+            }
+
+            // Field line; ignore blocks without a layout hash
+            let field = parse_field(line, &columns).map_err(|reason| Db2Error::Definition {
+                line: line_no,
+                reason,
+            })?;
+            if let Some(layout) = current.as_mut() {
+                layout.fields.push(field);
+            }
+        }
+        if let Some(layout) = current {
+            layouts.push(layout);
+        }
+
+        layouts.retain(|l| !l.hashes.is_empty());
+        Ok(Self { layouts })
+    }
+
+    /// Layout of files with `layout_hash`
+    pub fn layout(&self, layout_hash: u32) -> Option<&DbdLayout> {
+        self.layouts
+            .iter()
+            .find(|l| l.hashes.contains(&layout_hash))
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split_once("//").map_or(line, |(code, _)| code)
+}
+
+/// `int<Spell::ID> SpellID?` -> ("SpellID", Int)
+fn parse_column(line: &str) -> Option<(String, DbdType)> {
+    let (ty, name) = line.split_once(char::is_whitespace)?;
+    let ty = match ty.split('<').next()? {
+        "int" => DbdType::Int,
+        "float" => DbdType::Float,
+        "string" => DbdType::String,
+        "locstring" => DbdType::LocString,
+        _ => return None,
+    };
+    Some((name.trim().trim_end_matches('?').to_string(), ty))
+}
+
+/// `$noninline,id$ID<32>`, `Attributes<32>[17]`, `Field<u8>`
+fn parse_field(line: &str, columns: &HashMap<String, DbdType>) -> Result<DbdField, String> {
+    let mut rest = line;
+    let mut annotations: Vec<&str> = Vec::new();
+    if let Some(stripped) = rest.strip_prefix('$') {
+        let (list, tail) = stripped
+            .split_once('$')
+            .ok_or_else(|| format!("unclosed annotation in '{}'", line))?;
+        annotations = list.split(',').map(str::trim).collect();
+        rest = tail;
+    }
+
+    let mut array = 1;
+    if let Some((head, tail)) = rest.split_once('[') {
+        array = tail
+            .trim_end_matches(']')
+            .parse()
+            .map_err(|_| format!("invalid array length in '{}'", line))?;
+        rest = head;
+    }
+
+    let mut size = 32;
+    let mut signed = true;
+    if let Some((head, tail)) = rest.split_once('<') {
+        let spec = tail.trim_end_matches('>');
+        let bits = match spec.strip_prefix('u') {
+            Some(bits) => {
+                signed = false;
+                bits
+            }
+            None => spec,
+        };
+        size = bits
+            .parse()
+            .map_err(|_| format!("invalid size in '{}'", line))?;
+        rest = head;
+    }
+
+    let name = rest.trim().to_string();
+    let ty = *columns
+        .get(&name)
+        .ok_or_else(|| format!("field '{}' is not a column", name))?;
+    if ty != DbdType::Int {
+        size = 32;
+    }
+
+    Ok(DbdField {
+        is_id: annotations.contains(&"id"),
+        is_relation: annotations.contains(&"relation"),
+        inline: !annotations.contains(&"noninline"),
+        name,
+        ty,
+        size,
+        signed,
+        array,
+    })
+}
@@ -1,12 +1,17 @@
-//! DBC CSV loader
+//! DBC loader
 //!
-//! Loads WoW DBC (Database Client) tables from CSV files into indexed HashMaps.
-//! Tables are indexed either by their primary ID or by foreign key for fast lookups.
+//! Loads WoW DBC (Database Client) tables from CSV exports or the game's
+//! native DB2 files into indexed HashMaps. Tables are indexed either by their
+//! primary ID or by foreign key for fast lookups.
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
 
 use super::super::errors::DbcError;
+use super::db2::Db2File;
+use super::dbd::DbdDefinition;
 use super::rows::*;
 
 /// Container for all loaded DBC data with indexed lookups.
@@ -108,77 +113,95 @@ pub struct DbcData {
     pub curve: HashMap<i32, CurveRow>,
     pub curve_point: HashMap<i32, Vec<CurvePointRow>>,
     pub rand_prop_points: HashMap<i32, RandPropPointsRow>,
+    pub expected_stat: HashMap<i32, ExpectedStatRow>,
+    pub item_armor_quality: HashMap<i32, ItemArmorQualityRow>,
+    /// Indexed by ItemLevel
+    pub item_armor_total: HashMap<i32, ItemArmorTotalRow>,
+    /// Indexed by inventory type
+    pub armor_location: HashMap<i32, ArmorLocationRow>,
 }
 
 impl DbcData {
-    /// Load all DBC tables from CSV files in the given directory.
+    /// Load all DBC tables from the given directory.
     ///
-    /// Expects the directory structure: `{data_dir}/data/tables/*.csv`
+    /// Reads CSV exports from `{data_dir}/data/tables/*.csv`, or DB2 files
+    /// (see [`DbcData::load_db2`]) when there are no CSV tables but a
+    /// `{data_dir}/data/db2` directory.
     pub fn load_all(data_dir: &Path) -> Result<Self, DbcError> {
         let tables_dir = data_dir.join("data").join("tables");
+        if !tables_dir.exists() && data_dir.join("data").join("db2").exists() {
+            return Self::load_db2(data_dir);
+        }
+        Self::load(&TableSource::Csv(tables_dir))
+    }
+
+    /// Load all DBC tables from the game's DB2 files.
+    ///
+    /// Expects `{data_dir}/data/db2/*.db2`, decoded with the WoWDBDefs
+    /// definitions in `{data_dir}/data/definitions/*.dbd`.
+    pub fn load_db2(data_dir: &Path) -> Result<Self, DbcError> {
+        let data = data_dir.join("data");
+        Self::load(&TableSource::Db2 {
+            tables: data.join("db2"),
+            definitions: data.join("definitions"),
+        })
+    }
 
-        let spell_name = load_by_id::<SpellNameRow>(&tables_dir, "SpellName")?;
-        let spell = load_by_id::<SpellRow>(&tables_dir, "Spell")?;
-        let spell_cast_times = load_by_id::<SpellCastTimesRow>(&tables_dir, "SpellCastTimes")?;
-        let spell_duration = load_by_id::<SpellDurationRow>(&tables_dir, "SpellDuration")?;
-        let spell_range = load_by_id::<SpellRangeRow>(&tables_dir, "SpellRange")?;
-        let spell_radius = load_by_id::<SpellRadiusRow>(&tables_dir, "SpellRadius")?;
-        let spell_category = load_by_id::<SpellCategoryRow>(&tables_dir, "SpellCategory")?;
+    fn load(source: &TableSource) -> Result<Self, DbcError> {
+        let spell_name = load_by_id::<SpellNameRow>(source, "SpellName")?;
+        let spell = load_by_id::<SpellRow>(source, "Spell")?;
+        let spell_cast_times = load_by_id::<SpellCastTimesRow>(source, "SpellCastTimes")?;
+        let spell_duration = load_by_id::<SpellDurationRow>(source, "SpellDuration")?;
+        let spell_range = load_by_id::<SpellRangeRow>(source, "SpellRange")?;
+        let spell_radius = load_by_id::<SpellRadiusRow>(source, "SpellRadius")?;
+        let spell_category = load_by_id::<SpellCategoryRow>(source, "SpellCategory")?;
         let spell_description_variables =
-            load_by_id::<SpellDescriptionVariablesRow>(&tables_dir, "SpellDescriptionVariables")?;
-        let difficulty = load_by_id::<DifficultyRow>(&tables_dir, "Difficulty")?;
+            load_by_id::<SpellDescriptionVariablesRow>(source, "SpellDescriptionVariables")?;
+        let difficulty = load_by_id::<DifficultyRow>(source, "Difficulty")?;
         let manifest_interface_data =
-            load_by_id::<ManifestInterfaceDataRow>(&tables_dir, "ManifestInterfaceData")?;
+            load_by_id::<ManifestInterfaceDataRow>(source, "ManifestInterfaceData")?;
 
         // Spell tables indexed by SpellID
-        let spell_misc = load_one_by_fk::<SpellMiscRow>(&tables_dir, "SpellMisc")?;
-        let spell_effect = load_by_fk::<SpellEffectRow>(&tables_dir, "SpellEffect")?;
-        let spell_power = load_by_fk::<SpellPowerRow>(&tables_dir, "SpellPower")?;
-        let spell_cooldowns = load_one_by_fk::<SpellCooldownsRow>(&tables_dir, "SpellCooldowns")?;
-        let spell_categories =
-            load_one_by_fk::<SpellCategoriesRow>(&tables_dir, "SpellCategories")?;
+        let spell_misc = load_one_by_fk::<SpellMiscRow>(source, "SpellMisc")?;
+        let spell_effect = load_by_fk::<SpellEffectRow>(source, "SpellEffect")?;
+        let spell_power = load_by_fk::<SpellPowerRow>(source, "SpellPower")?;
+        let spell_cooldowns = load_one_by_fk::<SpellCooldownsRow>(source, "SpellCooldowns")?;
+        let spell_categories = load_one_by_fk::<SpellCategoriesRow>(source, "SpellCategories")?;
         let spell_class_options =
-            load_one_by_fk::<SpellClassOptionsRow>(&tables_dir, "SpellClassOptions")?;
+            load_one_by_fk::<SpellClassOptionsRow>(source, "SpellClassOptions")?;
         let spell_aura_restrictions =
-            load_one_by_fk::<SpellAuraRestrictionsRow>(&tables_dir, "SpellAuraRestrictions")?;
-        let spell_interrupts =
-            load_one_by_fk::<SpellInterruptsRow>(&tables_dir, "SpellInterrupts")?;
-        let spell_empower = load_one_by_fk::<SpellEmpowerRow>(&tables_dir, "SpellEmpower")?;
-        let spell_empower_stage =
-            load_by_fk::<SpellEmpowerStageRow>(&tables_dir, "SpellEmpowerStage")?;
+            load_one_by_fk::<SpellAuraRestrictionsRow>(source, "SpellAuraRestrictions")?;
+        let spell_interrupts = load_one_by_fk::<SpellInterruptsRow>(source, "SpellInterrupts")?;
+        let spell_empower = load_one_by_fk::<SpellEmpowerRow>(source, "SpellEmpower")?;
+        let spell_empower_stage = load_by_fk::<SpellEmpowerStageRow>(source, "SpellEmpowerStage")?;
         let spell_target_restrictions =
-            load_one_by_fk::<SpellTargetRestrictionsRow>(&tables_dir, "SpellTargetRestrictions")?;
-        let spell_levels = load_by_fk::<SpellLevelsRow>(&tables_dir, "SpellLevels")?;
-        let spell_learn_spell = load_by_fk::<SpellLearnSpellRow>(&tables_dir, "SpellLearnSpell")?;
-        let spell_replacement =
-            load_one_by_fk::<SpellReplacementRow>(&tables_dir, "SpellReplacement")?;
-        let spell_shapeshift =
-            load_one_by_fk::<SpellShapeshiftRow>(&tables_dir, "SpellShapeshift")?;
-        let spell_totems = load_by_fk::<SpellTotemsRow>(&tables_dir, "SpellTotems")?;
+            load_one_by_fk::<SpellTargetRestrictionsRow>(source, "SpellTargetRestrictions")?;
+        let spell_levels = load_by_fk::<SpellLevelsRow>(source, "SpellLevels")?;
+        let spell_learn_spell = load_by_fk::<SpellLearnSpellRow>(source, "SpellLearnSpell")?;
+        let spell_replacement = load_one_by_fk::<SpellReplacementRow>(source, "SpellReplacement")?;
+        let spell_shapeshift = load_one_by_fk::<SpellShapeshiftRow>(source, "SpellShapeshift")?;
+        let spell_totems = load_by_fk::<SpellTotemsRow>(source, "SpellTotems")?;
         let spell_x_description_variables =
-            load_by_fk::<SpellXDescriptionVariablesRow>(&tables_dir, "SpellXDescriptionVariables")?;
-        let spell_aura_options =
-            load_one_by_fk::<SpellAuraOptionsRow>(&tables_dir, "SpellAuraOptions")?;
+            load_by_fk::<SpellXDescriptionVariablesRow>(source, "SpellXDescriptionVariables")?;
+        let spell_aura_options = load_one_by_fk::<SpellAuraOptionsRow>(source, "SpellAuraOptions")?;
 
         // Character tables
-        let chr_specialization =
-            load_by_id::<ChrSpecializationRow>(&tables_dir, "ChrSpecialization")?;
-        let chr_classes = load_by_id::<ChrClassesRow>(&tables_dir, "ChrClasses")?;
+        let chr_specialization = load_by_id::<ChrSpecializationRow>(source, "ChrSpecialization")?;
+        let chr_classes = load_by_id::<ChrClassesRow>(source, "ChrClasses")?;
         let specialization_spells =
-            load_by_fk::<SpecializationSpellsRow>(&tables_dir, "SpecializationSpells")?;
+            load_by_fk::<SpecializationSpellsRow>(source, "SpecializationSpells")?;
 
         // Talent tables indexed by ID
-        let trait_node = load_by_id::<TraitNodeRow>(&tables_dir, "TraitNode")?;
-        let trait_node_entry = load_by_id::<TraitNodeEntryRow>(&tables_dir, "TraitNodeEntry")?;
-        let trait_definition = load_by_id::<TraitDefinitionRow>(&tables_dir, "TraitDefinition")?;
-        let trait_tree_loadout =
-            load_by_id::<TraitTreeLoadoutRow>(&tables_dir, "TraitTreeLoadout")?;
-        let trait_sub_tree = load_by_id::<TraitSubTreeRow>(&tables_dir, "TraitSubTree")?;
-        let trait_currency = load_by_id::<TraitCurrencyRow>(&tables_dir, "TraitCurrency")?;
-        let trait_cost = load_by_id::<TraitCostRow>(&tables_dir, "TraitCost")?;
-        let trait_cond = load_by_id::<TraitCondRow>(&tables_dir, "TraitCond")?;
+        let trait_node = load_by_id::<TraitNodeRow>(source, "TraitNode")?;
+        let trait_node_entry = load_by_id::<TraitNodeEntryRow>(source, "TraitNodeEntry")?;
+        let trait_definition = load_by_id::<TraitDefinitionRow>(source, "TraitDefinition")?;
+        let trait_tree_loadout = load_by_id::<TraitTreeLoadoutRow>(source, "TraitTreeLoadout")?;
+        let trait_sub_tree = load_by_id::<TraitSubTreeRow>(source, "TraitSubTree")?;
+        let trait_currency = load_by_id::<TraitCurrencyRow>(source, "TraitCurrency")?;
+        let trait_cost = load_by_id::<TraitCostRow>(source, "TraitCost")?;
+        let trait_cond = load_by_id::<TraitCondRow>(source, "TraitCond")?;
         let ui_texture_atlas_element =
-            load_by_id::<UiTextureAtlasElementRow>(&tables_dir, "UiTextureAtlasElement")?;
+            load_by_id::<UiTextureAtlasElementRow>(source, "UiTextureAtlasElement")?;
 
         // Build secondary indices for talent tables
         let trait_node_by_tree = group_by(&trait_node, |n| n.TraitTreeID);
@@ -186,23 +209,23 @@ impl DbcData {
 
         // Talent tables indexed by foreign key
         let trait_tree_loadout_entry =
-            load_by_fk::<TraitTreeLoadoutEntryRow>(&tables_dir, "TraitTreeLoadoutEntry")?;
-        let trait_edge = load_by_fk::<TraitEdgeRow>(&tables_dir, "TraitEdge")?;
+            load_by_fk::<TraitTreeLoadoutEntryRow>(source, "TraitTreeLoadoutEntry")?;
+        let trait_edge = load_by_fk::<TraitEdgeRow>(source, "TraitEdge")?;
         let trait_node_x_trait_node_entry =
-            load_by_fk::<TraitNodeXTraitNodeEntryRow>(&tables_dir, "TraitNodeXTraitNodeEntry")?;
+            load_by_fk::<TraitNodeXTraitNodeEntryRow>(source, "TraitNodeXTraitNodeEntry")?;
         let trait_tree_x_trait_currency =
-            load_by_fk::<TraitTreeXTraitCurrencyRow>(&tables_dir, "TraitTreeXTraitCurrency")?;
+            load_by_fk::<TraitTreeXTraitCurrencyRow>(source, "TraitTreeXTraitCurrency")?;
         let trait_currency_source =
-            load_by_fk::<TraitCurrencySourceRow>(&tables_dir, "TraitCurrencySource")?;
+            load_by_fk::<TraitCurrencySourceRow>(source, "TraitCurrencySource")?;
         let trait_node_group_x_trait_node =
-            load_by_fk::<TraitNodeGroupXTraitNodeRow>(&tables_dir, "TraitNodeGroupXTraitNode")?;
+            load_by_fk::<TraitNodeGroupXTraitNodeRow>(source, "TraitNodeGroupXTraitNode")?;
         let trait_node_group_x_trait_cost =
-            load_by_fk::<TraitNodeGroupXTraitCostRow>(&tables_dir, "TraitNodeGroupXTraitCost")?;
+            load_by_fk::<TraitNodeGroupXTraitCostRow>(source, "TraitNodeGroupXTraitCost")?;
         let trait_node_group_x_trait_cond =
-            load_by_fk::<TraitNodeGroupXTraitCondRow>(&tables_dir, "TraitNodeGroupXTraitCond")?;
+            load_by_fk::<TraitNodeGroupXTraitCondRow>(source, "TraitNodeGroupXTraitCond")?;
         let trait_node_x_trait_cond =
-            load_by_fk::<TraitNodeXTraitCondRow>(&tables_dir, "TraitNodeXTraitCond")?;
-        let spec_set_member = load_by_fk::<SpecSetMemberRow>(&tables_dir, "SpecSetMember")?;
+            load_by_fk::<TraitNodeXTraitCondRow>(source, "TraitNodeXTraitCond")?;
+        let spec_set_member = load_by_fk::<SpecSetMemberRow>(source, "SpecSetMember")?;
 
         // Index trait conditions by node group for fast lookup
         let trait_cond_by_node_group = group_by_filtered(
@@ -212,37 +235,44 @@ impl DbcData {
         );
 
         // Item tables indexed by ID
-        let item = load_by_id::<ItemRow>(&tables_dir, "Item")?;
-        let item_sparse = load_by_id::<ItemSparseRow>(&tables_dir, "ItemSparse")?;
-        let item_effect = load_by_id::<ItemEffectRow>(&tables_dir, "ItemEffect")?;
-        let item_set = load_by_id::<ItemSetRow>(&tables_dir, "ItemSet")?;
-        let item_class = load_by_id::<ItemClassRow>(&tables_dir, "ItemClass")?;
-        let item_sub_class = load_by_id::<ItemSubClassRow>(&tables_dir, "ItemSubClass")?;
-        let item_appearance = load_by_id::<ItemAppearanceRow>(&tables_dir, "ItemAppearance")?;
-        let journal_encounter = load_by_id::<JournalEncounterRow>(&tables_dir, "JournalEncounter")?;
-        let journal_instance = load_by_id::<JournalInstanceRow>(&tables_dir, "JournalInstance")?;
+        let item = load_by_id::<ItemRow>(source, "Item")?;
+        let item_sparse = load_by_id::<ItemSparseRow>(source, "ItemSparse")?;
+        let item_effect = load_by_id::<ItemEffectRow>(source, "ItemEffect")?;
+        let item_set = load_by_id::<ItemSetRow>(source, "ItemSet")?;
+        let item_class = load_by_id::<ItemClassRow>(source, "ItemClass")?;
+        let item_sub_class = load_by_id::<ItemSubClassRow>(source, "ItemSubClass")?;
+        let item_appearance = load_by_id::<ItemAppearanceRow>(source, "ItemAppearance")?;
+        let journal_encounter = load_by_id::<JournalEncounterRow>(source, "JournalEncounter")?;
+        let journal_instance = load_by_id::<JournalInstanceRow>(source, "JournalInstance")?;
 
         // Item tables indexed by foreign key
-        let item_x_item_effect = load_by_fk::<ItemXItemEffectRow>(&tables_dir, "ItemXItemEffect")?;
-        let item_set_spell = load_by_fk::<ItemSetSpellRow>(&tables_dir, "ItemSetSpell")?;
+        let item_x_item_effect = load_by_fk::<ItemXItemEffectRow>(source, "ItemXItemEffect")?;
+        let item_set_spell = load_by_fk::<ItemSetSpellRow>(source, "ItemSetSpell")?;
         let item_modified_appearance =
-            load_one_by_fk::<ItemModifiedAppearanceRow>(&tables_dir, "ItemModifiedAppearance")?;
+            load_one_by_fk::<ItemModifiedAppearanceRow>(source, "ItemModifiedAppearance")?;
         let journal_encounter_item =
-            load_by_fk::<JournalEncounterItemRow>(&tables_dir, "JournalEncounterItem")?;
+            load_by_fk::<JournalEncounterItemRow>(source, "JournalEncounterItem")?;
 
         // Build secondary indices for item tables
         let item_class_by_class_id = group_one_by(&item_class, |c| c.ClassID);
         let item_sub_class_by_class_id = group_by(&item_sub_class, |c| c.ClassID);
 
         // Global tables
-        let global_color = load_by_id::<GlobalColorRow>(&tables_dir, "GlobalColor")?;
-        let global_strings = load_by_id::<GlobalStringsRow>(&tables_dir, "GlobalStrings")?;
+        let global_color = load_by_id::<GlobalColorRow>(source, "GlobalColor")?;
+        let global_strings = load_by_id::<GlobalStringsRow>(source, "GlobalStrings")?;
 
         // Item scaling tables
-        let item_bonus = load_by_fk::<ItemBonusRow>(&tables_dir, "ItemBonus")?;
-        let curve = load_by_id::<CurveRow>(&tables_dir, "Curve")?;
-        let curve_point = load_by_fk::<CurvePointRow>(&tables_dir, "CurvePoint")?;
-        let rand_prop_points = load_by_id::<RandPropPointsRow>(&tables_dir, "RandPropPoints")?;
+        let item_bonus = load_by_fk::<ItemBonusRow>(source, "ItemBonus")?;
+        let curve = load_by_id::<CurveRow>(source, "Curve")?;
+        let curve_point = load_by_fk::<CurvePointRow>(source, "CurvePoint")?;
+        let rand_prop_points = load_by_id::<RandPropPointsRow>(source, "RandPropPoints")?;
+
+        // Armor tables; older data directories may not have them
+        let expected_stat = optional("ExpectedStat", load_by_id(source, "ExpectedStat"));
+        let item_armor_quality =
+            optional("ItemArmorQuality", load_by_id(source, "ItemArmorQuality"));
+        let item_armor_total = optional("ItemArmorTotal", load_one_by_fk(source, "ItemArmorTotal"));
+        let armor_location = optional("ArmorLocation", load_by_id(source, "ArmorLocation"));
 
         Ok(Self {
             // Spell tables
@@ -330,18 +360,42 @@ impl DbcData {
             curve,
             curve_point,
             rand_prop_points,
+            expected_stat,
+            item_armor_quality,
+            item_armor_total,
+            armor_location,
         })
     }
 }
 
-/// Load a CSV file into a HashMap indexed by row ID.
-fn load_by_id<T: serde::de::DeserializeOwned + HasId>(
-    path: &Path,
-    table_name: &str,
-) -> Result<HashMap<i32, T>, DbcError> {
-    let file_path = path.join(format!("{}.csv", table_name));
+/// Where table rows are read from
+enum TableSource {
+    /// CSV exports, `{Table}.csv`
+    Csv(PathBuf),
+    /// DB2 files, `{Table}.db2`, and their definitions, `{Table}.dbd`
+    Db2 {
+        tables: PathBuf,
+        definitions: PathBuf,
+    },
+}
+
+impl TableSource {
+    /// Read every row of a table (none if the table is missing).
+    fn rows<T: DeserializeOwned>(&self, table_name: &str) -> Result<Vec<T>, DbcError> {
+        match self {
+            Self::Csv(dir) => read_csv(dir, table_name),
+            Self::Db2 {
+                tables,
+                definitions,
+            } => read_db2(tables, definitions, table_name),
+        }
+    }
+}
+
+fn read_csv<T: DeserializeOwned>(dir: &Path, table_name: &str) -> Result<Vec<T>, DbcError> {
+    let file_path = dir.join(format!("{}.csv", table_name));
     if !file_path.exists() {
-        return Ok(HashMap::new());
+        return Ok(Vec::new());
     }
 
     let mut reader = csv::Reader::from_path(&file_path).map_err(|e| DbcError::CsvRead {
@@ -349,72 +403,107 @@ fn load_by_id<T: serde::de::DeserializeOwned + HasId>(
         source: e,
     })?;
 
-    let mut map = HashMap::new();
-    for result in reader.deserialize() {
-        let row: T = result.map_err(|e| DbcError::CsvParse {
+    reader
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(|e| DbcError::CsvParse {
             table: table_name.to_string(),
             source: e,
-        })?;
-        map.insert(row.id(), row);
-    }
-
-    Ok(map)
+        })
 }
 
-/// Load a CSV file and group rows by foreign key (one-to-many).
-fn load_by_fk<T: serde::de::DeserializeOwned + HasFk>(
-    path: &Path,
+fn read_db2<T: DeserializeOwned>(
+    dir: &Path,
+    definitions: &Path,
     table_name: &str,
-) -> Result<HashMap<i32, Vec<T>>, DbcError> {
-    let file_path = path.join(format!("{}.csv", table_name));
-    if !file_path.exists() {
-        return Ok(HashMap::new());
-    }
+) -> Result<Vec<T>, DbcError> {
+    // Client files are lowercase; exports keep the table's name
+    let Some(file_path) = [table_name.to_string(), table_name.to_lowercase()]
+        .iter()
+        .map(|name| dir.join(format!("{}.db2", name)))
+        .find(|path| path.exists())
+    else {
+        return Ok(Vec::new());
+    };
+    let definition_path = definitions.join(format!("{}.dbd", table_name));
 
-    let mut reader = csv::Reader::from_path(&file_path).map_err(|e| DbcError::CsvRead {
+    let read = |path: &Path| {
+        std::fs::read(path).map_err(|e| DbcError::Io {
+            path: path.display().to_string(),
+            source: e,
+        })
+    };
+    let file = Db2File::parse(read(&file_path)?).map_err(|e| DbcError::Db2Read {
+        path: file_path.display().to_string(),
+        source: e,
+    })?;
+    let definition_text = String::from_utf8_lossy(&read(&definition_path)?).into_owned();
+    let definition = DbdDefinition::parse(&definition_text).map_err(|e| DbcError::Db2Read {
+        path: definition_path.display().to_string(),
+        source: e,
+    })?;
+    let layout = definition
+        .layout(file.layout_hash)
+        .ok_or_else(|| DbcError::Db2Layout {
+            path: definition_path.display().to_string(),
+            layout_hash: file.layout_hash,
+        })?;
+
+    let table = file.read(layout).map_err(|e| DbcError::Db2Read {
         path: file_path.display().to_string(),
         source: e,
     })?;
+    table.deserialize().map_err(|e| DbcError::CsvParse {
+        table: table_name.to_string(),
+        source: e,
+    })
+}
+
+/// Load a table into a HashMap indexed by row ID.
+fn load_by_id<T: DeserializeOwned + HasId>(
+    source: &TableSource,
+    table_name: &str,
+) -> Result<HashMap<i32, T>, DbcError> {
+    Ok(source
+        .rows::<T>(table_name)?
+        .into_iter()
+        .map(|row| (row.id(), row))
+        .collect())
+}
 
+/// Load a table and group rows by foreign key (one-to-many).
+fn load_by_fk<T: DeserializeOwned + HasFk>(
+    source: &TableSource,
+    table_name: &str,
+) -> Result<HashMap<i32, Vec<T>>, DbcError> {
     let mut map: HashMap<i32, Vec<T>> = HashMap::new();
-    for result in reader.deserialize() {
-        let row: T = result.map_err(|e| DbcError::CsvParse {
-            table: table_name.to_string(),
-            source: e,
-        })?;
+    for row in source.rows::<T>(table_name)? {
         map.entry(row.fk()).or_default().push(row);
     }
-
     Ok(map)
 }
 
-/// Load a CSV file indexed by foreign key (one-to-one, first match wins).
-fn load_one_by_fk<T: serde::de::DeserializeOwned + HasFk>(
-    path: &Path,
+/// Load a table indexed by foreign key (one-to-one, first match wins).
+fn load_one_by_fk<T: DeserializeOwned + HasFk>(
+    source: &TableSource,
     table_name: &str,
 ) -> Result<HashMap<i32, T>, DbcError> {
-    let file_path = path.join(format!("{}.csv", table_name));
-    if !file_path.exists() {
-        return Ok(HashMap::new());
-    }
-
-    let mut reader = csv::Reader::from_path(&file_path).map_err(|e| DbcError::CsvRead {
-        path: file_path.display().to_string(),
-        source: e,
-    })?;
-
     let mut map = HashMap::new();
-    for result in reader.deserialize() {
-        let row: T = result.map_err(|e| DbcError::CsvParse {
-            table: table_name.to_string(),
-            source: e,
-        })?;
+    for row in source.rows::<T>(table_name)? {
         map.entry(row.fk()).or_insert(row);
     }
-
     Ok(map)
 }
 
+/// Keep a table that's only needed for armor out of the way of everything
+/// else: if it can't be loaded, log it and leave it empty.
+fn optional<T: Default>(table_name: &str, loaded: Result<T, DbcError>) -> T {
+    loaded.unwrap_or_else(|e| {
+        tracing::warn!(table = table_name, error = %e, "Skipping table, armor uses defaults");
+        T::default()
+    })
+}
+
 /// Group an existing HashMap's values by a key function.
 fn group_by<T: Clone, F: Fn(&T) -> i32>(
     source: &HashMap<i32, T>,
@@ -506,6 +595,9 @@ impl_has_id!(
     // Item scaling tables
     CurveRow,
     RandPropPointsRow,
+    ExpectedStatRow,
+    ItemArmorQualityRow,
+    ArmorLocationRow,
 );
 
 macro_rules! impl_has_fk {
@@ -562,3 +654,4 @@ impl_has_fk!(JournalEncounterItemRow, ItemID);
 // Item scaling tables
 impl_has_fk!(ItemBonusRow, ParentItemBonusListID);
 impl_has_fk!(CurvePointRow, CurveID);
+impl_has_fk!(ItemArmorTotalRow, ItemLevel);
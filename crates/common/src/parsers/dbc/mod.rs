@@ -1,12 +1,21 @@
-//! DBC CSV parsing and data structures
+//! DBC parsing and data structures
 //!
 //! This module provides:
 //! - Row structs for all required DBC tables
-//! - DbcData struct that loads all tables into indexed HashMaps
+//! - DbcData struct that loads all tables into indexed HashMaps, from CSV
+//!   exports or native DB2 files
+//! - A WDC3/WDC4/WDC5 reader and the WoWDBDefs definitions it decodes with
 //! - Helper methods for lookups by ID and foreign key
 
+mod db2;
+mod dbd;
 mod loader;
 pub mod rows;
 
+#[cfg(test)]
+mod tests;
+
+pub use db2::{Db2File, Db2Format, Db2Table};
+pub use dbd::{DbdDefinition, DbdField, DbdLayout, DbdType};
 pub use loader::DbcData;
 pub use rows::*;
@@ -1069,3 +1069,48 @@ pub struct RandPropPointsRow {
     pub Good_3: i32,
     pub Good_4: i32,
 }
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ItemArmorQualityRow {
+    pub ID: i32,
+    pub Qualitymod_0: f64,
+    pub Qualitymod_1: f64,
+    pub Qualitymod_2: f64,
+    pub Qualitymod_3: f64,
+    pub Qualitymod_4: f64,
+    pub Qualitymod_5: f64,
+    pub Qualitymod_6: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ItemArmorTotalRow {
+    pub ID: i32,
+    pub ItemLevel: i32,
+    pub Cloth: f64,
+    pub Leather: f64,
+    pub Mail: f64,
+    pub Plate: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ArmorLocationRow {
+    pub ID: i32,
+    pub Clothmodifier: f64,
+    pub Leathermodifier: f64,
+    pub Chainmodifier: f64,
+    pub Platemodifier: f64,
+    pub Modifier: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ExpectedStatRow {
+    pub ID: i32,
+    pub ExpansionID: i32,
+    pub CreatureArmor: f64,
+    pub ArmorConstant: f64,
+    pub Lvl: i32,
+}
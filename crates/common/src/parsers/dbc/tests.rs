@@ -0,0 +1,236 @@
+//! Unit tests for the DB2 reader and WoWDBDefs definitions
+//!
+//! The sample files in `testdata/db2` are written by its `generate.py`.
+
+use std::path::Path;
+
+use super::*;
+use crate::parsers::Db2Error;
+
+macro_rules! sample {
+    ($table:literal) => {
+        (
+            include_bytes!(concat!("../../../testdata/db2/data/db2/", $table, ".db2")).to_vec(),
+            include_str!(concat!(
+                "../../../testdata/db2/data/definitions/",
+                $table,
+                ".dbd"
+            )),
+        )
+    };
+}
+
+fn read<T: serde::de::DeserializeOwned>((data, definition): (Vec<u8>, &str)) -> Vec<T> {
+    let file = Db2File::parse(data).unwrap();
+    let definition = DbdDefinition::parse(definition).unwrap();
+    let layout = definition.layout(file.layout_hash).unwrap();
+    file.read(layout).unwrap().deserialize().unwrap()
+}
+
+// Definition Tests
+
+#[test]
+fn test_definition_layouts() {
+    let definition = DbdDefinition::parse(sample!("SpellName").1).unwrap();
+    assert_eq!(definition.layouts.len(), 2);
+
+    let layout = definition.layout(0x4F4B3F0E).unwrap();
+    let id = &layout.fields[0];
+    assert!(id.is_id && !id.inline);
+    assert_eq!(layout.inline_fields().count(), 1);
+    assert!(definition.layout(0xDEADBEEF).is_none());
+}
+
+#[test]
+fn test_definition_fields() {
+    let definition = DbdDefinition::parse(sample!("CurvePoint").1).unwrap();
+    let layout = &definition.layouts[0];
+    assert_eq!(
+        layout.headers(),
+        [
+            "Pos_0",
+            "Pos_1",
+            "PosPreSquish_0",
+            "PosPreSquish_1",
+            "ID",
+            "CurveID",
+            "OrderIndex"
+        ]
+    );
+
+    let curve = &layout.fields[3];
+    assert!(curve.is_relation && !curve.inline);
+    assert_eq!(curve.size, 16);
+    let order = &layout.fields[4];
+    assert_eq!((order.size, order.signed), (8, false));
+    assert_eq!(layout.fields[0].ty, DbdType::Float);
+}
+
+#[test]
+fn test_definition_unknown_column() {
+    let text = "COLUMNS\nint ID\n\nLAYOUT 00000001\nMissing<32>\n";
+    assert!(matches!(
+        DbdDefinition::parse(text),
+        Err(Db2Error::Definition { line: 5, .. })
+    ));
+}
+
+// Reader Tests
+
+#[test]
+fn test_unsupported_format() {
+    assert!(matches!(
+        Db2File::parse(b"WDC2\0\0\0\0".to_vec()),
+        Err(Db2Error::UnsupportedFormat(_))
+    ));
+}
+
+#[test]
+fn test_sections_strings_and_copies() {
+    let rows: Vec<SpellNameRow> = read(sample!("SpellName"));
+    let names: Vec<_> = rows
+        .iter()
+        .map(|r| (r.ID, r.Name_lang.as_deref()))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            (100, Some("Kill Command")),
+            (133, Some("Barbed Shot")),
+            (200, Some("Bestial Wrath")),
+            (300, None),
+            (201, Some("Bestial Wrath")),
+        ]
+    );
+}
+
+#[test]
+fn test_pallet_arrays_and_relations() {
+    let (data, _) = sample!("CurvePoint");
+    assert_eq!(Db2File::parse(data).unwrap().format, Db2Format::Wdc4);
+
+    // The encrypted section is skipped
+    let rows: Vec<CurvePointRow> = read(sample!("CurvePoint"));
+    assert_eq!(rows.len(), 3);
+
+    let point = &rows[1];
+    assert_eq!((point.ID, point.CurveID, point.OrderIndex), (502, 77, 1));
+    assert_eq!((point.Pos_0, point.Pos_1), (10.0, 2.5));
+    assert_eq!((point.PosPreSquish_0, point.PosPreSquish_1), (10.0, 2.5));
+    assert_eq!(rows[2].CurveID, 78);
+}
+
+#[test]
+fn test_common_data_and_signed_values() {
+    let rows: Vec<ExpectedStatRow> = read(sample!("ExpectedStat"));
+    let values: Vec<_> = rows
+        .iter()
+        .map(|r| (r.ID, r.ExpansionID, r.CreatureArmor, r.ArmorConstant, r.Lvl))
+        .collect();
+    assert_eq!(
+        values,
+        [
+            (1, -2, 100.5, 7390.0, 80),
+            (2, 10, 250.0, 8500.0, 81),
+            (3, 9, 100.5, 7390.0, 70),
+        ]
+    );
+}
+
+#[test]
+fn test_sparse_records() {
+    let (data, _) = sample!("GlobalStrings");
+    assert!(Db2File::parse(data).unwrap().is_sparse());
+
+    let rows: Vec<GlobalStringsRow> = read(sample!("GlobalStrings"));
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].ID, 10);
+    assert_eq!(rows[0].BaseTag, "HUNTER_PET");
+    assert_eq!(rows[0].TagText_lang.as_deref(), Some("Pet"));
+    assert_eq!(rows[0].Flags, 1);
+    assert_eq!((rows[1].ID, rows[1].TagText_lang.as_deref()), (20, None));
+}
+
+#[test]
+fn test_truncated_files() {
+    for (data, definition) in [
+        sample!("SpellName"),
+        sample!("CurvePoint"),
+        sample!("ExpectedStat"),
+        sample!("GlobalStrings"),
+    ] {
+        let definition = DbdDefinition::parse(definition).unwrap();
+        for len in 0..data.len() {
+            let Ok(file) = Db2File::parse(data[..len].to_vec()) else {
+                continue;
+            };
+            let layout = definition.layout(file.layout_hash).unwrap();
+            assert!(file.read(layout).is_err(), "truncated to {len} bytes");
+        }
+    }
+}
+
+#[test]
+fn test_field_offset_past_record() {
+    let (mut data, definition) = sample!("SpellName");
+    // Field storage info follows the 72-byte header, the section headers
+    // and the field structures
+    let word = |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap()) as usize;
+    let storage_info = 72 + word(68) * 40 + word(44) * 4;
+    data[storage_info..storage_info + 2].copy_from_slice(&0xFFF0u16.to_le_bytes());
+
+    let file = Db2File::parse(data).unwrap();
+    let definition = DbdDefinition::parse(definition).unwrap();
+    let layout = definition.layout(file.layout_hash).unwrap();
+    assert!(matches!(file.read(layout), Err(Db2Error::UnexpectedEof(_))));
+}
+
+#[test]
+fn test_huge_header_values() {
+    let (data, definition) = sample!("SpellName");
+    let definition = DbdDefinition::parse(definition).unwrap();
+    // Record count, record size, common and pallet data sizes, section count,
+    // then every field of the first section header
+    let words = [4, 12, 60, 64, 68].into_iter().chain((80..112).step_by(4));
+    for at in words {
+        let mut data = data.clone();
+        data[at..at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        // Must fail or decode, but never panic or abort on allocation
+        if let Ok(file) = Db2File::parse(data) {
+            if let Some(layout) = definition.layout(file.layout_hash) {
+                let _ = file.read(layout);
+            }
+        }
+    }
+}
+
+// Loader Tests
+
+#[test]
+fn test_load_db2() {
+    let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/db2");
+    let data = DbcData::load_db2(&data_dir).unwrap();
+
+    assert_eq!(data.spell_name.len(), 5);
+    assert_eq!(data.curve_point[&77].len(), 2);
+    assert_eq!(data.expected_stat[&2].ArmorConstant, 8500.0);
+    assert_eq!(data.global_strings[&10].BaseTag, "HUNTER_PET");
+    // Tables without a DB2 file load empty
+    assert!(data.spell.is_empty());
+}
+
+#[test]
+fn test_load_without_armor_tables() {
+    // An older export: ExpectedStat has different columns, the rest are absent
+    let data_dir = std::env::temp_dir().join(format!("wowlab-dbc-armor-{}", std::process::id()));
+    let tables = data_dir.join("data").join("tables");
+    std::fs::create_dir_all(&tables).unwrap();
+    std::fs::write(tables.join("ExpectedStat.csv"), "ID,Unknown\n1,2\n").unwrap();
+
+    let data = DbcData::load_all(&data_dir);
+    std::fs::remove_dir_all(&data_dir).unwrap();
+
+    let data = data.unwrap();
+    assert!(data.expected_stat.is_empty());
+    assert!(data.item_armor_total.is_empty());
+}
@@ -29,6 +29,39 @@ pub enum DbcError {
 
     #[error("Table '{table}' not found at path: {path}")]
     TableNotFound { table: String, path: String },
+
+    #[error("Failed to read DB2 file '{path}': {source}")]
+    Db2Read {
+        path: String,
+        #[source]
+        source: Db2Error,
+    },
+
+    #[error("No definition in '{path}' matches layout {layout_hash:08X}")]
+    Db2Layout { path: String, layout_hash: u32 },
+}
+
+/// Errors that can occur when decoding DB2 files or their definitions
+#[cfg(feature = "dbc")]
+#[derive(Debug, Error)]
+pub enum Db2Error {
+    #[error("Unsupported DB2 format '{0}'")]
+    UnsupportedFormat(String),
+
+    #[error("Unexpected end of data at offset {0}")]
+    UnexpectedEof(usize),
+
+    #[error("Unknown field storage type {0}")]
+    UnknownStorage(u32),
+
+    #[error("Size of {0} is out of range")]
+    Overflow(&'static str),
+
+    #[error("Record has no field {0}")]
+    MissingField(usize),
+
+    #[error("Invalid definition on line {line}: {reason}")]
+    Definition { line: usize, reason: String },
 }
 
 /// Errors that can occur during transformation
@@ -13,6 +13,10 @@ use std::collections::HashMap;
 use super::errors::TraitError;
 use crate::types::data::{TraitSelection, TraitTreeFlat, TraitTreeWithSelections};
 
+mod validate;
+
+pub use validate::*;
+
 /// A decoded trait loadout containing all node selections.
 #[derive(Debug, Clone)]
 pub struct DecodedTraitLoadout {
@@ -243,6 +247,25 @@ fn decode_node(reader: &mut BitReader) -> Result<DecodedTraitNode, TraitError> {
     })
 }
 
+/// Hash identifying a trait tree's node list.
+///
+/// Loadout strings store one entry per node, in the order of the tree's
+/// sorted node IDs, so the hash covers exactly that list: any added,
+/// removed or renumbered node changes it. 128-bit FNV-1a over each node ID
+/// as little-endian bytes. The client's own hash isn't public, but its
+/// exports leave the hash zeroed, which skips the check.
+pub fn trait_tree_hash(node_ids: &[i32]) -> [u8; 16] {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    let mut hash = OFFSET_BASIS;
+    for byte in node_ids.iter().flat_map(|id| id.to_le_bytes()) {
+        hash ^= u128::from(byte);
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash.to_be_bytes()
+}
+
 /// Create a minimal loadout string for a spec with no talent selections.
 /// Useful for "start from scratch" flows.
 pub fn encode_minimal_loadout(spec_id: u16) -> String {
@@ -1,4 +1,5 @@
 use super::*;
+use crate::types::data::{PointLimits, TraitEdge, TraitNode, TraitNodeEntry, TraitSubTree};
 
 fn make_node(selected: bool, purchased: bool, choice_index: Option<u8>) -> DecodedTraitNode {
     DecodedTraitNode {
@@ -38,3 +39,246 @@ fn test_roundtrip() {
         }
     }
 }
+
+fn purchased(ranks: Option<u8>, choice_index: Option<u8>) -> DecodedTraitNode {
+    DecodedTraitNode {
+        selected: true,
+        purchased: true,
+        partially_ranked: ranks.is_some(),
+        ranks_purchased: ranks,
+        choice_node: choice_index.is_some(),
+        choice_index,
+    }
+}
+
+fn skipped() -> DecodedTraitNode {
+    make_node(false, false, None)
+}
+
+fn tree_node(id: i32, tree_index: i32, max_ranks: i32, entries: usize) -> TraitNode {
+    TraitNode {
+        id,
+        pos_x: 0,
+        pos_y: 0,
+        max_ranks,
+        node_type: if entries > 1 { 2 } else { 0 },
+        tree_index,
+        order_index: 0,
+        sub_tree_id: 0,
+        entries: (0..entries as i32)
+            .map(|i| TraitNodeEntry {
+                id: id * 10 + i,
+                definition_id: id * 10 + i,
+                spell_id: id * 10 + i,
+                name: String::new(),
+                description: String::new(),
+                icon_file_name: String::new(),
+            })
+            .collect(),
+        required_points: 0,
+    }
+}
+
+/// Spec tree: 1 -> 2 (two ranks) -> 3 (choice), 4 gated at 3 points.
+/// Hero nodes 5 and 6 belong to sub-trees 100 and 200; only 100 is available.
+fn validation_tree() -> TraitTreeFlat {
+    let edge = |id, from_node_id, to_node_id| TraitEdge {
+        id,
+        from_node_id,
+        to_node_id,
+        visual_style: 0,
+    };
+
+    let mut nodes = vec![
+        tree_node(1, 2, 1, 1),
+        tree_node(2, 2, 2, 1),
+        tree_node(3, 2, 1, 2),
+        tree_node(4, 2, 1, 1),
+        tree_node(5, 3, 1, 1),
+        tree_node(6, 3, 1, 1),
+    ];
+    nodes[3].required_points = 3;
+    nodes[4].sub_tree_id = 100;
+    nodes[5].sub_tree_id = 200;
+
+    TraitTreeFlat {
+        spec_id: 253,
+        spec_name: String::new(),
+        class_name: String::new(),
+        tree_id: 1,
+        all_node_ids: vec![1, 2, 3, 4, 5, 6],
+        nodes,
+        edges: vec![edge(1, 1, 2), edge(2, 2, 3)],
+        sub_trees: vec![TraitSubTree {
+            id: 100,
+            name: String::new(),
+            description: String::new(),
+            icon_file_name: String::new(),
+        }],
+        point_limits: PointLimits {
+            class: 31,
+            spec: 5,
+            hero: 10,
+        },
+        tree_hash: Some([7; 16]),
+    }
+}
+
+fn loadout(nodes: Vec<DecodedTraitNode>) -> DecodedTraitLoadout {
+    DecodedTraitLoadout {
+        version: 1,
+        spec_id: 253,
+        tree_hash: [0; 16],
+        nodes,
+    }
+}
+
+#[test]
+fn test_validate_legal_build() {
+    let build = loadout(vec![
+        purchased(None, None),
+        purchased(None, None),
+        purchased(None, Some(1)),
+        purchased(None, None),
+        purchased(None, None),
+    ]);
+
+    assert_eq!(validate_trait_loadout(&validation_tree(), &build), vec![]);
+}
+
+#[test]
+fn test_validate_spec_and_hash() {
+    let mut build = loadout(Vec::new());
+    build.tree_hash = [1; 16];
+    assert_eq!(
+        validate_trait_loadout(&validation_tree(), &build),
+        vec![LoadoutViolation::TreeHashMismatch]
+    );
+
+    build.spec_id = 254;
+    assert_eq!(
+        validate_trait_loadout(&validation_tree(), &build),
+        vec![LoadoutViolation::SpecMismatch {
+            expected: 253,
+            found: 254
+        }]
+    );
+}
+
+#[test]
+fn test_validate_ranks_and_choices() {
+    let build = loadout(vec![
+        purchased(None, None),
+        purchased(Some(3), None),
+        purchased(None, None),
+        skipped(),
+        skipped(),
+        skipped(),
+        purchased(None, None),
+    ]);
+
+    assert_eq!(
+        validate_trait_loadout(&validation_tree(), &build),
+        vec![
+            LoadoutViolation::RankOverflow {
+                node_id: 2,
+                ranks: 3,
+                max_ranks: 2
+            },
+            LoadoutViolation::InvalidChoice {
+                node_id: 3,
+                choice_index: None
+            },
+            LoadoutViolation::UnknownNode { index: 6 },
+        ]
+    );
+}
+
+#[test]
+fn test_validate_prerequisites_and_gates() {
+    // Node 2 is only partially ranked, so node 3 stays locked
+    let build = loadout(vec![
+        purchased(None, None),
+        purchased(Some(1), None),
+        purchased(None, Some(0)),
+    ]);
+    assert_eq!(
+        validate_trait_loadout(&validation_tree(), &build),
+        vec![LoadoutViolation::MissingPrerequisite { node_id: 3 }]
+    );
+
+    let build = loadout(vec![
+        purchased(None, None),
+        purchased(Some(1), None),
+        skipped(),
+        purchased(None, None),
+    ]);
+    assert_eq!(
+        validate_trait_loadout(&validation_tree(), &build),
+        vec![LoadoutViolation::GateNotMet {
+            node_id: 4,
+            required: 3,
+            spent: 2
+        }]
+    );
+}
+
+#[test]
+fn test_validate_point_limits_and_sub_trees() {
+    let mut tree = validation_tree();
+    tree.point_limits.spec = 4;
+
+    let build = loadout(vec![
+        purchased(None, None),
+        purchased(None, None),
+        purchased(None, Some(0)),
+        purchased(None, None),
+        purchased(None, None),
+        purchased(None, None),
+    ]);
+
+    assert_eq!(
+        validate_trait_loadout(&tree, &build),
+        vec![
+            LoadoutViolation::PointLimitExceeded {
+                currency: TraitCurrency::Spec,
+                spent: 5,
+                limit: 4
+            },
+            LoadoutViolation::MultipleSubTrees {
+                sub_tree_ids: vec![100, 200]
+            },
+            LoadoutViolation::InvalidSubTree { sub_tree_id: 200 },
+        ]
+    );
+}
+
+#[cfg(feature = "dbc")]
+#[test]
+fn test_validate_hash_against_transformed_tree() {
+    use std::path::Path;
+
+    use crate::parsers::{transform_trait_tree, DbcData};
+
+    let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/traits");
+    let dbc = DbcData::load_all(&data_dir).unwrap();
+    let tree = transform_trait_tree(&dbc, 253).unwrap();
+
+    assert_eq!(tree.all_node_ids, [101, 102, 103]);
+    assert_eq!(tree.tree_hash, Some(trait_tree_hash(&[101, 102, 103])));
+
+    let mut build = loadout(Vec::new());
+    build.tree_hash = trait_tree_hash(&[101, 102, 103]);
+    assert_eq!(validate_trait_loadout(&tree, &build), vec![]);
+
+    // A node added to the tree changes the hash
+    build.tree_hash = trait_tree_hash(&[101, 102, 103, 104]);
+    assert_eq!(
+        validate_trait_loadout(&tree, &build),
+        vec![LoadoutViolation::TreeHashMismatch]
+    );
+
+    // Zeroed hashes are still accepted
+    build.tree_hash = [0; 16];
+    assert_eq!(validate_trait_loadout(&tree, &build), vec![]);
+}
@@ -0,0 +1,327 @@
+//! Trait loadout validation
+//!
+//! Checks a decoded loadout against the spec's trait tree: tree hash, ranks,
+//! choice nodes, point limits per currency, gates, edge prerequisites and
+//! hero sub-tree selection. Every violation is collected rather than stopping
+//! at the first one, so a talent builder can highlight all of them.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+use thiserror::Error;
+
+use super::DecodedTraitLoadout;
+use crate::types::data::{TraitNode, TraitTreeFlat};
+
+/// Tree index values for categorizing nodes (see `transform_trait_tree`)
+const TREE_INDEX_CLASS: i32 = 1;
+const TREE_INDEX_SPEC: i32 = 2;
+const TREE_INDEX_HERO: i32 = 3;
+
+/// Node type for choice nodes
+const NODE_TYPE_CHOICE: i32 = 2;
+
+/// Talent point currency a node is bought with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
+#[serde(rename_all = "camelCase")]
+pub enum TraitCurrency {
+    Class,
+    Spec,
+    Hero,
+}
+
+impl TraitCurrency {
+    /// Currency for a node's tree index, if it costs points
+    pub const fn from_tree_index(tree_index: i32) -> Option<Self> {
+        match tree_index {
+            TREE_INDEX_CLASS => Some(Self::Class),
+            TREE_INDEX_SPEC => Some(Self::Spec),
+            TREE_INDEX_HERO => Some(Self::Hero),
+            _ => None,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Class => "class",
+            Self::Spec => "spec",
+            Self::Hero => "hero",
+        }
+    }
+}
+
+impl fmt::Display for TraitCurrency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A rule a loadout breaks.
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize)]
+#[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LoadoutViolation {
+    #[error("Loadout is for spec {found}, expected {expected}")]
+    SpecMismatch { expected: i32, found: i32 },
+
+    #[error("Loadout was built for a different version of the talent tree")]
+    TreeHashMismatch,
+
+    #[error("Node at position {index} is not in the talent tree")]
+    UnknownNode { index: usize },
+
+    #[error("Node {node_id} has {ranks} ranks but only {max_ranks} are available")]
+    #[serde(rename_all = "camelCase")]
+    RankOverflow {
+        node_id: i32,
+        ranks: i32,
+        max_ranks: i32,
+    },
+
+    #[error("Node {node_id} has an invalid choice")]
+    #[serde(rename_all = "camelCase")]
+    InvalidChoice {
+        node_id: i32,
+        choice_index: Option<u8>,
+    },
+
+    #[error("{spent} {currency} points spent, limit is {limit}")]
+    PointLimitExceeded {
+        currency: TraitCurrency,
+        spent: i32,
+        limit: i32,
+    },
+
+    #[error("Node {node_id} needs {required} points spent above it, only {spent} are")]
+    #[serde(rename_all = "camelCase")]
+    GateNotMet {
+        node_id: i32,
+        required: i32,
+        spent: i32,
+    },
+
+    #[error("Node {node_id} is not connected to a completed node")]
+    #[serde(rename_all = "camelCase")]
+    MissingPrerequisite { node_id: i32 },
+
+    #[error("Talents from more than one hero tree are selected")]
+    #[serde(rename_all = "camelCase")]
+    MultipleSubTrees { sub_tree_ids: Vec<i32> },
+
+    #[error("Hero tree {sub_tree_id} is not available for this spec")]
+    #[serde(rename_all = "camelCase")]
+    InvalidSubTree { sub_tree_id: i32 },
+}
+
+/// A selected node resolved against the tree
+struct Selected<'a> {
+    node: &'a TraitNode,
+    /// Points spent on the node (0 for granted nodes)
+    ranks: i32,
+    /// Granted or fully ranked, so it unlocks its children
+    complete: bool,
+}
+
+/// Validate a decoded loadout against a spec's trait tree.
+///
+/// Returns every violation found; an empty list means the build is legal.
+/// A zeroed tree hash in the loadout skips the hash check, as in game.
+pub fn validate_trait_loadout(
+    tree: &TraitTreeFlat,
+    loadout: &DecodedTraitLoadout,
+) -> Vec<LoadoutViolation> {
+    let mut violations = Vec::new();
+
+    if loadout.spec_id as i32 != tree.spec_id {
+        violations.push(LoadoutViolation::SpecMismatch {
+            expected: tree.spec_id,
+            found: loadout.spec_id as i32,
+        });
+        return violations;
+    }
+
+    if let Some(hash) = tree.tree_hash {
+        if loadout.tree_hash != [0; 16] && loadout.tree_hash != hash {
+            violations.push(LoadoutViolation::TreeHashMismatch);
+        }
+    }
+
+    let node_by_id: HashMap<i32, &TraitNode> = tree.nodes.iter().map(|n| (n.id, n)).collect();
+    let mut selected: HashMap<i32, Selected> = HashMap::new();
+
+    for (index, decoded) in loadout.nodes.iter().enumerate() {
+        if !decoded.selected {
+            continue;
+        }
+        let Some(node_id) = tree.all_node_ids.get(index) else {
+            violations.push(LoadoutViolation::UnknownNode { index });
+            continue;
+        };
+        // Nodes filtered out of the tree (e.g. hero sub-tree selection) have no cost
+        let Some(&node) = node_by_id.get(node_id) else {
+            continue;
+        };
+
+        let max_ranks = node.max_ranks.max(1);
+        let ranks = if decoded.purchased {
+            decoded.ranks_purchased.map_or(max_ranks, i32::from)
+        } else {
+            0
+        };
+        if ranks > max_ranks {
+            violations.push(LoadoutViolation::RankOverflow {
+                node_id: node.id,
+                ranks,
+                max_ranks,
+            });
+        }
+
+        if decoded.purchased && !valid_choice(node, decoded.choice_index) {
+            violations.push(LoadoutViolation::InvalidChoice {
+                node_id: node.id,
+                choice_index: decoded.choice_index,
+            });
+        }
+
+        selected.insert(
+            node.id,
+            Selected {
+                node,
+                ranks,
+                complete: !decoded.purchased || ranks >= max_ranks,
+            },
+        );
+    }
+
+    check_point_limits(tree, &selected, &mut violations);
+    check_gates(&selected, &mut violations);
+    check_prerequisites(tree, &node_by_id, &selected, &mut violations);
+    check_sub_trees(tree, &selected, &mut violations);
+
+    violations
+}
+
+/// Choice nodes need an entry picked; other nodes may only use the first entry
+fn valid_choice(node: &TraitNode, choice_index: Option<u8>) -> bool {
+    let is_choice = node.node_type == NODE_TYPE_CHOICE || node.entries.len() > 1;
+    match choice_index {
+        Some(index) => (index as usize) < node.entries.len(),
+        None => !is_choice,
+    }
+}
+
+fn check_point_limits(
+    tree: &TraitTreeFlat,
+    selected: &HashMap<i32, Selected>,
+    violations: &mut Vec<LoadoutViolation>,
+) {
+    let limits = &tree.point_limits;
+    for (currency, limit) in [
+        (TraitCurrency::Class, limits.class),
+        (TraitCurrency::Spec, limits.spec),
+        (TraitCurrency::Hero, limits.hero),
+    ] {
+        let spent: i32 = selected
+            .values()
+            .filter(|s| TraitCurrency::from_tree_index(s.node.tree_index) == Some(currency))
+            .map(|s| s.ranks)
+            .sum();
+        if spent > limit {
+            violations.push(LoadoutViolation::PointLimitExceeded {
+                currency,
+                spent,
+                limit,
+            });
+        }
+    }
+}
+
+/// Gated nodes need enough points in their tree on nodes above the gate
+fn check_gates(selected: &HashMap<i32, Selected>, violations: &mut Vec<LoadoutViolation>) {
+    let mut gated: Vec<_> = selected
+        .values()
+        .filter(|s| s.ranks > 0 && s.node.required_points > 0)
+        .collect();
+    gated.sort_by_key(|s| s.node.id);
+
+    for s in gated {
+        let spent: i32 = selected
+            .values()
+            .filter(|other| other.node.tree_index == s.node.tree_index)
+            .filter(|other| other.node.required_points < s.node.required_points)
+            .map(|other| other.ranks)
+            .sum();
+        if spent < s.node.required_points {
+            violations.push(LoadoutViolation::GateNotMet {
+                node_id: s.node.id,
+                required: s.node.required_points,
+                spent,
+            });
+        }
+    }
+}
+
+/// Purchased nodes with parents need at least one completed parent
+fn check_prerequisites(
+    tree: &TraitTreeFlat,
+    node_by_id: &HashMap<i32, &TraitNode>,
+    selected: &HashMap<i32, Selected>,
+    violations: &mut Vec<LoadoutViolation>,
+) {
+    let mut parents: HashMap<i32, Vec<i32>> = HashMap::new();
+    for edge in &tree.edges {
+        if node_by_id.contains_key(&edge.from_node_id) {
+            parents
+                .entry(edge.to_node_id)
+                .or_default()
+                .push(edge.from_node_id);
+        }
+    }
+
+    let mut missing: Vec<i32> = selected
+        .values()
+        .filter(|s| s.ranks > 0)
+        .filter_map(|s| {
+            let parents = parents.get(&s.node.id)?;
+            let unlocked = parents
+                .iter()
+                .any(|id| selected.get(id).is_some_and(|p| p.complete));
+            (!unlocked).then_some(s.node.id)
+        })
+        .collect();
+    missing.sort_unstable();
+
+    violations.extend(
+        missing
+            .into_iter()
+            .map(|node_id| LoadoutViolation::MissingPrerequisite { node_id }),
+    );
+}
+
+/// Hero talents must all come from one sub-tree available to the spec
+fn check_sub_trees(
+    tree: &TraitTreeFlat,
+    selected: &HashMap<i32, Selected>,
+    violations: &mut Vec<LoadoutViolation>,
+) {
+    let sub_tree_ids: BTreeSet<i32> = selected
+        .values()
+        .filter(|s| s.ranks > 0 && s.node.sub_tree_id > 0)
+        .map(|s| s.node.sub_tree_id)
+        .collect();
+
+    if sub_tree_ids.len() > 1 {
+        violations.push(LoadoutViolation::MultipleSubTrees {
+            sub_tree_ids: sub_tree_ids.iter().copied().collect(),
+        });
+    }
+
+    violations.extend(
+        sub_tree_ids
+            .into_iter()
+            .filter(|id| !tree.sub_trees.iter().any(|s| s.id == *id))
+            .map(|sub_tree_id| LoadoutViolation::InvalidSubTree { sub_tree_id }),
+    );
+}
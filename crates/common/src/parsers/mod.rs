@@ -2,7 +2,7 @@
 //!
 //! This module provides parsers for:
 //! - SimC profile strings (character data, equipment, talents)
-//! - DBC CSV files (WoW database client tables)
+//! - DBC CSV and native DB2 files (WoW database client tables)
 //! - Talent loadout strings (base64-encoded talent selections)
 //! - Spell description strings (tooltip template language)
 
@@ -28,19 +28,20 @@ pub use simc::{
 pub use dbc::DbcData;
 
 // Error types
-#[cfg(feature = "dbc")]
-pub use errors::DbcError;
 pub use errors::TraitError;
 pub use errors::TransformError;
+#[cfg(feature = "dbc")]
+pub use errors::{Db2Error, DbcError};
 
 // DBC → Flat transformation (requires dbc feature for DbcData)
 #[cfg(feature = "dbc")]
 pub use transform::{
     transform_all_auras, transform_all_classes, transform_all_curve_points, transform_all_curves,
-    transform_all_global_colors, transform_all_global_strings, transform_all_item_bonuses,
-    transform_all_items, transform_all_rand_prop_points, transform_all_specs, transform_all_spells,
-    transform_all_trait_trees, transform_aura, transform_class, transform_global_color,
-    transform_global_string, transform_item, transform_spec, transform_spell, transform_trait_tree,
+    transform_all_expected_stats, transform_all_global_colors, transform_all_global_strings,
+    transform_all_item_bonuses, transform_all_items, transform_all_rand_prop_points,
+    transform_all_specs, transform_all_spells, transform_all_trait_trees, transform_aura,
+    transform_class, transform_global_color, transform_global_string, transform_item,
+    transform_item_scaling, transform_spec, transform_spell, transform_trait_tree,
 };
 
 // Always export SpellKnowledgeContext (doesn't need dbc)
@@ -49,7 +50,8 @@ pub use transform::SpellKnowledgeContext;
 // Talent loadout encoding/decoding
 pub use loadout::{
     apply_decoded_traits, decode_trait_loadout, encode_minimal_loadout, encode_trait_loadout,
-    DecodedTraitLoadout, DecodedTraitNode,
+    trait_tree_hash, validate_trait_loadout, DecodedTraitLoadout, DecodedTraitNode,
+    LoadoutViolation, TraitCurrency,
 };
 
 // Spell description parsing
@@ -61,7 +63,8 @@ pub use spell_desc::{
 
 // Item scaling functions
 pub use scaling::{
-    apply_item_bonuses, get_bonus_description, get_stat_budget, get_stat_name, interpolate_curve,
+    apply_item_bonuses, apply_item_bonuses_for_slot, get_bonus_description, get_item_armor,
+    get_stat_budget, get_stat_name, interpolate_curve, slot_budget_index,
 };
 
 // Crypto (node authentication)
@@ -0,0 +1,80 @@
+//! Base armor of cloth, leather, mail and plate items
+//!
+//! `armor = round(total[ilvl][type] * quality_mod[ilvl][quality] * location[inv_type][type])`
+
+use crate::types::data::ItemScalingData;
+
+/// Item class ID for armor
+const ITEM_CLASS_ARMOR: i32 = 4;
+
+/// Get the base armor of an item at an item level.
+///
+/// # Arguments
+/// * `scaling_data` - The scaling data bundle
+/// * `item_level` - The item level after bonuses
+/// * `quality` - The item quality (0-6)
+/// * `class_id` / `subclass_id` - Item class; only cloth (1) to plate (4) armor has base armor
+/// * `inventory_type` - The inventory type, which picks the slot's share of the set
+///
+/// # Returns
+/// The armor value, or None for items without base armor or missing table rows
+pub fn get_item_armor(
+    scaling_data: &ItemScalingData,
+    item_level: i32,
+    quality: i32,
+    class_id: i32,
+    subclass_id: i32,
+    inventory_type: i32,
+) -> Option<i32> {
+    if class_id != ITEM_CLASS_ARMOR || !(1..=4).contains(&subclass_id) {
+        return None;
+    }
+    let armor_type = (subclass_id - 1) as usize;
+    let tables = &scaling_data.armor;
+
+    let total = tables.total.get(&item_level)?[armor_type];
+    let quality_mod = *tables
+        .quality
+        .get(&item_level)?
+        .get(usize::try_from(quality).ok()?)?;
+    let location = tables.location.get(&inventory_type)?[armor_type];
+
+    Some((total * quality_mod * location).round() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scaling() -> ItemScalingData {
+        let mut scaling = ItemScalingData::default();
+        scaling
+            .armor
+            .total
+            .insert(600, [1000.0, 2000.0, 3000.0, 4000.0]);
+        scaling
+            .armor
+            .quality
+            .insert(600, [1.0, 1.0, 1.0, 1.0, 1.1, 1.0, 1.0]);
+        scaling.armor.location.insert(5, [0.16, 0.16, 0.16, 0.16]);
+        scaling
+    }
+
+    #[test]
+    fn test_plate_chest_armor() {
+        // 4000 * 1.1 * 0.16
+        assert_eq!(get_item_armor(&scaling(), 600, 4, 4, 4, 5), Some(704));
+        // Leather uses its own column
+        assert_eq!(get_item_armor(&scaling(), 600, 4, 4, 2, 5), Some(352));
+    }
+
+    #[test]
+    fn test_no_base_armor() {
+        // Weapons, shields and misc armor
+        assert_eq!(get_item_armor(&scaling(), 600, 4, 2, 4, 5), None);
+        assert_eq!(get_item_armor(&scaling(), 600, 4, 4, 6, 14), None);
+        assert_eq!(get_item_armor(&scaling(), 600, 4, 4, 0, 11), None);
+        // Unknown item level
+        assert_eq!(get_item_armor(&scaling(), 601, 4, 4, 4, 5), None);
+    }
+}
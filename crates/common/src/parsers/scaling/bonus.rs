@@ -48,6 +48,30 @@ pub fn apply_item_bonuses(
     bonus_ids: &[i32],
     scaling_data: &ItemScalingData,
     player_level: Option<i32>,
+) -> ScaledItemStats {
+    apply_item_bonuses_for_slot(
+        base_item_level,
+        base_stats,
+        quality,
+        bonus_ids,
+        scaling_data,
+        player_level,
+        0,
+    )
+}
+
+/// Apply item bonuses using the stat budget of an equipment slot.
+///
+/// Same as [`apply_item_bonuses`], with `slot_index` picking the budget
+/// column (see [`slot_budget_index`](super::slot_budget_index)).
+pub fn apply_item_bonuses_for_slot(
+    base_item_level: i32,
+    base_stats: &[ItemStat],
+    quality: i32,
+    bonus_ids: &[i32],
+    scaling_data: &ItemScalingData,
+    player_level: Option<i32>,
+    slot_index: usize,
 ) -> ScaledItemStats {
     let player_level = player_level.unwrap_or(80);
     let quality = ItemQuality::from(quality);
@@ -105,8 +129,7 @@ pub fn apply_item_bonuses(
     }
 
     // Get stat budget for the final item level
-    // Slot index 0 is typically used for most items
-    let budget = get_stat_budget(scaling_data, item_level, quality, 0).unwrap_or(0.0);
+    let budget = get_stat_budget(scaling_data, item_level, quality, slot_index).unwrap_or(0.0);
 
     // Second pass: calculate stats
     let mut stats_map: std::collections::HashMap<i32, f64> = std::collections::HashMap::new();
@@ -3,10 +3,12 @@
 //! Pure functions to compute scaled item stats from bonus IDs.
 //! These can be called from Rust or via WASM from the web interface.
 
+mod armor;
 mod bonus;
 mod curve;
 mod stats;
 
-pub use bonus::{apply_item_bonuses, get_bonus_description};
+pub use armor::get_item_armor;
+pub use bonus::{apply_item_bonuses, apply_item_bonuses_for_slot, get_bonus_description};
 pub use curve::interpolate_curve;
-pub use stats::{get_stat_budget, get_stat_name};
+pub use stats::{get_stat_budget, get_stat_name, slot_budget_index};
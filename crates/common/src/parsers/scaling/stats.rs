@@ -71,6 +71,25 @@ pub fn get_stat_budget_from_rpp(
     Some(budget)
 }
 
+/// Get the stat budget column for an inventory type.
+///
+/// Larger slots (head, chest, legs, two-handers) use column 0; smaller
+/// armor slots and trinkets 1; neck, wrists, back and rings 2; one-handers
+/// and off-hands 3.
+pub fn slot_budget_index(inventory_type: i32) -> usize {
+    match inventory_type {
+        // Head, chest, legs, robe, two-hand, ranged
+        1 | 5 | 7 | 15 | 17 | 20 | 26 => 0,
+        // Shoulders, waist, feet, hands, trinket
+        3 | 6 | 8 | 10 | 12 => 1,
+        // Neck, wrists, finger, back
+        2 | 9 | 11 | 16 => 2,
+        // One-hand, shield, main/off hand, held in off-hand
+        13 | 14 | 21 | 22 | 23 => 3,
+        _ => 0,
+    }
+}
+
 /// Get a human-readable name for a stat type ID.
 ///
 /// These IDs come from WoW's item stat types.
@@ -155,6 +174,14 @@ mod tests {
         assert_eq!(get_stat_name(49), "Mastery");
     }
 
+    #[test]
+    fn test_slot_budget_index() {
+        assert_eq!(slot_budget_index(5), 0); // Chest
+        assert_eq!(slot_budget_index(12), 1); // Trinket
+        assert_eq!(slot_budget_index(11), 2); // Finger
+        assert_eq!(slot_budget_index(13), 3); // One-hand
+    }
+
     #[test]
     fn test_stat_categories() {
         assert!(is_primary_stat(3));
@@ -121,6 +121,7 @@ pub fn transform_item(dbc: &DbcData, item_id: i32) -> Result<ItemDataFlat, Trans
                             charges: effect.Charges,
                             cooldown: effect.CoolDownMSec,
                             category_cooldown: effect.CategoryCoolDownMSec,
+                            category_id: effect.SpellCategoryID,
                         })
                 })
                 .collect()
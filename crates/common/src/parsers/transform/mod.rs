@@ -29,8 +29,9 @@ pub use item::{transform_all_items, transform_item};
 pub use r#trait::transform_trait_tree;
 #[cfg(feature = "dbc")]
 pub use scaling::{
-    transform_all_curve_points, transform_all_curves, transform_all_item_bonuses,
-    transform_all_rand_prop_points,
+    transform_all_curve_points, transform_all_curves, transform_all_expected_stats,
+    transform_all_item_bonuses, transform_all_rand_prop_points, transform_item_armor,
+    transform_item_scaling,
 };
 #[cfg(feature = "dbc")]
 pub use spec::{transform_all_specs, transform_spec};
@@ -2,7 +2,10 @@
 
 #[cfg(feature = "dbc")]
 use super::super::dbc::DbcData;
-use crate::types::data::{CurveFlat, CurvePointFlat, ItemBonusFlat, RandPropPointsFlat};
+use crate::types::data::{
+    CurveFlat, CurvePointFlat, ExpectedStatFlat, ItemArmorData, ItemBonusFlat, ItemScalingData,
+    RandPropPointsFlat,
+};
 
 /// Transform all item bonuses from DBC data
 pub fn transform_all_item_bonuses(dbc: &DbcData) -> Vec<ItemBonusFlat> {
@@ -51,6 +54,20 @@ pub fn transform_all_curve_points(dbc: &DbcData) -> Vec<CurvePointFlat> {
         .collect()
 }
 
+/// Transform all expected stats from DBC data
+pub fn transform_all_expected_stats(dbc: &DbcData) -> Vec<ExpectedStatFlat> {
+    dbc.expected_stat
+        .values()
+        .map(|row| ExpectedStatFlat {
+            id: row.ID,
+            expansion_id: row.ExpansionID,
+            level: row.Lvl,
+            creature_armor: row.CreatureArmor,
+            armor_constant: row.ArmorConstant,
+        })
+        .collect()
+}
+
 /// Transform all random property points from DBC data
 pub fn transform_all_rand_prop_points(dbc: &DbcData) -> Vec<RandPropPointsFlat> {
     dbc.rand_prop_points
@@ -94,3 +111,73 @@ pub fn transform_all_rand_prop_points(dbc: &DbcData) -> Vec<RandPropPointsFlat>
         })
         .collect()
 }
+
+/// Transform the base armor tables from DBC data
+pub fn transform_item_armor(dbc: &DbcData) -> ItemArmorData {
+    ItemArmorData {
+        quality: dbc
+            .item_armor_quality
+            .values()
+            .map(|row| {
+                let mods = [
+                    row.Qualitymod_0,
+                    row.Qualitymod_1,
+                    row.Qualitymod_2,
+                    row.Qualitymod_3,
+                    row.Qualitymod_4,
+                    row.Qualitymod_5,
+                    row.Qualitymod_6,
+                ];
+                (row.ID, mods)
+            })
+            .collect(),
+        total: dbc
+            .item_armor_total
+            .values()
+            .map(|row| (row.ItemLevel, [row.Cloth, row.Leather, row.Mail, row.Plate]))
+            .collect(),
+        location: dbc
+            .armor_location
+            .values()
+            .map(|row| {
+                let mods = [
+                    row.Clothmodifier,
+                    row.Leathermodifier,
+                    row.Chainmodifier,
+                    row.Platemodifier,
+                ];
+                (row.ID, mods)
+            })
+            .collect(),
+    }
+}
+
+/// Bundle the item bonus, curve, budget and armor tables, indexed for scaling
+pub fn transform_item_scaling(dbc: &DbcData) -> ItemScalingData {
+    let mut scaling = ItemScalingData::default();
+    for bonus in transform_all_item_bonuses(dbc) {
+        scaling
+            .bonuses
+            .entry(bonus.parent_item_bonus_list_id)
+            .or_default()
+            .push(bonus);
+    }
+    for curve in transform_all_curves(dbc) {
+        scaling.curves.insert(curve.id, curve);
+    }
+    for point in transform_all_curve_points(dbc) {
+        scaling
+            .curve_points
+            .entry(point.curve_id)
+            .or_default()
+            .push(point);
+    }
+    for points in scaling.curve_points.values_mut() {
+        points.sort_by_key(|p| p.order_index);
+    }
+    for rpp in transform_all_rand_prop_points(dbc) {
+        scaling.rand_prop_points.insert(rpp.id, rpp);
+    }
+    scaling.armor = transform_item_armor(dbc);
+    scaling
+}
@@ -20,6 +20,7 @@ use super::super::dbc::rows::{
 #[cfg(feature = "dbc")]
 use super::super::dbc::DbcData;
 use super::super::errors::TransformError;
+use super::super::loadout::trait_tree_hash;
 use crate::types::data::{
     PointLimits, TraitEdge, TraitNode, TraitNodeEntry, TraitSubTree, TraitTreeFlat,
 };
@@ -142,6 +143,7 @@ pub fn transform_trait_tree(dbc: &DbcData, spec_id: i32) -> Result<TraitTreeFlat
         .map(|m| m.TraitNodeGroupID)
         .collect();
     let group_to_tree_index = build_group_tree_index_map(dbc, &group_ids);
+    let group_gates = build_group_gate_map(dbc, &group_ids);
 
     // Step 11: Build node entries mapping with sorted selections
     let node_x_entries = build_node_entries_map(dbc, &tree_nodes);
@@ -159,6 +161,7 @@ pub fn transform_trait_tree(dbc: &DbcData, spec_id: i32) -> Result<TraitTreeFlat
                 &hero_tree_offsets,
                 &node_group_memberships,
                 &group_to_tree_index,
+                &group_gates,
                 &order_index_map,
             )
         })
@@ -187,6 +190,7 @@ pub fn transform_trait_tree(dbc: &DbcData, spec_id: i32) -> Result<TraitTreeFlat
     // All node IDs sorted for loadout string parsing
     let mut all_node_ids: Vec<i32> = tree_nodes.iter().map(|n| n.ID).collect();
     all_node_ids.sort();
+    let tree_hash = trait_tree_hash(&all_node_ids);
 
     Ok(TraitTreeFlat {
         spec_id,
@@ -198,6 +202,7 @@ pub fn transform_trait_tree(dbc: &DbcData, spec_id: i32) -> Result<TraitTreeFlat
         edges,
         sub_trees,
         point_limits,
+        tree_hash: Some(tree_hash),
     })
 }
 
@@ -284,6 +289,25 @@ fn build_group_tree_index_map(dbc: &DbcData, group_ids: &HashSet<i32>) -> HashMa
     map
 }
 
+/// Build a map from group ID to the points that must be spent before it unlocks.
+/// Gates are conditions on a node group with a spent-amount requirement.
+fn build_group_gate_map(dbc: &DbcData, group_ids: &HashSet<i32>) -> HashMap<i32, i32> {
+    group_ids
+        .iter()
+        .filter_map(|&group_id| {
+            let required = dbc
+                .trait_node_group_x_trait_cond
+                .get(&group_id)?
+                .iter()
+                .filter_map(|cond_ref| dbc.trait_cond.get(&cond_ref.TraitCondID))
+                .map(|cond| cond.SpentAmountRequired)
+                .max()
+                .filter(|&required| required > 0)?;
+            Some((group_id, required))
+        })
+        .collect()
+}
+
 /// Build a map from node ID to sorted entry references.
 /// Entries are sorted by their selection index for choice nodes.
 fn build_node_entries_map(
@@ -313,6 +337,7 @@ fn build_node_entries_map(
 // Helper Functions - Node Building
 
 /// Build a TraitNode from raw DBC data.
+#[allow(clippy::too_many_arguments)]
 fn build_talent_node(
     dbc: &DbcData,
     node: &TraitNodeRow,
@@ -320,6 +345,7 @@ fn build_talent_node(
     hero_tree_offsets: &HashMap<i32, (i32, i32)>,
     node_group_memberships: &[TraitNodeGroupXTraitNodeRow],
     group_to_tree_index: &HashMap<i32, i32>,
+    group_gates: &HashMap<i32, i32>,
     order_index_map: &HashMap<i32, i32>,
 ) -> Option<TraitNode> {
     let x_entries = node_x_entries.get(&node.ID)?;
@@ -359,6 +385,14 @@ fn build_talent_node(
     // Determine tree index based on subtree or group membership
     let tree_index = determine_tree_index(node, node_group_memberships, group_to_tree_index);
 
+    // Highest gate among the node's groups
+    let required_points = node_group_memberships
+        .iter()
+        .filter(|m| m.TraitNodeID == node.ID)
+        .filter_map(|m| group_gates.get(&m.TraitNodeGroupID).copied())
+        .max()
+        .unwrap_or(0);
+
     Some(TraitNode {
         id: node.ID,
         pos_x,
@@ -369,6 +403,7 @@ fn build_talent_node(
         order_index: order_index_map.get(&node.ID).copied().unwrap_or(-1),
         sub_tree_id: node.TraitSubTreeID,
         entries,
+        required_points,
     })
 }
 
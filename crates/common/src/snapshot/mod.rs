@@ -0,0 +1,145 @@
+//! Pre-built game data snapshots.
+//!
+//! A snapshot holds the transformed spells, auras, items and trait trees (plus
+//! the item scaling and expected stat tables) in one versioned, checksummed
+//! file. Records are stored as rkyv archives, so readers memory-map the file
+//! and read fields in place; opening one takes milliseconds instead of parsing
+//! and transforming every CSV table.
+//!
+//! Layout (little-endian):
+//!
+//! ```text
+//! header     magic "WLSNAP\0\0", format version, section count, CRC32 of
+//!            everything after the header, patch label length
+//! patch      UTF-8 label, padded to 8 bytes
+//! sections   kind, record count, index offset, data offset, data length
+//! index      per section: (id, length, offset) sorted by id
+//! data       per section: rkyv records, each aligned to 16 bytes
+//! ```
+//!
+//! Opening a snapshot checks the header and index; the checksum covers every
+//! byte and is only checked by [`DataSnapshot::verify`].
+
+mod reader;
+mod writer;
+
+pub use reader::DataSnapshot;
+pub use writer::SnapshotData;
+
+use thiserror::Error;
+
+/// Identifies a snapshot file
+pub(crate) const MAGIC: &[u8; 8] = b"WLSNAP\0\0";
+
+/// Bumped whenever the layout or the encoded types change
+pub const FORMAT_VERSION: u32 = 2;
+
+pub(crate) const HEADER_SIZE: usize = 24;
+pub(crate) const SECTION_SIZE: usize = 32;
+pub(crate) const INDEX_ENTRY_SIZE: usize = 16;
+
+/// Alignment of every record, as rkyv needs to read it in place
+pub(crate) const RECORD_ALIGN: usize = 16;
+
+/// Kind of records stored in a section
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SnapshotSection {
+    /// `SpellDataFlat` by spell ID
+    Spells,
+    /// `AuraDataFlat` by spell ID
+    Auras,
+    /// `ItemDataFlat` by item ID
+    Items,
+    /// `TraitTreeFlat` by spec ID
+    TraitTrees,
+    /// One `ItemScalingData` record
+    ItemScaling,
+    /// One `Vec<ExpectedStatFlat>` record
+    ExpectedStats,
+}
+
+impl SnapshotSection {
+    pub(crate) fn kind(self) -> u32 {
+        match self {
+            Self::Spells => 1,
+            Self::Auras => 2,
+            Self::Items => 3,
+            Self::TraitTrees => 4,
+            Self::ItemScaling => 5,
+            Self::ExpectedStats => 6,
+        }
+    }
+
+    pub(crate) fn from_kind(kind: u32) -> Option<Self> {
+        Some(match kind {
+            1 => Self::Spells,
+            2 => Self::Auras,
+            3 => Self::Items,
+            4 => Self::TraitTrees,
+            5 => Self::ItemScaling,
+            6 => Self::ExpectedStats,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for SnapshotSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spells => write!(f, "spells"),
+            Self::Auras => write!(f, "auras"),
+            Self::Items => write!(f, "items"),
+            Self::TraitTrees => write!(f, "trait_trees"),
+            Self::ItemScaling => write!(f, "item_scaling"),
+            Self::ExpectedStats => write!(f, "expected_stats"),
+        }
+    }
+}
+
+/// Errors that can occur when writing or reading snapshots
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("IO error on '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Not a game data snapshot")]
+    InvalidMagic,
+
+    #[error("Snapshot format version {found} is not supported (expected {FORMAT_VERSION})")]
+    UnsupportedVersion { found: u32 },
+
+    #[error("Snapshot checksum mismatch (stored {stored:08X}, computed {computed:08X})")]
+    ChecksumMismatch { stored: u32, computed: u32 },
+
+    #[error("Snapshot is truncated at offset {0}")]
+    Truncated(usize),
+
+    #[error("Snapshot {section} index is corrupt at entry {entry}")]
+    CorruptIndex {
+        section: SnapshotSection,
+        entry: usize,
+    },
+
+    #[error("Failed to encode {section} record {id}: {source}")]
+    Encode {
+        section: SnapshotSection,
+        id: i32,
+        #[source]
+        source: rkyv::rancor::Error,
+    },
+
+    #[error("Failed to decode {section} record {id}: {source}")]
+    Decode {
+        section: SnapshotSection,
+        id: i32,
+        #[source]
+        source: rkyv::rancor::Error,
+    },
+}
+
+#[cfg(test)]
+mod tests;
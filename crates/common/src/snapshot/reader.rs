@@ -0,0 +1,316 @@
+//! Snapshot reader
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::ops::Deref;
+use std::path::Path;
+
+use memmap2::Mmap;
+use rkyv::api::high::{HighDeserializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::rancor;
+use rkyv::util::AlignedVec;
+use rkyv::Archive;
+
+use super::{
+    SnapshotError, SnapshotSection, FORMAT_VERSION, HEADER_SIZE, INDEX_ENTRY_SIZE, MAGIC,
+    RECORD_ALIGN, SECTION_SIZE,
+};
+use crate::types::data::{
+    AuraDataFlat, ExpectedStatFlat, ItemDataFlat, ItemScalingData, SpellDataFlat, TraitTreeFlat,
+};
+
+/// Backing storage of an open snapshot
+enum Bytes {
+    Mapped(Mmap),
+    Owned(AlignedVec<RECORD_ALIGN>),
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mapped(map) => map,
+            Self::Owned(bytes) => bytes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SectionInfo {
+    count: usize,
+    index_offset: usize,
+    data_offset: usize,
+    data_len: usize,
+}
+
+/// An open snapshot.
+///
+/// Only the header, section table and index are read up front; records are
+/// found by binary search over the mapped index and read in place.
+pub struct DataSnapshot {
+    bytes: Bytes,
+    patch: String,
+    checksum: u32,
+    sections: HashMap<SnapshotSection, SectionInfo>,
+}
+
+impl DataSnapshot {
+    /// Memory-map a snapshot file and validate its header and index.
+    ///
+    /// The file must not be modified while it's open. Call [`Self::verify`]
+    /// to check the records against the checksum as well.
+    pub fn open(path: &Path) -> Result<Self, SnapshotError> {
+        let io_error = |e| SnapshotError::Io {
+            path: path.display().to_string(),
+            source: e,
+        };
+        let file = File::open(path).map_err(io_error)?;
+        // SAFETY: snapshots are written once and replaced, never edited in place
+        let map = unsafe { Mmap::map(&file) }.map_err(io_error)?;
+        Self::parse(Bytes::Mapped(map))
+    }
+
+    /// Validate the header and index of a snapshot held in memory.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, SnapshotError> {
+        // Records are read in place, which needs them aligned
+        let mut aligned = AlignedVec::with_capacity(bytes.len());
+        aligned.extend_from_slice(&bytes);
+        Self::parse(Bytes::Owned(aligned))
+    }
+
+    fn parse(bytes: Bytes) -> Result<Self, SnapshotError> {
+        let header = bytes
+            .get(..HEADER_SIZE)
+            .ok_or(SnapshotError::Truncated(bytes.len()))?;
+        if &header[..8] != MAGIC {
+            return Err(SnapshotError::InvalidMagic);
+        }
+        let version = u32_at(header, 8);
+        if version != FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion { found: version });
+        }
+        let section_count = u32_at(header, 12) as usize;
+        let checksum = u32_at(header, 16);
+        let patch_len = u32_at(header, 20) as usize;
+
+        let patch = slice(&bytes, HEADER_SIZE, patch_len)?;
+        let patch = String::from_utf8_lossy(patch).into_owned();
+
+        let table_start = HEADER_SIZE + patch_len.div_ceil(8) * 8;
+        let table = slice(&bytes, table_start, size(section_count, SECTION_SIZE)?)?;
+        let mut sections = HashMap::new();
+        for entry in table.chunks_exact(SECTION_SIZE) {
+            let info = SectionInfo {
+                count: u32_at(entry, 4) as usize,
+                index_offset: u64_at(entry, 8) as usize,
+                data_offset: u64_at(entry, 16) as usize,
+                data_len: u64_at(entry, 24) as usize,
+            };
+            slice(
+                &bytes,
+                info.index_offset,
+                size(info.count, INDEX_ENTRY_SIZE)?,
+            )?;
+            slice(&bytes, info.data_offset, info.data_len)?;
+            // Sections this build doesn't know are skipped
+            if let Some(section) = SnapshotSection::from_kind(u32_at(entry, 0)) {
+                sections.insert(section, info);
+            }
+        }
+
+        let snapshot = Self {
+            bytes,
+            patch,
+            checksum,
+            sections,
+        };
+        for (&section, info) in &snapshot.sections {
+            snapshot.check_index(section, info)?;
+        }
+        Ok(snapshot)
+    }
+
+    /// Check the whole file against its checksum.
+    ///
+    /// Reads every byte, so unlike opening it's proportional to the file size.
+    pub fn verify(&self) -> Result<(), SnapshotError> {
+        let body = self
+            .bytes
+            .get(HEADER_SIZE..)
+            .ok_or(SnapshotError::Truncated(self.bytes.len()))?;
+        let computed = crc32fast::hash(body);
+        if computed != self.checksum {
+            return Err(SnapshotError::ChecksumMismatch {
+                stored: self.checksum,
+                computed,
+            });
+        }
+        Ok(())
+    }
+
+    /// Index entries must be sorted by ID and point at aligned records inside
+    /// the section's data
+    fn check_index(
+        &self,
+        section: SnapshotSection,
+        info: &SectionInfo,
+    ) -> Result<(), SnapshotError> {
+        let mut previous = None;
+        for i in 0..info.count {
+            let corrupt = SnapshotError::CorruptIndex { section, entry: i };
+            let (id, len, offset) = self.entry(info, i).ok_or(corrupt)?;
+            let in_bounds = offset
+                .checked_add(len)
+                .is_some_and(|end| end <= info.data_len);
+            let aligned = info.data_offset.is_multiple_of(RECORD_ALIGN)
+                && offset.is_multiple_of(RECORD_ALIGN);
+            if !in_bounds || !aligned || previous.is_some_and(|p| p >= id) {
+                return Err(SnapshotError::CorruptIndex { section, entry: i });
+            }
+            previous = Some(id);
+        }
+        Ok(())
+    }
+
+    /// Game patch the snapshot was built from
+    pub fn patch(&self) -> &str {
+        &self.patch
+    }
+
+    /// Number of records in a section
+    pub fn count(&self, section: SnapshotSection) -> usize {
+        self.sections.get(&section).map_or(0, |info| info.count)
+    }
+
+    /// IDs of the records in a section, ascending
+    pub fn ids(&self, section: SnapshotSection) -> impl Iterator<Item = i32> + '_ {
+        let info = self.sections.get(&section).copied();
+        info.into_iter()
+            .flat_map(move |info| (0..info.count).filter_map(move |i| self.entry(&info, i)))
+            .map(|(id, _, _)| id)
+    }
+
+    /// Archived bytes of a record, without validating them
+    pub fn record(&self, section: SnapshotSection, id: i32) -> Option<&[u8]> {
+        let info = self.sections.get(&section)?;
+        let (mut low, mut high) = (0, info.count);
+        while low < high {
+            let mid = (low + high) / 2;
+            let (entry_id, len, offset) = self.entry(info, mid)?;
+            match entry_id.cmp(&id) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => {
+                    let start = info.data_offset.checked_add(offset)?;
+                    return self.bytes.get(start..start.checked_add(len)?);
+                }
+            }
+        }
+        None
+    }
+
+    /// Read one record in place.
+    ///
+    /// Only the record's own bytes are validated, so fields can be read
+    /// without decoding the record or checking the rest of the file.
+    pub fn archived<T>(
+        &self,
+        section: SnapshotSection,
+        id: i32,
+    ) -> Result<Option<&T::Archived>, SnapshotError>
+    where
+        T: Archive,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, rancor::Error>>,
+    {
+        self.record(section, id)
+            .map(|bytes| {
+                rkyv::access::<T::Archived, rancor::Error>(bytes).map_err(|e| {
+                    SnapshotError::Decode {
+                        section,
+                        id,
+                        source: e,
+                    }
+                })
+            })
+            .transpose()
+    }
+
+    /// Decode one record.
+    pub fn get<T>(&self, section: SnapshotSection, id: i32) -> Result<Option<T>, SnapshotError>
+    where
+        T: Archive,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, rancor::Error>>
+            + rkyv::Deserialize<T, HighDeserializer<rancor::Error>>,
+    {
+        self.archived::<T>(section, id)?
+            .map(|archived| {
+                rkyv::deserialize::<T, rancor::Error>(archived).map_err(|e| SnapshotError::Decode {
+                    section,
+                    id,
+                    source: e,
+                })
+            })
+            .transpose()
+    }
+
+    pub fn spell(&self, id: i32) -> Result<Option<SpellDataFlat>, SnapshotError> {
+        self.get(SnapshotSection::Spells, id)
+    }
+
+    pub fn aura(&self, spell_id: i32) -> Result<Option<AuraDataFlat>, SnapshotError> {
+        self.get(SnapshotSection::Auras, spell_id)
+    }
+
+    pub fn item(&self, id: i32) -> Result<Option<ItemDataFlat>, SnapshotError> {
+        self.get(SnapshotSection::Items, id)
+    }
+
+    pub fn trait_tree(&self, spec_id: i32) -> Result<Option<TraitTreeFlat>, SnapshotError> {
+        self.get(SnapshotSection::TraitTrees, spec_id)
+    }
+
+    pub fn item_scaling(&self) -> Result<Option<ItemScalingData>, SnapshotError> {
+        self.get(SnapshotSection::ItemScaling, 0)
+    }
+
+    pub fn expected_stats(&self) -> Result<Option<Vec<ExpectedStatFlat>>, SnapshotError> {
+        self.get(SnapshotSection::ExpectedStats, 0)
+    }
+
+    /// (id, length, offset into the section's data) of the `i`th index entry
+    fn entry(&self, info: &SectionInfo, i: usize) -> Option<(i32, usize, usize)> {
+        let at = i
+            .checked_mul(INDEX_ENTRY_SIZE)?
+            .checked_add(info.index_offset)?;
+        let entry = self.bytes.get(at..at.checked_add(INDEX_ENTRY_SIZE)?)?;
+        Some((
+            u32_at(entry, 0) as i32,
+            u32_at(entry, 4) as usize,
+            u64_at(entry, 8) as usize,
+        ))
+    }
+}
+
+fn slice(bytes: &[u8], start: usize, len: usize) -> Result<&[u8], SnapshotError> {
+    let end = start
+        .checked_add(len)
+        .ok_or(SnapshotError::Truncated(usize::MAX))?;
+    bytes.get(start..end).ok_or(SnapshotError::Truncated(end))
+}
+
+/// Size of `count` entries, failing like a truncated file when it overflows
+fn size(count: usize, entry_size: usize) -> Result<usize, SnapshotError> {
+    count
+        .checked_mul(entry_size)
+        .ok_or(SnapshotError::Truncated(usize::MAX))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
@@ -0,0 +1,157 @@
+//! Unit tests for game data snapshots
+
+use super::*;
+use crate::types::data::{AuraDataFlat, ExpectedStatFlat, ItemDataFlat, SpellDataFlat};
+
+fn spell(id: i32, name: &str) -> SpellDataFlat {
+    SpellDataFlat {
+        id,
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+fn sample() -> SnapshotData {
+    let mut data = SnapshotData {
+        spells: vec![spell(53351, "Kill Shot"), spell(34026, "Kill Command")],
+        auras: vec![AuraDataFlat {
+            spell_id: 19574,
+            ..Default::default()
+        }],
+        items: vec![ItemDataFlat {
+            id: 207170,
+            name: "Test Item".to_string(),
+            ..Default::default()
+        }],
+        expected_stats: vec![ExpectedStatFlat {
+            id: 1,
+            level: 83,
+            armor_constant: 7390.0,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    data.item_scaling.curves.insert(
+        7,
+        crate::types::data::CurveFlat {
+            id: 7,
+            curve_type: 0,
+            flags: 0,
+        },
+    );
+    data
+}
+
+#[test]
+fn test_round_trip() {
+    let bytes = sample().to_bytes("11.2.0").unwrap();
+    let snapshot = DataSnapshot::from_bytes(bytes).unwrap();
+
+    assert_eq!(snapshot.patch(), "11.2.0");
+    assert_eq!(snapshot.count(SnapshotSection::Spells), 2);
+    assert_eq!(
+        snapshot.ids(SnapshotSection::Spells).collect::<Vec<_>>(),
+        [34026, 53351]
+    );
+
+    assert_eq!(snapshot.spell(53351).unwrap().unwrap().name, "Kill Shot");
+    assert!(snapshot.spell(1).unwrap().is_none());
+    assert_eq!(snapshot.aura(19574).unwrap().unwrap().spell_id, 19574);
+    assert_eq!(snapshot.item(207170).unwrap().unwrap().name, "Test Item");
+    assert!(snapshot.trait_tree(253).unwrap().is_none());
+
+    let scaling = snapshot.item_scaling().unwrap().unwrap();
+    assert_eq!(scaling.curves[&7].id, 7);
+    let stats = snapshot.expected_stats().unwrap().unwrap();
+    assert_eq!((stats[0].level, stats[0].armor_constant), (83, 7390.0));
+}
+
+#[test]
+fn test_read_record_in_place() {
+    let snapshot = DataSnapshot::from_bytes(sample().to_bytes("").unwrap()).unwrap();
+    let spell = snapshot
+        .archived::<SpellDataFlat>(SnapshotSection::Spells, 34026)
+        .unwrap()
+        .unwrap();
+    assert_eq!(spell.name.as_str(), "Kill Command");
+    assert_eq!(spell.id.to_native(), 34026);
+}
+
+#[test]
+fn test_open_file() {
+    let path = std::env::temp_dir().join(format!("wowlab_snapshot_{}.bin", std::process::id()));
+    sample().write("11.2.0", &path).unwrap();
+
+    let snapshot = DataSnapshot::open(&path).unwrap();
+    assert!(snapshot.verify().is_ok());
+    assert_eq!(snapshot.spell(34026).unwrap().unwrap().name, "Kill Command");
+
+    drop(snapshot);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_rejects_corruption() {
+    let bytes = sample().to_bytes("11.2.0").unwrap();
+
+    // Record bytes are only checked by verify
+    let mut flipped = bytes.clone();
+    *flipped.last_mut().unwrap() ^= 0xFF;
+    let snapshot = DataSnapshot::from_bytes(flipped).unwrap();
+    assert!(matches!(
+        snapshot.verify(),
+        Err(SnapshotError::ChecksumMismatch { .. })
+    ));
+
+    let mut version = bytes.clone();
+    version[8] = 99;
+    assert!(matches!(
+        DataSnapshot::from_bytes(version),
+        Err(SnapshotError::UnsupportedVersion { found: 99 })
+    ));
+
+    assert!(matches!(
+        DataSnapshot::from_bytes(b"not a snapshot at all!!!".to_vec()),
+        Err(SnapshotError::InvalidMagic)
+    ));
+    assert!(matches!(
+        DataSnapshot::from_bytes(bytes[..10].to_vec()),
+        Err(SnapshotError::Truncated(_))
+    ));
+}
+
+#[test]
+fn test_rejects_huge_offsets() {
+    let bytes = sample().to_bytes("11.2.0").unwrap();
+    let table_start = HEADER_SIZE + 8;
+
+    // The first section's index and data offsets
+    for at in [table_start + 8, table_start + 16] {
+        let mut bytes = bytes.clone();
+        bytes[at..at + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            DataSnapshot::from_bytes(bytes),
+            Err(SnapshotError::Truncated(_))
+        ));
+    }
+}
+
+#[test]
+fn test_rejects_corrupt_index() {
+    let mut bytes = sample().to_bytes("11.2.0").unwrap();
+
+    // The spells index starts right after the section table; point its first
+    // record past the end of the section
+    let table_start = HEADER_SIZE + 8;
+    let index_offset =
+        u64::from_le_bytes(bytes[table_start + 8..table_start + 16].try_into().unwrap()) as usize;
+    bytes[index_offset + 8..index_offset + 16].copy_from_slice(&u64::MAX.to_le_bytes());
+
+    assert!(matches!(
+        DataSnapshot::from_bytes(bytes),
+        Err(SnapshotError::CorruptIndex {
+            section: SnapshotSection::Spells,
+            entry: 0
+        })
+    ));
+}
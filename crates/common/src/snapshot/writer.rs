@@ -0,0 +1,175 @@
+//! Snapshot writer
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use rkyv::api::high::HighSerializer;
+use rkyv::rancor;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+
+use super::{
+    SnapshotError, SnapshotSection, FORMAT_VERSION, HEADER_SIZE, INDEX_ENTRY_SIZE, MAGIC,
+    RECORD_ALIGN, SECTION_SIZE,
+};
+use crate::types::data::{
+    AuraDataFlat, ExpectedStatFlat, ItemDataFlat, ItemScalingData, SpellDataFlat, TraitTreeFlat,
+};
+
+/// Transformed game data to write into a snapshot
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotData {
+    pub spells: Vec<SpellDataFlat>,
+    pub auras: Vec<AuraDataFlat>,
+    pub items: Vec<ItemDataFlat>,
+    pub trait_trees: Vec<TraitTreeFlat>,
+    pub item_scaling: ItemScalingData,
+    pub expected_stats: Vec<ExpectedStatFlat>,
+}
+
+impl SnapshotData {
+    /// Transform every table a resolver serves.
+    #[cfg(feature = "dbc")]
+    pub fn from_dbc(dbc: &crate::parsers::DbcData) -> Self {
+        use crate::parsers::{
+            transform_all_auras, transform_all_expected_stats, transform_all_items,
+            transform_all_spells, transform_all_trait_trees, transform_item_scaling,
+        };
+
+        Self {
+            spells: transform_all_spells(dbc),
+            auras: transform_all_auras(dbc),
+            items: transform_all_items(dbc),
+            trait_trees: transform_all_trait_trees(dbc),
+            item_scaling: transform_item_scaling(dbc),
+            expected_stats: transform_all_expected_stats(dbc),
+        }
+    }
+
+    /// Encode the snapshot, labelled with the game patch it was built from.
+    pub fn to_bytes(&self, patch: &str) -> Result<Vec<u8>, SnapshotError> {
+        let sections = [
+            encode(
+                SnapshotSection::Spells,
+                self.spells.iter().map(|s| (s.id, s)),
+            )?,
+            encode(
+                SnapshotSection::Auras,
+                self.auras.iter().map(|a| (a.spell_id, a)),
+            )?,
+            encode(SnapshotSection::Items, self.items.iter().map(|i| (i.id, i)))?,
+            encode(
+                SnapshotSection::TraitTrees,
+                self.trait_trees.iter().map(|t| (t.spec_id, t)),
+            )?,
+            encode(
+                SnapshotSection::ItemScaling,
+                [(0, &self.item_scaling)].into_iter(),
+            )?,
+            encode(
+                SnapshotSection::ExpectedStats,
+                [(0, &self.expected_stats)].into_iter(),
+            )?,
+        ];
+
+        let patch_size = padded(patch.len());
+        let table_start = HEADER_SIZE + patch_size;
+        let body_start = table_start + sections.len() * SECTION_SIZE;
+        let mut offset = body_start;
+
+        let mut table = Vec::with_capacity(sections.len() * SECTION_SIZE);
+        let mut body = Vec::new();
+        for section in &sections {
+            let index_offset = offset;
+            let data_offset = aligned(index_offset + section.records.len() * INDEX_ENTRY_SIZE);
+            let data_len: usize = section.records.values().map(|r| aligned(r.len())).sum();
+
+            table.extend_from_slice(&section.kind.kind().to_le_bytes());
+            table.extend_from_slice(&(section.records.len() as u32).to_le_bytes());
+            table.extend_from_slice(&(index_offset as u64).to_le_bytes());
+            table.extend_from_slice(&(data_offset as u64).to_le_bytes());
+            table.extend_from_slice(&(data_len as u64).to_le_bytes());
+
+            let mut record_offset = 0u64;
+            for (id, record) in &section.records {
+                body.extend_from_slice(&id.to_le_bytes());
+                body.extend_from_slice(&(record.len() as u32).to_le_bytes());
+                body.extend_from_slice(&record_offset.to_le_bytes());
+                record_offset += aligned(record.len()) as u64;
+            }
+            // Pad so the data and every record start aligned in the file
+            body.resize(data_offset - body_start, 0);
+            for record in section.records.values() {
+                body.extend_from_slice(record);
+                body.resize(body.len() + aligned(record.len()) - record.len(), 0);
+            }
+            offset = data_offset + data_len;
+        }
+
+        let mut payload = Vec::with_capacity(offset - HEADER_SIZE);
+        payload.extend_from_slice(patch.as_bytes());
+        payload.resize(patch_size, 0);
+        payload.extend_from_slice(&table);
+        payload.extend_from_slice(&body);
+
+        let mut bytes = Vec::with_capacity(offset);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+        bytes.extend_from_slice(&(patch.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+
+    /// Write the snapshot to `path`.
+    ///
+    /// Writes a temporary file and renames it over `path`, so processes that
+    /// have the old snapshot mapped keep reading intact data.
+    pub fn write(&self, patch: &str, path: &Path) -> Result<(), SnapshotError> {
+        let bytes = self.to_bytes(patch)?;
+        let temp = path.with_extension("tmp");
+        let io_error = |e| SnapshotError::Io {
+            path: path.display().to_string(),
+            source: e,
+        };
+        std::fs::write(&temp, bytes).map_err(io_error)?;
+        std::fs::rename(&temp, path).map_err(io_error)
+    }
+}
+
+struct EncodedSection {
+    kind: SnapshotSection,
+    /// Archived records, sorted by ID
+    records: BTreeMap<i32, AlignedVec>,
+}
+
+fn encode<'a, T>(
+    kind: SnapshotSection,
+    records: impl Iterator<Item = (i32, &'a T)>,
+) -> Result<EncodedSection, SnapshotError>
+where
+    T: for<'b> rkyv::Serialize<HighSerializer<AlignedVec, ArenaHandle<'b>, rancor::Error>> + 'a,
+{
+    let mut encoded = BTreeMap::new();
+    for (id, record) in records {
+        let bytes = rkyv::to_bytes::<rancor::Error>(record).map_err(|e| SnapshotError::Encode {
+            section: kind,
+            id,
+            source: e,
+        })?;
+        encoded.insert(id, bytes);
+    }
+    Ok(EncodedSection {
+        kind,
+        records: encoded,
+    })
+}
+
+fn padded(len: usize) -> usize {
+    len.div_ceil(8) * 8
+}
+
+fn aligned(len: usize) -> usize {
+    len.div_ceil(RECORD_ALIGN) * RECORD_ALIGN
+}
@@ -3,6 +3,9 @@
 //! - `Streaming`: O(1) memory, online mean/variance via Welford's algorithm
 //! - `Batch`: Full dataset statistics including percentiles
 
+/// Two-sided 95% z-score for normal-approximation confidence intervals.
+pub const Z_95: f64 = 1.959_963_984_540_054;
+
 /// Streaming statistics using Welford's online algorithm.
 ///
 /// Computes mean and variance incrementally with O(1) memory.
@@ -26,6 +29,24 @@ impl Streaming {
         }
     }
 
+    /// Rebuild from a summary (count, mean, sample std dev, min, max).
+    ///
+    /// Lets summaries computed elsewhere, such as per-chunk results, be
+    /// pooled with `merge`. A non-finite std dev is treated as zero.
+    pub fn from_parts(count: u64, mean: f64, std_dev: f64, min: f64, max: f64) -> Self {
+        if count == 0 {
+            return Self::new();
+        }
+        let std_dev = if std_dev.is_finite() { std_dev } else { 0.0 };
+        Self {
+            n: count,
+            mean,
+            m2: std_dev * std_dev * (count - 1) as f64,
+            min,
+            max,
+        }
+    }
+
     /// Add a value to the running statistics.
     #[inline]
     pub fn push(&mut self, x: f64) {
@@ -139,6 +160,18 @@ impl Streaming {
             self.std_dev() / mean
         }
     }
+
+    /// Standard error of the mean.
+    #[inline]
+    pub fn std_error(&self) -> f64 {
+        self.std_dev() / (self.n as f64).sqrt()
+    }
+
+    /// Confidence interval for the mean, `z` standard errors either side.
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let half_width = z * self.std_error();
+        (self.mean() - half_width, self.mean() + half_width)
+    }
 }
 
 impl FromIterator<f64> for Streaming {
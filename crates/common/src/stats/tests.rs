@@ -36,6 +36,37 @@ fn streaming_merge() {
     assert_eq!(a.max(), 5.0);
 }
 
+#[test]
+fn streaming_merge_from_parts() {
+    let a: Streaming = vec![1.0, 2.0, 3.0].into_iter().collect();
+    let b: Streaming = vec![4.0, 5.0, 9.0, 10.0].into_iter().collect();
+    let all: Streaming = vec![1.0, 2.0, 3.0, 4.0, 5.0, 9.0, 10.0]
+        .into_iter()
+        .collect();
+
+    let mut pooled = Streaming::from_parts(3, a.mean(), a.std_dev(), 1.0, 3.0);
+    pooled.merge(&Streaming::from_parts(4, b.mean(), b.std_dev(), 4.0, 10.0));
+
+    assert_eq!(pooled.count(), 7);
+    assert!((pooled.mean() - all.mean()).abs() < 1e-10);
+    assert!((pooled.variance() - all.variance()).abs() < 1e-10);
+    assert_eq!((pooled.min(), pooled.max()), (1.0, 10.0));
+}
+
+#[test]
+fn streaming_confidence_interval() {
+    let s: Streaming = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]
+        .into_iter()
+        .collect();
+
+    let se = s.std_dev() / 8f64.sqrt();
+    assert!((s.std_error() - se).abs() < 1e-10);
+
+    let (lower, upper) = s.confidence_interval(Z_95);
+    assert!((lower - (5.0 - Z_95 * se)).abs() < 1e-10);
+    assert!((upper - (5.0 + Z_95 * se)).abs() < 1e-10);
+}
+
 #[test]
 fn streaming_empty() {
     let s = Streaming::new();
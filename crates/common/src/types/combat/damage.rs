@@ -51,11 +51,16 @@ pub enum HitResult {
     Miss,
     Dodge,
     Parry,
+    /// Melee auto attack against a higher-level target, dealing reduced damage
+    Glancing,
+    /// Partially blocked hit
+    Block,
 }
 
 impl HitResult {
+    /// Whether the attack landed (possibly glancing or blocked)
     pub const fn is_hit(self) -> bool {
-        matches!(self, Self::Hit | Self::Crit)
+        matches!(self, Self::Hit | Self::Crit | Self::Glancing | Self::Block)
     }
 
     pub const fn is_crit(self) -> bool {
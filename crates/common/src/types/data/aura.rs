@@ -6,6 +6,10 @@ use super::{PeriodicType, RefreshBehavior};
 
 /// Flat aura data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct AuraDataFlat {
     pub spell_id: i32,
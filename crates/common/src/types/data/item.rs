@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 
 /// A stat on an item
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemStat {
     #[serde(rename = "type")]
@@ -13,6 +17,10 @@ pub struct ItemStat {
 
 /// An effect (proc/use) on an item
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemEffect {
     pub spell_id: i32,
@@ -20,10 +28,17 @@ pub struct ItemEffect {
     pub charges: i32,
     pub cooldown: i32,
     pub category_cooldown: i32,
+    /// Spell category sharing the category cooldown (0 if none)
+    #[serde(default)]
+    pub category_id: i32,
 }
 
 /// Item classification info
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemClassification {
     pub class_id: i32,
@@ -38,6 +53,10 @@ pub struct ItemClassification {
 
 /// Item set info
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemSetInfo {
     pub set_id: i32,
@@ -48,6 +67,10 @@ pub struct ItemSetInfo {
 
 /// A set bonus
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemSetBonus {
     pub threshold: i32,
@@ -57,6 +80,10 @@ pub struct ItemSetBonus {
 
 /// A drop source for an item
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemDropSource {
     pub instance_id: i32,
@@ -68,6 +95,10 @@ pub struct ItemDropSource {
 
 /// Flat item data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemDataFlat {
     // Basic
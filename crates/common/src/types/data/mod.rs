@@ -24,8 +24,8 @@ pub use r#trait::{
     TraitTreeWithSelections,
 };
 pub use scaling::{
-    AppliedBonus, CurveFlat, CurvePointFlat, ItemBonusFlat, ItemQuality, ItemScalingData,
-    RandPropPointsFlat, ScaledItemStats, ScaledStat,
+    AppliedBonus, CurveFlat, CurvePointFlat, ExpectedStatFlat, ItemArmorData, ItemBonusFlat,
+    ItemQuality, ItemScalingData, RandPropPointsFlat, ScaledItemStats, ScaledStat,
 };
 pub use shared::{KnowledgeSource, PeriodicType, RefreshBehavior};
 pub use spec::SpecDataFlat;
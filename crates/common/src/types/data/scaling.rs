@@ -1,7 +1,9 @@
 //! Flat types for item scaling DBC tables and scaling computation results.
 //!
 //! Contains:
-//! - Flat DBC types: ItemBonusFlat, CurveFlat, CurvePointFlat, RandPropPointsFlat
+//! - Flat DBC types: ItemBonusFlat, CurveFlat, CurvePointFlat, RandPropPointsFlat,
+//!   ExpectedStatFlat
+//! - Armor tables: ItemArmorData
 //! - Scaling results: ScaledItemStats, AppliedBonus
 //! - Data bundle: ItemScalingData
 
@@ -52,6 +54,10 @@ pub struct AppliedBonus {
 /// Bundle of all scaling data needed to compute item stats.
 /// Load this once and pass to scaling functions.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 pub struct ItemScalingData {
@@ -63,6 +69,29 @@ pub struct ItemScalingData {
     pub curve_points: HashMap<i32, Vec<CurvePointFlat>>,
     /// Rand prop points by item level (id = item_level)
     pub rand_prop_points: HashMap<i32, RandPropPointsFlat>,
+    /// Base armor tables for cloth, leather, mail and plate
+    #[serde(default)]
+    pub armor: ItemArmorData,
+}
+
+/// Base armor tables, from WoW's ItemArmorQuality, ItemArmorTotal and
+/// ArmorLocation DBC tables.
+///
+/// Armor type arrays are indexed cloth, leather, mail, plate (item subclass - 1).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
+pub struct ItemArmorData {
+    /// Multiplier per item quality, by item level
+    pub quality: HashMap<i32, [f64; 7]>,
+    /// Armor of a full set per armor type, by item level
+    pub total: HashMap<i32, [f64; 4]>,
+    /// Share of the full set per armor type, by inventory type
+    pub location: HashMap<i32, [f64; 4]>,
 }
 
 /// Item quality tiers for stat budget lookup
@@ -101,6 +130,10 @@ impl From<i32> for ItemQuality {
 /// Flat item bonus structure for database storage
 /// Maps directly to WoW's ItemBonus DBC table
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 pub struct ItemBonusFlat {
@@ -118,6 +151,10 @@ pub struct ItemBonusFlat {
 /// Flat curve structure for database storage
 /// Maps directly to WoW's Curve DBC table
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 pub struct CurveFlat {
@@ -130,6 +167,10 @@ pub struct CurveFlat {
 /// Flat curve point structure for database storage
 /// Maps directly to WoW's CurvePoint DBC table
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 pub struct CurvePointFlat {
@@ -142,10 +183,33 @@ pub struct CurvePointFlat {
     pub pos_pre_squish_1: f64,
 }
 
+/// Flat expected stat structure for database storage
+/// Maps to WoW's ExpectedStat DBC table (per-level creature baselines)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
+pub struct ExpectedStatFlat {
+    pub id: i32,
+    /// Expansion the row applies to (-2 for the current one)
+    pub expansion_id: i32,
+    pub level: i32,
+    pub creature_armor: f64,
+    /// Armor constant (K) against a creature of this level
+    pub armor_constant: f64,
+}
+
 /// Flat random property points structure for database storage
 /// Maps directly to WoW's RandPropPoints DBC table
 /// Contains stat budgets per item level for different quality tiers
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 pub struct RandPropPointsFlat {
@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 
 /// How a spell was learned (for knowledge tracking)
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(tag = "source", rename_all = "camelCase")]
 pub enum KnowledgeSource {
     #[serde(rename = "talent")]
@@ -28,6 +32,10 @@ pub enum KnowledgeSource {
 
 /// Type of periodic effect (damage/heal over time)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "snake_case")]
 pub enum PeriodicType {
     Damage,
@@ -39,6 +47,10 @@ pub enum PeriodicType {
 
 /// How an aura's duration refreshes when reapplied
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "snake_case")]
 pub enum RefreshBehavior {
     Pandemic,
@@ -6,6 +6,10 @@ use super::KnowledgeSource;
 
 /// A spell's empower stage (for empowered abilities like Evoker spells)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct EmpowerStage {
     pub stage: i32,
@@ -14,6 +18,10 @@ pub struct EmpowerStage {
 
 /// A spell learned from another spell
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct LearnSpell {
     pub learn_spell_id: i32,
@@ -23,6 +31,10 @@ pub struct LearnSpell {
 /// Denormalized spell effect data for description variable resolution.
 /// Contains the values needed for $s1, $t1, $x1, $a1, etc.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct SpellEffect {
     /// Effect index (0, 1, 2...) - used for $s1, $s2, $s3 (1-indexed in descriptions)
@@ -63,6 +75,10 @@ pub struct SpellEffect {
 
 /// Flat spell data structure matching TypeScript SpellDataFlatSchema exactly
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[serde(rename_all = "camelCase")]
 pub struct SpellDataFlat {
     // Core
@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 
 /// Complete trait tree for a specialization.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 #[serde(rename_all = "camelCase")]
 pub struct TraitTreeFlat {
@@ -16,10 +20,17 @@ pub struct TraitTreeFlat {
     pub edges: Vec<TraitEdge>,
     pub sub_trees: Vec<TraitSubTree>,
     pub point_limits: PointLimits,
+    /// Tree hash loadout strings are checked against, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree_hash: Option<[u8; 16]>,
 }
 
 /// A trait node with position, type, and available choices.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 #[serde(rename_all = "camelCase")]
 pub struct TraitNode {
@@ -33,10 +44,17 @@ pub struct TraitNode {
     pub order_index: i32,
     pub sub_tree_id: i32,
     pub entries: Vec<TraitNodeEntry>,
+    /// Points to spend in this node's tree before it unlocks (0 if ungated)
+    #[serde(default)]
+    pub required_points: i32,
 }
 
 /// A selectable trait within a node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 #[serde(rename_all = "camelCase")]
 pub struct TraitNodeEntry {
@@ -50,6 +68,10 @@ pub struct TraitNodeEntry {
 
 /// A directed edge connecting two nodes (prerequisite relationship).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 #[serde(rename_all = "camelCase")]
 pub struct TraitEdge {
@@ -61,6 +83,10 @@ pub struct TraitEdge {
 
 /// A hero trait subtree (introduced in The War Within).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 #[serde(rename_all = "camelCase")]
 pub struct TraitSubTree {
@@ -72,6 +98,10 @@ pub struct TraitSubTree {
 
 /// Maximum spendable points per tree section.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 pub struct PointLimits {
     pub class: i32,
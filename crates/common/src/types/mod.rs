@@ -7,11 +7,11 @@ pub mod spell_desc;
 pub use combat::{DamageFlags, DamageSchool, HitResult, ResourceType};
 pub use data::{
     argb_to_hex, rgb_to_hex, AppliedBonus, AuraDataFlat, ClassDataFlat, CurveFlat, CurvePointFlat,
-    EmpowerStage, GlobalColorFlat, GlobalStringFlat, ItemBonusFlat, ItemClassification,
-    ItemDataFlat, ItemDropSource, ItemEffect, ItemQuality, ItemScalingData, ItemSetBonus,
-    ItemSetInfo, ItemStat, ItemSummary, KnowledgeSource, LearnSpell, PeriodicType, PointLimits,
-    RandPropPointsFlat, RefreshBehavior, ScaledItemStats, ScaledStat, SpecDataFlat, SpellCost,
-    SpellDamage, SpellDataFlat, SpellEffect, SpellRange, SpellSummary, SpellTiming,
+    EmpowerStage, GlobalColorFlat, GlobalStringFlat, ItemArmorData, ItemBonusFlat,
+    ItemClassification, ItemDataFlat, ItemDropSource, ItemEffect, ItemQuality, ItemScalingData,
+    ItemSetBonus, ItemSetInfo, ItemStat, ItemSummary, KnowledgeSource, LearnSpell, PeriodicType,
+    PointLimits, RandPropPointsFlat, RefreshBehavior, ScaledItemStats, ScaledStat, SpecDataFlat,
+    SpellCost, SpellDamage, SpellDataFlat, SpellEffect, SpellRange, SpellSummary, SpellTiming,
     TalentNodeSummary, TraitEdge, TraitNode, TraitNodeEntry, TraitSelection, TraitSubTree,
     TraitTreeFlat, TraitTreeWithSelections,
 };
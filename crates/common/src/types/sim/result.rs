@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::stats::{Streaming, Z_95};
+
 /// Result from a single simulation chunk (processed by a node).
 ///
 /// This is what nodes write to `jobs_chunks.result` after completing their work.
@@ -15,6 +17,7 @@ pub struct ChunkResult {
     /// Mean DPS across all iterations in this chunk
     pub mean_dps: f64,
     /// Standard deviation of DPS in this chunk
+    #[serde(deserialize_with = "null_as_zero")]
     pub std_dps: f64,
     /// Minimum DPS observed
     pub min_dps: f64,
@@ -22,6 +25,19 @@ pub struct ChunkResult {
     pub max_dps: f64,
 }
 
+impl ChunkResult {
+    /// Summary statistics of this chunk, for pooling with other chunks
+    pub fn streaming(&self) -> Streaming {
+        Streaming::from_parts(
+            u64::from(self.iterations),
+            self.mean_dps,
+            self.std_dps,
+            self.min_dps,
+            self.max_dps,
+        )
+    }
+}
+
 /// Aggregated result from a completed simulation job.
 ///
 /// This is stored in `jobs.result` once all chunks are complete.
@@ -37,6 +53,18 @@ pub struct SimulationResult {
     pub min_dps: f64,
     /// Maximum DPS observed across all iterations
     pub max_dps: f64,
+    /// Pooled standard deviation of DPS across all iterations
+    #[serde(default)]
+    pub std_dps: f64,
+    /// Standard error of the mean DPS
+    #[serde(default)]
+    pub std_error: f64,
+    /// Lower bound of the 95% confidence interval for the mean DPS
+    #[serde(default)]
+    pub ci_lower: f64,
+    /// Upper bound of the 95% confidence interval for the mean DPS
+    #[serde(default)]
+    pub ci_upper: f64,
     /// Total number of iterations completed
     pub total_iterations: u32,
     /// Number of chunks that were processed
@@ -50,6 +78,10 @@ impl SimulationResult {
             mean_dps: 0.0,
             min_dps: f64::MAX,
             max_dps: f64::MIN,
+            std_dps: 0.0,
+            std_error: 0.0,
+            ci_lower: 0.0,
+            ci_upper: 0.0,
             total_iterations: 0,
             chunks_completed: 0,
         }
@@ -57,25 +89,63 @@ impl SimulationResult {
 
     /// Merge a chunk result into this aggregated result
     pub fn merge_chunk(&mut self, chunk: &ChunkResult) {
-        // Update min/max
-        self.min_dps = self.min_dps.min(chunk.min_dps);
-        self.max_dps = self.max_dps.max(chunk.max_dps);
-
-        // Weighted mean calculation
-        let total_before = self.total_iterations as f64;
-        let chunk_count = chunk.iterations as f64;
-        let total_after = total_before + chunk_count;
-
-        if total_after > 0.0 {
-            self.mean_dps =
-                (self.mean_dps * total_before + chunk.mean_dps * chunk_count) / total_after;
+        let mut pooled = self.streaming();
+        pooled.merge(&chunk.streaming());
+        self.chunks_completed += 1;
+        if pooled.count() == 0 {
+            return;
         }
 
-        self.total_iterations += chunk.iterations;
-        self.chunks_completed += 1;
+        self.mean_dps = pooled.mean();
+        self.min_dps = pooled.min();
+        self.max_dps = pooled.max();
+        self.std_dps = finite_or_zero(pooled.std_dev());
+        self.std_error = finite_or_zero(pooled.std_error());
+        self.ci_lower = self.mean_dps - Z_95 * self.std_error;
+        self.ci_upper = self.mean_dps + Z_95 * self.std_error;
+        self.total_iterations = pooled.count() as u32;
+    }
+
+    /// Half-width of the 95% confidence interval as a fraction of the mean,
+    /// comparable to SimC's `target_error`
+    pub fn relative_error(&self) -> f64 {
+        if self.mean_dps > 0.0 {
+            (self.ci_upper - self.mean_dps) / self.mean_dps
+        } else {
+            f64::NAN
+        }
+    }
+
+    /// Summary statistics merged so far
+    pub fn streaming(&self) -> Streaming {
+        Streaming::from_parts(
+            u64::from(self.total_iterations),
+            self.mean_dps,
+            self.std_dps,
+            self.min_dps,
+            self.max_dps,
+        )
+    }
+}
+
+/// NaN (too few iterations for a variance) is stored as zero, since JSON
+/// has no NaN
+fn finite_or_zero(x: f64) -> f64 {
+    if x.is_finite() {
+        x
+    } else {
+        0.0
     }
 }
 
+/// A NaN written by serde_json arrives as `null`; read it back as zero
+fn null_as_zero<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<f64>::deserialize(deserializer)?.map_or(0.0, finite_or_zero))
+}
+
 impl Default for SimulationResult {
     fn default() -> Self {
         Self::new()
@@ -120,4 +190,68 @@ mod tests {
         assert_eq!(result.max_dps, 58000.0); // max of both
         assert_eq!(result.chunks_completed, 2);
     }
+
+    #[test]
+    fn test_merge_pools_variance() {
+        let chunks: [&[f64]; 3] = [&[10.0, 12.0, 14.0], &[20.0, 22.0], &[11.0, 13.0, 30.0, 8.0]];
+        let mut result = SimulationResult::new();
+        for values in chunks {
+            let stats: Streaming = values.iter().copied().collect();
+            result.merge_chunk(&ChunkResult {
+                iterations: values.len() as u32,
+                mean_dps: stats.mean(),
+                std_dps: stats.std_dev(),
+                min_dps: stats.min(),
+                max_dps: stats.max(),
+            });
+        }
+
+        let all: Streaming = chunks.iter().flat_map(|v| v.iter().copied()).collect();
+        assert_eq!(result.total_iterations, 9);
+        assert!((result.mean_dps - all.mean()).abs() < 1e-9);
+        assert!((result.std_dps - all.std_dev()).abs() < 1e-9);
+        assert!((result.std_error - all.std_error()).abs() < 1e-9);
+
+        let (lower, upper) = all.confidence_interval(Z_95);
+        assert!((result.ci_lower - lower).abs() < 1e-9);
+        assert!((result.ci_upper - upper).abs() < 1e-9);
+        assert!((result.relative_error() - (upper - all.mean()) / all.mean()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_single_iteration_chunk_has_zero_error() {
+        let mut result = SimulationResult::new();
+        result.merge_chunk(&ChunkResult {
+            iterations: 1,
+            mean_dps: 40000.0,
+            std_dps: f64::NAN,
+            min_dps: 40000.0,
+            max_dps: 40000.0,
+        });
+
+        assert_eq!(result.std_error, 0.0);
+        assert_eq!((result.ci_lower, result.ci_upper), (40000.0, 40000.0));
+    }
+
+    #[test]
+    fn test_nan_std_dps_survives_json() {
+        let chunk = ChunkResult {
+            iterations: 1,
+            mean_dps: 40000.0,
+            std_dps: f64::NAN,
+            min_dps: 40000.0,
+            max_dps: 40000.0,
+        };
+
+        let json = serde_json::to_value(&chunk).unwrap();
+        assert!(json["stdDps"].is_null());
+
+        let parsed: ChunkResult = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.std_dps, 0.0);
+
+        let mut result = SimulationResult::new();
+        result.merge_chunk(&parsed);
+        assert_eq!(result.total_iterations, 1);
+        assert_eq!(result.std_error, 0.0);
+    }
 }
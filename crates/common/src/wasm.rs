@@ -3,17 +3,17 @@
 //! This module provides JavaScript-callable functions for:
 //! - SimC parsing
 //! - Spell description parsing and rendering
-//! - Talent loadout encoding/decoding
+//! - Talent loadout encoding/decoding and validation
 //! - Item scaling calculations
 //! - Node authentication (crypto)
 
 use wasm_bindgen::prelude::*;
 
 use crate::parsers::{
-    apply_item_bonuses, encode_minimal_loadout, parse_simc, parse_spell_desc,
-    ParsedSpellDescription, Profile,
+    apply_item_bonuses, decode_trait_loadout, encode_minimal_loadout, parse_simc, parse_spell_desc,
+    validate_trait_loadout, ParsedSpellDescription, Profile,
 };
-use crate::types::data::{ItemQuality, ItemScalingData, ItemStat};
+use crate::types::data::{ItemQuality, ItemScalingData, ItemStat, TraitTreeFlat};
 
 // Re-export spell description WASM functions from parsers
 pub use crate::parsers::spell_desc::{
@@ -53,6 +53,19 @@ pub fn wasm_encode_minimal_loadout(spec_id: u16) -> String {
     encode_minimal_loadout(spec_id)
 }
 
+/// Validate a loadout string against a trait tree.
+///
+/// Returns the list of violations, empty if the build is legal.
+#[wasm_bindgen(js_name = validateTraitLoadout)]
+pub fn wasm_validate_trait_loadout(tree: JsValue, loadout: &str) -> Result<JsValue, JsError> {
+    let tree: TraitTreeFlat =
+        serde_wasm_bindgen::from_value(tree).map_err(|e| JsError::new(&e.to_string()))?;
+    let decoded = decode_trait_loadout(loadout).map_err(|e| JsError::new(&e.to_string()))?;
+
+    serde_wasm_bindgen::to_value(&validate_trait_loadout(&tree, &decoded))
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
 /// Apply item bonuses to compute scaled stats.
 ///
 /// Takes base item info and bonus IDs, returns computed stats.
@@ -194,10 +207,16 @@ mod crypto_wasm {
 
     /// Build the message to sign for a node request.
     ///
-    /// Format: `timestamp:method:path:sha256(body)`
+    /// Format: `timestamp\0nonce\0method\0path\0sha256(body)`
     #[wasm_bindgen(js_name = buildSignMessage)]
-    pub fn wasm_build_sign_message(timestamp: u64, method: &str, path: &str, body: &str) -> String {
-        crypto::build_sign_message(timestamp, method, path, body.as_bytes())
+    pub fn wasm_build_sign_message(
+        timestamp: u64,
+        nonce: &str,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> String {
+        crypto::build_sign_message(timestamp, nonce, method, path, body.as_bytes())
     }
 
     /// Compute SHA256 hash of a string and return as hex.
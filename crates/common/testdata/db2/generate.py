@@ -0,0 +1,210 @@
+#!/usr/bin/env python3
+"""Writes the sample DB2 files the DB2 reader tests decode.
+
+Each table exercises a different part of the format:
+
+- SpellName (WDC3): two sections, ID lists, string tables, copy table
+- CurvePoint (WDC4): pallet arrays, plain arrays, bitpacked inline ID,
+  relationship map, an encrypted (zeroed) section
+- ExpectedStat (WDC5): signed bitpacking, pallet and common data
+- GlobalStrings (WDC5): sparse records with inline strings
+
+Run from this directory: python3 generate.py
+"""
+
+import struct
+from pathlib import Path
+
+OUT = Path(__file__).parent / "data" / "db2"
+
+NONE, BITPACKED, COMMON, PALLET, PALLET_ARRAY, BITPACKED_SIGNED = range(6)
+FLAG_SPARSE = 0x1
+
+
+def f32(value):
+    return struct.unpack("<I", struct.pack("<f", value))[0]
+
+
+def storage(offset_bits, size_bits, kind, additional=0, values=(0, 0, 0)):
+    return struct.pack("<HHII3I", offset_bits, size_bits, additional, kind, *values)
+
+
+def pack_bits(fields, size):
+    """fields: (offset_bits, size_bits, value)"""
+    value = 0
+    for offset, bits, field in fields:
+        value |= (field & ((1 << bits) - 1)) << offset
+    return value.to_bytes(size, "little")
+
+
+class Section:
+    def __init__(self, records=b"", strings=b"", record_count=0, ids=(), copies=(),
+                 relations=(), tact_key_hash=0, sparse=None, offset_map_ids=()):
+        self.records = records
+        self.strings = strings
+        self.record_count = record_count
+        self.ids = list(ids)
+        self.copies = list(copies)
+        self.relations = list(relations)
+        self.tact_key_hash = tact_key_hash
+        self.sparse = sparse
+        self.offset_map_ids = list(offset_map_ids)
+
+    def relationship_block(self):
+        if not self.relations:
+            return b""
+        foreign = [f for f, _ in self.relations]
+        block = struct.pack("<3I", len(self.relations), min(foreign), max(foreign))
+        for foreign_id, index in self.relations:
+            block += struct.pack("<2I", foreign_id, index)
+        return block
+
+
+def write(name, magic, *, layout_hash, record_size, field_count, fields, sections,
+          id_index=0, flags=0, pallet=b"", common=b"", encrypted=b""):
+    header_size = 4 + (4 + 128 if magic == b"WDC5" else 0) + 17 * 4
+    data_start = (header_size + 40 * len(sections) + 4 * field_count + len(fields)
+                  + len(pallet) + len(common) + len(encrypted))
+
+    body = b""
+    section_headers = b""
+    for section in sections:
+        file_offset = data_start + len(body)
+        offset_map = b""
+        if section.sparse is not None:
+            offset = file_offset
+            for record in section.sparse:
+                offset_map += struct.pack("<IH", offset, len(record))
+                offset += len(record)
+            records = b"".join(section.sparse)
+            offset_records_end = offset
+        else:
+            records = section.records + section.strings
+            offset_records_end = 0
+        relationship = section.relationship_block()
+        body += records
+        body += b"".join(struct.pack("<I", i) for i in section.ids)
+        body += b"".join(struct.pack("<2I", *c) for c in section.copies)
+        body += offset_map + relationship
+        body += b"".join(struct.pack("<I", i) for i in section.offset_map_ids)
+        section_headers += struct.pack(
+            "<Q8I", section.tact_key_hash, file_offset, section.record_count,
+            len(section.strings), offset_records_end, 4 * len(section.ids),
+            len(relationship), len(section.offset_map_ids), len(section.copies))
+
+    ids = [i for s in sections for i in s.ids + s.offset_map_ids] or [0]
+    header = magic
+    if magic == b"WDC5":
+        header += struct.pack("<I", 5) + b"WowLab sample".ljust(128, b"\0")
+    header += struct.pack(
+        "<9IHH7I", sum(s.record_count for s in sections), field_count, record_size,
+        sum(len(s.strings) for s in sections), 0x57575757, layout_hash, min(ids), max(ids),
+        0, flags, id_index, field_count, 0, 0, len(fields), len(common), len(pallet),
+        len(sections))
+    structures = b"".join(struct.pack("<hH", 0, 0) for _ in range(field_count))
+
+    blob = header + section_headers + structures + fields + pallet + common + encrypted
+    assert len(blob) == data_start
+    (OUT / f"{name}.db2").write_bytes(blob + body)
+
+
+def spell_name():
+    # Non-inline ID, one string field; strings are addressed from the field
+    sections = [
+        (100, "Kill Command"), (133, "Barbed Shot"),
+    ], [
+        (200, "Bestial Wrath"), (300, ""),
+    ]
+    total = sum(len(s) for s in sections)
+    string_tables = []
+    position = 0
+    index = 0
+    built = []
+    for rows in sections:
+        strings = b"\0"
+        records = b""
+        for _, name in rows:
+            at = position if not name else position + len(strings)
+            if name:
+                strings += name.encode() + b"\0"
+            records += struct.pack("<I", at + (total - index) * 4)
+            index += 1
+        position += len(strings)
+        string_tables.append(strings)
+        built.append((records, strings, [i for i, _ in rows]))
+
+    write("SpellName", b"WDC3", layout_hash=0x4F4B3F0E, record_size=4, field_count=1,
+          fields=storage(0, 32, NONE),
+          sections=[
+              Section(built[0][0], built[0][1], 2, built[0][2]),
+              Section(built[1][0], built[1][1], 2, built[1][2], copies=[(201, 200)]),
+          ])
+
+
+def curve_point():
+    # Pos[2] via pallet array, PosPreSquish[2] plain, ID bitpacked,
+    # CurveID from the relationship map, OrderIndex via pallet
+    positions = [(0.0, 1.0), (10.0, 2.5), (20.0, 4.0)]
+    pallet = b"".join(struct.pack("<2I", f32(x), f32(y)) for x, y in positions)
+    pallet += b"".join(struct.pack("<I", i) for i in range(3))
+    fields = (storage(0, 2, PALLET_ARRAY, 24, (0, 2, 2))
+              + storage(8, 64, NONE)
+              + storage(72, 16, BITPACKED, 0, (72, 16, 0))
+              + storage(88, 2, PALLET, 12, (88, 2, 0)))
+
+    records = b""
+    for index, record_id in enumerate([501, 502, 503]):
+        x, y = positions[index]
+        bits = pack_bits([(0, 2, index), (72, 16, record_id), (88, 2, index)], 12)
+        records += bits[:1] + struct.pack("<2I", f32(x), f32(y)) + bits[9:]
+
+    write("CurvePoint", b"WDC4", layout_hash=0x1A2B3C4D, record_size=12, field_count=4,
+          id_index=2, fields=fields, pallet=pallet,
+          encrypted=struct.pack("<2I", 1, 504),
+          sections=[
+              Section(records, record_count=3, relations=[(77, 0), (77, 1), (78, 2)]),
+              Section(bytes(12), record_count=1, tact_key_hash=0x0123456789ABCDEF),
+          ])
+
+
+def expected_stat():
+    # ID and Lvl bitpacked, ExpansionID signed, CreatureArmor via pallet,
+    # ArmorConstant as common data with an exception for ID 2
+    pallet = struct.pack("<2I", f32(100.5), f32(250.0))
+    common = struct.pack("<2I", 2, f32(8500.0))
+    fields = (storage(0, 8, BITPACKED, 0, (0, 8, 0))
+              + storage(8, 5, BITPACKED_SIGNED, 0, (8, 5, 1))
+              + storage(13, 2, PALLET, 8, (13, 2, 0))
+              + storage(0, 0, COMMON, 8, (f32(7390.0), 0, 0))
+              + storage(15, 7, BITPACKED, 0, (15, 7, 0)))
+
+    rows = [(1, -2, 0, 80), (2, 10, 1, 81), (3, 9, 0, 70)]
+    records = b"".join(
+        pack_bits([(0, 8, i), (8, 5, expansion), (13, 2, armor), (15, 7, level)], 3)
+        for i, expansion, armor, level in rows)
+
+    write("ExpectedStat", b"WDC5", layout_hash=0x2C3D4E5F, record_size=3, field_count=5,
+          fields=fields, pallet=pallet, common=common,
+          sections=[Section(records, record_count=3)])
+
+
+def global_strings():
+    # Sparse: records are stored whole, strings inline, IDs in the offset map ID list
+    rows = [(10, "HUNTER_PET", "Pet", 1), (20, "EMPTY_TEXT", "", 0)]
+    records = [tag.encode() + b"\0" + text.encode() + b"\0" + bytes([flags])
+               for _, tag, text, flags in rows]
+    fields = storage(0, 0, NONE) + storage(0, 0, NONE) + storage(0, 8, NONE)
+
+    write("GlobalStrings", b"WDC5", layout_hash=0x6E7F8091,
+          record_size=max(len(r) for r in records), field_count=3, flags=FLAG_SPARSE,
+          fields=fields,
+          sections=[Section(record_count=2, sparse=records,
+                            offset_map_ids=[i for i, *_ in rows])])
+
+
+if __name__ == "__main__":
+    OUT.mkdir(parents=True, exist_ok=True)
+    spell_name()
+    curve_point()
+    expected_stat()
+    global_strings()
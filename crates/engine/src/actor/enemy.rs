@@ -1,5 +1,13 @@
+use super::DEFAULT_LEVEL;
 use crate::aura::TargetAuras;
-use wowlab_common::types::{SimTime, TargetIdx};
+use crate::combat::{ArmorConstants, Mitigation};
+use wowlab_common::types::{DamageSchool, SimTime, TargetIdx};
+
+/// Distance an enemy stands at unless a raid event moves it (melee range).
+pub const DEFAULT_ENEMY_DISTANCE: f32 = 5.0;
+
+/// Level of raid bosses and the enemies around them (three above the player).
+pub const DEFAULT_ENEMY_LEVEL: u8 = DEFAULT_LEVEL + 3;
 
 #[derive(Clone, Debug)]
 pub struct Enemy {
@@ -8,12 +16,24 @@ pub struct Enemy {
     pub max_health: f32,
     pub current_health: f32,
     pub armor: f32,
+    /// Innate damage reduction per school (0 to 1)
+    pub resistances: Vec<(DamageSchool, f32)>,
+    pub level: u8,
     pub is_boss: bool,
     pub debuffs: TargetAuras,
     pub dies_at: Option<SimTime>,
     pub distance: f32,
     pub is_casting: bool,
     pub is_moving: bool,
+    /// Spawned by a raid event rather than present from the pull
+    pub is_add: bool,
+    /// In the fight (adds are despawned until their wave starts)
+    pub spawned: bool,
+    /// Takes no damage (boss invulnerability phase)
+    pub invulnerable: bool,
+    /// Health lost since `first_damage`, for time-to-die estimates
+    pub damage_taken: f32,
+    pub first_damage: Option<SimTime>,
 }
 
 impl Enemy {
@@ -24,12 +44,19 @@ impl Enemy {
             max_health: 10_000_000.0, // Default raid boss health
             current_health: 10_000_000.0,
             armor: 11300.0, // Boss armor
+            resistances: Vec::new(),
+            level: DEFAULT_ENEMY_LEVEL,
             is_boss: true,
             debuffs: TargetAuras::new(),
             dies_at: None,
-            distance: 5.0,
+            distance: DEFAULT_ENEMY_DISTANCE,
             is_casting: false,
             is_moving: false,
+            is_add: false,
+            spawned: true,
+            invulnerable: false,
+            damage_taken: 0.0,
+            first_damage: None,
         }
     }
 
@@ -41,6 +68,7 @@ impl Enemy {
         let mut enemy = Self::new(id, name);
         enemy.max_health = 2_000_000.0;
         enemy.current_health = 2_000_000.0;
+        enemy.level = DEFAULT_LEVEL + 2;
         enemy
     }
 
@@ -52,11 +80,42 @@ impl Enemy {
         enemy
     }
 
+    /// An add that only joins the fight when its raid event spawns it.
+    pub fn add(id: TargetIdx, name: impl Into<String>, health: f32) -> Self {
+        let mut enemy = Self::new(id, name);
+        enemy.max_health = health;
+        enemy.current_health = health;
+        enemy.is_boss = false;
+        enemy.is_add = true;
+        enemy.spawned = false;
+        enemy
+    }
+
+    /// Bring the enemy into the fight at full health.
+    pub fn spawn(&mut self) {
+        self.current_health = self.max_health;
+        self.debuffs = TargetAuras::new();
+        self.spawned = true;
+        self.dies_at = None;
+        self.damage_taken = 0.0;
+        self.first_damage = None;
+    }
+
+    pub fn despawn(&mut self) {
+        self.spawned = false;
+    }
+
     pub fn reset(&mut self) {
         self.current_health = self.max_health;
         self.debuffs = TargetAuras::new();
         self.is_casting = false;
         self.is_moving = false;
+        self.distance = DEFAULT_ENEMY_DISTANCE;
+        self.spawned = !self.is_add;
+        self.invulnerable = false;
+        self.dies_at = None;
+        self.damage_taken = 0.0;
+        self.first_damage = None;
     }
 
     pub fn time_to_percent(&self, percent: f32, dps: f32) -> SimTime {
@@ -79,7 +138,13 @@ impl Enemy {
 
     #[inline]
     pub fn is_alive(&self) -> bool {
-        self.current_health > 0.0
+        self.spawned && self.current_health > 0.0
+    }
+
+    /// Alive and not invulnerable.
+    #[inline]
+    pub fn is_attackable(&self) -> bool {
+        self.is_alive() && !self.invulnerable
     }
 
     #[inline]
@@ -91,13 +156,37 @@ impl Enemy {
         self.current_health = (self.current_health - amount).max(0.0);
     }
 
-    pub fn armor_mitigation(&self, attacker_level: u8) -> f32 {
-        let k = if self.is_boss {
-            (attacker_level as f32) * 467.5 + 16593.0
-        } else {
-            (attacker_level as f32) * 467.5
+    /// Take damage and track it for [`damage_rate`](Self::damage_rate).
+    pub fn record_damage_taken(&mut self, now: SimTime, amount: f32) {
+        self.first_damage.get_or_insert(now);
+        self.damage_taken += amount;
+        self.take_damage(amount);
+    }
+
+    /// Health lost per second since the first hit.
+    pub fn damage_rate(&self, now: SimTime) -> f32 {
+        let Some(first) = self.first_damage else {
+            return 0.0;
         };
-        self.armor / (self.armor + k)
+        let seconds = now.saturating_sub(first).as_secs_f32();
+        if seconds > 0.0 {
+            self.damage_taken / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// Armor and resistances, with the armor constant for this enemy's level.
+    pub fn mitigation(&self, constants: &ArmorConstants) -> Mitigation {
+        self.resistances.iter().fold(
+            Mitigation::new(self.armor, constants.get(self.level)),
+            |mitigation, &(school, amount)| mitigation.with_resistance(school, amount),
+        )
+    }
+
+    /// Share of physical damage this enemy's armor prevents
+    pub fn armor_mitigation(&self, constants: &ArmorConstants) -> f32 {
+        self.mitigation(constants).armor_mitigation()
     }
 
     pub fn time_to_die(&self, dps: f32) -> SimTime {
@@ -158,6 +247,20 @@ impl EnemyManager {
         for enemy in &mut self.enemies {
             enemy.reset();
         }
+        self.primary = TargetIdx(0);
+    }
+
+    /// Point `primary` at the first attackable enemy, so bosses take
+    /// precedence over adds. Falls back to the first living enemy.
+    pub fn retarget(&mut self) {
+        let index = self
+            .enemies
+            .iter()
+            .position(Enemy::is_attackable)
+            .or_else(|| self.enemies.iter().position(Enemy::is_alive));
+        if let Some(index) = index {
+            self.primary = TargetIdx(index as u16);
+        }
     }
 
     pub fn alive_count(&self) -> usize {
@@ -176,6 +279,11 @@ impl EnemyManager {
         self.enemies.len()
     }
 
+    /// Iterate all enemies, dead or alive
+    pub fn iter(&self) -> impl Iterator<Item = &Enemy> {
+        self.enemies.iter()
+    }
+
     pub fn average_health_percent(&self) -> f32 {
         let alive: Vec<_> = self.alive().collect();
         if alive.is_empty() {
@@ -0,0 +1,259 @@
+//! Equipped items the rotation can query and use.
+//!
+//! Each trinket slot carries its on-use spell with its own cooldown and the
+//! on-use category it shares with other items, which locks the whole
+//! category out when any of them is used. On-equip effects are permanent
+//! auras and on-proc effects are registered as procs.
+
+use crate::combat::Cooldown;
+use crate::proc::{ProcEffect, ProcFlags, ProcHandler, ProcRegistry, RppmState};
+use std::collections::HashMap;
+use wowlab_common::parsers::Slot;
+use wowlab_common::types::data::ItemDataFlat;
+use wowlab_common::types::{AuraIdx, ProcIdx, SimTime, SpellIdx};
+
+/// Rotation trinket slots (`trinket.1`, `trinket.2`)
+pub const TRINKET_SLOTS: [u8; 2] = [1, 2];
+
+/// Item effect trigger types
+const TRIGGER_ON_USE: i32 = 0;
+const TRIGGER_ON_EQUIP: i32 = 1;
+const TRIGGER_ON_PROC: i32 = 2;
+
+/// First proc ID used for trinket procs
+const TRINKET_PROC_BASE: u32 = 1000;
+
+/// Placeholder RPPM for on-proc effects: item effects don't carry proc
+/// rates, so this stands in until proc data is loaded
+pub const TRINKET_PROC_RPPM: f32 = 1.0;
+
+/// A trinket's on-use effect
+#[derive(Clone, Debug)]
+pub struct OnUseEffect {
+    pub spell: SpellIdx,
+    pub cooldown: Cooldown,
+    /// On-use category shared with other items (0 if none)
+    pub category: u32,
+    /// Lockout applied to the category on use
+    pub category_cooldown: SimTime,
+}
+
+/// An on-proc trinket effect
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrinketProc {
+    pub spell: SpellIdx,
+    pub icd: Option<SimTime>,
+}
+
+/// An equipped trinket
+#[derive(Clone, Debug)]
+pub struct Trinket {
+    pub item_id: u32,
+    /// Rotation name, e.g. `algethar_puzzle_box`
+    pub name: String,
+    pub on_use: Option<OnUseEffect>,
+    /// On-equip auras, active for the whole fight
+    pub passives: Vec<AuraIdx>,
+    pub procs: Vec<TrinketProc>,
+}
+
+impl Trinket {
+    /// Build a trinket from its item effects.
+    ///
+    /// On-use effects without their own cooldown use the category cooldown;
+    /// ones with neither are skipped, as nothing would stop them being used
+    /// again straight away.
+    pub fn from_item(item: &ItemDataFlat) -> Self {
+        let on_use = item
+            .effects
+            .iter()
+            .filter(|e| e.trigger_type == TRIGGER_ON_USE && e.spell_id > 0)
+            .find(|e| e.cooldown > 0 || e.category_cooldown > 0)
+            .map(|e| OnUseEffect {
+                spell: SpellIdx(e.spell_id as u32),
+                cooldown: Cooldown::new(e.cooldown.max(e.category_cooldown) as f32 / 1000.0),
+                category: e.category_id.max(0) as u32,
+                category_cooldown: SimTime::from_millis(e.category_cooldown.max(0) as u32),
+            });
+        let passives = item
+            .effects
+            .iter()
+            .filter(|e| e.trigger_type == TRIGGER_ON_EQUIP && e.spell_id > 0)
+            .map(|e| AuraIdx(e.spell_id as u32))
+            .collect();
+        let procs = item
+            .effects
+            .iter()
+            .filter(|e| e.trigger_type == TRIGGER_ON_PROC && e.spell_id > 0)
+            .map(|e| TrinketProc {
+                spell: SpellIdx(e.spell_id as u32),
+                icd: (e.cooldown > 0).then(|| SimTime::from_millis(e.cooldown as u32)),
+            })
+            .collect();
+
+        Self {
+            item_id: item.id as u32,
+            name: item_token(&item.name),
+            on_use,
+            passives,
+            procs,
+        }
+    }
+
+    /// Whether `name` (rotation name or item ID) refers to this trinket
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.item_id.to_string() == name
+    }
+}
+
+/// A player's equipped items.
+#[derive(Clone, Debug, Default)]
+pub struct Equipment {
+    trinkets: [Option<Trinket>; 2],
+    /// Rotation name and ID of the item in each slot
+    items: HashMap<Slot, (String, u32)>,
+    /// When each on-use category unlocks
+    lockouts: HashMap<u32, SimTime>,
+}
+
+impl Equipment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equip an item; trinket slots also pick up its on-use and proc effects.
+    pub fn equip(&mut self, slot: Slot, item: &ItemDataFlat) {
+        self.items
+            .insert(slot, (item_token(&item.name), item.id as u32));
+        match slot {
+            Slot::Trinket1 => self.trinkets[0] = Some(Trinket::from_item(item)),
+            Slot::Trinket2 => self.trinkets[1] = Some(Trinket::from_item(item)),
+            _ => {}
+        }
+    }
+
+    /// Put a trinket in rotation slot 1 or 2.
+    pub fn with_trinket(mut self, slot: u8, trinket: Trinket) -> Self {
+        let item_slot = match slot {
+            1 => Slot::Trinket1,
+            2 => Slot::Trinket2,
+            _ => return self,
+        };
+        self.items
+            .insert(item_slot, (trinket.name.clone(), trinket.item_id));
+        if let Some(entry) = self.trinket_entry(slot) {
+            *entry = Some(trinket);
+        }
+        self
+    }
+
+    /// Trinket in rotation slot 1 or 2
+    pub fn trinket(&self, slot: u8) -> Option<&Trinket> {
+        self.trinkets.get((slot as usize).checked_sub(1)?)?.as_ref()
+    }
+
+    fn trinket_entry(&mut self, slot: u8) -> Option<&mut Option<Trinket>> {
+        self.trinkets.get_mut((slot as usize).checked_sub(1)?)
+    }
+
+    /// Whether an item is equipped, by rotation name or item ID
+    pub fn is_equipped(&self, name: &str) -> bool {
+        self.items
+            .values()
+            .any(|(token, id)| token == name || id.to_string() == name)
+    }
+
+    /// On-equip auras of the equipped trinkets
+    pub fn passive_auras(&self) -> impl Iterator<Item = AuraIdx> + '_ {
+        self.trinkets
+            .iter()
+            .flatten()
+            .flat_map(|t| t.passives.iter().copied())
+    }
+
+    /// Whether the trinket in `slot` has an on-use effect ready
+    pub fn trinket_ready(&self, slot: u8, now: SimTime) -> bool {
+        self.trinket(slot)
+            .and_then(|t| t.on_use.as_ref())
+            .is_some_and(|on_use| self.on_use_remaining(on_use, now) == SimTime::ZERO)
+    }
+
+    /// Time until the trinket in `slot` can be used (zero without an on-use effect)
+    pub fn trinket_remaining(&self, slot: u8, now: SimTime) -> SimTime {
+        self.trinket(slot)
+            .and_then(|t| t.on_use.as_ref())
+            .map_or(SimTime::ZERO, |on_use| self.on_use_remaining(on_use, now))
+    }
+
+    fn on_use_remaining(&self, on_use: &OnUseEffect, now: SimTime) -> SimTime {
+        let lockout = match self.lockouts.get(&on_use.category) {
+            Some(ready_at) if on_use.category != 0 => ready_at.saturating_sub(now),
+            _ => SimTime::ZERO,
+        };
+        on_use.cooldown.remaining(now).max(lockout)
+    }
+
+    /// Use the trinket in `slot`, starting its cooldown and category lockout.
+    ///
+    /// Returns the on-use spell, or `None` if the trinket isn't ready.
+    pub fn use_trinket(&mut self, slot: u8, now: SimTime) -> Option<SpellIdx> {
+        if !self.trinket_ready(slot, now) {
+            return None;
+        }
+        let on_use = self.trinket_entry(slot)?.as_mut()?.on_use.as_mut()?;
+        on_use.cooldown.start(now, 1.0);
+        let (spell, category, lockout) = (on_use.spell, on_use.category, on_use.category_cooldown);
+        if category != 0 {
+            self.lockouts.insert(category, now + lockout);
+        }
+        Some(spell)
+    }
+
+    /// Register on-proc trinket effects as RPPM procs.
+    pub fn register_procs(&self, registry: &mut ProcRegistry) {
+        for (index, trinket) in self.trinkets.iter().enumerate() {
+            let Some(trinket) = trinket else { continue };
+            let name = if index == 0 { "Trinket 1" } else { "Trinket 2" };
+            for (i, proc) in trinket.procs.iter().enumerate() {
+                let id = ProcIdx(TRINKET_PROC_BASE + (index * 16 + i) as u32);
+                let mut rppm = RppmState::new(id, TRINKET_PROC_RPPM);
+                if let Some(icd) = proc.icd {
+                    rppm = rppm.with_icd(icd);
+                }
+                registry.register_rppm(
+                    rppm,
+                    ProcHandler::new(
+                        id,
+                        name,
+                        ProcFlags::ON_SPELL_CAST | ProcFlags::ON_AUTO_ATTACK,
+                        ProcEffect::CastSpell { spell: proc.spell },
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Reset cooldowns and lockouts for a new iteration
+    pub fn reset(&mut self) {
+        for on_use in self
+            .trinkets
+            .iter_mut()
+            .flatten()
+            .filter_map(|t| t.on_use.as_mut())
+        {
+            on_use.cooldown.reset();
+        }
+        self.lockouts.clear();
+    }
+}
+
+/// Rotation name for an item: lowercase words joined by underscores,
+/// apostrophes dropped ("Algeth'ar Puzzle Box" -> `algethar_puzzle_box`).
+pub fn item_token(name: &str) -> String {
+    name.to_lowercase()
+        .replace(['\'', '’'], "")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
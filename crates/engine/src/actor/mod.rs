@@ -1,8 +1,10 @@
 mod enemy;
+mod equipment;
 mod pet;
 mod player;
 
 pub use enemy::*;
+pub use equipment::*;
 pub use pet::*;
 pub use player::*;
 
@@ -1,10 +1,11 @@
+use super::Equipment;
 use crate::aura::TargetAuras;
 use crate::combat::{ChargedCooldown, Cooldown};
 use crate::proc::ProcRegistry;
 use crate::resource::UnitResources;
 use crate::stats::StatCache;
 use std::collections::HashMap;
-use wowlab_common::types::{SimTime, SpecId, SpellIdx, UnitIdx};
+use wowlab_common::types::{AuraIdx, SimTime, SpecId, SpellIdx, UnitIdx};
 
 #[derive(Clone, Debug)]
 pub struct Player {
@@ -16,6 +17,9 @@ pub struct Player {
     pub cooldowns: HashMap<SpellIdx, Cooldown>,
     pub charged_cooldowns: HashMap<SpellIdx, ChargedCooldown>,
     pub procs: ProcRegistry,
+    pub equipment: Equipment,
+    /// Auras granted by set bonuses, active all fight
+    pub set_auras: Vec<AuraIdx>,
     pub gcd_end: SimTime,
     pub cast_end: Option<SimTime>,
     pub channel_end: Option<SimTime>,
@@ -29,7 +33,8 @@ pub struct Player {
     pub in_combat: bool,
     pub stealthed: bool,
     pub mounted: bool,
-    pub movement_duration: f64,
+    /// When the current movement window ends
+    pub movement_end: SimTime,
 }
 
 pub const DEFAULT_MAX_HEALTH: f64 = 1_000_000.0;
@@ -46,6 +51,8 @@ impl Player {
             cooldowns: HashMap::new(),
             charged_cooldowns: HashMap::new(),
             procs: ProcRegistry::new(),
+            equipment: Equipment::new(),
+            set_auras: Vec::new(),
             gcd_end: SimTime::ZERO,
             cast_end: None,
             channel_end: None,
@@ -59,7 +66,7 @@ impl Player {
             in_combat: true,
             stealthed: false,
             mounted: false,
-            movement_duration: 0.0,
+            movement_end: SimTime::ZERO,
         }
     }
 
@@ -76,7 +83,7 @@ impl Player {
         self.in_combat = true;
         self.stealthed = false;
         self.mounted = false;
-        self.movement_duration = 0.0;
+        self.movement_end = SimTime::ZERO;
 
         for cd in self.cooldowns.values_mut() {
             cd.reset();
@@ -86,6 +93,7 @@ impl Player {
         }
 
         self.procs.reset();
+        self.equipment.reset();
 
         if let Some(ref mut primary) = self.resources.primary {
             primary.current = primary.max;
@@ -95,6 +103,12 @@ impl Player {
         }
     }
 
+    /// Equip items, registering their passive effects as procs
+    pub fn equip(&mut self, equipment: Equipment) {
+        equipment.register_procs(&mut self.procs);
+        self.equipment = equipment;
+    }
+
     pub fn add_cooldown(&mut self, spell: SpellIdx, cooldown: Cooldown) {
         self.cooldowns.insert(spell, cooldown);
     }
@@ -241,6 +255,11 @@ impl PlayerBuilder {
         self
     }
 
+    pub fn with_equipment(mut self, equipment: Equipment) -> Self {
+        self.player.equip(equipment);
+        self
+    }
+
     pub fn with_dual_wield(mut self) -> Self {
         self.player.next_auto_oh = Some(SimTime::ZERO);
         self
@@ -1,5 +1,11 @@
 use super::*;
-use wowlab_common::types::{PetKind, SimTime, SpecId, TargetIdx, UnitIdx};
+use crate::combat::ArmorConstants;
+use crate::proc::ProcEffect;
+use wowlab_common::parsers::Slot;
+use wowlab_common::types::data::{ItemDataFlat, ItemEffect};
+use wowlab_common::types::{
+    AuraIdx, DamageSchool, PetKind, ProcIdx, SimTime, SpecId, SpellIdx, TargetIdx, UnitIdx,
+};
 
 #[test]
 fn player_basic() {
@@ -139,13 +145,28 @@ fn enemy_take_damage() {
 
 #[test]
 fn enemy_armor_mitigation() {
-    let enemy = Enemy::raid_boss(TargetIdx(0), "Boss");
+    let mut enemy = Enemy::raid_boss(TargetIdx(0), "Boss");
+    enemy.armor = 10_000.0;
+    enemy.level = 83;
+    let constants = ArmorConstants::new()
+        .with_level(80, 5_000.0)
+        .with_level(83, 10_000.0);
+
+    // Uses the constant for the enemy's level.
+    assert!((enemy.armor_mitigation(&constants) - 0.5).abs() < 1e-6);
+
+    enemy.level = 80;
+    assert!((enemy.armor_mitigation(&constants) - 2.0 / 3.0).abs() < 1e-6);
+}
 
-    let mitigation = enemy.armor_mitigation(80);
+#[test]
+fn enemy_resistances() {
+    let mut enemy = Enemy::raid_boss(TargetIdx(0), "Boss");
+    enemy.resistances = vec![(DamageSchool::Fire, 0.2), (DamageSchool::Fire, 0.5)];
+    let mitigation = enemy.mitigation(&ArmorConstants::default());
 
-    // Should be roughly 15-25% for boss armor at level 80
-    // Formula: armor / (armor + k) where k = level * 467.5 + 16593 for bosses
-    assert!(mitigation > 0.15 && mitigation < 0.25);
+    assert!((mitigation.damage_multiplier(DamageSchool::Fire) - 0.4).abs() < 1e-6);
+    assert_eq!(mitigation.damage_multiplier(DamageSchool::Frost), 1.0);
 }
 
 #[test]
@@ -177,3 +198,191 @@ fn enemy_time_to_die() {
     let ttd = enemy.time_to_die(10_000.0); // 10k DPS
     assert!((ttd.as_secs_f32() - 50.0).abs() < 0.1);
 }
+
+#[test]
+fn enemy_add_spawn() {
+    let mut manager = EnemyManager::with_bosses(1);
+    manager.add(Enemy::add(TargetIdx(1), "Add", 100_000.0));
+    assert_eq!(manager.alive_count(), 1);
+
+    manager.get_mut(TargetIdx(1)).unwrap().spawn();
+    assert_eq!(manager.alive_count(), 2);
+
+    manager.reset();
+    assert_eq!(manager.alive_count(), 1);
+}
+
+#[test]
+fn enemy_manager_retarget() {
+    let mut manager = EnemyManager::with_bosses(1);
+    manager.add(Enemy::add(TargetIdx(1), "Add", 100_000.0));
+    manager.get_mut(TargetIdx(0)).unwrap().invulnerable = true;
+
+    // Nothing attackable: stay on the living boss.
+    manager.retarget();
+    assert_eq!(manager.primary, TargetIdx(0));
+
+    manager.get_mut(TargetIdx(1)).unwrap().spawn();
+    manager.retarget();
+    assert_eq!(manager.primary, TargetIdx(1));
+
+    // The boss takes precedence once it can be attacked again.
+    manager.get_mut(TargetIdx(0)).unwrap().invulnerable = false;
+    manager.retarget();
+    assert_eq!(manager.primary, TargetIdx(0));
+}
+
+#[test]
+fn enemy_damage_rate() {
+    let mut enemy = Enemy::new(TargetIdx(0), "Boss");
+    assert_eq!(enemy.damage_rate(SimTime::from_secs(5)), 0.0);
+
+    enemy.record_damage_taken(SimTime::from_secs(2), 5_000.0);
+    enemy.record_damage_taken(SimTime::from_secs(7), 5_000.0);
+
+    assert!((enemy.damage_rate(SimTime::from_secs(7)) - 2_000.0).abs() < 0.01);
+    assert_eq!(enemy.current_health, enemy.max_health - 10_000.0);
+}
+
+fn trinket_item(id: i32, name: &str, effects: &[(i32, i32, i32, i32, i32)]) -> ItemDataFlat {
+    ItemDataFlat {
+        id,
+        name: name.to_string(),
+        effects: effects
+            .iter()
+            .map(
+                |&(spell_id, trigger_type, cooldown, category_cooldown, category_id)| ItemEffect {
+                    spell_id,
+                    trigger_type,
+                    charges: 0,
+                    cooldown,
+                    category_cooldown,
+                    category_id,
+                },
+            )
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn item_token_normalizes_names() {
+    assert_eq!(item_token("Algeth'ar Puzzle Box"), "algethar_puzzle_box");
+    assert_eq!(item_token("Mad Queen's Mandate"), "mad_queens_mandate");
+    assert_eq!(
+        item_token("Skardyn's Grace - Rank 2"),
+        "skardyns_grace_rank_2"
+    );
+}
+
+#[test]
+fn trinket_from_item_effects() {
+    let item = trinket_item(
+        193701,
+        "Algeth'ar Puzzle Box",
+        &[
+            (383781, 0, 120_000, 20_000, 1141),
+            (999, 1, 0, 0, 0),
+            (777, 2, 30_000, 0, 0),
+            (0, 2, 0, 0, 0),
+        ],
+    );
+    let trinket = Trinket::from_item(&item);
+
+    assert_eq!(trinket.name, "algethar_puzzle_box");
+    assert!(trinket.matches("algethar_puzzle_box"));
+    assert!(trinket.matches("193701"));
+    let on_use = trinket.on_use.unwrap();
+    assert_eq!(on_use.spell, SpellIdx(383781));
+    assert_eq!(on_use.cooldown.base_duration, SimTime::from_secs(120));
+    assert_eq!(on_use.category, 1141);
+    // On-equip effects are auras, not procs
+    assert_eq!(trinket.passives, vec![AuraIdx(999)]);
+    assert_eq!(
+        trinket.procs,
+        vec![TrinketProc {
+            spell: SpellIdx(777),
+            icd: Some(SimTime::from_secs(30))
+        }]
+    );
+
+    // No cooldown at all: not usable
+    let item = trinket_item(1, "Free Lunch", &[(5, 0, 0, 0, 0)]);
+    assert!(Trinket::from_item(&item).on_use.is_none());
+}
+
+#[test]
+fn equipment_trinket_cooldown_and_category_lockout() {
+    let mut equipment = Equipment::new();
+    equipment.equip(
+        Slot::Trinket1,
+        &trinket_item(1, "Box", &[(11, 0, 120_000, 20_000, 7)]),
+    );
+    equipment.equip(
+        Slot::Trinket2,
+        &trinket_item(2, "Orb", &[(22, 0, 90_000, 20_000, 7)]),
+    );
+    equipment.equip(Slot::Head, &trinket_item(3, "Helm", &[]));
+
+    assert!(equipment.is_equipped("helm"));
+    assert!(equipment.is_equipped("2"));
+    assert!(!equipment.is_equipped("sword"));
+
+    let now = SimTime::ZERO;
+    assert!(equipment.trinket_ready(1, now));
+    assert_eq!(equipment.use_trinket(1, now), Some(SpellIdx(11)));
+    assert_eq!(equipment.use_trinket(1, now), None);
+    assert_eq!(equipment.trinket_remaining(1, now), SimTime::from_secs(120));
+
+    // Slot 2 shares the category: locked out for 20s
+    assert!(!equipment.trinket_ready(2, now));
+    assert_eq!(equipment.trinket_remaining(2, now), SimTime::from_secs(20));
+    assert_eq!(
+        equipment.use_trinket(2, SimTime::from_secs(20)),
+        Some(SpellIdx(22))
+    );
+
+    equipment.reset();
+    assert!(equipment.trinket_ready(1, now));
+    assert!(equipment.trinket_ready(2, now));
+    assert!(!equipment.trinket_ready(3, now));
+}
+
+#[test]
+fn equipment_replacing_item_forgets_old_one() {
+    let mut equipment = Equipment::new();
+    equipment.equip(
+        Slot::Trinket1,
+        &trinket_item(1, "Box", &[(11, 0, 120_000, 0, 0)]),
+    );
+    equipment.equip(Slot::Trinket1, &trinket_item(2, "Orb", &[]));
+    assert!(!equipment.is_equipped("box"));
+    assert!(!equipment.is_equipped("1"));
+    assert!(equipment.is_equipped("orb"));
+
+    let equipment = equipment.with_trinket(1, Trinket::from_item(&trinket_item(3, "Charm", &[])));
+    assert!(!equipment.is_equipped("orb"));
+    assert!(equipment.is_equipped("3"));
+}
+
+#[test]
+fn equipment_registers_trinket_procs() {
+    let mut player = Player::new(SpecId::BeastMastery);
+    let mut equipment = Equipment::new();
+    equipment.equip(
+        Slot::Trinket2,
+        &trinket_item(1, "Charm", &[(33, 2, 15_000, 0, 0)]),
+    );
+    player.equip(equipment);
+
+    let handler = player.procs.get_handler(ProcIdx(1016)).unwrap();
+    assert_eq!(handler.name, "Trinket 2");
+    assert!(matches!(
+        handler.effect,
+        ProcEffect::CastSpell {
+            spell: SpellIdx(33)
+        }
+    ));
+    let rppm = player.procs.get_rppm_mut(ProcIdx(1016)).unwrap();
+    assert_eq!(rppm.icd, Some(SimTime::from_secs(15)));
+}
@@ -14,18 +14,24 @@ pub mod shared;
 
 pub use focus::{focus_regen_rate, regenerate_focus, FOCUS_MAX, FOCUS_REGEN_BASE};
 pub use pet::{
-    calculate_pet_damage, default_pet_attack, PET_ATTACK_SPEED, PET_AUTO_ATTACK_COEF,
+    calculate_pet_damage, default_pet_attack, PET_ATTACK_SPEED, PET_AUTO_ATTACK_COEF, PET_MELEE,
     PET_STAT_INHERITANCE,
 };
 pub use shared::{
     calculate_kill_shot_damage, can_use_kill_shot, ranged_attack_speed, ARCANE_SHOT,
-    ASPECT_OF_THE_CHEETAH, ASPECT_OF_THE_TURTLE, KILL_SHOT, KILL_SHOT_AP_COEF, KILL_SHOT_COOLDOWN,
-    KILL_SHOT_COST, KILL_SHOT_THRESHOLD, RANGED_ATTACK_SPEED, STEADY_SHOT, TRANQUILIZING_SHOT,
+    ASPECT_OF_THE_CHEETAH, ASPECT_OF_THE_TURTLE, AUTO_SHOT, KILL_SHOT, KILL_SHOT_AP_COEF,
+    KILL_SHOT_COOLDOWN, KILL_SHOT_COST, KILL_SHOT_THRESHOLD, RANGED_ATTACK_SPEED, STEADY_SHOT,
+    TRANQUILIZING_SHOT,
 };
 
+use crate::combat::DamageMultipliers;
 use crate::handler::SpecHandler;
 use crate::sim::SimState;
-use wowlab_common::types::{DamageSchool, TargetIdx, UnitIdx};
+use wowlab_common::types::{DamageSchool, SpellIdx, TargetIdx, UnitIdx};
+
+/// Names for Hunter damage that has no spell definition.
+pub const HUNTER_DAMAGE_NAMES: &[(SpellIdx, &str)] =
+    &[(AUTO_SHOT, "Auto Shot"), (PET_MELEE, "Pet Melee")];
 
 /// Shared behavior for all Hunter specs.
 ///
@@ -37,13 +43,11 @@ use wowlab_common::types::{DamageSchool, TargetIdx, UnitIdx};
 ///
 /// ```ignore
 /// impl HunterClass for BmHunter {
-///     // Override pet damage modifier for BM-specific bonuses
-///     fn pet_damage_modifier(&self, state: &SimState) -> f32 {
-///         let base = 1.0;
+///     // Add BM-specific pet damage bonuses
+///     fn pet_damage_multipliers(&self, state: &SimState, multipliers: &mut DamageMultipliers) {
 ///         if state.player.buffs.has(BESTIAL_WRATH_BUFF, state.now()) {
-///             base * 2.0  // Bestial Wrath doubles pet damage
-///         } else {
-///             base
+///             // Bestial Wrath doubles pet damage
+///             multipliers.apply_pet(MultiplierSource::Aura(BESTIAL_WRATH_BUFF), 2.0);
 ///         }
 ///     }
 /// }
@@ -64,12 +68,10 @@ pub trait HunterClass: SpecHandler {
         self.base_focus_regen() * haste
     }
 
-    /// Pet damage modifier (multiplier applied to all pet damage).
+    /// Pet damage multipliers on top of versatility and mastery.
     ///
     /// Override for specs with pet damage bonuses (e.g., BM's Bestial Wrath).
-    fn pet_damage_modifier(&self, _state: &SimState) -> f32 {
-        1.0
-    }
+    fn pet_damage_multipliers(&self, _state: &SimState, _multipliers: &mut DamageMultipliers) {}
 
     /// Pet attack speed modifier (multiplier for attack speed).
     ///
@@ -96,9 +98,11 @@ pub trait HunterClass: SpecHandler {
         }
 
         // Calculate damage with modifiers
-        let damage =
-            calculate_pet_damage(state, PET_AUTO_ATTACK_COEF, self.pet_damage_modifier(state));
-        state.record_damage(damage);
+        let target = state.enemies.primary;
+        let mut multipliers = state.damage_multipliers(DamageSchool::Physical, false, true, target);
+        self.pet_damage_multipliers(state, &mut multipliers);
+        let (damage, result) = calculate_pet_damage(state, PET_AUTO_ATTACK_COEF, &multipliers);
+        state.record_pet_damage(PET_MELEE, target, damage, result, false);
 
         damage
     }
@@ -126,9 +130,9 @@ pub trait HunterClass: SpecHandler {
     /// Execute Kill Shot on a target.
     ///
     /// Returns the damage dealt.
-    fn do_kill_shot(&self, state: &mut SimState, _target: TargetIdx) -> f32 {
-        let damage = calculate_kill_shot_damage(state);
-        state.record_damage(damage);
+    fn do_kill_shot(&self, state: &mut SimState, target: TargetIdx) -> f32 {
+        let (damage, result) = calculate_kill_shot_damage(state);
+        state.record_spell_damage(KILL_SHOT, target, damage, result, false);
         damage
     }
 
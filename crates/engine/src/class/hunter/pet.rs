@@ -3,10 +3,13 @@
 //! All Hunter specs have pets with similar base mechanics. This module
 //! provides shared constants and default behavior that specs can override.
 
-use crate::combat::DamagePipeline;
+use crate::combat::{Attack, DamageMultipliers, DamagePipeline};
 use crate::core::SimEvent;
 use crate::sim::SimState;
-use wowlab_common::types::{DamageSchool, SimTime, UnitIdx};
+use wowlab_common::types::{DamageSchool, HitResult, SimTime, SpellIdx, UnitIdx};
+
+/// Pet auto-attack (pseudo spell ID used for damage attribution).
+pub const PET_MELEE: SpellIdx = SpellIdx(100001);
 
 /// Base pet attack speed (ms).
 pub const PET_ATTACK_SPEED: SimTime = SimTime::from_millis(2000);
@@ -17,16 +20,26 @@ pub const PET_STAT_INHERITANCE: f32 = 0.6;
 /// Pet auto-attack AP coefficient (base).
 pub const PET_AUTO_ATTACK_COEF: f32 = 0.5;
 
-/// Calculate base pet damage.
+/// Calculate base pet auto-attack damage.
 ///
-/// This uses the owner's attack power scaled by inheritance and coefficient.
-pub fn calculate_pet_damage(state: &mut SimState, ap_coef: f32, damage_multiplier: f32) -> f32 {
+/// This uses the owner's attack power scaled by inheritance and coefficient,
+/// and `multipliers` from [`SimState::damage_multipliers`] plus any spec
+/// bonuses. Returns the damage and the attack table outcome.
+pub fn calculate_pet_damage(
+    state: &mut SimState,
+    ap_coef: f32,
+    multipliers: &DamageMultipliers,
+) -> (f32, HitResult) {
     let ap = state.player.stats.attack_power();
     let sp = state.player.stats.spell_power();
     let crit = state.player.stats.crit_chance();
-    let armor = state.enemies.primary().map(|e| e.armor).unwrap_or(0.0);
+    let mitigation = state.mitigation(state.enemies.primary);
+    let table = state
+        .pet_attack_table(Attack::melee().auto(), state.enemies.primary)
+        .with_crit(crit);
 
     let inherited_coef = ap_coef * PET_STAT_INHERITANCE;
+    state.audit_multipliers(multipliers);
 
     let result = DamagePipeline::calculate(
         0.0,            // base damage
@@ -34,14 +47,14 @@ pub fn calculate_pet_damage(state: &mut SimState, ap_coef: f32, damage_multiplie
         0.0,            // SP coefficient
         ap,
         sp,
-        &state.multipliers,
-        crit,
+        multipliers,
+        &table,
         DamageSchool::Physical,
-        armor,
+        &mitigation,
         &mut state.rng,
     );
 
-    result.final_amount * damage_multiplier
+    (result.final_amount, result.hit_result)
 }
 
 /// Default pet auto-attack behavior.
@@ -52,7 +65,7 @@ pub fn default_pet_attack(
     state: &mut SimState,
     pet: UnitIdx,
     attack_speed_modifier: f32,
-    damage_multiplier: f32,
+    multipliers: &DamageMultipliers,
 ) {
     let now = state.now();
 
@@ -67,8 +80,9 @@ pub fn default_pet_attack(
     }
 
     // Calculate and record damage
-    let damage = calculate_pet_damage(state, PET_AUTO_ATTACK_COEF, damage_multiplier);
-    state.record_damage(damage);
+    let (damage, result) = calculate_pet_damage(state, PET_AUTO_ATTACK_COEF, multipliers);
+    let target = state.enemies.primary;
+    state.record_pet_damage(PET_MELEE, target, damage, result, false);
 
     // Schedule next attack if simulation continues
     if !state.finished {
@@ -3,9 +3,12 @@
 //! Abilities like Kill Shot, Tranquilizing Shot, and aspects are available
 //! to all Hunter specs with the same base implementation.
 
-use crate::combat::DamagePipeline;
+use crate::combat::{Attack, DamagePipeline};
 use crate::sim::SimState;
-use wowlab_common::types::{AuraIdx, DamageSchool, SimTime, SpellIdx, TargetIdx};
+use wowlab_common::types::{AuraIdx, DamageSchool, HitResult, SimTime, SpellIdx, TargetIdx};
+
+/// Auto Shot - Ranged auto-attack
+pub const AUTO_SHOT: SpellIdx = SpellIdx(75);
 
 /// Kill Shot - Execute ability available below 20% health
 pub const KILL_SHOT: SpellIdx = SpellIdx(53351);
@@ -50,11 +53,17 @@ pub fn can_use_kill_shot(state: &SimState, target: TargetIdx) -> bool {
 /// Calculate Kill Shot damage.
 ///
 /// Kill Shot deals high damage to targets below 20% health.
-pub fn calculate_kill_shot_damage(state: &mut SimState) -> f32 {
+pub fn calculate_kill_shot_damage(state: &mut SimState) -> (f32, HitResult) {
     let ap = state.player.stats.attack_power();
     let sp = state.player.stats.spell_power();
     let crit = state.player.stats.crit_chance();
-    let armor = state.enemies.primary().map(|e| e.armor).unwrap_or(0.0);
+    let mitigation = state.mitigation(state.enemies.primary);
+    let table = state
+        .attack_table(Attack::ranged(), state.enemies.primary)
+        .with_crit(crit);
+    let multipliers =
+        state.damage_multipliers(DamageSchool::Physical, false, false, state.enemies.primary);
+    state.audit_multipliers(&multipliers);
 
     let result = DamagePipeline::calculate(
         0.0,
@@ -62,14 +71,14 @@ pub fn calculate_kill_shot_damage(state: &mut SimState) -> f32 {
         0.0,
         ap,
         sp,
-        &state.multipliers,
-        crit,
+        &multipliers,
+        &table,
         DamageSchool::Physical,
-        armor,
+        &mitigation,
         &mut state.rng,
     );
 
-    result.final_amount
+    (result.final_amount, result.hit_result)
 }
 
 /// Base ranged auto-attack speed (ms).
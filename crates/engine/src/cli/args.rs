@@ -1,3 +1,8 @@
+use crate::actor::DEFAULT_ENEMY_LEVEL;
+use crate::combat::AttackPosition;
+use crate::results::TraceFormat;
+use crate::rotation::RotationBackend;
+use crate::sim::FightStyle;
 use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
@@ -9,6 +14,7 @@ pub struct Args {
 }
 
 #[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)] // Parsed once at startup
 pub enum Command {
     /// Run a simulation
     Sim {
@@ -28,6 +34,30 @@ pub enum Command {
         #[arg(short, long, default_value = "1")]
         targets: usize,
 
+        /// Prepull window in seconds (runs the rotation's precombat list)
+        #[arg(long, default_value = "0")]
+        prepull: f32,
+
+        /// Fight style (Patchwerk, HecticAddCleave, LightMovement, DungeonSlice)
+        #[arg(long, default_value_t = FightStyle::default())]
+        fight_style: FightStyle,
+
+        /// Boss health; ends the fight when every boss dies (duration becomes a cap)
+        #[arg(long)]
+        target_health: Option<f32>,
+
+        /// Vary each iteration's fight length (or boss health) by up to this fraction
+        #[arg(long, default_value = "0")]
+        vary_combat_length: f32,
+
+        /// Where the player attacks from (back or front)
+        #[arg(long, default_value_t = AttackPosition::default())]
+        position: AttackPosition,
+
+        /// Boss level
+        #[arg(long, default_value_t = DEFAULT_ENEMY_LEVEL)]
+        target_level: u8,
+
         /// Number of threads (defaults to optimal for your CPU)
         #[arg(long)]
         threads: Option<usize>,
@@ -44,23 +74,65 @@ pub enum Command {
         #[arg(long)]
         rotation: Option<String>,
 
+        /// Rotation backend (jit or interpreter)
+        #[arg(long, default_value_t = RotationBackend::default())]
+        rotation_backend: RotationBackend,
+
         /// Gear profile file
         #[arg(long)]
         gear: Option<String>,
 
+        /// SimC profile (/simc export) to take gear and talents from
+        #[arg(long, conflicts_with = "gear")]
+        simc: Option<String>,
+
         /// Enable detailed trace
         #[arg(long)]
         trace: bool,
+
+        /// Write the first iteration's combat trace to this file
+        #[arg(long)]
+        trace_file: Option<String>,
+
+        /// Trace file format (jsonl or log)
+        #[arg(long, default_value_t = TraceFormat::default())]
+        trace_format: TraceFormat,
+
+        /// In-game talent loadout string
+        #[arg(long)]
+        talents: Option<String>,
+
+        /// Game data directory for --talents, --simc and armor constants
+        /// (defaults to $WOWLAB_DATA_DIR)
+        #[arg(long)]
+        data_dir: Option<String>,
+
+        /// Game data snapshot, used instead of --data-dir
+        /// (defaults to $WOWLAB_SNAPSHOT)
+        #[arg(long)]
+        snapshot: Option<String>,
+
+        /// Compute stat weights instead of a plain batch
+        #[arg(long)]
+        stat_weights: bool,
+
+        /// Points added to each stat for stat weights
+        #[arg(long, default_value = "1000")]
+        scale_delta: f32,
     },
 
     /// List available specs
     Specs,
 
-    /// Validate a rotation script
+    /// Validate a rotation script (JSON or text syntax)
     Validate {
         /// Rotation script file
         #[arg(short, long)]
         file: String,
+
+        /// Print the rotation back in text syntax
+        #[arg(long)]
+        print: bool,
     },
 
     /// Show version info
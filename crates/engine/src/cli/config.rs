@@ -1,3 +1,4 @@
+use crate::sim::{FightStyle, RaidEvent};
 use crate::stats::StatCache;
 use serde::{Deserialize, Serialize};
 use wowlab_common::types::{RatingType, SpecId};
@@ -69,8 +70,12 @@ pub struct FightConfig {
     pub duration: f32,
     /// Number of targets
     pub targets: usize,
-    /// Fight type name
-    pub fight_type: String,
+    /// Fight style preset
+    #[serde(default)]
+    pub fight_style: FightStyle,
+    /// Extra raid events on top of the fight style's
+    #[serde(default)]
+    pub raid_events: Vec<RaidEvent>,
 }
 
 impl Default for FightConfig {
@@ -78,7 +83,8 @@ impl Default for FightConfig {
         Self {
             duration: 300.0,
             targets: 1,
-            fight_type: "Patchwerk".to_string(),
+            fight_style: FightStyle::Patchwerk,
+            raid_events: Vec::new(),
         }
     }
 }
@@ -9,7 +9,8 @@ use tabled::{
 };
 
 use super::OutputFormat;
-use crate::sim::{BatchResults, Simulation};
+use crate::results::ResultsExporter;
+use crate::sim::{BatchResults, Simulation, StatWeights};
 
 /// Get number of CPU cores available for parallel simulation
 pub fn num_cores() -> usize {
@@ -105,6 +106,37 @@ impl Output {
         }
     }
 
+    /// Display stat weights.
+    pub fn stat_weights(&self, weights: &StatWeights, format: OutputFormat) {
+        match format {
+            OutputFormat::Text => self.stat_weights_text(weights),
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(weights).unwrap());
+            }
+            OutputFormat::Csv => {
+                println!("stat,factor,std_error,weight");
+                for f in &weights.factors {
+                    println!(
+                        "{},{:.4},{:.4},{:.4}",
+                        f.stat, f.factor, f.std_error, f.weight
+                    );
+                }
+            }
+        }
+    }
+
+    fn stat_weights_text(&self, weights: &StatWeights) {
+        self.blank();
+        self.header("Stat Weights");
+        self.kv(
+            "Base DPS",
+            &format!("{:.2} ±{:.2}", weights.base_dps, weights.base_std_error),
+        );
+        self.kv("Delta", &format!("+{:.0}", weights.delta));
+        eprintln!("{}", weights.to_table());
+        self.kv("Pawn", &weights.pawn);
+    }
+
     fn single_result_text(&self, sim: &Simulation) {
         self.header("Results");
 
@@ -113,7 +145,7 @@ impl Output {
             ResultRow::new("Total Damage", format!("{:.0}", sim.total_damage())),
             ResultRow::new(
                 "Duration",
-                format!("{:.1}s", sim.state.config.duration.as_secs_f32()),
+                format!("{:.1}s", sim.state.combat_time().as_secs_f32()),
             ),
         ];
 
@@ -129,7 +161,7 @@ impl Output {
         let json = serde_json::json!({
             "dps": format!("{:.2}", sim.dps()).parse::<f64>().unwrap_or(0.0),
             "damage": sim.total_damage() as u64,
-            "duration": sim.state.config.duration.as_secs_f32(),
+            "duration": sim.state.combat_time().as_secs_f32(),
         });
         println!("{}", serde_json::to_string_pretty(&json).unwrap());
     }
@@ -140,7 +172,7 @@ impl Output {
             "{:.2},{:.0},{:.2}",
             sim.dps(),
             sim.total_damage(),
-            sim.state.config.duration.as_secs_f32(),
+            sim.state.combat_time().as_secs_f32(),
         );
     }
 
@@ -164,6 +196,10 @@ impl Output {
 
         eprintln!("{}", table);
 
+        if let Some(ref breakdown) = results.breakdown {
+            eprintln!("{}", breakdown.to_table());
+        }
+
         // Summary line with core count
         let iter_per_sec = results.iterations as f64 / elapsed.as_secs_f64();
         let num_cores = rayon::current_num_threads();
@@ -189,14 +225,15 @@ impl Output {
             "parallelism": {
                 "cores": rayon::current_num_threads(),
             },
+            "breakdown": results.breakdown,
         });
         println!("{}", serde_json::to_string_pretty(&json).unwrap());
     }
 
     fn batch_result_csv(&self, results: &BatchResults) {
-        println!("iteration,dps");
-        for (i, &dps) in results.dps_values.iter().enumerate() {
-            println!("{},{:.2}", i + 1, dps);
+        match results.breakdown {
+            Some(ref breakdown) => print!("{}", ResultsExporter::breakdown_to_csv(breakdown)),
+            None => print!("{}", ResultsExporter::to_csv(results)),
         }
     }
 
@@ -1,15 +1,33 @@
 use super::{banner, Args, Command, GearConfig, Output, OutputFormat, SpecArg};
-use crate::actor::Player;
-use crate::handler::{create_handler, SpecHandler};
-use crate::rotation::Rotation;
-use crate::sim::{BatchResults, BatchRunner, ExactProgress, SimConfig, Simulation};
+use crate::actor::{Equipment, Player};
+use crate::combat::{ArmorConstants, AttackPosition};
+use crate::data::{create_resolver, load_armor_constants, DataResolver, ResolverConfig};
+use crate::handler::{create_handler_with_backend, create_handler_with_gear, SpecHandler};
+use crate::results::{ResultsExporter, TraceExporter, TraceFormat};
+use crate::rotation::{Rotation, RotationBackend};
+use crate::sim::{
+    BatchResults, BatchRunner, ExactProgress, FightStyle, SimConfig, Simulation, StatWeightRunner,
+};
+use crate::spec::{SetBonuses, TalentLoadout};
+use crate::stats::GearStats;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use tracing::{debug, info, instrument};
+use wowlab_common::parsers::parse_simc;
+use wowlab_common::types::SpecId;
 
 pub struct Runner;
 
+/// What a SimC profile contributes to a sim
+struct SimcProfile {
+    gear: GearStats,
+    sets: SetBonuses,
+    equipment: Equipment,
+    talents: Option<String>,
+}
+
 impl Runner {
     pub fn run(args: Args) -> Result<(), String> {
         match args.command {
@@ -18,19 +36,56 @@ impl Runner {
                 duration,
                 iterations,
                 targets,
+                prepull,
+                fight_style,
+                target_health,
+                vary_combat_length,
+                position,
+                target_level,
                 seed,
                 output,
                 rotation,
+                rotation_backend,
                 gear,
+                simc,
+                talents,
+                data_dir,
+                snapshot,
                 trace,
+                trace_file,
+                trace_format,
+                stat_weights,
+                scale_delta,
                 threads: _, // Handled in main.rs before run()
             } => Self::run_sim(
-                spec, duration, iterations, targets, seed, output, rotation, gear, trace,
+                spec,
+                duration,
+                iterations,
+                targets,
+                prepull,
+                fight_style,
+                target_health,
+                vary_combat_length,
+                position,
+                target_level,
+                seed,
+                output,
+                rotation,
+                rotation_backend,
+                gear,
+                simc,
+                talents,
+                data_dir,
+                snapshot,
+                trace,
+                trace_file,
+                trace_format,
+                stat_weights.then_some(scale_delta),
             ),
 
             Command::Specs => Self::list_specs(),
 
-            Command::Validate { file } => Self::validate_rotation(&file),
+            Command::Validate { file, print } => Self::validate_rotation(&file, print),
 
             Command::Version => {
                 println!("engine_new v{}", env!("CARGO_PKG_VERSION"));
@@ -46,11 +101,25 @@ impl Runner {
         duration: f32,
         iterations: u32,
         targets: usize,
+        prepull: f32,
+        fight_style: FightStyle,
+        target_health: Option<f32>,
+        vary_combat_length: f32,
+        position: AttackPosition,
+        target_level: u8,
         seed: Option<u64>,
         output_format: OutputFormat,
         rotation_file: Option<String>,
+        rotation_backend: RotationBackend,
         gear_file: Option<String>,
+        simc_file: Option<String>,
+        talents: Option<String>,
+        data_dir: Option<String>,
+        snapshot: Option<String>,
         trace: bool,
+        trace_file: Option<String>,
+        trace_format: TraceFormat,
+        scale_delta: Option<f32>,
     ) -> Result<(), String> {
         let out = Output::new();
 
@@ -63,27 +132,106 @@ impl Runner {
         info!(spec = ?spec, iterations, targets, duration_secs = duration, "Starting simulation");
 
         let spec_id = spec.to_spec_id();
+        let game_data = Self::game_data(data_dir, snapshot)?;
 
         // Load rotation script
         let rotation_script = Self::load_rotation_script(spec, rotation_file.as_deref())?;
 
-        // Create handler with rotation
-        let handler = create_handler(spec_id, &rotation_script)?;
+        // Load gear, set bonuses, equipment and talents from a SimC profile
+        let (simc_gear, sets, equipment, talents) = match simc_file {
+            Some(ref path) => {
+                let SimcProfile {
+                    gear,
+                    sets,
+                    equipment,
+                    talents: profile_talents,
+                } = Self::load_simc_profile(spec_id, path, game_data.as_deref())?;
+                if matches!(output_format, OutputFormat::Text) {
+                    out.kv(
+                        "Gear",
+                        &format!(
+                            "{} items, average item level {:.1}",
+                            gear.items.len(),
+                            gear.average_item_level()
+                        ),
+                    );
+                    if !gear.unresolved.is_empty() {
+                        out.kv("Unresolved gear", &gear.unresolved.join(", "));
+                    }
+                    out.blank();
+                }
+                (Some(gear), sets, equipment, talents.or(profile_talents))
+            }
+            None => (None, SetBonuses::default(), Equipment::new(), talents),
+        };
 
-        // Load gear
-        let gear = if let Some(ref path) = gear_file {
-            debug!(path, "Loading gear configuration");
-            GearConfig::from_file(path)?
+        // Armor constants by target level, when game data is available
+        let armor_constants = Self::load_armor_constants(game_data.as_deref())?;
+
+        // Create handler with rotation, talents and set bonuses
+        let mut set_auras = Vec::new();
+        let handler = if talents.is_some() || !sets.is_empty() {
+            let loadout = match talents {
+                Some(loadout) => Self::decode_loadout(spec_id, &loadout, game_data.as_deref())?,
+                None => TalentLoadout::default(),
+            };
+            let (handler, report, set_report) = create_handler_with_gear(
+                spec_id,
+                &rotation_script,
+                rotation_backend,
+                &loadout,
+                &sets,
+            )?;
+            info!(
+                talents = report.applied.len(),
+                set_bonuses = set_report.applied.len(),
+                "Talents and set bonuses applied"
+            );
+            if matches!(output_format, OutputFormat::Text) {
+                if !report.unimplemented.is_empty() {
+                    let names: Vec<_> = report
+                        .unimplemented
+                        .iter()
+                        .map(|t| format!("{} ({})", t.name, t.spell_id))
+                        .collect();
+                    out.kv("Unimplemented talents", &names.join(", "));
+                }
+                if !set_report.applied.is_empty() {
+                    let names: Vec<_> =
+                        set_report.applied.iter().map(|b| b.name.as_str()).collect();
+                    out.kv("Set bonuses", &names.join(", "));
+                }
+                if !set_report.unimplemented.is_empty() {
+                    let names: Vec<_> = set_report
+                        .unimplemented
+                        .iter()
+                        .map(|b| format!("{} ({})", b.name, b.spell_id))
+                        .collect();
+                    out.kv("Unimplemented set bonuses", &names.join(", "));
+                }
+                out.blank();
+            }
+            set_auras = set_report.auras;
+            handler
         } else {
-            debug!("Using default gear configuration");
-            GearConfig::default()
+            create_handler_with_backend(spec_id, &rotation_script, rotation_backend)?
         };
 
         // Setup player
         let mut player = Player::new(spec_id);
+        player.set_auras = set_auras;
+        player.equip(equipment);
 
         // Apply gear stats
-        gear.apply_to(&mut player.stats, spec_id);
+        if let Some(gear) = simc_gear {
+            gear.apply_to(&mut player.stats);
+        } else if let Some(ref path) = gear_file {
+            debug!(path, "Loading gear configuration");
+            GearConfig::from_file(path)?.apply_to(&mut player.stats, spec_id);
+        } else {
+            debug!("Using default gear configuration");
+            GearConfig::default().apply_to(&mut player.stats, spec_id);
+        }
         // Compute derived combat stats
         player.stats.update(1.0);
         debug!(
@@ -94,7 +242,18 @@ impl Runner {
         );
 
         // Setup config
-        let mut config = SimConfig::default().with_duration(duration);
+        let mut config = SimConfig::default()
+            .with_duration(duration)
+            .with_prepull(prepull)
+            .with_fight_style(fight_style)
+            .with_vary_combat_length(vary_combat_length)
+            .with_position(position)
+            .with_target_level(target_level)
+            .with_armor_constants(armor_constants);
+
+        if let Some(health) = target_health {
+            config = config.with_target_health(health);
+        }
 
         if let Some(s) = seed {
             config = config.with_seed(s);
@@ -108,6 +267,31 @@ impl Runner {
 
         config.target_count = targets;
 
+        if let Some(ref path) = trace_file {
+            Self::export_trace(&handler, &config, &player, path, trace_format)?;
+            if matches!(output_format, OutputFormat::Text) {
+                out.kv("Trace", path);
+                out.blank();
+            }
+        }
+
+        if let Some(delta) = scale_delta {
+            if delta <= 0.0 {
+                return Err("scale delta must be positive".to_string());
+            }
+            debug!(delta, "Computing stat weights");
+            let runner =
+                BatchRunner::with_handler(handler, config, player).with_iterations(iterations);
+            let weights = StatWeightRunner::new(runner).with_delta(delta).run();
+            info!(
+                base_dps = weights.base_dps,
+                iterations = weights.iterations,
+                "Stat weights complete"
+            );
+            out.stat_weights(&weights, output_format);
+            return Ok(());
+        }
+
         // Run simulation
         if iterations == 1 {
             debug!("Running single iteration");
@@ -148,8 +332,9 @@ impl Runner {
         let num_threads = rayon::current_num_threads();
 
         // Create batch runner
-        let runner =
-            BatchRunner::with_handler(handler, config, player_template).with_iterations(iterations);
+        let runner = BatchRunner::with_handler(handler, config, player_template)
+            .with_iterations(iterations)
+            .with_breakdown();
 
         // Create progress tracker
         let progress = Arc::new(ExactProgress::new(iterations));
@@ -204,6 +389,147 @@ impl Runner {
         (results, elapsed)
     }
 
+    /// Run one traced iteration (same seed as the batch's first) and write it out
+    fn export_trace(
+        handler: &Arc<dyn SpecHandler>,
+        config: &SimConfig,
+        player: &Player,
+        path: &str,
+        format: TraceFormat,
+    ) -> Result<(), String> {
+        let mut sim = Simulation::new(
+            Arc::clone(handler),
+            config.clone().with_trace(),
+            player.clone(),
+        );
+        sim.run();
+
+        let content = TraceExporter::new(&sim).export(format);
+        ResultsExporter::write_to_file(Path::new(path), &content)
+            .map_err(|e| format!("Failed to write trace file: {}", e))?;
+        info!(path, events = sim.state.trace.len(), %format, "Trace written");
+        Ok(())
+    }
+
+    /// Decode a loadout string against the spec's trait tree from game data
+    fn decode_loadout(
+        spec_id: SpecId,
+        loadout: &str,
+        game_data: Option<&dyn DataResolver>,
+    ) -> Result<TalentLoadout, String> {
+        let resolver = Self::require_game_data(game_data, "--talents")?;
+        debug!("Loading trait tree");
+
+        let runtime = Self::runtime()?;
+        let tree = runtime
+            .block_on(resolver.get_trait_tree(spec_id.wow_spec_id() as i32))
+            .map_err(|e| format!("Failed to load trait tree: {}", e))?;
+
+        TalentLoadout::decode(loadout, &tree)
+    }
+
+    /// Scale a SimC profile's equipment and find its set bonuses; also returns
+    /// its talent string, if any
+    fn load_simc_profile(
+        spec_id: SpecId,
+        path: &str,
+        game_data: Option<&dyn DataResolver>,
+    ) -> Result<SimcProfile, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let profile =
+            parse_simc(&content).map_err(|e| format!("Failed to parse SimC profile: {}", e))?;
+        let resolver = Self::require_game_data(game_data, "--simc")?;
+        debug!(path, items = profile.equipment.len(), "Loading SimC gear");
+
+        let runtime = Self::runtime()?;
+        let scaling = runtime
+            .block_on(resolver.get_item_scaling_data())
+            .map_err(|e| format!("Failed to load item scaling data: {}", e))?;
+        let item = |id: u32| runtime.block_on(resolver.get_item(id as i32)).ok();
+        let gear = GearStats::from_profile(&profile, spec_id, &scaling, item);
+
+        let items: Vec<_> = gear.items.iter().filter_map(|i| item(i.item_id)).collect();
+        let sets = SetBonuses::from_items(&items, spec_id.wow_spec_id()).with_names(|id| {
+            runtime
+                .block_on(resolver.get_spell(id))
+                .ok()
+                .map(|spell| spell.name)
+        });
+
+        let mut equipment = Equipment::new();
+        for equipped in &profile.equipment {
+            if let Some(data) = item(equipped.id) {
+                equipment.equip(equipped.slot, &data);
+            }
+        }
+
+        let talents = Some(profile.talents.encoded).filter(|t| !t.is_empty());
+        Ok(SimcProfile {
+            gear,
+            sets,
+            equipment,
+            talents,
+        })
+    }
+
+    /// Armor constants from the game's ExpectedStat table, or the level 80
+    /// default without game data
+    fn load_armor_constants(
+        game_data: Option<&dyn DataResolver>,
+    ) -> Result<ArmorConstants, String> {
+        let Some(resolver) = game_data else {
+            debug!("No game data, using the default armor constant");
+            return Ok(ArmorConstants::default());
+        };
+        Self::runtime()?
+            .block_on(load_armor_constants(resolver))
+            .map_err(|e| format!("Failed to load expected stats: {}", e))
+    }
+
+    /// Game data from `--snapshot`/`$WOWLAB_SNAPSHOT`, or else from
+    /// `--data-dir`/`$WOWLAB_DATA_DIR`
+    fn game_data(
+        data_dir: Option<String>,
+        snapshot: Option<String>,
+    ) -> Result<Option<Arc<dyn DataResolver>>, String> {
+        let config = if let Some(path) = snapshot.or_else(|| std::env::var("WOWLAB_SNAPSHOT").ok())
+        {
+            debug!(path, "Using game data snapshot");
+            ResolverConfig::Snapshot {
+                path: PathBuf::from(path),
+            }
+        } else if let Some(dir) = data_dir.or_else(|| std::env::var("WOWLAB_DATA_DIR").ok()) {
+            debug!(data_dir = dir, "Using local game data");
+            ResolverConfig::Local {
+                data_dir: PathBuf::from(dir),
+            }
+        } else {
+            return Ok(None);
+        };
+        create_resolver(config)
+            .map(Some)
+            .map_err(|e| format!("Failed to open game data: {}", e))
+    }
+
+    fn require_game_data<'a>(
+        game_data: Option<&'a dyn DataResolver>,
+        flag: &str,
+    ) -> Result<&'a dyn DataResolver, String> {
+        game_data.ok_or_else(|| {
+            format!(
+                "{} needs game data: pass --data-dir or --snapshot, or set WOWLAB_DATA_DIR or WOWLAB_SNAPSHOT",
+                flag
+            )
+        })
+    }
+
+    fn runtime() -> Result<tokio::runtime::Runtime, String> {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .map_err(|e| format!("Failed to start runtime: {}", e))
+    }
+
     fn list_specs() -> Result<(), String> {
         println!("Available specs:");
         println!("  bm-hunter  - Beast Mastery Hunter");
@@ -228,14 +554,23 @@ impl Runner {
         }
     }
 
-    fn validate_rotation(file: &str) -> Result<(), String> {
+    fn validate_rotation(file: &str, print: bool) -> Result<(), String> {
         debug!(file, "Validating rotation file");
         let content =
             std::fs::read_to_string(file).map_err(|e| format!("Failed to read file: {}", e))?;
 
-        // Parse JSON rotation
-        let rotation = Rotation::from_json(&content)
-            .map_err(|e| format!("Failed to parse rotation: {}", e))?;
+        // JSON rotations are objects; anything else is text syntax
+        let rotation = if content.trim_start().starts_with('{') {
+            Rotation::from_json(&content)
+        } else {
+            Rotation::from_text(&content)
+        }
+        .map_err(|e| format!("Failed to parse rotation: {}", e))?;
+
+        if print {
+            print!("{}", rotation.to_text());
+            return Ok(());
+        }
 
         info!(name = %rotation.name, actions = rotation.actions.len(), "Parsed rotation");
 
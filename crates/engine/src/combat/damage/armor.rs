@@ -0,0 +1,184 @@
+//! Armor and resistance mitigation.
+//!
+//! Physical damage is reduced by `armor / (armor + K)`, where the armor
+//! constant K depends on the target's level and comes from the game's
+//! ExpectedStat table. Armor reduction debuffs on the target and armor
+//! penetration on the attacker each scale the armor that counts, and stack
+//! multiplicatively. Other schools are reduced by the target's resistances,
+//! which stack the same way.
+
+use std::collections::BTreeMap;
+use wowlab_common::types::data::ExpectedStatFlat;
+use wowlab_common::types::{AuraIdx, DamageSchool};
+
+/// Armor constant against a level 80 target, used without game data
+pub const DEFAULT_ARMOR_CONSTANT: f32 = 7390.0;
+
+/// Most damage armor can prevent
+pub const MAX_ARMOR_MITIGATION: f32 = 0.85;
+
+/// ExpectedStat expansion ID of the current expansion's rows
+const CURRENT_EXPANSION: i32 = -2;
+
+/// Number of damage schools
+const SCHOOLS: usize = 8;
+
+/// Armor constants by target level.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArmorConstants {
+    by_level: BTreeMap<u8, f32>,
+}
+
+impl Default for ArmorConstants {
+    fn default() -> Self {
+        Self::new().with_level(80, DEFAULT_ARMOR_CONSTANT)
+    }
+}
+
+impl ArmorConstants {
+    /// An empty table (every level uses the default constant)
+    pub fn new() -> Self {
+        Self {
+            by_level: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_level(mut self, level: u8, constant: f32) -> Self {
+        self.by_level.insert(level, constant);
+        self
+    }
+
+    /// Build from ExpectedStat rows.
+    ///
+    /// The current expansion's rows win; levels without one use the
+    /// latest expansion that has them.
+    pub fn from_expected_stats(rows: &[ExpectedStatFlat]) -> Self {
+        let mut best: BTreeMap<u8, (i32, f32)> = BTreeMap::new();
+        for row in rows.iter().filter(|r| r.armor_constant > 0.0) {
+            let Ok(level) = u8::try_from(row.level) else {
+                continue;
+            };
+            let rank = if row.expansion_id == CURRENT_EXPANSION {
+                i32::MAX
+            } else {
+                row.expansion_id
+            };
+            let entry = best.entry(level).or_insert((i32::MIN, 0.0));
+            if rank > entry.0 {
+                *entry = (rank, row.armor_constant as f32);
+            }
+        }
+
+        let mut constants = Self::new();
+        for (level, (_, constant)) in best {
+            constants = constants.with_level(level, constant);
+        }
+        if constants.is_empty() {
+            return Self::default();
+        }
+        constants
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_level.is_empty()
+    }
+
+    /// Constant for a target of `level`, falling back to the closest level below
+    /// (or above) it.
+    pub fn get(&self, level: u8) -> f32 {
+        self.by_level
+            .range(..=level)
+            .next_back()
+            .or_else(|| self.by_level.range(level..).next())
+            .map_or(DEFAULT_ARMOR_CONSTANT, |(_, &constant)| constant)
+    }
+}
+
+/// Damage reduction one attacker faces against one target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mitigation {
+    pub armor: f32,
+    pub armor_constant: f32,
+    /// Share of armor left after reductions and penetration
+    pub armor_multiplier: f32,
+    /// Damage taken per school after resistances
+    resist_multiplier: [f32; SCHOOLS],
+}
+
+impl Default for Mitigation {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl Mitigation {
+    pub fn new(armor: f32, armor_constant: f32) -> Self {
+        Self {
+            armor,
+            armor_constant,
+            armor_multiplier: 1.0,
+            resist_multiplier: [1.0; SCHOOLS],
+        }
+    }
+
+    /// No armor or resistances.
+    pub fn none() -> Self {
+        Self::new(0.0, DEFAULT_ARMOR_CONSTANT)
+    }
+
+    /// Reduce the target's armor by `amount` (0 to 1), e.g. from a debuff.
+    pub fn with_armor_reduction(mut self, amount: f32) -> Self {
+        self.armor_multiplier *= 1.0 - amount.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Ignore `amount` (0 to 1) of the target's remaining armor.
+    pub fn with_armor_penetration(mut self, amount: f32) -> Self {
+        self.armor_multiplier *= 1.0 - amount.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Add a resistance reducing `school` damage by `amount` (0 to 1).
+    pub fn with_resistance(mut self, school: DamageSchool, amount: f32) -> Self {
+        self.resist_multiplier[school as usize] *= 1.0 - amount.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Armor that counts after reductions and penetration
+    pub fn effective_armor(&self) -> f32 {
+        (self.armor * self.armor_multiplier).max(0.0)
+    }
+
+    /// Share of physical damage armor prevents
+    pub fn armor_mitigation(&self) -> f32 {
+        let armor = self.effective_armor();
+        if armor <= 0.0 {
+            return 0.0;
+        }
+        (armor / (armor + self.armor_constant)).min(MAX_ARMOR_MITIGATION)
+    }
+
+    /// Share of `school` damage that gets through
+    pub fn damage_multiplier(&self, school: DamageSchool) -> f32 {
+        if school.is_physical() {
+            (1.0 - self.armor_mitigation()) * self.resist_multiplier[school as usize]
+        } else {
+            self.resist_multiplier[school as usize]
+        }
+    }
+}
+
+/// Spec auras that change mitigation, by effect.
+#[derive(Clone, Debug, Default)]
+pub struct MitigationAuras {
+    /// Target debuffs reducing armor, per stack
+    pub armor_reduction: Vec<(AuraIdx, f32)>,
+    /// Player buffs ignoring part of the target's armor
+    pub armor_penetration: Vec<(AuraIdx, f32)>,
+}
+
+impl MitigationAuras {
+    pub fn is_empty(&self) -> bool {
+        self.armor_reduction.is_empty() && self.armor_penetration.is_empty()
+    }
+}
@@ -0,0 +1,239 @@
+//! Attack table: a single roll decides miss, dodge, parry, glancing, block,
+//! crit or hit.
+//!
+//! Avoidance grows with the target's level over the attacker's. Players have
+//! innate hit and expertise that cancel miss and dodge against targets up to
+//! three levels higher, so specials against a boss only miss or get dodged
+//! beyond that. Parry and block only happen to melee attacks from the front,
+//! and glancing blows only to melee auto attacks against higher-level targets.
+
+use crate::core::FastRng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+use wowlab_common::types::HitResult;
+
+/// Miss, dodge, parry and block chance against a same-level target
+pub const BASE_AVOIDANCE: f32 = 0.03;
+
+/// Extra avoidance per level the target has over the attacker
+pub const AVOIDANCE_PER_LEVEL: f32 = 0.015;
+
+/// Innate hit chance, offsetting misses against targets up to three levels higher
+pub const INNATE_HIT: f32 = 0.075;
+
+/// Innate expertise, offsetting dodges against targets up to three levels higher
+pub const INNATE_EXPERTISE: f32 = 0.075;
+
+/// Extra miss chance of auto attacks while dual wielding
+pub const DUAL_WIELD_MISS: f32 = 0.19;
+
+/// Glancing blow chance per level the target has over the attacker
+pub const GLANCE_PER_LEVEL: f32 = 0.08;
+
+/// Damage dealt by a glancing blow
+pub const GLANCE_DAMAGE: f32 = 0.75;
+
+/// Damage prevented by a block
+pub const BLOCK_REDUCTION: f32 = 0.30;
+
+/// How an attack is delivered
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AttackType {
+    Melee,
+    #[default]
+    Ranged,
+    Spell,
+    /// Damage over time ticks, which can't be avoided
+    Periodic,
+}
+
+/// What is being rolled on the attack table
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Attack {
+    pub kind: AttackType,
+    /// White (auto) attack rather than a special
+    pub auto_attack: bool,
+    /// Attacker is dual wielding
+    pub dual_wield: bool,
+}
+
+impl Attack {
+    pub const fn melee() -> Self {
+        Self {
+            kind: AttackType::Melee,
+            auto_attack: false,
+            dual_wield: false,
+        }
+    }
+
+    pub const fn ranged() -> Self {
+        Self {
+            kind: AttackType::Ranged,
+            auto_attack: false,
+            dual_wield: false,
+        }
+    }
+
+    pub const fn spell() -> Self {
+        Self {
+            kind: AttackType::Spell,
+            auto_attack: false,
+            dual_wield: false,
+        }
+    }
+
+    pub const fn periodic() -> Self {
+        Self {
+            kind: AttackType::Periodic,
+            auto_attack: false,
+            dual_wield: false,
+        }
+    }
+
+    /// Mark as an auto attack.
+    pub const fn auto(mut self) -> Self {
+        self.auto_attack = true;
+        self
+    }
+
+    /// Mark as coming from a dual-wielding attacker.
+    pub const fn dual_wield(mut self) -> Self {
+        self.dual_wield = true;
+        self
+    }
+}
+
+/// Where the player attacks the target from, SimC's `position`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum AttackPosition {
+    /// Behind the target: no parries or blocks.
+    #[default]
+    Back,
+    /// In front of the target: melee attacks can be parried and blocked.
+    Front,
+}
+
+impl AttackPosition {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Back => "back",
+            Self::Front => "front",
+        }
+    }
+}
+
+impl fmt::Display for AttackPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AttackPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "back" => Ok(Self::Back),
+            "front" => Ok(Self::Front),
+            _ => Err(format!("unknown position '{}' (expected back or front)", s)),
+        }
+    }
+}
+
+/// Outcome chances for one attack against one target.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AttackTable {
+    pub miss: f32,
+    pub dodge: f32,
+    pub parry: f32,
+    pub glance: f32,
+    pub block: f32,
+    pub crit: f32,
+}
+
+impl AttackTable {
+    /// Chances for `attack` against a target `level_diff` levels above the attacker.
+    pub fn new(attack: Attack, level_diff: i32, position: AttackPosition) -> Self {
+        let avoidance = BASE_AVOIDANCE + AVOIDANCE_PER_LEVEL * level_diff as f32;
+        let front = position == AttackPosition::Front;
+        let mut table = Self::default();
+
+        match attack.kind {
+            AttackType::Periodic => {}
+            AttackType::Spell | AttackType::Ranged => {
+                table.miss = (avoidance - INNATE_HIT).max(0.0);
+            }
+            AttackType::Melee => {
+                table.miss = (avoidance - INNATE_HIT).max(0.0);
+                table.dodge = (avoidance - INNATE_EXPERTISE).max(0.0);
+                if front {
+                    table.parry = avoidance.max(0.0);
+                    table.block = avoidance.max(0.0);
+                }
+                if attack.auto_attack {
+                    table.glance = (GLANCE_PER_LEVEL * level_diff as f32).max(0.0);
+                }
+            }
+        }
+        if attack.auto_attack && attack.dual_wield && attack.kind != AttackType::Periodic {
+            table.miss += DUAL_WIELD_MISS;
+        }
+
+        table
+    }
+
+    /// A table where every attack lands, only rolling for crits.
+    pub fn unavoidable(crit: f32) -> Self {
+        Self {
+            crit,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_crit(mut self, crit: f32) -> Self {
+        self.crit = crit;
+        self
+    }
+
+    /// Total chance of not landing a full hit or crit
+    fn avoidance(&self) -> f32 {
+        self.miss + self.dodge + self.parry + self.glance + self.block
+    }
+
+    /// Roll an outcome.
+    ///
+    /// Outcomes fill a single table in order, so crits are pushed off it
+    /// when avoidance leaves no room for them.
+    pub fn roll(&self, rng: &mut FastRng) -> HitResult {
+        if self.avoidance() <= 0.0 {
+            return if rng.roll(self.crit) {
+                HitResult::Crit
+            } else {
+                HitResult::Hit
+            };
+        }
+
+        let roll = rng.next_f32();
+        let mut ceiling = 0.0;
+        for (chance, result) in [
+            (self.miss, HitResult::Miss),
+            (self.dodge, HitResult::Dodge),
+            (self.parry, HitResult::Parry),
+            (self.glance, HitResult::Glancing),
+            (self.block, HitResult::Block),
+            (self.crit, HitResult::Crit),
+        ] {
+            ceiling += chance;
+            if roll < ceiling {
+                return result;
+            }
+        }
+        HitResult::Hit
+    }
+}
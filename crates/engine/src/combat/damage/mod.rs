@@ -1,5 +1,9 @@
+mod armor;
+mod attack_table;
 mod multipliers;
 mod pipeline;
 
+pub use armor::*;
+pub use attack_table::*;
 pub use multipliers::*;
 pub use pipeline::*;
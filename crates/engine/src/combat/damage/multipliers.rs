@@ -1,4 +1,46 @@
-use wowlab_common::types::DamageSchool;
+//! Damage multipliers, with an audit trail of where each one came from.
+
+use serde::Serialize;
+use smallvec::SmallVec;
+use std::borrow::Cow;
+use std::fmt;
+use wowlab_common::types::{AuraIdx, DamageSchool};
+
+/// Where a damage multiplier came from
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MultiplierSource {
+    Versatility,
+    Mastery,
+    /// A buff on the player or debuff on the target
+    Aura(AuraIdx),
+    /// A talent, set bonus or other named modifier
+    Named(Cow<'static, str>),
+}
+
+impl MultiplierSource {
+    pub fn named(name: impl Into<Cow<'static, str>>) -> Self {
+        Self::Named(name.into())
+    }
+}
+
+impl fmt::Display for MultiplierSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Versatility => f.write_str("Versatility"),
+            Self::Mastery => f.write_str("Mastery"),
+            Self::Aura(aura) => write!(f, "Aura {}", aura.0),
+            Self::Named(name) => f.write_str(name),
+        }
+    }
+}
+
+/// One multiplier applied to a hit
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MultiplierEntry {
+    pub source: MultiplierSource,
+    pub value: f32,
+}
 
 #[derive(Clone, Debug)]
 pub struct DamageMultipliers {
@@ -11,6 +53,8 @@ pub struct DamageMultipliers {
     pub versatility: f32,
     pub pet: f32,
     pub crit: f32,
+    /// Every multiplier applied through `apply`, `apply_pet` or `with_versatility`
+    active: SmallVec<[MultiplierEntry; 4]>,
 }
 
 impl Default for DamageMultipliers {
@@ -25,6 +69,7 @@ impl Default for DamageMultipliers {
             versatility: 0.0,
             pet: 1.0,
             crit: 2.0,
+            active: SmallVec::new(),
         }
     }
 }
@@ -34,6 +79,38 @@ impl DamageMultipliers {
         Self::default()
     }
 
+    /// Multiply player damage by `value`, recording its source.
+    pub fn apply(&mut self, source: MultiplierSource, value: f32) {
+        self.player *= value;
+        self.record(source, value);
+    }
+
+    /// Multiply pet damage by `value`, recording its source.
+    pub fn apply_pet(&mut self, source: MultiplierSource, value: f32) {
+        self.pet *= value;
+        self.record(source, value);
+    }
+
+    /// Add versatility (as a fraction) to damage done.
+    pub fn with_versatility(mut self, versatility: f32) -> Self {
+        self.versatility += versatility;
+        if versatility != 0.0 {
+            self.record(MultiplierSource::Versatility, 1.0 + versatility);
+        }
+        self
+    }
+
+    fn record(&mut self, source: MultiplierSource, value: f32) {
+        if value != 1.0 {
+            self.active.push(MultiplierEntry { source, value });
+        }
+    }
+
+    /// Multipliers applied so far, in order
+    pub fn active(&self) -> &[MultiplierEntry] {
+        &self.active
+    }
+
     pub fn total_da(&self, is_crit: bool) -> f32 {
         let mut mult = self.action
             * self.da
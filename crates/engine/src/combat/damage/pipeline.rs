@@ -1,4 +1,4 @@
-use super::DamageMultipliers;
+use super::{AttackTable, DamageMultipliers, Mitigation, BLOCK_REDUCTION, GLANCE_DAMAGE};
 use crate::core::FastRng;
 use wowlab_common::types::{DamageFlags, DamageSchool, HitResult};
 
@@ -14,6 +14,10 @@ pub struct DamageResult {
 pub struct DamagePipeline;
 
 impl DamagePipeline {
+    /// Direct damage, with the outcome rolled on `table`.
+    ///
+    /// Avoided attacks deal no damage; glancing blows and blocks deal
+    /// reduced damage. Armor and resistances apply last.
     #[allow(clippy::too_many_arguments)]
     pub fn calculate(
         base: f32,
@@ -22,9 +26,9 @@ impl DamagePipeline {
         attack_power: f32,
         spell_power: f32,
         multipliers: &DamageMultipliers,
-        crit_chance: f32,
+        table: &AttackTable,
         school: DamageSchool,
-        armor: f32,
+        mitigation: &Mitigation,
         rng: &mut FastRng,
     ) -> DamageResult {
         let mut amount = base;
@@ -33,19 +37,19 @@ impl DamagePipeline {
 
         let raw = amount;
 
-        let is_crit = rng.roll(crit_chance);
-        let hit_result = if is_crit {
-            HitResult::Crit
-        } else {
-            HitResult::Hit
-        };
+        let hit_result = table.roll(rng);
+        let is_crit = hit_result.is_crit();
+
+        match hit_result {
+            HitResult::Miss | HitResult::Dodge | HitResult::Parry => amount = 0.0,
+            HitResult::Glancing => amount *= GLANCE_DAMAGE,
+            HitResult::Block => amount *= 1.0 - BLOCK_REDUCTION,
+            HitResult::Hit | HitResult::Crit => {}
+        }
 
         amount *= multipliers.total_da(is_crit);
 
-        if school.is_physical() && armor > 0.0 {
-            let mitigation = Self::armor_mitigation(armor);
-            amount *= 1.0 - mitigation;
-        }
+        amount *= mitigation.damage_multiplier(school);
 
         let mut flags = DamageFlags::empty();
         if is_crit {
@@ -71,7 +75,7 @@ impl DamagePipeline {
         multipliers: &DamageMultipliers,
         crit_chance: f32,
         school: DamageSchool,
-        armor: f32,
+        mitigation: &Mitigation,
         rng: &mut FastRng,
     ) -> DamageResult {
         let mut amount = base;
@@ -89,10 +93,7 @@ impl DamagePipeline {
 
         amount *= multipliers.total_ta(is_crit);
 
-        if school.is_physical() && armor > 0.0 {
-            let mitigation = Self::armor_mitigation(armor);
-            amount *= 1.0 - mitigation;
-        }
+        amount *= mitigation.damage_multiplier(school);
 
         let mut flags = DamageFlags::PERIODIC;
         if is_crit {
@@ -107,10 +108,4 @@ impl DamagePipeline {
             flags,
         }
     }
-
-    fn armor_mitigation(armor: f32) -> f32 {
-        // TODO: move constant, this is for level 80
-        const ARMOR_CONSTANT: f32 = 7390.0;
-        (armor / (armor + ARMOR_CONSTANT)).min(0.85)
-    }
 }
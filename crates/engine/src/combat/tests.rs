@@ -1,6 +1,7 @@
 use super::*;
 use crate::core::FastRng;
 use crate::stats::StatCache;
+use wowlab_common::types::data::ExpectedStatFlat;
 use wowlab_common::types::*;
 
 #[test]
@@ -40,6 +41,21 @@ fn damage_multipliers_crit() {
     assert!((with_crit / no_crit - 2.0).abs() < 0.01); // 2x crit
 }
 
+#[test]
+fn damage_multipliers_audit() {
+    let mut mult = DamageMultipliers::new().with_versatility(0.1);
+    mult.apply(MultiplierSource::Mastery, 1.2);
+    mult.apply_pet(MultiplierSource::Aura(AuraIdx(19574)), 1.25);
+    mult.apply(MultiplierSource::named("Unused"), 1.0);
+
+    // 1.1 * 1.2 * 1.25 = 1.65
+    assert!((mult.total_da(false) - 1.65).abs() < 1e-4);
+
+    let sources: Vec<_> = mult.active().iter().map(|e| e.source.to_string()).collect();
+    assert_eq!(sources, ["Versatility", "Mastery", "Aura 19574"]);
+    assert!((mult.active()[0].value - 1.1).abs() < 1e-6);
+}
+
 #[test]
 fn damage_pipeline_basic() {
     let mut rng = FastRng::new(42);
@@ -52,9 +68,9 @@ fn damage_pipeline_basic() {
         5000.0, // ap
         0.0,    // sp
         &mult,
-        0.0, // crit chance (guaranteed no crit)
+        &AttackTable::unavoidable(0.0), // guaranteed no crit
         DamageSchool::Physical,
-        0.0, // no armor
+        &Mitigation::none(),
         &mut rng,
     );
 
@@ -74,9 +90,9 @@ fn damage_pipeline_armor() {
         0.0,
         0.0,
         &mult,
-        0.0,
+        &AttackTable::unavoidable(0.0),
         DamageSchool::Physical,
-        0.0,
+        &Mitigation::none(),
         &mut rng,
     );
 
@@ -87,15 +103,221 @@ fn damage_pipeline_armor() {
         0.0,
         0.0,
         &mult,
-        0.0,
+        &AttackTable::unavoidable(0.0),
         DamageSchool::Physical,
-        5000.0,
+        &Mitigation::new(5000.0, DEFAULT_ARMOR_CONSTANT),
         &mut rng,
     );
 
     assert!(with_armor.final_amount < no_armor.final_amount);
 }
 
+#[test]
+fn attack_table_against_boss() {
+    // Innate hit and expertise cover a boss three levels up.
+    let ranged = AttackTable::new(Attack::ranged(), 3, AttackPosition::Back);
+    assert!(ranged.miss.abs() < 1e-6);
+    assert_eq!(ranged.dodge, 0.0);
+
+    let melee = AttackTable::new(Attack::melee(), 3, AttackPosition::Back);
+    assert!(melee.miss.abs() < 1e-6);
+    assert!(melee.dodge.abs() < 1e-6);
+    assert_eq!(melee.parry, 0.0);
+    assert_eq!(melee.block, 0.0);
+    assert_eq!(melee.glance, 0.0);
+
+    // A level further and specials start missing.
+    let higher = AttackTable::new(Attack::ranged(), 4, AttackPosition::Back);
+    assert!((higher.miss - AVOIDANCE_PER_LEVEL).abs() < 1e-6);
+}
+
+#[test]
+fn attack_table_front_parry_and_block() {
+    let front = AttackTable::new(Attack::melee(), 3, AttackPosition::Front);
+    assert!((front.parry - 0.075).abs() < 1e-6);
+    assert!((front.block - 0.075).abs() < 1e-6);
+
+    // Ranged attacks and spells can't be parried or blocked.
+    let ranged = AttackTable::new(Attack::ranged(), 3, AttackPosition::Front);
+    assert_eq!(ranged.parry, 0.0);
+    assert_eq!(ranged.block, 0.0);
+}
+
+#[test]
+fn attack_table_glancing_only_melee_autos() {
+    let auto = AttackTable::new(Attack::melee().auto(), 3, AttackPosition::Back);
+    assert!((auto.glance - 0.24).abs() < 1e-6);
+
+    let special = AttackTable::new(Attack::melee(), 3, AttackPosition::Back);
+    assert_eq!(special.glance, 0.0);
+
+    let auto_shot = AttackTable::new(Attack::ranged().auto(), 3, AttackPosition::Back);
+    assert_eq!(auto_shot.glance, 0.0);
+
+    let same_level = AttackTable::new(Attack::melee().auto(), 0, AttackPosition::Back);
+    assert_eq!(same_level.glance, 0.0);
+}
+
+#[test]
+fn attack_table_dual_wield_penalty() {
+    let dw = AttackTable::new(Attack::melee().auto().dual_wield(), 3, AttackPosition::Back);
+    assert!((dw.miss - DUAL_WIELD_MISS).abs() < 1e-6);
+
+    // Specials don't suffer the penalty.
+    let special = AttackTable::new(Attack::melee().dual_wield(), 3, AttackPosition::Back);
+    assert!(special.miss.abs() < 1e-6);
+}
+
+#[test]
+fn attack_table_periodic_unavoidable() {
+    let table = AttackTable::new(Attack::periodic(), 10, AttackPosition::Front);
+    assert_eq!(table, AttackTable::default());
+}
+
+#[test]
+fn attack_table_roll_distribution() {
+    let table = AttackTable {
+        miss: 0.1,
+        dodge: 0.1,
+        glance: 0.2,
+        crit: 0.3,
+        ..AttackTable::default()
+    };
+    let mut rng = FastRng::new(7);
+    let mut counts = [0u32; 5];
+    let n = 100_000;
+    for _ in 0..n {
+        let i = match table.roll(&mut rng) {
+            HitResult::Miss => 0,
+            HitResult::Dodge => 1,
+            HitResult::Glancing => 2,
+            HitResult::Crit => 3,
+            HitResult::Hit => 4,
+            other => panic!("unexpected outcome {:?}", other),
+        };
+        counts[i] += 1;
+    }
+    for (count, expected) in counts.iter().zip([0.1, 0.1, 0.2, 0.3, 0.3]) {
+        let rate = *count as f32 / n as f32;
+        assert!((rate - expected).abs() < 0.01, "{} vs {}", rate, expected);
+    }
+}
+
+#[test]
+fn attack_table_avoidance_pushes_crit_off() {
+    let table = AttackTable {
+        miss: 0.6,
+        crit: 0.8,
+        ..AttackTable::default()
+    };
+    let mut rng = FastRng::new(1);
+    for _ in 0..1000 {
+        assert_ne!(table.roll(&mut rng), HitResult::Hit);
+    }
+}
+
+#[test]
+fn attack_position_parse() {
+    assert_eq!("front".parse::<AttackPosition>(), Ok(AttackPosition::Front));
+    assert_eq!("Back".parse::<AttackPosition>(), Ok(AttackPosition::Back));
+    assert!("side".parse::<AttackPosition>().is_err());
+    assert_eq!(AttackPosition::Front.to_string(), "front");
+}
+
+#[test]
+fn damage_pipeline_glancing_and_block() {
+    let mult = DamageMultipliers::new();
+    let calc = |table: AttackTable| {
+        let mut rng = FastRng::new(42);
+        DamagePipeline::calculate(
+            1000.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            &mult,
+            &table,
+            DamageSchool::Physical,
+            &Mitigation::none(),
+            &mut rng,
+        )
+    };
+
+    let glance = calc(AttackTable {
+        glance: 1.0,
+        ..AttackTable::default()
+    });
+    assert_eq!(glance.hit_result, HitResult::Glancing);
+    assert!((glance.final_amount - 1000.0 * GLANCE_DAMAGE).abs() < 1.0);
+
+    let block = calc(AttackTable {
+        block: 1.0,
+        ..AttackTable::default()
+    });
+    assert_eq!(block.hit_result, HitResult::Block);
+    assert!((block.final_amount - 1000.0 * (1.0 - BLOCK_REDUCTION)).abs() < 1.0);
+
+    let miss = calc(AttackTable {
+        miss: 1.0,
+        ..AttackTable::default()
+    });
+    assert_eq!(miss.hit_result, HitResult::Miss);
+    assert_eq!(miss.final_amount, 0.0);
+}
+
+#[test]
+fn armor_constants_by_level() {
+    let rows = [
+        (1, 0, 80, 7000.0),
+        (2, -2, 80, 7390.0),
+        (3, 9, 80, 7100.0),
+        (4, 9, 83, 9000.0),
+    ]
+    .map(
+        |(id, expansion_id, level, armor_constant)| ExpectedStatFlat {
+            id,
+            expansion_id,
+            level,
+            armor_constant,
+            ..Default::default()
+        },
+    );
+    let constants = ArmorConstants::from_expected_stats(&rows);
+
+    // The current expansion's row wins.
+    assert_eq!(constants.get(80), 7390.0);
+    assert_eq!(constants.get(83), 9000.0);
+    // Missing levels use the closest one below, or above.
+    assert_eq!(constants.get(82), 7390.0);
+    assert_eq!(constants.get(90), 9000.0);
+    assert_eq!(constants.get(70), 7390.0);
+
+    assert_eq!(
+        ArmorConstants::from_expected_stats(&[]),
+        ArmorConstants::default()
+    );
+}
+
+#[test]
+fn mitigation_stacks_multiplicatively() {
+    let base = Mitigation::new(10_000.0, 10_000.0);
+    assert!((base.armor_mitigation() - 0.5).abs() < 1e-6);
+
+    let reduced = base
+        .with_armor_reduction(0.2)
+        .with_armor_reduction(0.5)
+        .with_armor_penetration(0.5);
+    // 10000 * 0.8 * 0.5 * 0.5 = 2000 armor
+    assert!((reduced.effective_armor() - 2000.0).abs() < 1e-3);
+    assert!((reduced.armor_mitigation() - 2000.0 / 12_000.0).abs() < 1e-6);
+
+    // Armor only applies to physical damage.
+    assert_eq!(reduced.damage_multiplier(DamageSchool::Arcane), 1.0);
+
+    let capped = Mitigation::new(1_000_000.0, 1.0);
+    assert_eq!(capped.armor_mitigation(), MAX_ARMOR_MITIGATION);
+}
+
 #[test]
 fn cooldown_basic() {
     let mut cd = Cooldown::new(10.0);
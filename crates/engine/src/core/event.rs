@@ -17,5 +17,7 @@ pub enum SimEvent {
     PetAttack { pet: UnitIdx },
     ResourceTick,
     ProcIcdEnd { proc: ProcIdx },
+    RaidEventStart { index: u16 },
+    RaidEventEnd { index: u16 },
     SimEnd,
 }
@@ -9,9 +9,12 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::RwLock;
 use wowlab_common::parsers::{
-    transform_all_auras, transform_all_items, transform_all_trait_trees, transform_spell, DbcData,
+    transform_all_auras, transform_all_expected_stats, transform_all_items,
+    transform_all_trait_trees, transform_item_scaling, transform_spell, DbcData,
+};
+use wowlab_common::types::data::{
+    AuraDataFlat, ExpectedStatFlat, ItemDataFlat, ItemScalingData, SpellDataFlat, TraitTreeFlat,
 };
-use wowlab_common::types::data::{AuraDataFlat, ItemDataFlat, SpellDataFlat, TraitTreeFlat};
 
 /// Resolver that loads data from local CSV files.
 ///
@@ -29,6 +32,8 @@ pub struct LocalResolver {
     items: RwLock<Option<HashMap<i32, ItemDataFlat>>>,
     /// Cached transformed auras (lazy loaded)
     auras: RwLock<Option<HashMap<i32, AuraDataFlat>>>,
+    /// Cached item scaling tables (lazy loaded)
+    scaling: RwLock<Option<ItemScalingData>>,
 }
 
 impl LocalResolver {
@@ -43,6 +48,7 @@ impl LocalResolver {
             traits: RwLock::new(None),
             items: RwLock::new(None),
             auras: RwLock::new(None),
+            scaling: RwLock::new(None),
         }
     }
 
@@ -163,6 +169,27 @@ impl LocalResolver {
 
         Ok(())
     }
+
+    /// Lazy load item scaling tables on first access.
+    fn ensure_scaling_loaded(&self) -> Result<(), ResolverError> {
+        if self.scaling.read().unwrap().is_some() {
+            return Ok(());
+        }
+
+        self.ensure_dbc_loaded()?;
+
+        tracing::debug!("Transforming item scaling data");
+        let dbc_guard = self.dbc.read().unwrap();
+        let dbc = dbc_guard.as_ref().unwrap();
+
+        let scaling = transform_item_scaling(dbc);
+
+        let count = scaling.bonuses.len();
+        *self.scaling.write().unwrap() = Some(scaling);
+        tracing::debug!(count, "Item scaling data loaded");
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -213,6 +240,17 @@ impl DataResolver for LocalResolver {
             .ok_or(ResolverError::ItemNotFound(id))
     }
 
+    async fn get_item_scaling_data(&self) -> Result<ItemScalingData, ResolverError> {
+        self.ensure_scaling_loaded()?;
+        Ok(self.scaling.read().unwrap().as_ref().unwrap().clone())
+    }
+
+    async fn get_expected_stats(&self) -> Result<Vec<ExpectedStatFlat>, ResolverError> {
+        self.ensure_dbc_loaded()?;
+        let dbc = self.dbc.read().unwrap();
+        Ok(transform_all_expected_stats(dbc.as_ref().unwrap()))
+    }
+
     async fn get_aura(&self, spell_id: i32) -> Result<AuraDataFlat, ResolverError> {
         self.ensure_auras_loaded()?;
         self.auras
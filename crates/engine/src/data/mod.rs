@@ -3,12 +3,15 @@
 //! This module provides:
 //! - **DataResolver trait**: Abstract interface for loading game data
 //! - **LocalResolver**: Loads from local CSV files (default, offline, portable)
+//! - **SnapshotResolver**: Loads from a pre-built snapshot file (requires `local` feature)
 //! - **SupabaseResolver**: Loads from Supabase API (optional, requires `supabase` feature)
 
 #[cfg(feature = "supabase")]
 mod cache;
 mod local;
 mod resolver;
+#[cfg(feature = "local")]
+mod snapshot;
 #[cfg(feature = "supabase")]
 mod supabase;
 
@@ -16,9 +19,12 @@ mod supabase;
 pub use cache::{CacheStats, DiskStats, GameDataCache, MemoryStats};
 pub use local::LocalResolver;
 pub use resolver::{DataResolver, ResolverConfig, ResolverError};
+#[cfg(feature = "local")]
+pub use snapshot::SnapshotResolver;
 #[cfg(feature = "supabase")]
 pub use supabase::SupabaseResolver;
 
+use crate::combat::ArmorConstants;
 use std::sync::Arc;
 
 /// Create a resolver from configuration.
@@ -34,6 +40,11 @@ use std::sync::Arc;
 ///     data_dir: PathBuf::from("./data"),
 /// })?;
 ///
+/// // Pre-built snapshot
+/// let resolver = create_resolver(ResolverConfig::Snapshot {
+///     path: PathBuf::from("./data/game.snapshot"),
+/// })?;
+///
 /// // Supabase mode (requires feature)
 /// #[cfg(feature = "supabase")]
 /// let resolver = create_resolver(ResolverConfig::Supabase {
@@ -46,6 +57,11 @@ pub fn create_resolver(config: ResolverConfig) -> Result<Arc<dyn DataResolver>,
             tracing::info!(data_dir = %data_dir.display(), "Creating LocalResolver");
             Ok(Arc::new(LocalResolver::new(data_dir)))
         }
+        #[cfg(feature = "local")]
+        ResolverConfig::Snapshot { path } => {
+            tracing::info!(path = %path.display(), "Creating SnapshotResolver");
+            Ok(Arc::new(SnapshotResolver::open(&path)?))
+        }
         #[cfg(feature = "supabase")]
         ResolverConfig::Supabase { patch } => {
             tracing::info!("Creating SupabaseResolver (patch: {})", patch);
@@ -55,5 +71,18 @@ pub fn create_resolver(config: ResolverConfig) -> Result<Arc<dyn DataResolver>,
     }
 }
 
+/// Armor constants by target level from the resolver's ExpectedStat rows.
+pub async fn load_armor_constants(
+    resolver: &dyn DataResolver,
+) -> Result<ArmorConstants, ResolverError> {
+    let stats = resolver.get_expected_stats().await?;
+    if stats.is_empty() {
+        tracing::warn!("No ExpectedStat rows in game data, using the default armor constant");
+    } else {
+        tracing::debug!(rows = stats.len(), "Armor constants loaded");
+    }
+    Ok(ArmorConstants::from_expected_stats(&stats))
+}
+
 #[cfg(test)]
 mod tests;
@@ -3,12 +3,14 @@
 //! The resolver trait allows the engine to load spell, talent, item, and aura data
 //! from different sources:
 //! - `LocalResolver`: Loads from local CSV files via snapshot-parser (offline, portable)
+//! - `SnapshotResolver`: Loads from a pre-built snapshot file (offline, fast startup)
 //! - `SupabaseResolver`: Loads from Supabase PostgREST API (online, requires feature)
 
 use async_trait::async_trait;
 use std::path::PathBuf;
 use wowlab_common::types::data::{
-    AuraDataFlat, ItemDataFlat, SpellDataFlat, TraitTreeFlat, TraitTreeWithSelections,
+    AuraDataFlat, ExpectedStatFlat, ItemDataFlat, ItemScalingData, SpellDataFlat, TraitTreeFlat,
+    TraitTreeWithSelections,
 };
 
 /// Errors that can occur during data resolution.
@@ -26,6 +28,12 @@ pub enum ResolverError {
     #[error("Aura not found for spell: {0}")]
     AuraNotFound(i32),
 
+    #[error("Item scaling data not available from this resolver")]
+    ScalingDataUnavailable,
+
+    #[error("Expected stats not available from this resolver")]
+    ExpectedStatsUnavailable,
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -44,6 +52,10 @@ pub enum ResolverError {
 
     #[error("Environment variable error: {0}")]
     EnvVar(String),
+
+    #[cfg(feature = "local")]
+    #[error("Snapshot error: {0}")]
+    Snapshot(#[from] wowlab_common::snapshot::SnapshotError),
 }
 
 impl From<wowlab_common::parsers::DbcError> for ResolverError {
@@ -68,6 +80,7 @@ impl From<wowlab_common::parsers::errors::TraitError> for ResolverError {
 ///
 /// Implementations include:
 /// - `LocalResolver`: Loads from local CSV files (default, offline)
+/// - `SnapshotResolver`: Loads from a pre-built snapshot file (offline)
 /// - `SupabaseResolver`: Loads from Supabase API (optional, online)
 #[async_trait]
 pub trait DataResolver: Send + Sync {
@@ -86,6 +99,20 @@ pub trait DataResolver: Send + Sync {
     /// Get aura by spell ID.
     async fn get_aura(&self, spell_id: i32) -> Result<AuraDataFlat, ResolverError>;
 
+    /// Get the item bonus, curve and budget tables used to scale items.
+    ///
+    /// Not supported by all resolvers.
+    async fn get_item_scaling_data(&self) -> Result<ItemScalingData, ResolverError> {
+        Err(ResolverError::ScalingDataUnavailable)
+    }
+
+    /// Get the per-level creature baselines (armor constants).
+    ///
+    /// Not supported by all resolvers.
+    async fn get_expected_stats(&self) -> Result<Vec<ExpectedStatFlat>, ResolverError> {
+        Err(ResolverError::ExpectedStatsUnavailable)
+    }
+
     /// Search spells by name (optional, may not be supported by all resolvers).
     async fn search_spells(
         &self,
@@ -148,6 +175,13 @@ pub enum ResolverConfig {
         /// Expected structure: `{data_dir}/data/tables/*.csv`
         data_dir: PathBuf,
     },
+    /// Use a snapshot file written by `wowlab snapshot build` (offline, loads in
+    /// milliseconds).
+    #[cfg(feature = "local")]
+    Snapshot {
+        /// Path to the snapshot file.
+        path: PathBuf,
+    },
     /// Use Supabase PostgREST API (requires `supabase` feature).
     #[cfg(feature = "supabase")]
     Supabase {
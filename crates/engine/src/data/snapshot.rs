@@ -0,0 +1,108 @@
+//! SnapshotResolver: Loads data from a pre-built game data snapshot.
+//!
+//! Snapshots are written by `wowlab snapshot build`. Opening one only maps the
+//! file and validates its header and index; records are read in place on
+//! access.
+
+use crate::data::resolver::{DataResolver, ResolverError};
+use async_trait::async_trait;
+use std::path::Path;
+use wowlab_common::snapshot::{DataSnapshot, SnapshotSection};
+use wowlab_common::types::data::{
+    AuraDataFlat, ExpectedStatFlat, ItemDataFlat, ItemScalingData, SpellDataFlat, TraitTreeFlat,
+};
+
+/// Resolver that reads a memory-mapped snapshot file.
+pub struct SnapshotResolver {
+    snapshot: DataSnapshot,
+}
+
+impl SnapshotResolver {
+    /// Open a snapshot file, validating its header and index.
+    pub fn open(path: &Path) -> Result<Self, ResolverError> {
+        let snapshot = DataSnapshot::open(path)?;
+        tracing::info!(
+            patch = snapshot.patch(),
+            spells = snapshot.count(SnapshotSection::Spells),
+            "Opened game data snapshot"
+        );
+        Ok(Self { snapshot })
+    }
+
+    /// Game patch the snapshot was built from.
+    pub fn patch(&self) -> &str {
+        self.snapshot.patch()
+    }
+}
+
+#[async_trait]
+impl DataResolver for SnapshotResolver {
+    async fn get_spell(&self, id: i32) -> Result<SpellDataFlat, ResolverError> {
+        self.snapshot
+            .spell(id)?
+            .ok_or(ResolverError::SpellNotFound(id))
+    }
+
+    async fn get_spells(&self, ids: &[i32]) -> Result<Vec<SpellDataFlat>, ResolverError> {
+        let mut spells = Vec::with_capacity(ids.len());
+        for &id in ids {
+            spells.extend(self.snapshot.spell(id)?);
+        }
+        Ok(spells)
+    }
+
+    async fn get_trait_tree(&self, spec_id: i32) -> Result<TraitTreeFlat, ResolverError> {
+        self.snapshot
+            .trait_tree(spec_id)?
+            .ok_or(ResolverError::TraitTreeNotFound(spec_id))
+    }
+
+    async fn get_item(&self, id: i32) -> Result<ItemDataFlat, ResolverError> {
+        self.snapshot
+            .item(id)?
+            .ok_or(ResolverError::ItemNotFound(id))
+    }
+
+    async fn get_item_scaling_data(&self) -> Result<ItemScalingData, ResolverError> {
+        self.snapshot
+            .item_scaling()?
+            .ok_or(ResolverError::ScalingDataUnavailable)
+    }
+
+    async fn get_expected_stats(&self) -> Result<Vec<ExpectedStatFlat>, ResolverError> {
+        self.snapshot
+            .expected_stats()?
+            .ok_or(ResolverError::ExpectedStatsUnavailable)
+    }
+
+    async fn get_aura(&self, spell_id: i32) -> Result<AuraDataFlat, ResolverError> {
+        self.snapshot
+            .aura(spell_id)?
+            .ok_or(ResolverError::AuraNotFound(spell_id))
+    }
+
+    async fn search_spells(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SpellDataFlat>, ResolverError> {
+        // Names are compared in place; only matches are decoded
+        let query_lower = query.to_lowercase();
+        let mut results = Vec::new();
+        for id in self.snapshot.ids(SnapshotSection::Spells) {
+            if results.len() >= limit {
+                break;
+            }
+            let Some(spell) = self
+                .snapshot
+                .archived::<SpellDataFlat>(SnapshotSection::Spells, id)?
+            else {
+                continue;
+            };
+            if spell.name.to_lowercase().contains(&query_lower) {
+                results.extend(self.snapshot.spell(id)?);
+            }
+        }
+        Ok(results)
+    }
+}
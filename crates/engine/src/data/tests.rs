@@ -18,7 +18,7 @@ fn resolver_config_local() {
         ResolverConfig::Local { data_dir } => {
             assert_eq!(data_dir.to_str().unwrap(), "/tmp/test");
         }
-        #[cfg(feature = "supabase")]
+        #[allow(unreachable_patterns)]
         _ => panic!("Expected Local config"),
     }
 }
@@ -37,6 +37,70 @@ fn resolver_config_supabase() {
     }
 }
 
+#[cfg(feature = "local")]
+#[test]
+fn snapshot_resolver_serves_records() {
+    use wowlab_common::snapshot::SnapshotData;
+    use wowlab_common::types::data::{ExpectedStatFlat, SpellDataFlat};
+
+    let spell = |id, name: &str| SpellDataFlat {
+        id,
+        name: name.to_string(),
+        ..Default::default()
+    };
+    let data = SnapshotData {
+        spells: vec![spell(34026, "Kill Command"), spell(53351, "Kill Shot")],
+        expected_stats: vec![ExpectedStatFlat {
+            level: 83,
+            armor_constant: 7390.0,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let path = std::env::temp_dir().join(format!(
+        "wowlab_snapshot_resolver_{}.bin",
+        std::process::id()
+    ));
+    data.write("11.2.0", &path).unwrap();
+
+    let resolver = create_resolver(ResolverConfig::Snapshot { path: path.clone() }).unwrap();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let spell = rt.block_on(resolver.get_spell(34026)).unwrap();
+    assert_eq!(spell.name, "Kill Command");
+    assert!(matches!(
+        rt.block_on(resolver.get_spell(1)),
+        Err(ResolverError::SpellNotFound(1))
+    ));
+    assert_eq!(
+        rt.block_on(resolver.get_spells(&[53351, 1])).unwrap().len(),
+        1
+    );
+    let found = rt.block_on(resolver.search_spells("kill s", 5)).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, 53351);
+    assert_eq!(
+        rt.block_on(resolver.get_expected_stats()).unwrap()[0].level,
+        83
+    );
+    assert!(matches!(
+        rt.block_on(resolver.get_trait_tree(253)),
+        Err(ResolverError::TraitTreeNotFound(253))
+    ));
+
+    drop(resolver);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "local")]
+#[test]
+fn snapshot_resolver_rejects_missing_file() {
+    let result = create_resolver(ResolverConfig::Snapshot {
+        path: std::path::PathBuf::from("/tmp/nonexistent.snapshot"),
+    });
+    assert!(matches!(result, Err(ResolverError::Snapshot(_))));
+}
+
 #[test]
 fn resolver_error_display() {
     let err = ResolverError::SpellNotFound(12345);
@@ -6,7 +6,8 @@
 mod registry;
 mod traits;
 
-#[cfg(feature = "jit")]
-pub use registry::create_handler;
 pub use registry::HandlerRegistry;
+pub use registry::{
+    create_handler_with_backend, create_handler_with_gear, create_handler_with_loadout,
+};
 pub use traits::SpecHandler;
@@ -1,6 +1,8 @@
 //! Handler registry - maps spec IDs to handler implementations.
 
 use super::SpecHandler;
+use crate::rotation::RotationBackend;
+use crate::spec::{LoadoutReport, SetBonusReport, SetBonuses, TalentLoadout};
 use std::collections::HashMap;
 use std::sync::Arc;
 use wowlab_common::types::SpecId;
@@ -66,23 +68,72 @@ impl Default for HandlerRegistry {
     }
 }
 
-/// Create a spec handler for the given spec with rotation and talents.
-#[cfg(feature = "jit")]
-pub fn create_handler(
+/// Create a spec handler with no talents whose rotation runs on the given
+/// backend.
+pub fn create_handler_with_backend(
     spec_id: SpecId,
     rotation_json: &str,
+    backend: RotationBackend,
 ) -> Result<Arc<dyn SpecHandler>, String> {
-    use crate::specs::hunter::bm::{BmHunter, TalentFlags, TierSetFlags};
-    use crate::specs::hunter::mm::MmHunter;
+    let (handler, _) =
+        create_handler_with_loadout(spec_id, rotation_json, backend, &TalentLoadout::default())?;
+    Ok(handler)
+}
+
+/// Create a spec handler with a loadout's talents.
+///
+/// Talents the spec doesn't implement are returned in the report.
+pub fn create_handler_with_loadout(
+    spec_id: SpecId,
+    rotation_json: &str,
+    backend: RotationBackend,
+    loadout: &TalentLoadout,
+) -> Result<(Arc<dyn SpecHandler>, LoadoutReport), String> {
+    let (handler, report, _) = create_handler_with_gear(
+        spec_id,
+        rotation_json,
+        backend,
+        loadout,
+        &SetBonuses::default(),
+    )?;
+    Ok((handler, report))
+}
+
+/// Create a spec handler with a loadout's talents and equipped set bonuses.
+///
+/// Set bonus auras in the returned report go on `Player::set_auras`.
+pub fn create_handler_with_gear(
+    spec_id: SpecId,
+    rotation_json: &str,
+    backend: RotationBackend,
+    loadout: &TalentLoadout,
+    sets: &SetBonuses,
+) -> Result<(Arc<dyn SpecHandler>, LoadoutReport, SetBonusReport), String> {
+    use crate::specs::hunter::{bm, mm};
+
+    if loadout.spec_id != 0 && SpecId::from_wow_spec_id(loadout.spec_id) != Some(spec_id) {
+        return Err(format!(
+            "Loadout is for spec ID {}, not {:?}",
+            loadout.spec_id, spec_id
+        ));
+    }
 
     match spec_id {
         SpecId::BeastMastery => {
-            let handler = BmHunter::new(rotation_json, TalentFlags::empty(), TierSetFlags::NONE)?;
-            Ok(Arc::new(handler))
+            let talents = bm::talents_from_loadout(loadout);
+            let tier_sets = bm::tier_sets_from_gear(sets);
+            let handler =
+                bm::BmHunter::with_loadout(rotation_json, &talents, tier_sets.flags, backend)?;
+            Ok((Arc::new(handler), talents.report, tier_sets.report))
         }
         SpecId::Marksmanship => {
-            let handler = MmHunter::new(rotation_json)?;
-            Ok(Arc::new(handler))
+            let talents = mm::talents_from_loadout(loadout);
+            let handler = mm::MmHunter::with_loadout(rotation_json, &talents, backend)?;
+            Ok((
+                Arc::new(handler),
+                talents.report,
+                mm::set_bonuses_from_gear(sets),
+            ))
         }
         _ => Err(format!("Spec {:?} not implemented", spec_id)),
     }
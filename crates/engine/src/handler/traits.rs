@@ -3,9 +3,11 @@
 //! All specializations must implement this trait to participate in the simulation.
 
 use crate::actor::Player;
+use crate::aura::AuraInstance;
 use crate::rotation::Action;
-use crate::sim::SimState;
+use crate::sim::{SimState, TraceEventType};
 use crate::spec::{AuraDef, SpellDef};
+use std::collections::HashMap;
 use wowlab_common::types::{AuraIdx, ClassId, DamageSchool, SpecId, SpellIdx, TargetIdx, UnitIdx};
 
 /// Core trait all specs must implement.
@@ -41,6 +43,32 @@ pub trait SpecHandler: Send + Sync {
     /// Returns all aura definitions implemented by this spec.
     fn aura_definitions(&self) -> &'static [AuraDef];
 
+    /// Display names for damage recorded under ids that have no spell
+    /// definition, such as auto attacks.
+    fn damage_names(&self) -> &'static [(SpellIdx, &'static str)] {
+        &[]
+    }
+
+    /// Display names for everything damage or auras are recorded under.
+    ///
+    /// Periodic damage is recorded under its aura's id, so aura names are
+    /// included; spell names win on collision.
+    fn spell_names(&self) -> HashMap<SpellIdx, String> {
+        let auras = self
+            .aura_definitions()
+            .iter()
+            .map(|aura| (SpellIdx(aura.id.0), aura.name.clone()));
+        let spells = self
+            .spell_definitions()
+            .iter()
+            .map(|spell| (spell.id, spell.name.clone()));
+        let extra = self
+            .damage_names()
+            .iter()
+            .map(|&(id, name)| (id, name.to_string()));
+        auras.chain(spells).chain(extra).collect()
+    }
+
     /// Returns all talent names for this spec.
     fn talent_names(&self) -> Vec<String>;
 
@@ -81,6 +109,34 @@ pub trait SpecHandler: Send + Sync {
     /// Cast a spell on a target.
     fn cast_spell(&self, state: &mut SimState, spell: SpellIdx, target: TargetIdx);
 
+    /// Use the trinket in rotation slot `slot`.
+    ///
+    /// Starts its cooldown and applies the on-use spell: as a buff when it's
+    /// one of the spec's auras, otherwise as a cast on the primary target.
+    /// Returns false if the trinket isn't ready.
+    fn use_trinket(&self, state: &mut SimState, slot: u8) -> bool {
+        let now = state.now();
+        let Some(spell) = state.player.equipment.use_trinket(slot, now) else {
+            return false;
+        };
+        state.trace(TraceEventType::CooldownStart { spell });
+
+        let aura_id = AuraIdx(spell.0);
+        if let Some(aura) = self.get_aura(aura_id) {
+            state.trace(TraceEventType::AuraApply {
+                aura: aura_id,
+                target: TargetIdx(0),
+                is_debuff: false,
+            });
+            let instance = AuraInstance::new(aura_id, TargetIdx(0), aura.duration, now, aura.flags);
+            state.player.buffs.apply(instance, now);
+        } else if self.get_spell(spell).is_some() {
+            let target = state.enemies.primary;
+            self.cast_spell(state, spell, target);
+        }
+        true
+    }
+
     /// Get the next action from the rotation.
     fn next_action(&self, state: &SimState) -> Action;
 
@@ -107,12 +163,17 @@ pub trait SpecHandler: Send + Sync {
         sp_coef: f32,
         school: DamageSchool,
     ) -> f32 {
-        use crate::combat::DamagePipeline;
+        use crate::combat::{Attack, DamagePipeline};
 
         let ap = state.player.stats.attack_power();
         let sp = state.player.stats.spell_power();
         let crit = state.player.stats.crit_chance();
-        let armor = state.enemies.primary().map(|e| e.armor).unwrap_or(0.0);
+        let mitigation = state.mitigation(state.enemies.primary);
+        let table = state
+            .attack_table(Attack::spell(), state.enemies.primary)
+            .with_crit(crit);
+        let multipliers = state.damage_multipliers(school, false, false, state.enemies.primary);
+        state.audit_multipliers(&multipliers);
 
         let result = DamagePipeline::calculate(
             base,
@@ -120,10 +181,10 @@ pub trait SpecHandler: Send + Sync {
             sp_coef,
             ap,
             sp,
-            &state.multipliers,
-            crit,
+            &multipliers,
+            &table,
             school,
-            armor,
+            &mitigation,
             &mut state.rng,
         );
 
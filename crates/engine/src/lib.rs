@@ -15,7 +15,6 @@ pub mod results;
 pub mod rotation;
 pub mod sim;
 pub mod spec;
-#[cfg(feature = "jit")]
 pub mod specs;
 pub mod stats;
 #[cfg(feature = "wasm")]
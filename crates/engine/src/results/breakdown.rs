@@ -1,69 +1,158 @@
-use super::StatsCollector;
+use super::{DamageSource, StatsCollector};
+use crate::combat::MultiplierSource;
+use serde::Serialize;
 use std::collections::HashMap;
-use wowlab_common::types::SpellIdx;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+use wowlab_common::types::{SpellIdx, TargetIdx};
 
-/// Damage breakdown entry
-#[derive(Clone, Debug)]
+/// Damage breakdown entry.
+///
+/// Damage and counts are means per iteration.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
 pub struct BreakdownEntry {
     pub spell: SpellIdx,
     pub name: String,
+    pub source: DamageSource,
     pub damage: f64,
     pub dps: f64,
     pub percent: f32,
-    pub count: u32,
+    pub casts: f32,
+    pub hits: f32,
+    pub crits: f32,
+    pub ticks: f32,
     pub average: f32,
     pub crit_rate: f32,
+    pub min_hit: f32,
+    pub max_hit: f32,
+    /// Multipliers behind its hits
+    pub multipliers: Vec<MultiplierBreakdown>,
+}
+
+/// A damage multiplier applied to a spell's hits
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+pub struct MultiplierBreakdown {
+    pub source: String,
+    /// Mean value while it applied
+    pub average: f32,
+    /// Share of hits it applied to
+    pub uptime: f32,
+}
+
+/// Damage dealt by one source (player or pet)
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+pub struct SourceEntry {
+    pub source: DamageSource,
+    pub damage: f64,
+    pub dps: f64,
+    pub percent: f32,
+}
+
+/// Damage dealt to one target
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+pub struct TargetEntry {
+    pub target: TargetIdx,
+    pub damage: f64,
+    pub dps: f64,
+    pub percent: f32,
 }
 
 /// Complete damage breakdown
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
 pub struct DamageBreakdown {
     pub entries: Vec<BreakdownEntry>,
+    pub sources: Vec<SourceEntry>,
+    pub targets: Vec<TargetEntry>,
+    pub iterations: u32,
     pub total_damage: f64,
     pub total_dps: f64,
     pub duration_secs: f32,
 }
 
 impl DamageBreakdown {
-    /// Build breakdown from collector
+    /// Build breakdown from collector.
+    ///
+    /// Merged collectors are averaged over the iterations they cover.
     pub fn from_collector(
         collector: &StatsCollector,
         spell_names: &HashMap<SpellIdx, String>,
     ) -> Self {
-        let duration = collector.duration().as_secs_f32();
+        let iterations = collector.iterations().max(1);
+        let n = iterations as f64;
+        let duration = collector.duration().as_secs_f32() as f64;
         let total = collector.total_damage;
+        // Per-iteration damage, DPS and percent of total
+        let share = |damage: f64| {
+            let dps = if duration > 0.0 {
+                damage / duration
+            } else {
+                0.0
+            };
+            let percent = if total > 0.0 {
+                (damage / total * 100.0) as f32
+            } else {
+                0.0
+            };
+            (damage / n, dps, percent)
+        };
 
         let mut entries: Vec<_> = collector
             .spells()
+            .filter(|stats| stats.total() > 0.0)
             .map(|stats| {
                 let name = spell_names
                     .get(&stats.spell)
                     .cloned()
                     .unwrap_or_else(|| format!("Spell_{}", stats.spell.0));
-
-                let damage = stats.total();
-                let dps = if duration > 0.0 {
-                    damage / duration as f64
-                } else {
-                    0.0
-                };
-                let percent = if total > 0.0 {
-                    (damage / total * 100.0) as f32
-                } else {
-                    0.0
-                };
+                let (damage, dps, percent) = share(stats.total());
+                let hits = stats.count + stats.tick_count;
+                let multipliers = stats
+                    .multipliers
+                    .iter()
+                    .map(|m| MultiplierBreakdown {
+                        source: match &m.source {
+                            MultiplierSource::Aura(aura) => spell_names
+                                .get(&SpellIdx(aura.0))
+                                .cloned()
+                                .unwrap_or_else(|| m.source.to_string()),
+                            source => source.to_string(),
+                        },
+                        average: m.average(),
+                        uptime: (m.hits as f32 / hits.max(1) as f32).min(1.0),
+                    })
+                    .collect();
 
                 BreakdownEntry {
                     spell: stats.spell,
                     name,
+                    source: stats.source,
                     damage,
                     dps,
                     percent,
-                    count: stats.count + stats.tick_count,
-                    average: ((stats.total_damage + stats.tick_damage)
-                        / (stats.count + stats.tick_count).max(1) as f64)
-                        as f32,
+                    casts: (stats.casts as f64 / n) as f32,
+                    hits: (stats.count as f64 / n) as f32,
+                    crits: (stats.crits as f64 / n) as f32,
+                    ticks: (stats.tick_count as f64 / n) as f32,
+                    average: (stats.total() / hits.max(1) as f64) as f32,
                     crit_rate: stats.crit_rate(),
+                    min_hit: if stats.count > 0 { stats.min_hit } else { 0.0 },
+                    max_hit: stats.max_hit,
+                    multipliers,
                 }
             })
             .collect();
@@ -71,42 +160,123 @@ impl DamageBreakdown {
         // Sort by damage descending
         entries.sort_by(|a, b| b.damage.partial_cmp(&a.damage).unwrap());
 
+        let mut sources: Vec<_> = [DamageSource::Player, DamageSource::Pet]
+            .into_iter()
+            .filter_map(|source| {
+                let damage: f64 = collector
+                    .spells()
+                    .filter(|stats| stats.source == source)
+                    .map(|stats| stats.total())
+                    .sum();
+                let (damage, dps, percent) = share(damage);
+                (damage > 0.0).then_some(SourceEntry {
+                    source,
+                    damage,
+                    dps,
+                    percent,
+                })
+            })
+            .collect();
+        sources.sort_by(|a, b| b.damage.partial_cmp(&a.damage).unwrap());
+
+        let mut targets: Vec<_> = collector
+            .targets()
+            .map(|(target, damage)| {
+                let (damage, dps, percent) = share(damage);
+                TargetEntry {
+                    target,
+                    damage,
+                    dps,
+                    percent,
+                }
+            })
+            .collect();
+        targets.sort_by_key(|t| t.target);
+
         Self {
             entries,
-            total_damage: total,
-            total_dps: if duration > 0.0 {
-                total / duration as f64
-            } else {
-                0.0
-            },
-            duration_secs: duration,
+            sources,
+            targets,
+            iterations,
+            total_damage: total / n,
+            total_dps: share(total).1,
+            duration_secs: (duration / n) as f32,
         }
     }
 
+    /// Damage share of `source`, in percent
+    pub fn source_percent(&self, source: DamageSource) -> f32 {
+        self.sources
+            .iter()
+            .find(|s| s.source == source)
+            .map_or(0.0, |s| s.percent)
+    }
+
     /// Format as table
     pub fn to_table(&self) -> String {
         let mut output = String::new();
 
         output.push_str(&format!(
-            "\n{:30} {:>12} {:>10} {:>8} {:>8} {:>8}\n",
-            "Ability", "Damage", "DPS", "%", "Count", "Avg"
+            "\n{:30} {:>6} {:>12} {:>10} {:>7} {:>7} {:>7} {:>7} {:>6} {:>9} {:>9}\n",
+            "Ability",
+            "Source",
+            "Damage",
+            "DPS",
+            "%",
+            "Casts",
+            "Hits",
+            "Ticks",
+            "Crit",
+            "Min",
+            "Max"
         ));
-        output.push_str(&"-".repeat(80));
+        output.push_str(&"-".repeat(121));
         output.push('\n');
 
         for entry in &self.entries {
             output.push_str(&format!(
-                "{:30} {:>12.0} {:>10.1} {:>7.1}% {:>8} {:>8.0}\n",
-                entry.name, entry.damage, entry.dps, entry.percent, entry.count, entry.average,
+                "{:30} {:>6} {:>12.0} {:>10.1} {:>6.1}% {:>7.1} {:>7.1} {:>7.1} {:>5.1}% {:>9.0} {:>9.0}\n",
+                entry.name,
+                entry.source,
+                entry.damage,
+                entry.dps,
+                entry.percent,
+                entry.casts,
+                entry.hits,
+                entry.ticks,
+                entry.crit_rate * 100.0,
+                entry.min_hit,
+                entry.max_hit,
             ));
         }
 
-        output.push_str(&"-".repeat(80));
+        output.push_str(&"-".repeat(121));
         output.push_str(&format!(
-            "\n{:30} {:>12.0} {:>10.1}\n",
-            "Total", self.total_damage, self.total_dps,
+            "\n{:30} {:>6} {:>12.0} {:>10.1}\n",
+            "Total", "", self.total_damage, self.total_dps,
         ));
 
+        for source in &self.sources {
+            output.push_str(&format!(
+                "{:30} {:>6} {:>12.0} {:>10.1} {:>6.1}%\n",
+                "", source.source, source.damage, source.dps, source.percent,
+            ));
+        }
+
+        if self.targets.len() > 1 {
+            output.push('\n');
+            for target in &self.targets {
+                output.push_str(&format!(
+                    "{:30} {:>6} {:>12.0} {:>10.1} {:>6.1}%\n",
+                    format!("Target {}", target.target.0),
+                    "",
+                    target.damage,
+                    target.dps,
+                    target.percent,
+                ));
+            }
+        }
+
         output
     }
 }
@@ -1,5 +1,104 @@
+use crate::combat::{MultiplierEntry, MultiplierSource};
+use serde::Serialize;
 use std::collections::HashMap;
-use wowlab_common::types::{SimTime, SpellIdx, TargetIdx};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+use wowlab_common::types::{HitResult, SimTime, SpellIdx, TargetIdx};
+
+/// Who dealt a piece of damage
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+pub enum DamageSource {
+    #[default]
+    Player,
+    Pet,
+}
+
+impl DamageSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Player => "player",
+            Self::Pet => "pet",
+        }
+    }
+}
+
+impl std::fmt::Display for DamageSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// Attack table outcomes by type
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+pub struct OutcomeCounts {
+    pub hit: u32,
+    pub crit: u32,
+    pub miss: u32,
+    pub dodge: u32,
+    pub parry: u32,
+    pub glancing: u32,
+    pub block: u32,
+}
+
+impl OutcomeCounts {
+    pub fn record(&mut self, result: HitResult) {
+        *self.count_mut(result) += 1;
+    }
+
+    /// Number of attacks with `result`
+    pub fn get(&self, result: HitResult) -> u32 {
+        match result {
+            HitResult::Hit => self.hit,
+            HitResult::Crit => self.crit,
+            HitResult::Miss => self.miss,
+            HitResult::Dodge => self.dodge,
+            HitResult::Parry => self.parry,
+            HitResult::Glancing => self.glancing,
+            HitResult::Block => self.block,
+        }
+    }
+
+    /// Number of attacks rolled
+    pub fn total(&self) -> u32 {
+        self.hit + self.crit + self.miss + self.dodge + self.parry + self.glancing + self.block
+    }
+
+    /// Share of attacks with `result`
+    pub fn rate(&self, result: HitResult) -> f32 {
+        match self.total() {
+            0 => 0.0,
+            total => self.get(result) as f32 / total as f32,
+        }
+    }
+
+    pub fn merge(&mut self, other: &OutcomeCounts) {
+        self.hit += other.hit;
+        self.crit += other.crit;
+        self.miss += other.miss;
+        self.dodge += other.dodge;
+        self.parry += other.parry;
+        self.glancing += other.glancing;
+        self.block += other.block;
+    }
+
+    fn count_mut(&mut self, result: HitResult) -> &mut u32 {
+        match result {
+            HitResult::Hit => &mut self.hit,
+            HitResult::Crit => &mut self.crit,
+            HitResult::Miss => &mut self.miss,
+            HitResult::Dodge => &mut self.dodge,
+            HitResult::Parry => &mut self.parry,
+            HitResult::Glancing => &mut self.glancing,
+            HitResult::Block => &mut self.block,
+        }
+    }
+}
 
 /// Single damage event record
 #[derive(Clone, Debug)]
@@ -12,12 +111,37 @@ pub struct DamageRecord {
     pub is_periodic: bool,
 }
 
+/// How often one damage multiplier applied to a spell, and by how much
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiplierStats {
+    pub source: MultiplierSource,
+    /// Hits it applied to
+    pub hits: u32,
+    /// Sum of its value over those hits
+    pub total: f64,
+}
+
+impl MultiplierStats {
+    /// Mean value over the hits it applied to
+    pub fn average(&self) -> f32 {
+        if self.hits > 0 {
+            (self.total / self.hits as f64) as f32
+        } else {
+            1.0
+        }
+    }
+}
+
 /// Accumulated spell statistics
 #[derive(Clone, Debug, Default)]
 pub struct SpellStats {
     /// Spell ID
     pub spell: SpellIdx,
-    /// Number of casts/hits
+    /// Who dealt the damage
+    pub source: DamageSource,
+    /// Number of casts
+    pub casts: u32,
+    /// Number of direct hits
     pub count: u32,
     /// Number of crits
     pub crits: u32,
@@ -31,6 +155,10 @@ pub struct SpellStats {
     pub tick_count: u32,
     /// Total periodic damage
     pub tick_damage: f64,
+    /// Attack table outcomes of direct attacks, including avoided ones
+    pub outcomes: OutcomeCounts,
+    /// Multipliers applied to its hits, by source
+    pub multipliers: Vec<MultiplierStats>,
 }
 
 impl SpellStats {
@@ -58,6 +186,27 @@ impl SpellStats {
         }
     }
 
+    /// Note the multipliers behind one hit
+    pub fn record_multipliers(&mut self, entries: &[MultiplierEntry]) {
+        for entry in entries {
+            self.add_multiplier(&entry.source, 1, entry.value as f64);
+        }
+    }
+
+    fn add_multiplier(&mut self, source: &MultiplierSource, hits: u32, total: f64) {
+        match self.multipliers.iter_mut().find(|m| m.source == *source) {
+            Some(stats) => {
+                stats.hits += hits;
+                stats.total += total;
+            }
+            None => self.multipliers.push(MultiplierStats {
+                source: source.clone(),
+                hits,
+                total,
+            }),
+        }
+    }
+
     /// Average damage per hit
     pub fn average(&self) -> f32 {
         if self.count > 0 {
@@ -89,17 +238,40 @@ impl SpellStats {
     pub fn total(&self) -> f64 {
         self.total_damage + self.tick_damage
     }
+
+    /// Merge stats for the same spell from another iteration
+    pub fn merge(&mut self, other: &SpellStats) {
+        if other.source != DamageSource::Player {
+            self.source = other.source;
+        }
+        self.casts += other.casts;
+        self.count += other.count;
+        self.crits += other.crits;
+        self.total_damage += other.total_damage;
+        self.max_hit = self.max_hit.max(other.max_hit);
+        self.min_hit = self.min_hit.min(other.min_hit);
+        self.tick_count += other.tick_count;
+        self.tick_damage += other.tick_damage;
+        self.outcomes.merge(&other.outcomes);
+        for m in &other.multipliers {
+            self.add_multiplier(&m.source, m.hits, m.total);
+        }
+    }
 }
 
 /// Collects statistics during simulation
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct StatsCollector {
     /// Per-spell statistics
     spells: HashMap<SpellIdx, SpellStats>,
+    /// Damage dealt to each target
+    targets: HashMap<TargetIdx, f64>,
     /// All damage events (if trace enabled)
     events: Vec<DamageRecord>,
     /// Total damage
     pub total_damage: f64,
+    /// Number of iterations this collector covers
+    iterations: u32,
     /// Fight start time
     start_time: SimTime,
     /// Fight end time
@@ -108,9 +280,33 @@ pub struct StatsCollector {
     trace_enabled: bool,
 }
 
+impl Default for StatsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl StatsCollector {
+    /// Collector for a single iteration
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            iterations: 1,
+            ..Self::accumulator()
+        }
+    }
+
+    /// Empty collector that iterations are merged into
+    pub fn accumulator() -> Self {
+        Self {
+            spells: HashMap::new(),
+            targets: HashMap::new(),
+            events: Vec::new(),
+            total_damage: 0.0,
+            iterations: 0,
+            start_time: SimTime::ZERO,
+            end_time: SimTime::ZERO,
+            trace_enabled: false,
+        }
     }
 
     pub fn with_trace(mut self) -> Self {
@@ -121,6 +317,7 @@ impl StatsCollector {
     /// Reset for new iteration
     pub fn reset(&mut self) {
         self.spells.clear();
+        self.targets.clear();
         self.events.clear();
         self.total_damage = 0.0;
         self.start_time = SimTime::ZERO;
@@ -136,16 +333,41 @@ impl StatsCollector {
         amount: f32,
         is_crit: bool,
         is_periodic: bool,
+    ) {
+        self.record_damage_from(
+            DamageSource::Player,
+            time,
+            spell,
+            target,
+            amount,
+            is_crit,
+            is_periodic,
+        );
+    }
+
+    /// Record damage event dealt by `source`
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_damage_from(
+        &mut self,
+        source: DamageSource,
+        time: SimTime,
+        spell: SpellIdx,
+        target: TargetIdx,
+        amount: f32,
+        is_crit: bool,
+        is_periodic: bool,
     ) {
         // Update totals
         self.total_damage += amount as f64;
         self.end_time = time;
+        *self.targets.entry(target).or_default() += amount as f64;
 
         // Update spell stats
         let stats = self
             .spells
             .entry(spell)
             .or_insert_with(|| SpellStats::new(spell));
+        stats.source = source;
         stats.record(amount, is_crit, is_periodic);
 
         // Store event if tracing
@@ -161,6 +383,35 @@ impl StatsCollector {
         }
     }
 
+    /// Record the attack table outcome of a direct attack
+    pub fn record_outcome(&mut self, source: DamageSource, spell: SpellIdx, result: HitResult) {
+        let stats = self
+            .spells
+            .entry(spell)
+            .or_insert_with(|| SpellStats::new(spell));
+        stats.source = source;
+        stats.outcomes.record(result);
+    }
+
+    /// Record the multipliers behind a hit of a spell
+    pub fn record_multipliers(&mut self, spell: SpellIdx, entries: &[MultiplierEntry]) {
+        if entries.is_empty() {
+            return;
+        }
+        self.spells
+            .entry(spell)
+            .or_insert_with(|| SpellStats::new(spell))
+            .record_multipliers(entries);
+    }
+
+    /// Record a cast of a spell
+    pub fn record_cast(&mut self, spell: SpellIdx) {
+        self.spells
+            .entry(spell)
+            .or_insert_with(|| SpellStats::new(spell))
+            .casts += 1;
+    }
+
     /// Get spell stats
     pub fn spell(&self, spell: SpellIdx) -> Option<&SpellStats> {
         self.spells.get(&spell)
@@ -171,6 +422,27 @@ impl StatsCollector {
         self.spells.values()
     }
 
+    /// Attack table outcomes across all spells
+    pub fn outcomes(&self) -> OutcomeCounts {
+        let mut total = OutcomeCounts::default();
+        for stats in self.spells.values() {
+            total.merge(&stats.outcomes);
+        }
+        total
+    }
+
+    /// Damage dealt to each target
+    pub fn targets(&self) -> impl Iterator<Item = (TargetIdx, f64)> + '_ {
+        self.targets
+            .iter()
+            .map(|(&target, &damage)| (target, damage))
+    }
+
+    /// Number of iterations merged into this collector
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
     /// Get all events (if trace enabled)
     pub fn events(&self) -> &[DamageRecord] {
         &self.events
@@ -200,6 +472,25 @@ impl StatsCollector {
     pub fn duration(&self) -> SimTime {
         self.end_time - self.start_time
     }
+
+    /// Fold another iteration's statistics into this one.
+    ///
+    /// Durations add up, so the merged DPS is total damage over total fight
+    /// time. Individual events are not merged.
+    pub fn merge(&mut self, other: &StatsCollector) {
+        for stats in other.spells() {
+            self.spells
+                .entry(stats.spell)
+                .or_insert_with(|| SpellStats::new(stats.spell))
+                .merge(stats);
+        }
+        for (target, damage) in other.targets() {
+            *self.targets.entry(target).or_default() += damage;
+        }
+        self.total_damage += other.total_damage;
+        self.iterations += other.iterations;
+        self.end_time += other.duration();
+    }
 }
 
 /// Resource usage statistics
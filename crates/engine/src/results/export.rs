@@ -1,5 +1,4 @@
 use super::{DamageBreakdown, StatsCollector};
-#[cfg(feature = "parallel")]
 use crate::sim::BatchResults;
 use std::io::Write;
 
@@ -16,7 +15,6 @@ pub struct ResultsExporter;
 
 impl ResultsExporter {
     /// Export batch results to JSON
-    #[cfg(feature = "parallel")]
     pub fn to_json(results: &BatchResults) -> String {
         format!(
             r#"{{
@@ -39,7 +37,6 @@ impl ResultsExporter {
     }
 
     /// Export batch results to CSV
-    #[cfg(feature = "parallel")]
     pub fn to_csv(results: &BatchResults) -> String {
         let mut output = String::from("iteration,dps\n");
         for (i, dps) in results.dps_values.iter().enumerate() {
@@ -48,6 +45,32 @@ impl ResultsExporter {
         output
     }
 
+    /// Export breakdown to CSV, one row per ability
+    pub fn breakdown_to_csv(breakdown: &DamageBreakdown) -> String {
+        let mut output = String::from(
+            "ability,source,damage,dps,percent,casts,hits,crits,ticks,average,crit_rate,min_hit,max_hit\n",
+        );
+        for e in &breakdown.entries {
+            output.push_str(&format!(
+                "{},{},{:.0},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.0},{:.4},{:.0},{:.0}\n",
+                e.name,
+                e.source,
+                e.damage,
+                e.dps,
+                e.percent,
+                e.casts,
+                e.hits,
+                e.crits,
+                e.ticks,
+                e.average,
+                e.crit_rate,
+                e.min_hit,
+                e.max_hit
+            ));
+        }
+        output
+    }
+
     /// Export breakdown to JSON
     pub fn breakdown_to_json(breakdown: &DamageBreakdown) -> String {
         let entries: Vec<String> = breakdown
@@ -57,14 +80,30 @@ impl ResultsExporter {
                 format!(
                     r#"    {{
       "name": "{}",
+      "source": "{}",
       "damage": {:.0},
       "dps": {:.2},
       "percent": {:.2},
-      "count": {},
+      "casts": {:.2},
+      "hits": {:.2},
+      "ticks": {:.2},
       "average": {:.0},
-      "crit_rate": {:.2}
+      "crit_rate": {:.2},
+      "min_hit": {:.0},
+      "max_hit": {:.0}
     }}"#,
-                    e.name, e.damage, e.dps, e.percent, e.count, e.average, e.crit_rate
+                    e.name,
+                    e.source,
+                    e.damage,
+                    e.dps,
+                    e.percent,
+                    e.casts,
+                    e.hits,
+                    e.ticks,
+                    e.average,
+                    e.crit_rate,
+                    e.min_hit,
+                    e.max_hit
                 )
             })
             .collect();
@@ -74,6 +113,7 @@ impl ResultsExporter {
   "total_damage": {:.0},
   "total_dps": {:.2},
   "duration": {:.2},
+  "iterations": {},
   "abilities": [
 {}
   ]
@@ -81,6 +121,7 @@ impl ResultsExporter {
             breakdown.total_damage,
             breakdown.total_dps,
             breakdown.duration_secs,
+            breakdown.iterations,
             entries.join(",\n")
         )
     }
@@ -114,7 +155,6 @@ impl SimSummary {
         }
     }
 
-    #[cfg(feature = "parallel")]
     pub fn from_batch(results: &BatchResults, duration_secs: f32) -> Self {
         Self {
             dps: results.mean_dps,
@@ -1,10 +1,12 @@
 mod breakdown;
 mod collector;
 mod export;
+mod trace;
 
 pub use breakdown::*;
 pub use collector::*;
 pub use export::*;
+pub use trace::*;
 
 #[cfg(test)]
 mod tests;
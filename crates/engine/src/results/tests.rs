@@ -42,6 +42,25 @@ fn spell_stats_crit_rate() {
     assert!((stats.crit_rate() - 0.5).abs() < 0.01);
 }
 
+#[test]
+fn outcome_counts() {
+    let mut outcomes = OutcomeCounts::default();
+    outcomes.record(HitResult::Hit);
+    outcomes.record(HitResult::Crit);
+    outcomes.record(HitResult::Glancing);
+    outcomes.record(HitResult::Miss);
+
+    assert_eq!(outcomes.total(), 4);
+    assert_eq!(outcomes.get(HitResult::Glancing), 1);
+    assert!((outcomes.rate(HitResult::Miss) - 0.25).abs() < 1e-6);
+    assert_eq!(OutcomeCounts::default().rate(HitResult::Hit), 0.0);
+
+    let mut merged = outcomes;
+    merged.merge(&outcomes);
+    assert_eq!(merged.total(), 8);
+    assert_eq!(merged.miss, 2);
+}
+
 #[test]
 fn collector_record() {
     let mut collector = StatsCollector::new();
@@ -153,6 +172,48 @@ fn breakdown_from_collector() {
     assert!((breakdown.entries[0].percent - 60.0).abs() < 0.1);
 }
 
+#[test]
+fn breakdown_explains_multipliers() {
+    use crate::combat::{MultiplierEntry, MultiplierSource};
+
+    let mut collector = StatsCollector::new();
+    let entries = [
+        MultiplierEntry {
+            source: MultiplierSource::Versatility,
+            value: 1.1,
+        },
+        MultiplierEntry {
+            source: MultiplierSource::Aura(AuraIdx(19574)),
+            value: 1.25,
+        },
+    ];
+    for hit in 0..4 {
+        let time = SimTime::from_secs(hit);
+        collector.record_damage(time, SpellIdx(1), TargetIdx(0), 100.0, false, false);
+        // Bestial Wrath is up for every other hit
+        let active = if hit % 2 == 0 { 2 } else { 1 };
+        collector.record_multipliers(SpellIdx(1), &entries[..active]);
+    }
+
+    let mut names = HashMap::new();
+    names.insert(SpellIdx(19574), "Bestial Wrath".to_string());
+    let breakdown = DamageBreakdown::from_collector(&collector, &names);
+    let multipliers = &breakdown.entries[0].multipliers;
+
+    assert_eq!(multipliers.len(), 2);
+    assert_eq!(multipliers[0].source, "Versatility");
+    assert!((multipliers[0].uptime - 1.0).abs() < 1e-6);
+    assert_eq!(multipliers[1].source, "Bestial Wrath");
+    assert!((multipliers[1].average - 1.25).abs() < 1e-6);
+    assert!((multipliers[1].uptime - 0.5).abs() < 1e-6);
+
+    // Merging iterations keeps the per-source totals
+    let mut merged = StatsCollector::accumulator();
+    merged.merge(&collector);
+    merged.merge(&collector);
+    assert_eq!(merged.spell(SpellIdx(1)).unwrap().multipliers[1].hits, 4);
+}
+
 #[test]
 fn breakdown_table() {
     let mut collector = StatsCollector::new();
@@ -177,6 +238,80 @@ fn breakdown_table() {
     assert!(table.contains("10000"));
 }
 
+#[test]
+fn collector_merge_averages_iterations() {
+    let mut total = StatsCollector::accumulator();
+    for damage in [1000.0, 3000.0] {
+        let mut run = StatsCollector::new();
+        run.record_cast(SpellIdx(1));
+        run.record_damage(
+            SimTime::from_secs(1),
+            SpellIdx(1),
+            TargetIdx(0),
+            damage,
+            false,
+            false,
+        );
+        run.record_damage_from(
+            DamageSource::Pet,
+            SimTime::from_secs(2),
+            SpellIdx(2),
+            TargetIdx(1),
+            1000.0,
+            true,
+            false,
+        );
+        run.set_end(SimTime::from_secs(10));
+        total.merge(&run);
+    }
+
+    assert_eq!(total.iterations(), 2);
+    assert_eq!(total.spell(SpellIdx(1)).unwrap().casts, 2);
+    assert_eq!(total.spell(SpellIdx(2)).unwrap().source, DamageSource::Pet);
+
+    let breakdown = DamageBreakdown::from_collector(&total, &HashMap::new());
+    assert_eq!(breakdown.iterations, 2);
+    assert!((breakdown.total_damage - 3000.0).abs() < 0.1);
+    assert!((breakdown.total_dps - 300.0).abs() < 0.1);
+    assert!((breakdown.duration_secs - 10.0).abs() < 0.01);
+
+    let spell = &breakdown.entries[0];
+    assert_eq!(spell.source, DamageSource::Player);
+    assert!((spell.damage - 2000.0).abs() < 0.1);
+    assert!((spell.casts - 1.0).abs() < 0.01);
+    assert!((spell.min_hit - 1000.0).abs() < 0.1);
+    assert!((spell.max_hit - 3000.0).abs() < 0.1);
+
+    assert!((breakdown.source_percent(DamageSource::Pet) - 100.0 / 3.0).abs() < 0.1);
+    assert_eq!(breakdown.targets.len(), 2);
+    assert_eq!(breakdown.targets[1].target, TargetIdx(1));
+    assert!((breakdown.targets[1].damage - 1000.0).abs() < 0.1);
+}
+
+#[test]
+fn breakdown_skips_spells_without_damage() {
+    let mut collector = StatsCollector::new();
+    collector.record_cast(SpellIdx(1));
+    collector.record_damage(
+        SimTime::from_secs(1),
+        SpellIdx(2),
+        TargetIdx(0),
+        500.0,
+        false,
+        true,
+    );
+
+    let breakdown = DamageBreakdown::from_collector(&collector, &HashMap::new());
+
+    assert_eq!(breakdown.entries.len(), 1);
+    assert_eq!(breakdown.entries[0].min_hit, 0.0);
+    assert!((breakdown.entries[0].ticks - 1.0).abs() < 0.01);
+
+    let csv = ResultsExporter::breakdown_to_csv(&breakdown);
+    assert!(csv.starts_with("ability,source,"));
+    assert!(csv.contains("Spell_2,player,500"));
+}
+
 #[test]
 fn resource_stats() {
     let mut stats = ResourceStats::default();
@@ -239,3 +374,69 @@ fn sim_summary_batch() {
     assert_eq!(summary.iterations, 3);
     assert!(summary.std_dev.is_some());
 }
+
+fn traced_sim() -> crate::sim::Simulation {
+    use crate::sim::{SimConfig, Simulation};
+
+    let handler = crate::handler::create_handler_with_backend(
+        SpecId::BeastMastery,
+        include_str!("../../rotations/bm_hunter.json"),
+        crate::rotation::RotationBackend::default(),
+    )
+    .unwrap();
+    let mut player = crate::actor::Player::new(SpecId::BeastMastery);
+    player.stats.primary.set(Attribute::Agility, 15000.0);
+    player.stats.update(1.0);
+    let config = SimConfig::default()
+        .with_duration(20.0)
+        .with_prepull(2.0)
+        .with_trace();
+    let mut sim = Simulation::new(handler, config, player);
+    sim.run();
+    sim
+}
+
+#[test]
+fn trace_format_from_str() {
+    assert_eq!("jsonl".parse::<TraceFormat>(), Ok(TraceFormat::Jsonl));
+    assert_eq!("LOG".parse::<TraceFormat>(), Ok(TraceFormat::Log));
+    assert!("xml".parse::<TraceFormat>().is_err());
+    assert_eq!(TraceFormat::Log.to_string(), "log");
+}
+
+#[test]
+fn trace_export_jsonl() {
+    let sim = traced_sim();
+    let jsonl = TraceExporter::new(&sim).export(TraceFormat::Jsonl);
+
+    let lines: Vec<serde_json::Value> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), sim.state.trace.len());
+
+    let cast = lines.iter().find(|l| l["type"] == "spellCast").unwrap();
+    assert!(cast["name"].is_string());
+    assert_eq!(cast["targetName"], "Boss 1");
+    assert_eq!(cast["resources"][0]["resource"], "Focus");
+    assert!(cast["auras"].is_array());
+
+    let damage = lines.iter().find(|l| l["type"] == "damage").unwrap();
+    assert!(damage["isCrit"].is_boolean());
+    // Times are relative to the pull
+    assert!(lines.iter().all(|l| l["time"].as_f64().unwrap() >= -2.0));
+}
+
+#[test]
+fn trace_export_combat_log() {
+    let sim = traced_sim();
+    let log = TraceExporter::new(&sim).to_combat_log();
+
+    assert_eq!(log.lines().count(), sim.state.trace.len());
+    assert!(log.contains("SPELL_CAST_SUCCESS,Player,\"Boss 1\",34026,\"Kill Command\",Focus="));
+    assert!(log.contains("SPELL_DAMAGE,Pet,\"Boss 1\",100001,\"Pet Melee\""));
+    assert!(log.contains("SPELL_AURA_APPLIED,Player,\"Player\""));
+    assert!(log
+        .lines()
+        .all(|l| l.starts_with(" 00:") || l.starts_with("-00:")));
+}
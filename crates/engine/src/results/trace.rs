@@ -0,0 +1,283 @@
+use super::DamageSource;
+use crate::sim::{AuraSnapshot, Simulation, TraceEvent, TraceEventType};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use wowlab_common::types::{AuraIdx, SimTime, SpellIdx, TargetIdx};
+
+/// Trace export format
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// One JSON object per line
+    #[default]
+    Jsonl,
+    /// WoW combat-log-like text
+    Log,
+}
+
+impl TraceFormat {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Jsonl => "jsonl",
+            Self::Log => "log",
+        }
+    }
+}
+
+impl fmt::Display for TraceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TraceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "jsonl" | "json" => Ok(Self::Jsonl),
+            "log" | "text" => Ok(Self::Log),
+            _ => Err(format!(
+                "unknown trace format '{}' (expected 'jsonl' or 'log')",
+                s
+            )),
+        }
+    }
+}
+
+/// One JSON Lines record: the event plus display names
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceLine<'a> {
+    /// Seconds since the pull (negative during prepull)
+    time: f64,
+    #[serde(flatten)]
+    event: &'a TraceEventType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_name: Option<&'a str>,
+}
+
+/// Exports a single iteration's trace.
+///
+/// Requires the simulation to have run with `SimConfig::with_trace`.
+pub struct TraceExporter<'a> {
+    events: &'a [TraceEvent],
+    names: HashMap<SpellIdx, String>,
+    targets: Vec<&'a str>,
+    combat_start: SimTime,
+}
+
+impl<'a> TraceExporter<'a> {
+    pub fn new(sim: &'a Simulation) -> Self {
+        Self {
+            events: &sim.state.trace,
+            names: sim.handler.spell_names(),
+            targets: sim.state.enemies.iter().map(|e| e.name.as_str()).collect(),
+            combat_start: sim.state.config.combat_start(),
+        }
+    }
+
+    /// Export in the given format
+    pub fn export(&self, format: TraceFormat) -> String {
+        match format {
+            TraceFormat::Jsonl => self.to_jsonl(),
+            TraceFormat::Log => self.to_combat_log(),
+        }
+    }
+
+    /// Export as JSON Lines, one event per line
+    pub fn to_jsonl(&self) -> String {
+        let mut output = String::new();
+        for event in self.events {
+            let (name, target_name) = self.names_for(&event.event_type);
+            let line = TraceLine {
+                time: self.seconds(event.time),
+                event: &event.event_type,
+                name,
+                target_name,
+            };
+            output.push_str(&serde_json::to_string(&line).unwrap_or_default());
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Export as combat-log-like text, one event per line
+    pub fn to_combat_log(&self) -> String {
+        let mut output = String::new();
+        for event in self.events {
+            output.push_str(&self.timestamp(event.time));
+            output.push_str("  ");
+            output.push_str(&self.log_line(&event.event_type));
+            output.push('\n');
+        }
+        output
+    }
+
+    fn log_line(&self, event: &TraceEventType) -> String {
+        match event {
+            TraceEventType::SpellCast {
+                spell,
+                target,
+                resources,
+                auras,
+            } => {
+                let resources: Vec<_> = resources
+                    .iter()
+                    .map(|r| format!("{:?}={:.0}/{:.0}", r.resource, r.current, r.max))
+                    .collect();
+                let auras: Vec<_> = auras.iter().map(|a| self.aura_label(a)).collect();
+                format!(
+                    "SPELL_CAST_SUCCESS,Player,\"{}\",{},\"{}\",{},[{}]",
+                    self.target_name(*target),
+                    spell.0,
+                    self.spell_name(*spell),
+                    resources.join(","),
+                    auras.join("; ")
+                )
+            }
+            TraceEventType::Damage {
+                spell,
+                target,
+                source,
+                amount,
+                is_crit,
+                is_periodic,
+            } => format!(
+                "{},{},\"{}\",{},\"{}\",{:.0},{}",
+                if *is_periodic {
+                    "SPELL_PERIODIC_DAMAGE"
+                } else {
+                    "SPELL_DAMAGE"
+                },
+                match source {
+                    DamageSource::Player => "Player",
+                    DamageSource::Pet => "Pet",
+                },
+                self.target_name(*target),
+                spell.0,
+                self.spell_name(*spell),
+                amount,
+                if *is_crit { "crit" } else { "hit" }
+            ),
+            TraceEventType::AuraApply {
+                aura,
+                target,
+                is_debuff,
+            } => format!(
+                "SPELL_AURA_APPLIED,Player,\"{}\",{},\"{}\",{}",
+                self.aura_target_name(*target, *is_debuff),
+                aura.0,
+                self.aura_name(*aura),
+                if *is_debuff { "DEBUFF" } else { "BUFF" }
+            ),
+            TraceEventType::AuraExpire {
+                aura,
+                target,
+                is_debuff,
+            } => format!(
+                "SPELL_AURA_REMOVED,Player,\"{}\",{},\"{}\",{}",
+                self.aura_target_name(*target, *is_debuff),
+                aura.0,
+                self.aura_name(*aura),
+                if *is_debuff { "DEBUFF" } else { "BUFF" }
+            ),
+            TraceEventType::ResourceGain { resource, amount } => {
+                format!("SPELL_ENERGIZE,Player,{:?},{:.1}", resource, amount)
+            }
+            TraceEventType::CooldownStart { spell } => format!(
+                "SPELL_COOLDOWN_START,Player,{},\"{}\"",
+                spell.0,
+                self.spell_name(*spell)
+            ),
+            TraceEventType::ProcTrigger { proc } => format!("SPELL_PROC,Player,{}", proc.0),
+        }
+    }
+
+    fn names_for(&self, event: &TraceEventType) -> (Option<&str>, Option<&str>) {
+        let spell_name = |spell: SpellIdx| self.names.get(&spell).map(String::as_str);
+        let target_name = |target: TargetIdx| self.targets.get(target.0 as usize).copied();
+        match event {
+            TraceEventType::SpellCast { spell, target, .. }
+            | TraceEventType::Damage { spell, target, .. } => {
+                (spell_name(*spell), target_name(*target))
+            }
+            TraceEventType::AuraApply {
+                aura,
+                target,
+                is_debuff,
+            }
+            | TraceEventType::AuraExpire {
+                aura,
+                target,
+                is_debuff,
+            } => (
+                spell_name(SpellIdx(aura.0)),
+                is_debuff.then(|| target_name(*target)).flatten(),
+            ),
+            TraceEventType::CooldownStart { spell } => (spell_name(*spell), None),
+            TraceEventType::ResourceGain { .. } | TraceEventType::ProcTrigger { .. } => {
+                (None, None)
+            }
+        }
+    }
+
+    fn aura_label(&self, aura: &AuraSnapshot) -> String {
+        let mut label = self.aura_name(aura.aura);
+        if aura.is_debuff {
+            label = format!("{}@{}", label, self.target_name(aura.target));
+        }
+        if aura.stacks > 1 {
+            label = format!("{} x{}", label, aura.stacks);
+        }
+        format!("{} {:.1}s", label, aura.remaining)
+    }
+
+    fn spell_name(&self, spell: SpellIdx) -> String {
+        self.names
+            .get(&spell)
+            .cloned()
+            .unwrap_or_else(|| format!("Spell_{}", spell.0))
+    }
+
+    fn aura_name(&self, aura: AuraIdx) -> String {
+        self.spell_name(SpellIdx(aura.0))
+    }
+
+    fn target_name(&self, target: TargetIdx) -> String {
+        self.targets
+            .get(target.0 as usize)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("Target {}", target.0))
+    }
+
+    fn aura_target_name(&self, target: TargetIdx, is_debuff: bool) -> String {
+        if is_debuff {
+            self.target_name(target)
+        } else {
+            "Player".to_string()
+        }
+    }
+
+    /// Seconds relative to the pull
+    fn seconds(&self, time: SimTime) -> f64 {
+        (time.as_millis() as f64 - self.combat_start.as_millis() as f64) / 1000.0
+    }
+
+    /// `mm:ss.mmm` relative to the pull, negative during prepull
+    fn timestamp(&self, time: SimTime) -> String {
+        let ms = time.as_millis() as i64 - self.combat_start.as_millis() as i64;
+        let sign = if ms < 0 { "-" } else { " " };
+        let ms = ms.unsigned_abs();
+        format!(
+            "{}{:02}:{:02}.{:03}",
+            sign,
+            ms / 60_000,
+            ms / 1000 % 60,
+            ms % 1000
+        )
+    }
+}
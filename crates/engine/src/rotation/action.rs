@@ -1,4 +1,4 @@
-//! Rotation action types.
+//! Rotation action and evaluation result types.
 
 use wowlab_common::types::SpellIdx;
 
@@ -16,6 +16,8 @@ pub enum Action {
         /// Target resource amount to pool to.
         target: f64,
     },
+    /// Use the trinket in slot 1 or 2.
+    UseTrinket(u8),
     /// No action.
     None,
 }
@@ -31,3 +33,88 @@ impl Action {
         }
     }
 }
+
+/// Rotation evaluation result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct EvalResult {
+    /// Action kind: 0=none, 1=cast, 2=wait, 3=pool, 4=use trinket
+    pub kind: u8,
+    /// Spell ID (for cast), trinket slot (for use trinket) or 0
+    pub spell_id: u32,
+    /// Wait duration in seconds (for wait) or pool target (for pool)
+    pub wait_time: f32,
+}
+
+impl EvalResult {
+    pub const NONE: Self = Self {
+        kind: 0,
+        spell_id: 0,
+        wait_time: 0.0,
+    };
+
+    pub fn cast(spell: SpellIdx) -> Self {
+        Self {
+            kind: 1,
+            spell_id: spell.0,
+            wait_time: 0.0,
+        }
+    }
+
+    pub fn wait(seconds: f32) -> Self {
+        Self {
+            kind: 2,
+            spell_id: 0,
+            wait_time: seconds,
+        }
+    }
+
+    /// Create a pool result with the target resource amount.
+    pub fn pool(target: f32) -> Self {
+        Self {
+            kind: 3,
+            spell_id: 0,
+            wait_time: target, // Reuse wait_time field for pool target
+        }
+    }
+
+    /// Create a result that uses the trinket in `slot`.
+    pub fn use_trinket(slot: u8) -> Self {
+        Self {
+            kind: 4,
+            spell_id: slot as u32,
+            wait_time: 0.0,
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.kind == 0
+    }
+
+    pub fn is_cast(&self) -> bool {
+        self.kind == 1
+    }
+
+    pub fn is_wait(&self) -> bool {
+        self.kind == 2
+    }
+
+    /// Returns true if this is a pool result.
+    pub fn is_pool(&self) -> bool {
+        self.kind == 3
+    }
+
+    /// Returns the trinket slot if this is a use trinket result.
+    pub fn trinket_slot(&self) -> Option<u8> {
+        (self.kind == 4).then_some(self.spell_id as u8)
+    }
+
+    /// Returns the pool target if this is a pool result.
+    pub fn pool_target(&self) -> Option<f32> {
+        if self.is_pool() {
+            Some(self.wait_time)
+        } else {
+            None
+        }
+    }
+}
@@ -30,6 +30,28 @@ pub struct Rotation {
     pub lists: HashMap<String, Vec<Action>>,
     /// Entry point actions.
     pub actions: Vec<Action>,
+    /// Actions run once each, in order, during the prepull window.
+    #[serde(default)]
+    pub precombat: Vec<Action>,
+}
+
+impl Rotation {
+    /// Heuristic for ASTs parsed with `from_json` instead of `from_json_resolved`:
+    /// unresolved paths survive as bare user variable references.
+    pub(crate) fn looks_unresolved(&self) -> bool {
+        self.actions.iter().any(|a| {
+            matches!(
+                a,
+                Action::Cast {
+                    condition: Some(Expr::UserVar { .. }),
+                    ..
+                }
+            )
+        }) || self
+            .variables
+            .values()
+            .any(|v| matches!(v, Expr::UserVar { .. }))
+    }
 }
 
 /// An action in the rotation.
@@ -284,6 +306,16 @@ impl From<FieldType> for ValueType {
     }
 }
 
+impl From<ValueType> for FieldType {
+    fn from(vt: ValueType) -> Self {
+        match vt {
+            ValueType::Bool => FieldType::Bool,
+            ValueType::Int => FieldType::Int,
+            ValueType::Float => FieldType::Float,
+        }
+    }
+}
+
 impl Expr {
     /// Returns the type of value this expression produces.
     pub fn value_type(&self) -> ValueType {
@@ -0,0 +1,150 @@
+//! Rotation evaluation backends.
+//!
+//! Rotations can be evaluated by the Cranelift JIT (`jit` feature) or by the
+//! tree-walking interpreter, which is always available. The default is picked
+//! at build time; callers can override it at runtime.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::sim::SimState;
+
+use super::action::EvalResult;
+use super::ast::Rotation;
+#[cfg(feature = "jit")]
+use super::compiler::CompiledRotation;
+use super::context::ContextSchema;
+use super::error::{Error, Result};
+use super::interpreter::InterpretedRotation;
+use super::resolver::SpecResolver;
+
+/// Which backend evaluates a rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RotationBackend {
+    /// Native code via Cranelift. Requires the `jit` feature.
+    #[cfg_attr(feature = "jit", default)]
+    Jit,
+    /// Tree-walking interpreter over the resolved AST.
+    #[cfg_attr(not(feature = "jit"), default)]
+    Interpreter,
+}
+
+impl RotationBackend {
+    /// Whether this backend was compiled into the build.
+    pub const fn is_available(self) -> bool {
+        match self {
+            Self::Jit => cfg!(feature = "jit"),
+            Self::Interpreter => true,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Jit => "jit",
+            Self::Interpreter => "interpreter",
+        }
+    }
+}
+
+impl fmt::Display for RotationBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RotationBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "jit" => Ok(Self::Jit),
+            "interpreter" | "interp" => Ok(Self::Interpreter),
+            _ => Err(format!(
+                "unknown rotation backend '{}' (expected 'jit' or 'interpreter')",
+                s
+            )),
+        }
+    }
+}
+
+/// A rotation compiled for one of the available backends.
+pub enum RotationEvaluator {
+    #[cfg(feature = "jit")]
+    Jit(Box<CompiledRotation>),
+    Interpreter(InterpretedRotation),
+}
+
+impl RotationEvaluator {
+    /// Compile a rotation from JSON string for the given backend.
+    pub fn compile_json(
+        json: &str,
+        resolver: &SpecResolver,
+        backend: RotationBackend,
+    ) -> Result<Self> {
+        match backend {
+            #[cfg(feature = "jit")]
+            RotationBackend::Jit => Ok(Self::Jit(Box::new(CompiledRotation::compile_json(
+                json, resolver,
+            )?))),
+            RotationBackend::Interpreter => Ok(Self::Interpreter(
+                InterpretedRotation::compile_json(json, resolver)?,
+            )),
+            #[allow(unreachable_patterns)]
+            _ => Err(unavailable(backend)),
+        }
+    }
+
+    /// Compile a resolved rotation for the given backend.
+    pub fn compile(
+        rotation: &Rotation,
+        resolver: &SpecResolver,
+        backend: RotationBackend,
+    ) -> Result<Self> {
+        match backend {
+            #[cfg(feature = "jit")]
+            RotationBackend::Jit => Ok(Self::Jit(Box::new(CompiledRotation::compile(
+                rotation, resolver,
+            )?))),
+            RotationBackend::Interpreter => Ok(Self::Interpreter(InterpretedRotation::compile(
+                rotation, resolver,
+            )?)),
+            #[allow(unreachable_patterns)]
+            _ => Err(unavailable(backend)),
+        }
+    }
+
+    /// Evaluate the rotation.
+    #[inline]
+    pub fn evaluate(&self, state: &SimState) -> EvalResult {
+        match self {
+            #[cfg(feature = "jit")]
+            Self::Jit(rotation) => rotation.evaluate(state),
+            Self::Interpreter(rotation) => rotation.evaluate(state),
+        }
+    }
+
+    /// Get the context schema.
+    pub fn schema(&self) -> &ContextSchema {
+        match self {
+            #[cfg(feature = "jit")]
+            Self::Jit(rotation) => rotation.schema(),
+            Self::Interpreter(rotation) => rotation.schema(),
+        }
+    }
+
+    /// The backend this rotation was compiled for.
+    pub fn backend(&self) -> RotationBackend {
+        match self {
+            #[cfg(feature = "jit")]
+            Self::Jit(_) => RotationBackend::Jit,
+            Self::Interpreter(_) => RotationBackend::Interpreter,
+        }
+    }
+}
+
+fn unavailable(backend: RotationBackend) -> Error {
+    Error::Compilation(format!(
+        "rotation backend '{}' is not available in this build",
+        backend
+    ))
+}
@@ -9,95 +9,16 @@ use cranelift::prelude::*;
 use cranelift_jit::{JITBuilder, JITModule};
 use cranelift_module::{Linkage, Module};
 
+use crate::actor::TRINKET_SLOTS;
 use crate::sim::SimState;
-use wowlab_common::types::SpellIdx;
 
-use super::ast::{Action as AstAction, Expr, Rotation, ValueType, VarOp};
-use super::context::{populate_context, ContextSchema, ExprKey, SchemaBuilder};
+use super::action::EvalResult;
+use super::ast::{Action as AstAction, Expr, Rotation, VarOp};
+use super::context::{populate_context, ContextSchema, ExprKey};
 use super::error::{Error, Result};
 use super::expr::{FieldType, TalentExpr};
 use super::resolver::SpecResolver;
 
-/// Convert ValueType to FieldType.
-fn value_type_to_field_type(vt: ValueType) -> FieldType {
-    match vt {
-        ValueType::Bool => FieldType::Bool,
-        ValueType::Int => FieldType::Int,
-        ValueType::Float => FieldType::Float,
-    }
-}
-
-/// Rotation evaluation result.
-#[derive(Debug, Clone, Copy, PartialEq)]
-#[repr(C)]
-pub struct EvalResult {
-    /// Action kind: 0=none, 1=cast, 2=wait, 3=pool
-    pub kind: u8,
-    /// Spell ID (for cast) or 0
-    pub spell_id: u32,
-    /// Wait duration in seconds (for wait) or pool target (for pool)
-    pub wait_time: f32,
-}
-
-impl EvalResult {
-    pub const NONE: Self = Self {
-        kind: 0,
-        spell_id: 0,
-        wait_time: 0.0,
-    };
-
-    pub fn cast(spell: SpellIdx) -> Self {
-        Self {
-            kind: 1,
-            spell_id: spell.0,
-            wait_time: 0.0,
-        }
-    }
-
-    pub fn wait(seconds: f32) -> Self {
-        Self {
-            kind: 2,
-            spell_id: 0,
-            wait_time: seconds,
-        }
-    }
-
-    /// Create a pool result with the target resource amount.
-    pub fn pool(target: f32) -> Self {
-        Self {
-            kind: 3,
-            spell_id: 0,
-            wait_time: target, // Reuse wait_time field for pool target
-        }
-    }
-
-    pub fn is_none(&self) -> bool {
-        self.kind == 0
-    }
-
-    pub fn is_cast(&self) -> bool {
-        self.kind == 1
-    }
-
-    pub fn is_wait(&self) -> bool {
-        self.kind == 2
-    }
-
-    /// Returns true if this is a pool result.
-    pub fn is_pool(&self) -> bool {
-        self.kind == 3
-    }
-
-    /// Returns the pool target if this is a pool result.
-    pub fn pool_target(&self) -> Option<f32> {
-        if self.is_pool() {
-            Some(self.wait_time)
-        } else {
-            None
-        }
-    }
-}
-
 /// Function signature: fn(*const u8) -> u64 (packed EvalResult)
 type RotationFn = unsafe extern "C" fn(*const u8) -> u64;
 
@@ -136,24 +57,7 @@ impl CompiledRotation {
     /// or this function will re-parse it from JSON which may not work correctly
     /// for already-processed ASTs.
     pub fn compile(rotation: &Rotation, resolver: &SpecResolver) -> Result<Self> {
-        // If the rotation was parsed with from_json_resolved, it's already resolved.
-        // If it was parsed with from_json (unresolved), we need to re-parse from JSON.
-        // Try to detect this by checking if we have any domain expressions.
-        let needs_resolution = rotation.actions.iter().any(|a| {
-            if let AstAction::Cast {
-                condition: Some(c), ..
-            } = a
-            {
-                matches!(c, Expr::UserVar { .. })
-            } else {
-                false
-            }
-        }) || rotation
-            .variables
-            .values()
-            .any(|v| matches!(v, Expr::UserVar { .. }));
-
-        if needs_resolution {
+        if rotation.looks_unresolved() {
             // Re-serialize and re-parse - this works because we use the original JSON format
             // Note: This won't work correctly because serde serialization uses different format
             // Use compile_json instead for unresolved rotations
@@ -167,46 +71,7 @@ impl CompiledRotation {
 
     /// Compile an already-resolved rotation.
     fn compile_resolved(resolved: Rotation, resolver: &SpecResolver) -> Result<Self> {
-        // Build context schema by walking all expressions
-        let mut schema_builder = SchemaBuilder::new();
-
-        // First pass: Register all user variables from the variables map.
-        // These have initial values defined as expressions.
-        for (name, expr) in &resolved.variables {
-            let var_type = value_type_to_field_type(expr.value_type());
-            schema_builder.add_user_var(name, var_type);
-        }
-
-        // Second pass: Register any user variables from SetVar actions
-        // (they may not be in the variables map if they have no initial expression)
-        fn register_vars_from_actions(actions: &[AstAction], schema: &mut SchemaBuilder) {
-            for action in actions {
-                if let AstAction::SetVar { name, value, .. } = action {
-                    let var_type = value_type_to_field_type(value.value_type());
-                    // add_user_var deduplicates, so this is safe
-                    schema.add_user_var(name, var_type);
-                }
-            }
-        }
-        register_vars_from_actions(&resolved.actions, &mut schema_builder);
-        for actions in resolved.lists.values() {
-            register_vars_from_actions(actions, &mut schema_builder);
-        }
-
-        // Third pass: Walk all actions and expressions to find domain variables
-        for action in &resolved.actions {
-            collect_vars_from_action(action, &mut schema_builder);
-        }
-        for actions in resolved.lists.values() {
-            for action in actions {
-                collect_vars_from_action(action, &mut schema_builder);
-            }
-        }
-        for expr in resolved.variables.values() {
-            collect_vars_from_expr(expr, &mut schema_builder);
-        }
-
-        let schema = schema_builder.build();
+        let schema = ContextSchema::for_rotation(&resolved);
 
         // Compile to native code
         let (module, func_ptr) = compile_rotation(&resolved, resolver, &schema)?;
@@ -237,81 +102,6 @@ impl CompiledRotation {
     }
 }
 
-fn collect_vars_from_action(action: &AstAction, schema: &mut SchemaBuilder) {
-    match action {
-        AstAction::Cast { condition, .. }
-        | AstAction::Call { condition, .. }
-        | AstAction::Run { condition, .. }
-        | AstAction::Wait { condition, .. }
-        | AstAction::Pool { condition, .. }
-        | AstAction::UseTrinket { condition, .. }
-        | AstAction::UseItem { condition, .. } => {
-            if let Some(cond) = condition {
-                collect_vars_from_expr(cond, schema);
-            }
-        }
-        AstAction::SetVar {
-            value, condition, ..
-        } => {
-            collect_vars_from_expr(value, schema);
-            if let Some(cond) = condition {
-                collect_vars_from_expr(cond, schema);
-            }
-        }
-        AstAction::ModifyVar {
-            value, condition, ..
-        } => {
-            collect_vars_from_expr(value, schema);
-            if let Some(cond) = condition {
-                collect_vars_from_expr(cond, schema);
-            }
-        }
-        AstAction::WaitUntil { condition } => {
-            collect_vars_from_expr(condition, schema);
-        }
-    }
-}
-
-fn collect_vars_from_expr(expr: &Expr, schema: &mut SchemaBuilder) {
-    // Add domain expressions to schema
-    schema.add(expr);
-
-    // Recurse into sub-expressions
-    match expr {
-        Expr::And { operands } | Expr::Or { operands } => {
-            for e in operands {
-                collect_vars_from_expr(e, schema);
-            }
-        }
-
-        Expr::Not { operand }
-        | Expr::Floor { operand }
-        | Expr::Ceil { operand }
-        | Expr::Abs { operand } => {
-            collect_vars_from_expr(operand, schema);
-        }
-
-        Expr::Gt { left, right }
-        | Expr::Gte { left, right }
-        | Expr::Lt { left, right }
-        | Expr::Lte { left, right }
-        | Expr::Eq { left, right }
-        | Expr::Ne { left, right }
-        | Expr::Add { left, right }
-        | Expr::Sub { left, right }
-        | Expr::Mul { left, right }
-        | Expr::Div { left, right }
-        | Expr::Mod { left, right }
-        | Expr::Min { left, right }
-        | Expr::Max { left, right } => {
-            collect_vars_from_expr(left, schema);
-            collect_vars_from_expr(right, schema);
-        }
-
-        _ => {}
-    }
-}
-
 fn compile_rotation(
     rotation: &Rotation,
     resolver: &SpecResolver,
@@ -597,33 +387,59 @@ impl<'a, 'b> ExprCompiler<'a, 'b> {
             }
 
             AstAction::UseTrinket { slot, condition } => {
-                // TODO: Implement when equipment system exists.
-                // For now, skip and continue to next action.
-                // Stub: treat as if trinket is always on cooldown.
-                let _ = slot; // Acknowledge the slot parameter
-                if let Some(cond) = condition {
-                    let cond_val = self.compile_bool_expr(cond)?;
-                    self.compile_if_then_else(cond_val, |s| next(s), |s| next(s))
-                } else {
-                    next(self)
-                }
+                let ready = self.load_bool_key(&ExprKey::TrinketReady(*slot))?;
+                let cond_val = self.compile_use_condition(ready, condition.as_ref())?;
+                let result = self.pack_result(4, *slot as u32, 0.0);
+                self.compile_if_then_else(cond_val, |_| Ok(result), |s| next(s))
             }
 
             AstAction::UseItem { name, condition } => {
-                // TODO: Implement when equipment/inventory system exists.
-                // For now, skip and continue to next action.
-                // Stub: treat as if item is not available.
-                let _ = name; // Acknowledge the name parameter
-                if let Some(cond) = condition {
-                    let cond_val = self.compile_bool_expr(cond)?;
-                    self.compile_if_then_else(cond_val, |s| next(s), |s| next(s))
-                } else {
-                    next(self)
-                }
+                // Use whichever trinket slot holds the item
+                let [first, second] = TRINKET_SLOTS;
+                let first_val = self.compile_use_item_slot(first, name, condition.as_ref())?;
+                let first_result = self.pack_result(4, first as u32, 0.0);
+                self.compile_if_then_else(
+                    first_val,
+                    |_| Ok(first_result),
+                    |s| {
+                        let second_val =
+                            s.compile_use_item_slot(second, name, condition.as_ref())?;
+                        let second_result = s.pack_result(4, second as u32, 0.0);
+                        s.compile_if_then_else(second_val, |_| Ok(second_result), |s| next(s))
+                    },
+                )
             }
         }
     }
 
+    /// Combine a trinket readiness check with an action's optional condition.
+    fn compile_use_condition(&mut self, ready: Value, condition: Option<&Expr>) -> Result<Value> {
+        match condition {
+            Some(cond) => {
+                let cond_val = self.compile_bool_expr(cond)?;
+                let truthy = self.builder.ins().icmp_imm(IntCC::NotEqual, cond_val, 0);
+                Ok(self.builder.ins().band(ready, truthy))
+            }
+            None => Ok(ready),
+        }
+    }
+
+    /// Whether `slot` holds item `name`, is ready and the condition passes.
+    fn compile_use_item_slot(
+        &mut self,
+        slot: u8,
+        name: &str,
+        condition: Option<&Expr>,
+    ) -> Result<Value> {
+        let ready = self.load_bool_key(&ExprKey::TrinketReady(slot))?;
+        let holds = self.load_bool_key(&ExprKey::TrinketItem {
+            slot,
+            item: name.to_string(),
+        })?;
+        let usable = self.builder.ins().band(ready, holds);
+        self.compile_use_condition(usable, condition)
+    }
+
     fn compile_if_then_else<T, E>(&mut self, cond: Value, then_val: T, else_val: E) -> Result<Value>
     where
         T: FnOnce(&mut Self) -> Result<Value>,
@@ -1179,10 +995,13 @@ impl<'a, 'b> ExprCompiler<'a, 'b> {
 
         let key = ExprKey::from_expr(expr)
             .ok_or_else(|| Error::Compilation(format!("expression not loadable: {:?}", expr)))?;
+        self.load_bool_key(&key)
+    }
 
+    fn load_bool_key(&mut self, key: &ExprKey) -> Result<Value> {
         let offset = self
             .schema
-            .offset(&key)
+            .offset(key)
             .ok_or_else(|| Error::Compilation(format!("variable not in schema: {:?}", key)))?;
 
         let addr = self.builder.ins().iadd_imm(self.ctx_ptr, offset as i64);
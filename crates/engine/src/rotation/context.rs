@@ -1,14 +1,15 @@
-//! Runtime context for JIT-compiled rotations.
+//! Runtime context for compiled rotations.
 //!
 //! Dynamically builds a context schema based on what variables the rotation uses,
 //! then populates it at runtime from SimState using the PopulateContext trait.
 
 use std::collections::HashMap;
 
+use crate::actor::TRINKET_SLOTS;
 use crate::sim::SimState;
 use wowlab_common::types::SimTime;
 
-use super::ast::Expr;
+use super::ast::{Action, Expr, Rotation};
 use super::expr::{write_bool, write_f64, FieldType, PopulateContext};
 
 /// Context schema - describes the layout of the runtime context buffer.
@@ -42,6 +43,12 @@ pub enum ExprKey {
     Pet(super::expr::PetExpr),
     TrinketReady(u8),
     TrinketRemaining(u8),
+    Equipped(String),
+    /// Whether a trinket slot holds an item (for `use_item`).
+    TrinketItem {
+        slot: u8,
+        item: String,
+    },
     /// User-defined runtime variable.
     UserVar {
         name: String,
@@ -68,6 +75,7 @@ impl ExprKey {
             Expr::Pet(e) => Some(Self::Pet(e.clone())),
             Expr::TrinketReady { slot } => Some(Self::TrinketReady(*slot)),
             Expr::TrinketRemaining { slot } => Some(Self::TrinketRemaining(*slot)),
+            Expr::Equipped { item } => Some(Self::Equipped(item.clone())),
             _ => None,
         }
     }
@@ -88,7 +96,7 @@ impl ExprKey {
             Self::Talent(e) => e.field_type(),
             Self::Gcd(e) => e.field_type(),
             Self::Pet(e) => e.field_type(),
-            Self::TrinketReady(_) => FieldType::Bool,
+            Self::TrinketReady(_) | Self::Equipped(_) | Self::TrinketItem { .. } => FieldType::Bool,
             Self::TrinketRemaining(_) => FieldType::Float,
             Self::UserVar { var_type, .. } => *var_type,
         }
@@ -113,8 +121,32 @@ impl ExprKey {
             Self::Talent(e) => e.populate(buffer, offset, state, now),
             Self::Gcd(e) => e.populate(buffer, offset, state, now),
             Self::Pet(e) => e.populate(buffer, offset, state, now),
-            Self::TrinketReady(_) => write_bool(buffer, offset, false),
-            Self::TrinketRemaining(_) => write_f64(buffer, offset, 0.0),
+            Self::TrinketReady(slot) => write_bool(
+                buffer,
+                offset,
+                state.player.equipment.trinket_ready(*slot, now),
+            ),
+            Self::TrinketRemaining(slot) => write_f64(
+                buffer,
+                offset,
+                state
+                    .player
+                    .equipment
+                    .trinket_remaining(*slot, now)
+                    .as_secs_f64(),
+            ),
+            Self::Equipped(item) => {
+                write_bool(buffer, offset, state.player.equipment.is_equipped(item))
+            }
+            Self::TrinketItem { slot, item } => write_bool(
+                buffer,
+                offset,
+                state
+                    .player
+                    .equipment
+                    .trinket(*slot)
+                    .is_some_and(|t| t.matches(item)),
+            ),
             // UserVar is initialized separately - skip here
             Self::UserVar { .. } => {}
         }
@@ -169,6 +201,40 @@ impl ContextSchema {
                 .map(|f| f.field_type)
         })
     }
+
+    /// Build the schema for a resolved rotation.
+    ///
+    /// User variables are registered first (from `variables`, then from any
+    /// `SetVar` targets), followed by every domain expression the rotation
+    /// reads. Both rotation backends use this so their buffers share a layout.
+    pub fn for_rotation(rotation: &Rotation) -> Self {
+        let mut builder = SchemaBuilder::new();
+
+        for (name, expr) in &rotation.variables {
+            builder.add_user_var(name, expr.value_type().into());
+        }
+
+        // SetVar targets may not have an initial expression in `variables`
+        let lists = std::iter::once(&rotation.actions).chain(rotation.lists.values());
+        for actions in lists.clone() {
+            for action in actions {
+                if let Action::SetVar { name, value, .. } = action {
+                    builder.add_user_var(name, value.value_type().into());
+                }
+            }
+        }
+
+        for actions in lists {
+            for action in actions {
+                collect_vars_from_action(action, &mut builder);
+            }
+        }
+        for expr in rotation.variables.values() {
+            collect_vars_from_expr(expr, &mut builder);
+        }
+
+        builder.build()
+    }
 }
 
 /// Builder for context schema.
@@ -269,6 +335,90 @@ impl Default for SchemaBuilder {
     }
 }
 
+fn collect_vars_from_action(action: &Action, schema: &mut SchemaBuilder) {
+    match action {
+        Action::Cast { condition, .. }
+        | Action::Call { condition, .. }
+        | Action::Run { condition, .. }
+        | Action::Wait { condition, .. }
+        | Action::Pool { condition, .. } => {
+            if let Some(cond) = condition {
+                collect_vars_from_expr(cond, schema);
+            }
+        }
+        Action::UseTrinket { slot, condition } => {
+            schema.add_key(ExprKey::TrinketReady(*slot));
+            if let Some(cond) = condition {
+                collect_vars_from_expr(cond, schema);
+            }
+        }
+        Action::UseItem { name, condition } => {
+            for slot in TRINKET_SLOTS {
+                schema.add_key(ExprKey::TrinketReady(slot));
+                schema.add_key(ExprKey::TrinketItem {
+                    slot,
+                    item: name.clone(),
+                });
+            }
+            if let Some(cond) = condition {
+                collect_vars_from_expr(cond, schema);
+            }
+        }
+        Action::SetVar {
+            value, condition, ..
+        }
+        | Action::ModifyVar {
+            value, condition, ..
+        } => {
+            collect_vars_from_expr(value, schema);
+            if let Some(cond) = condition {
+                collect_vars_from_expr(cond, schema);
+            }
+        }
+        Action::WaitUntil { condition } => {
+            collect_vars_from_expr(condition, schema);
+        }
+    }
+}
+
+fn collect_vars_from_expr(expr: &Expr, schema: &mut SchemaBuilder) {
+    schema.add(expr);
+
+    match expr {
+        Expr::And { operands } | Expr::Or { operands } => {
+            for e in operands {
+                collect_vars_from_expr(e, schema);
+            }
+        }
+
+        Expr::Not { operand }
+        | Expr::Floor { operand }
+        | Expr::Ceil { operand }
+        | Expr::Abs { operand } => {
+            collect_vars_from_expr(operand, schema);
+        }
+
+        Expr::Gt { left, right }
+        | Expr::Gte { left, right }
+        | Expr::Lt { left, right }
+        | Expr::Lte { left, right }
+        | Expr::Eq { left, right }
+        | Expr::Ne { left, right }
+        | Expr::Add { left, right }
+        | Expr::Sub { left, right }
+        | Expr::Mul { left, right }
+        | Expr::Div { left, right }
+        | Expr::Mod { left, right }
+        | Expr::Min { left, right }
+        | Expr::Max { left, right } => {
+            collect_vars_from_expr(left, schema);
+            collect_vars_from_expr(right, schema);
+        }
+
+        _ => {}
+    }
+}
+
 /// Populate a context buffer from SimState.
 ///
 /// This function is now very simple - it just iterates over the fields
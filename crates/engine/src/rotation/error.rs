@@ -1,9 +1,25 @@
 //! Rotation compilation errors.
 
+use std::fmt;
+
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Location in rotation text (1-based line and column, length in bytes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("JSON parse error: {0}")]
@@ -12,6 +28,9 @@ pub enum Error {
     #[error("invalid syntax: {0}")]
     Syntax(String),
 
+    #[error("{span}: {message}")]
+    Text { span: Span, message: String },
+
     #[error("unknown variable path: {0}")]
     UnknownPath(String),
 
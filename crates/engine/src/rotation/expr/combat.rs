@@ -26,7 +26,9 @@ impl PopulateContext for CombatExpr {
     fn populate(&self, buffer: &mut [u8], offset: usize, state: &SimState, now: SimTime) {
         match self {
             Self::Time => {
-                let time = now.as_secs_f64();
+                let time = now
+                    .saturating_sub(state.config.combat_start())
+                    .as_secs_f64();
                 write_f64(buffer, offset, time);
             }
             Self::Remaining => {
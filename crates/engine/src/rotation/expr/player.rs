@@ -60,7 +60,7 @@ pub enum PlayerExpr {
 }
 
 impl PopulateContext for PlayerExpr {
-    fn populate(&self, buffer: &mut [u8], offset: usize, state: &SimState, _now: SimTime) {
+    fn populate(&self, buffer: &mut [u8], offset: usize, state: &SimState, now: SimTime) {
         let player = &state.player;
         match self {
             Self::Health => {
@@ -126,7 +126,8 @@ impl PopulateContext for PlayerExpr {
                 write_bool(buffer, offset, player.is_moving);
             }
             Self::MovementRemaining => {
-                write_f64(buffer, offset, player.movement_duration);
+                let remaining = player.movement_end.saturating_sub(now);
+                write_f64(buffer, offset, remaining.as_secs_f32() as f64);
             }
             Self::Alive => {
                 write_bool(buffer, offset, player.alive);
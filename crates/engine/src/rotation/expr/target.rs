@@ -42,7 +42,7 @@ pub enum TargetExpr {
     HealthMax,
     /// Target health percentage (0-100).
     HealthPercent,
-    /// Estimated time to die (seconds) - uses rolling DPS.
+    /// Estimated time to die (seconds) from the target's health loss.
     TimeToDie,
     /// Time to reach a specific health percentage.
     TimeToPercent { percent: PercentValue },
@@ -80,41 +80,23 @@ impl PopulateContext for TargetExpr {
                 write_f64(buffer, offset, pct as f64);
             }
             Self::TimeToDie => {
-                // Use rolling DPS estimate for TTD calculation
-                let dps = state.rolling_dps_estimate();
-                let ttd = if dps > 0.0 {
-                    state
-                        .enemies
-                        .primary()
-                        .map(|e| e.time_to_die(dps).as_secs_f64())
-                        .unwrap_or(f64::MAX)
-                } else {
-                    // Fallback to remaining fight duration if no DPS yet
-                    state.remaining().as_secs_f64()
-                };
-                write_f64(buffer, offset, ttd);
+                let ttd = state.time_to_die(state.enemies.primary);
+                write_f64(buffer, offset, ttd.as_secs_f64());
             }
             Self::TimeToPercent { percent } => {
                 let target_pct = percent.0 as f32;
-                let dps = state.rolling_dps_estimate();
-                let ttp = if dps > 0.0 {
-                    state
-                        .enemies
-                        .primary()
-                        .map(|e| e.time_to_percent(target_pct, dps).as_secs_f64())
-                        .unwrap_or(0.0)
-                } else {
-                    // No DPS yet, estimate based on fight progress
-                    let current_pct = state
-                        .enemies
-                        .primary()
-                        .map(|e| e.health_percent() * 100.0)
-                        .unwrap_or(100.0);
-                    if current_pct <= target_pct {
-                        0.0
-                    } else {
-                        state.remaining().as_secs_f64()
+                let ttp = match state.enemies.primary() {
+                    Some(enemy) if enemy.health_percent() * 100.0 <= target_pct => 0.0,
+                    Some(enemy) => {
+                        let rate = enemy.damage_rate(state.now());
+                        if rate > 0.0 {
+                            enemy.time_to_percent(target_pct, rate).as_secs_f64()
+                        } else {
+                            // Not losing health: assume it lasts the fight
+                            state.time_to_die(enemy.id).as_secs_f64()
+                        }
                     }
+                    None => 0.0,
                 };
                 write_f64(buffer, offset, ttp);
             }
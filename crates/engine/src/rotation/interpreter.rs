@@ -0,0 +1,885 @@
+//! Tree-walking rotation interpreter.
+//!
+//! Evaluates the same resolved AST and context schema as the Cranelift JIT,
+//! for builds without a native code generator (WASM, no `jit` feature).
+//!
+//! The rotation is lowered once into a small node tree with all names and
+//! schema offsets resolved, so compile errors surface at the same point as
+//! with the JIT and evaluation does no lookups. Values live in the same
+//! context buffer layout the JIT reads, including user variables, so both
+//! backends make identical decisions for the same `SimState`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::actor::TRINKET_SLOTS;
+use crate::sim::SimState;
+
+use super::action::EvalResult;
+use super::ast::{Action as AstAction, Expr, Rotation, VarOp};
+use super::context::{populate_context, ContextSchema, ExprKey};
+use super::error::{Error, Result};
+use super::eval::{safe_div, EPSILON};
+use super::expr::{FieldType, TalentExpr};
+use super::resolver::SpecResolver;
+
+thread_local! {
+    /// Context buffer reused across evaluations on this thread, so the
+    /// per-tick hot path doesn't allocate.
+    static CONTEXT: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A rotation lowered for tree-walking evaluation.
+#[derive(Debug, Clone)]
+pub struct InterpretedRotation {
+    schema: ContextSchema,
+    /// User variable initializers, run at the start of every evaluation.
+    init: Vec<Store>,
+    /// Entry point actions.
+    actions: Vec<Step>,
+    /// Reachable action lists, referenced by index from `Step::Call`/`Step::Run`.
+    lists: Vec<Vec<Step>>,
+}
+
+impl InterpretedRotation {
+    /// Compile a rotation from JSON string with a spec resolver.
+    pub fn compile_json(json: &str, resolver: &SpecResolver) -> Result<Self> {
+        let resolved = Rotation::from_json_resolved(json, resolver)?;
+        Self::compile_resolved(&resolved, resolver)
+    }
+
+    /// Compile a rotation with a spec resolver.
+    ///
+    /// Like the JIT, rejects rotations parsed without a resolver.
+    pub fn compile(rotation: &Rotation, resolver: &SpecResolver) -> Result<Self> {
+        if rotation.looks_unresolved() {
+            return Err(Error::Compilation(
+                "Rotation appears unresolved. Use compile_json() with the original JSON string instead.".to_string()
+            ));
+        }
+
+        Self::compile_resolved(rotation, resolver)
+    }
+
+    /// Lower an already-resolved rotation.
+    fn compile_resolved(rotation: &Rotation, resolver: &SpecResolver) -> Result<Self> {
+        let schema = ContextSchema::for_rotation(rotation);
+
+        let mut lowering = Lowering {
+            resolver,
+            schema: &schema,
+            variables: &rotation.variables,
+            source_lists: &rotation.lists,
+            list_index: HashMap::new(),
+            lists: Vec::new(),
+        };
+
+        let mut init = Vec::with_capacity(rotation.variables.len());
+        for (name, expr) in &rotation.variables {
+            if schema.user_var_offset(name).is_some() {
+                init.push(lowering.lower_set_var(name, expr)?);
+            }
+        }
+
+        let actions = lowering.lower_actions(&rotation.actions)?;
+        let lists = lowering
+            .lists
+            .into_iter()
+            .map(|list| list.expect("action list lowered"))
+            .collect();
+
+        Ok(Self {
+            schema,
+            init,
+            actions,
+            lists,
+        })
+    }
+
+    /// Evaluate the rotation.
+    pub fn evaluate(&self, state: &SimState) -> EvalResult {
+        CONTEXT.with(|buffer| {
+            let mut buffer = buffer.borrow_mut();
+            buffer.clear();
+            buffer.resize(self.schema.size.max(8), 0);
+            populate_context(&mut buffer, &self.schema, state);
+
+            for store in &self.init {
+                store.exec(&mut buffer);
+            }
+
+            self.run_steps(&self.actions, &mut buffer)
+        })
+    }
+
+    /// Get the context schema.
+    pub fn schema(&self) -> &ContextSchema {
+        &self.schema
+    }
+
+    fn run_steps(&self, steps: &[Step], buf: &mut [u8]) -> EvalResult {
+        for step in steps {
+            match step {
+                Step::Cast { spell, cond } => {
+                    if test(cond, buf) {
+                        return EvalResult {
+                            kind: 1,
+                            spell_id: *spell,
+                            wait_time: 0.0,
+                        };
+                    }
+                }
+                Step::Call { list, cond } => {
+                    if test(cond, buf) {
+                        let result = self.run_steps(&self.lists[*list], buf);
+                        if !result.is_none() {
+                            return result;
+                        }
+                    }
+                }
+                Step::Run { list, cond } => {
+                    if test(cond, buf) {
+                        return self.run_steps(&self.lists[*list], buf);
+                    }
+                }
+                Step::Store { store, cond } => {
+                    if test(cond, buf) {
+                        store.exec(buf);
+                    }
+                }
+                Step::Wait { seconds, cond } => {
+                    if test(cond, buf) {
+                        return EvalResult::wait(*seconds);
+                    }
+                }
+                Step::WaitUntil { cond } => {
+                    if cond.eval(buf) == 0 {
+                        return EvalResult::wait(0.1);
+                    }
+                }
+                Step::Pool { target, cond } => {
+                    if test(cond, buf) {
+                        return EvalResult::pool(*target);
+                    }
+                }
+                Step::UseTrinket { slots, cond } => {
+                    if !test(cond, buf) {
+                        continue;
+                    }
+                    if let Some(slot) = slots.iter().find(|s| s.usable(buf)) {
+                        return EvalResult::use_trinket(slot.slot);
+                    }
+                }
+            }
+        }
+        EvalResult::NONE
+    }
+}
+
+#[inline]
+fn test(cond: &Option<BoolNode>, buf: &[u8]) -> bool {
+    cond.as_ref().is_none_or(|c| c.eval(buf) != 0)
+}
+
+/// A lowered action.
+#[derive(Debug, Clone)]
+enum Step {
+    Cast {
+        spell: u32,
+        cond: Option<BoolNode>,
+    },
+    Call {
+        list: usize,
+        cond: Option<BoolNode>,
+    },
+    Run {
+        list: usize,
+        cond: Option<BoolNode>,
+    },
+    Store {
+        store: Store,
+        cond: Option<BoolNode>,
+    },
+    Wait {
+        seconds: f32,
+        cond: Option<BoolNode>,
+    },
+    WaitUntil {
+        cond: BoolNode,
+    },
+    Pool {
+        target: f32,
+        cond: Option<BoolNode>,
+    },
+    /// Use the first usable trinket slot.
+    UseTrinket {
+        slots: Vec<TrinketSlot>,
+        cond: Option<BoolNode>,
+    },
+}
+
+/// A trinket slot a use action can pick.
+#[derive(Debug, Clone)]
+struct TrinketSlot {
+    slot: u8,
+    /// Offset of the slot's readiness flag
+    ready: usize,
+    /// Offset of the "slot holds the item" flag (`use_item` only)
+    holds: Option<usize>,
+}
+
+impl TrinketSlot {
+    fn usable(&self, buf: &[u8]) -> bool {
+        buf[self.ready] != 0 && self.holds.is_none_or(|offset| buf[offset] != 0)
+    }
+}
+
+/// A write to a user variable slot.
+#[derive(Debug, Clone)]
+struct Store {
+    offset: usize,
+    field_type: FieldType,
+    value: StoreValue,
+}
+
+#[derive(Debug, Clone)]
+enum StoreValue {
+    Bool(BoolNode),
+    Num(NumNode),
+    /// Read-modify-write of the current value.
+    Modify {
+        op: VarOp,
+        operand: NumNode,
+    },
+    Zero,
+}
+
+impl Store {
+    fn exec(&self, buf: &mut [u8]) {
+        let value = match &self.value {
+            StoreValue::Bool(node) => Num::Int(node.eval(buf) as i32),
+            StoreValue::Num(node) => node.eval(buf),
+            StoreValue::Modify { op, operand } => {
+                let current = load_num(buf, self.offset, self.field_type);
+                modify(*op, current, operand.eval(buf))
+            }
+            StoreValue::Zero => Num::Int(0),
+        };
+
+        match self.field_type {
+            FieldType::Bool => {
+                let byte = match value {
+                    Num::Float(f) => (f != 0.0) as u8,
+                    Num::Int(i) => i as u8,
+                };
+                buf[self.offset] = byte;
+            }
+            FieldType::Int => write_i32(buf, self.offset, value.as_i32()),
+            FieldType::Float => write_f64(buf, self.offset, value.as_f64()),
+        }
+    }
+}
+
+fn modify(op: VarOp, current: Num, operand: Num) -> Num {
+    let (a, b) = match (current, operand) {
+        (Num::Int(a), Num::Int(b)) if op != VarOp::Div => (a, b),
+        _ => {
+            let (a, b) = (current.as_f64(), operand.as_f64());
+            return Num::Float(match op {
+                VarOp::Add => a + b,
+                VarOp::Sub => a - b,
+                VarOp::Mul => a * b,
+                VarOp::Div => a / b,
+                VarOp::Min => fmin(a, b),
+                VarOp::Max => fmax(a, b),
+                VarOp::Set | VarOp::Reset => unreachable!("lowered as plain stores"),
+            });
+        }
+    };
+
+    Num::Int(match op {
+        VarOp::Add => a.wrapping_add(b),
+        VarOp::Sub => a.wrapping_sub(b),
+        VarOp::Mul => a.wrapping_mul(b),
+        VarOp::Min => a.min(b),
+        VarOp::Max => a.max(b),
+        VarOp::Div | VarOp::Set | VarOp::Reset => unreachable!("handled above"),
+    })
+}
+
+/// Numeric value: i32 or f64, mirroring the JIT's register types.
+#[derive(Debug, Clone, Copy)]
+enum Num {
+    Int(i32),
+    Float(f64),
+}
+
+impl Num {
+    #[inline]
+    fn as_f64(self) -> f64 {
+        match self {
+            Self::Int(i) => i as f64,
+            Self::Float(f) => f,
+        }
+    }
+
+    #[inline]
+    fn as_i32(self) -> i32 {
+        match self {
+            Self::Int(i) => i,
+            Self::Float(f) => f as i32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CmpOp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+#[derive(Debug, Clone)]
+enum BoolNode {
+    Const(bool),
+    Load(usize),
+    And(Vec<BoolNode>),
+    Or(Vec<BoolNode>),
+    Not(Box<BoolNode>),
+    Cmp(CmpOp, Box<NumNode>, Box<NumNode>),
+    Eq {
+        ne: bool,
+        left: Box<NumNode>,
+        right: Box<NumNode>,
+    },
+}
+
+impl BoolNode {
+    /// Evaluates to the raw byte the JIT would produce; any non-zero value
+    /// is truthy, but `not` only flips the low bit.
+    fn eval(&self, buf: &[u8]) -> u8 {
+        match self {
+            Self::Const(b) => *b as u8,
+            Self::Load(offset) => buf[*offset],
+            Self::And(operands) => {
+                let (last, rest) = operands.split_last().expect("at least two operands");
+                if rest.iter().any(|o| o.eval(buf) == 0) {
+                    return 0;
+                }
+                last.eval(buf)
+            }
+            Self::Or(operands) => {
+                let (last, rest) = operands.split_last().expect("at least two operands");
+                if rest.iter().any(|o| o.eval(buf) != 0) {
+                    return 1;
+                }
+                last.eval(buf)
+            }
+            Self::Not(operand) => operand.eval(buf) ^ 1,
+            Self::Cmp(op, left, right) => {
+                (match (left.eval(buf), right.eval(buf)) {
+                    (Num::Int(a), Num::Int(b)) => match op {
+                        CmpOp::Gt => a > b,
+                        CmpOp::Gte => a >= b,
+                        CmpOp::Lt => a < b,
+                        CmpOp::Lte => a <= b,
+                    },
+                    (a, b) => {
+                        let (a, b) = (a.as_f64(), b.as_f64());
+                        match op {
+                            CmpOp::Gt => a > b,
+                            CmpOp::Gte => a >= b,
+                            CmpOp::Lt => a < b,
+                            CmpOp::Lte => a <= b,
+                        }
+                    }
+                }) as u8
+            }
+            Self::Eq { ne, left, right } => {
+                (match (left.eval(buf), right.eval(buf)) {
+                    (Num::Int(a), Num::Int(b)) => (a == b) != *ne,
+                    (a, b) => {
+                        let diff = (a.as_f64() - b.as_f64()).abs();
+                        if *ne {
+                            diff >= EPSILON
+                        } else {
+                            diff < EPSILON
+                        }
+                    }
+                }) as u8
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone)]
+enum NumNode {
+    Const(Num),
+    Load(usize, FieldType),
+    Arith(ArithOp, Box<NumNode>, Box<NumNode>),
+    Div(Box<NumNode>, Box<NumNode>),
+    Mod(Box<NumNode>, Box<NumNode>),
+    Floor(Box<NumNode>),
+    Ceil(Box<NumNode>),
+    Abs(Box<NumNode>),
+}
+
+impl NumNode {
+    fn eval(&self, buf: &[u8]) -> Num {
+        match self {
+            Self::Const(n) => *n,
+            Self::Load(offset, field_type) => load_num(buf, *offset, *field_type),
+            Self::Arith(op, left, right) => match (left.eval(buf), right.eval(buf)) {
+                (Num::Int(a), Num::Int(b)) => Num::Int(match op {
+                    ArithOp::Add => a.wrapping_add(b),
+                    ArithOp::Sub => a.wrapping_sub(b),
+                    ArithOp::Mul => a.wrapping_mul(b),
+                    ArithOp::Min => a.min(b),
+                    ArithOp::Max => a.max(b),
+                }),
+                (a, b) => {
+                    let (a, b) = (a.as_f64(), b.as_f64());
+                    Num::Float(match op {
+                        ArithOp::Add => a + b,
+                        ArithOp::Sub => a - b,
+                        ArithOp::Mul => a * b,
+                        ArithOp::Min => fmin(a, b),
+                        ArithOp::Max => fmax(a, b),
+                    })
+                }
+            },
+            Self::Div(left, right) => {
+                Num::Float(safe_div(left.eval(buf).as_f64(), right.eval(buf).as_f64()))
+            }
+            Self::Mod(left, right) => {
+                let (a, b) = (left.eval(buf).as_f64(), right.eval(buf).as_f64());
+                if b == 0.0 {
+                    return Num::Float(0.0);
+                }
+                // Same floor-based formulation as the JIT (not Rust's `%`)
+                let std_mod = a - b * (a / b).floor();
+                let with_b = std_mod + b;
+                Num::Float(with_b - b * (with_b / b).floor())
+            }
+            Self::Floor(operand) => Num::Float(operand.eval(buf).as_f64().floor()),
+            Self::Ceil(operand) => Num::Float(operand.eval(buf).as_f64().ceil()),
+            Self::Abs(operand) => match operand.eval(buf) {
+                Num::Int(i) => Num::Int(i.wrapping_abs()),
+                Num::Float(f) => Num::Float(f.abs()),
+            },
+        }
+    }
+}
+
+/// Cranelift `fmin`: NaN-propagating, and -0.0 orders below +0.0.
+#[inline]
+fn fmin(a: f64, b: f64) -> f64 {
+    if a.is_nan() || b.is_nan() {
+        f64::NAN
+    } else if a == b {
+        if a.is_sign_negative() {
+            a
+        } else {
+            b
+        }
+    } else {
+        a.min(b)
+    }
+}
+
+/// Cranelift `fmax`: NaN-propagating, and +0.0 orders above -0.0.
+#[inline]
+fn fmax(a: f64, b: f64) -> f64 {
+    if a.is_nan() || b.is_nan() {
+        f64::NAN
+    } else if a == b {
+        if a.is_sign_positive() {
+            a
+        } else {
+            b
+        }
+    } else {
+        a.max(b)
+    }
+}
+
+#[inline]
+fn load_num(buf: &[u8], offset: usize, field_type: FieldType) -> Num {
+    match field_type {
+        FieldType::Bool => Num::Int(buf[offset] as i32),
+        FieldType::Int => Num::Int(i32::from_ne_bytes(
+            buf[offset..offset + 4].try_into().unwrap(),
+        )),
+        FieldType::Float => Num::Float(f64::from_ne_bytes(
+            buf[offset..offset + 8].try_into().unwrap(),
+        )),
+    }
+}
+
+#[inline]
+fn write_i32(buf: &mut [u8], offset: usize, value: i32) {
+    buf[offset..offset + 4].copy_from_slice(&value.to_ne_bytes());
+}
+
+#[inline]
+fn write_f64(buf: &mut [u8], offset: usize, value: f64) {
+    buf[offset..offset + 8].copy_from_slice(&value.to_ne_bytes());
+}
+
+/// Lowers AST to interpreter nodes, resolving names and schema offsets.
+///
+/// Accepts and rejects exactly what the JIT compiler does.
+struct Lowering<'a> {
+    resolver: &'a SpecResolver,
+    schema: &'a ContextSchema,
+    variables: &'a HashMap<String, Expr>,
+    source_lists: &'a HashMap<String, Vec<AstAction>>,
+    list_index: HashMap<&'a str, usize>,
+    /// `None` while a list is being lowered (guards against recursion).
+    lists: Vec<Option<Vec<Step>>>,
+}
+
+impl<'a> Lowering<'a> {
+    fn lower_actions(&mut self, actions: &'a [AstAction]) -> Result<Vec<Step>> {
+        actions.iter().map(|a| self.lower_action(a)).collect()
+    }
+
+    fn lower_action(&mut self, action: &'a AstAction) -> Result<Step> {
+        Ok(match action {
+            AstAction::Cast { spell, condition } => {
+                // The JIT packs spell IDs into 24 bits
+                let spell = self.resolver.resolve_spell(spell)?.0 & 0x00FF_FFFF;
+                Step::Cast {
+                    spell,
+                    cond: self.lower_cond(condition)?,
+                }
+            }
+            AstAction::Call { list, condition } => {
+                let list = self.lower_list(list)?;
+                Step::Call {
+                    list,
+                    cond: self.lower_cond(condition)?,
+                }
+            }
+            AstAction::Run { list, condition } => {
+                let list = self.lower_list(list)?;
+                Step::Run {
+                    list,
+                    cond: self.lower_cond(condition)?,
+                }
+            }
+            AstAction::SetVar {
+                name,
+                value,
+                condition,
+            } => {
+                let cond = self.lower_cond(condition)?;
+                Step::Store {
+                    store: self.lower_set_var(name, value)?,
+                    cond,
+                }
+            }
+            AstAction::ModifyVar {
+                name,
+                op,
+                value,
+                condition,
+            } => {
+                let cond = self.lower_cond(condition)?;
+                Step::Store {
+                    store: self.lower_modify_var(name, *op, value)?,
+                    cond,
+                }
+            }
+            AstAction::Wait { seconds, condition } => Step::Wait {
+                seconds: *seconds as f32,
+                cond: self.lower_cond(condition)?,
+            },
+            AstAction::WaitUntil { condition } => Step::WaitUntil {
+                cond: self.lower_bool(condition)?,
+            },
+            AstAction::Pool { extra, condition } => Step::Pool {
+                target: extra.unwrap_or(0.0) as f32,
+                cond: self.lower_cond(condition)?,
+            },
+            AstAction::UseTrinket { slot, condition } => Step::UseTrinket {
+                slots: vec![self.trinket_slot(*slot, None)?],
+                cond: self.lower_cond(condition)?,
+            },
+            AstAction::UseItem { name, condition } => Step::UseTrinket {
+                slots: TRINKET_SLOTS
+                    .iter()
+                    .map(|&slot| self.trinket_slot(slot, Some(name)))
+                    .collect::<Result<_>>()?,
+                cond: self.lower_cond(condition)?,
+            },
+        })
+    }
+
+    fn trinket_slot(&self, slot: u8, item: Option<&str>) -> Result<TrinketSlot> {
+        let holds = item
+            .map(|item| {
+                self.key_offset(&ExprKey::TrinketItem {
+                    slot,
+                    item: item.to_string(),
+                })
+            })
+            .transpose()?;
+        Ok(TrinketSlot {
+            slot,
+            ready: self.key_offset(&ExprKey::TrinketReady(slot))?,
+            holds,
+        })
+    }
+
+    fn lower_list(&mut self, name: &str) -> Result<usize> {
+        let (key, actions) = self
+            .source_lists
+            .get_key_value(name)
+            .ok_or_else(|| Error::UnknownList(name.to_string()))?;
+
+        if let Some(&idx) = self.list_index.get(key.as_str()) {
+            if self.lists[idx].is_none() {
+                return Err(Error::Compilation(format!(
+                    "recursive action list: {}",
+                    name
+                )));
+            }
+            return Ok(idx);
+        }
+
+        let idx = self.lists.len();
+        self.lists.push(None);
+        self.list_index.insert(key.as_str(), idx);
+        let steps = self.lower_actions(actions)?;
+        self.lists[idx] = Some(steps);
+        Ok(idx)
+    }
+
+    fn lower_cond(&mut self, condition: &Option<Expr>) -> Result<Option<BoolNode>> {
+        condition.as_ref().map(|c| self.lower_bool(c)).transpose()
+    }
+
+    fn user_var(&self, name: &str) -> Result<(usize, FieldType)> {
+        let offset = self
+            .schema
+            .user_var_offset(name)
+            .ok_or_else(|| Error::UnknownUserVar(name.to_string()))?;
+        let field_type = self
+            .schema
+            .user_var_type(name)
+            .ok_or_else(|| Error::UnknownUserVar(name.to_string()))?;
+        Ok((offset, field_type))
+    }
+
+    fn lower_set_var(&mut self, name: &str, value: &Expr) -> Result<Store> {
+        let (offset, field_type) = self.user_var(name)?;
+        let value = match field_type {
+            FieldType::Bool => StoreValue::Bool(self.lower_bool(value)?),
+            FieldType::Int | FieldType::Float => StoreValue::Num(self.lower_num(value)?),
+        };
+        Ok(Store {
+            offset,
+            field_type,
+            value,
+        })
+    }
+
+    fn lower_modify_var(&mut self, name: &str, op: VarOp, value: &Expr) -> Result<Store> {
+        let (offset, field_type) = self.user_var(name)?;
+
+        match op {
+            VarOp::Reset => match self.variables.get(name) {
+                Some(init) => self.lower_set_var(name, init),
+                None => Ok(Store {
+                    offset,
+                    field_type,
+                    value: StoreValue::Zero,
+                }),
+            },
+            VarOp::Set => self.lower_set_var(name, value),
+            _ if field_type == FieldType::Bool => self.lower_set_var(name, value),
+            _ => {
+                let operand = self.lower_num(value)?;
+                Ok(Store {
+                    offset,
+                    field_type,
+                    value: StoreValue::Modify { op, operand },
+                })
+            }
+        }
+    }
+
+    fn schema_offset(&self, expr: &Expr) -> Result<(usize, FieldType)> {
+        let key = ExprKey::from_expr(expr)
+            .ok_or_else(|| Error::Compilation(format!("expression not loadable: {:?}", expr)))?;
+        Ok((self.key_offset(&key)?, key.field_type()))
+    }
+
+    fn key_offset(&self, key: &ExprKey) -> Result<usize> {
+        self.schema
+            .offset(key)
+            .ok_or_else(|| Error::Compilation(format!("variable not in schema: {:?}", key)))
+    }
+
+    fn lower_bool(&mut self, expr: &Expr) -> Result<BoolNode> {
+        Ok(match expr {
+            Expr::Bool { value } => BoolNode::Const(*value),
+
+            // Talent is a compile-time constant
+            Expr::Talent(TalentExpr::Enabled { value }) => BoolNode::Const(*value),
+
+            Expr::Resource(_)
+            | Expr::Cooldown(_)
+            | Expr::Buff(_)
+            | Expr::Debuff(_)
+            | Expr::Dot(_)
+            | Expr::Combat(_)
+            | Expr::Target(_)
+            | Expr::Player(_)
+            | Expr::Spell(_)
+            | Expr::Gcd(_)
+            | Expr::Pet(_)
+            | Expr::TrinketReady { .. }
+            | Expr::TrinketRemaining { .. }
+            | Expr::Equipped { .. } => BoolNode::Load(self.schema_offset(expr)?.0),
+
+            Expr::UserVar { name } => BoolNode::Load(self.user_var(name)?.0),
+
+            Expr::And { operands } => match operands.len() {
+                0 => BoolNode::Const(true),
+                1 => self.lower_bool(&operands[0])?,
+                _ => BoolNode::And(
+                    operands
+                        .iter()
+                        .map(|o| self.lower_bool(o))
+                        .collect::<Result<_>>()?,
+                ),
+            },
+            Expr::Or { operands } => match operands.len() {
+                0 => BoolNode::Const(false),
+                1 => self.lower_bool(&operands[0])?,
+                _ => BoolNode::Or(
+                    operands
+                        .iter()
+                        .map(|o| self.lower_bool(o))
+                        .collect::<Result<_>>()?,
+                ),
+            },
+            Expr::Not { operand } => BoolNode::Not(Box::new(self.lower_bool(operand)?)),
+
+            Expr::Gt { left, right } => self.lower_cmp(CmpOp::Gt, left, right)?,
+            Expr::Gte { left, right } => self.lower_cmp(CmpOp::Gte, left, right)?,
+            Expr::Lt { left, right } => self.lower_cmp(CmpOp::Lt, left, right)?,
+            Expr::Lte { left, right } => self.lower_cmp(CmpOp::Lte, left, right)?,
+            Expr::Eq { left, right } => BoolNode::Eq {
+                ne: false,
+                left: Box::new(self.lower_num(left)?),
+                right: Box::new(self.lower_num(right)?),
+            },
+            Expr::Ne { left, right } => BoolNode::Eq {
+                ne: true,
+                left: Box::new(self.lower_num(left)?),
+                right: Box::new(self.lower_num(right)?),
+            },
+
+            _ => {
+                return Err(Error::TypeError {
+                    expected: "bool",
+                    got: "number",
+                })
+            }
+        })
+    }
+
+    fn lower_cmp(&mut self, op: CmpOp, left: &Expr, right: &Expr) -> Result<BoolNode> {
+        Ok(BoolNode::Cmp(
+            op,
+            Box::new(self.lower_num(left)?),
+            Box::new(self.lower_num(right)?),
+        ))
+    }
+
+    fn lower_num(&mut self, expr: &Expr) -> Result<NumNode> {
+        let binary = |s: &mut Self, l: &Expr, r: &Expr| -> Result<(Box<NumNode>, Box<NumNode>)> {
+            Ok((Box::new(s.lower_num(l)?), Box::new(s.lower_num(r)?)))
+        };
+
+        Ok(match expr {
+            Expr::Int { value } => NumNode::Const(Num::Int(*value as i32)),
+            Expr::Float { value } => NumNode::Const(Num::Float(*value)),
+
+            Expr::Resource(_)
+            | Expr::Cooldown(_)
+            | Expr::Buff(_)
+            | Expr::Debuff(_)
+            | Expr::Dot(_)
+            | Expr::Combat(_)
+            | Expr::Target(_)
+            | Expr::Player(_)
+            | Expr::Spell(_)
+            | Expr::Gcd(_)
+            | Expr::Pet(_)
+            | Expr::TrinketReady { .. }
+            | Expr::TrinketRemaining { .. } => {
+                let (offset, field_type) = self.schema_offset(expr)?;
+                NumNode::Load(offset, field_type)
+            }
+
+            Expr::UserVar { name } => {
+                let (offset, field_type) = self.user_var(name)?;
+                NumNode::Load(offset, field_type)
+            }
+
+            Expr::Add { left, right } => {
+                let (l, r) = binary(self, left, right)?;
+                NumNode::Arith(ArithOp::Add, l, r)
+            }
+            Expr::Sub { left, right } => {
+                let (l, r) = binary(self, left, right)?;
+                NumNode::Arith(ArithOp::Sub, l, r)
+            }
+            Expr::Mul { left, right } => {
+                let (l, r) = binary(self, left, right)?;
+                NumNode::Arith(ArithOp::Mul, l, r)
+            }
+            Expr::Min { left, right } => {
+                let (l, r) = binary(self, left, right)?;
+                NumNode::Arith(ArithOp::Min, l, r)
+            }
+            Expr::Max { left, right } => {
+                let (l, r) = binary(self, left, right)?;
+                NumNode::Arith(ArithOp::Max, l, r)
+            }
+            Expr::Div { left, right } => {
+                let (l, r) = binary(self, left, right)?;
+                NumNode::Div(l, r)
+            }
+            Expr::Mod { left, right } => {
+                let (l, r) = binary(self, left, right)?;
+                NumNode::Mod(l, r)
+            }
+
+            Expr::Floor { operand } => NumNode::Floor(Box::new(self.lower_num(operand)?)),
+            Expr::Ceil { operand } => NumNode::Ceil(Box::new(self.lower_num(operand)?)),
+            Expr::Abs { operand } => NumNode::Abs(Box::new(self.lower_num(operand)?)),
+
+            _ => {
+                return Err(Error::TypeError {
+                    expected: "number",
+                    got: "bool",
+                })
+            }
+        })
+    }
+}
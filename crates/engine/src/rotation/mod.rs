@@ -1,14 +1,19 @@
-//! JIT-compiled rotation system.
+//! Rotation system.
 //!
-//! Compiles user-defined rotations from JSON to native machine code
-//! via Cranelift for ~3ns evaluation time.
+//! Compiles user-defined rotations from JSON or SimC-style text (see
+//! [`Rotation::from_text`]) to native machine code
+//! via Cranelift for ~3ns evaluation time. Builds without the `jit`
+//! feature (e.g. WASM) use a tree-walking interpreter over the same
+//! AST and context layout instead; see [`RotationBackend`].
 //!
 //! # Pipeline
 //!
 //! ```text
 //! JSON → AST → Cranelift IR → Native Code
-//!                                  ↓
-//!        SimState → Context → evaluate() → EvalResult
+//!          ↘                       ↓
+//!            Interpreter nodes → evaluate() → EvalResult
+//!                                  ↑
+//!                  SimState → Context
 //! ```
 //!
 //! Names are resolved at parse time using a SpecResolver, eliminating
@@ -45,14 +50,21 @@
 
 mod action;
 mod ast;
+mod backend;
 #[cfg(feature = "jit")]
 mod compiler;
 mod context;
 mod error;
 pub mod eval;
 pub mod expr;
+mod interpreter;
 mod parser;
+mod precombat;
+mod printer;
 mod resolver;
+mod simc;
+mod syntax;
+mod text;
 mod validate;
 
 #[cfg(test)]
@@ -60,14 +72,19 @@ mod tests;
 
 // Re-export evaluation result type (output of compiled rotation)
 // This is what SpecHandler::next_action returns
-pub use action::Action;
+pub use action::{Action, EvalResult};
 
 // Re-export AST types (Action renamed to AstAction to avoid conflict)
 pub use ast::{Action as AstAction, Expr, Rotation, ValueType, VarOp};
 
 // Re-export compiler (only with jit feature)
 #[cfg(feature = "jit")]
-pub use compiler::{CompiledRotation, EvalResult};
+pub use compiler::CompiledRotation;
+
+// Re-export interpreter and backend selection
+pub use backend::{RotationBackend, RotationEvaluator};
+pub use interpreter::InterpretedRotation;
+pub use precombat::PrecombatList;
 
 // Re-export context types
 pub use context::{populate_context, ContextField, ContextSchema, ExprKey, SchemaBuilder};
@@ -79,11 +96,14 @@ pub use expr::{
 };
 
 // Re-export error types
-pub use error::{Error, Result};
+pub use error::{Error, Result, Span};
 
 // Re-export resolver types
 pub use resolver::SpecResolver;
 
+// Re-export SimC import
+pub use simc::{import_simc, ImportIssue, SimcImport};
+
 // Re-export validation types
 pub use validate::{
     get_var_path_schema, validate_rotation, ValidationError, ValidationResult, ValidationWarning,
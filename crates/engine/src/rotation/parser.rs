@@ -71,11 +71,20 @@ impl Rotation {
             }
         }
 
+        // Parse precombat actions
+        let mut precombat = Vec::new();
+        if let Some(arr) = obj.get("precombat").and_then(|v| v.as_array()) {
+            for item in arr {
+                precombat.push(parse_action_unresolved(item)?);
+            }
+        }
+
         Ok(Rotation {
             name,
             variables,
             lists,
             actions,
+            precombat,
         })
     }
 
@@ -122,11 +131,20 @@ impl Rotation {
             }
         }
 
+        // Parse precombat actions
+        let mut precombat = Vec::new();
+        if let Some(arr) = obj.get("precombat").and_then(|v| v.as_array()) {
+            for item in arr {
+                precombat.push(parse_action_resolved(item, resolver)?);
+            }
+        }
+
         Ok(Rotation {
             name,
             variables,
             lists,
             actions,
+            precombat,
         })
     }
 }
@@ -722,7 +740,7 @@ fn parse_expr_object_resolved(
 }
 
 /// Parse a variable path string and resolve to the appropriate Expr variant.
-fn parse_var_path_resolved(s: &str, resolver: &SpecResolver) -> Result<Expr> {
+pub(super) fn parse_var_path_resolved(s: &str, resolver: &SpecResolver) -> Result<Expr> {
     let parts: Vec<&str> = s.split('.').collect();
 
     match parts.as_slice() {
@@ -1010,7 +1028,7 @@ fn parse_var_path_resolved(s: &str, resolver: &SpecResolver) -> Result<Expr> {
     }
 }
 
-fn parse_var_op(s: &str) -> Result<VarOp> {
+pub(super) fn parse_var_op(s: &str) -> Result<VarOp> {
     match s {
         "set" => Ok(VarOp::Set),
         "add" => Ok(VarOp::Add),
@@ -0,0 +1,85 @@
+//! Precombat action list.
+//!
+//! [`Rotation::precombat`] runs during the prepull window (see
+//! `SimConfig::prepull`). Unlike the combat priority list, each action is
+//! tried once, in order: a cast or fixed wait moves on to the next action, a
+//! false condition skips it, and `wait_until` holds the list until it is
+//! satisfied. Progress lives in `SimState::precombat_next` so it resets
+//! with the iteration.
+
+use crate::sim::SimState;
+
+use super::action::EvalResult;
+use super::ast::{Action, Rotation};
+use super::backend::{RotationBackend, RotationEvaluator};
+use super::error::Result;
+use super::resolver::SpecResolver;
+
+/// Precombat actions, each compiled as its own single-action rotation.
+pub struct PrecombatList {
+    steps: Vec<PrecombatStep>,
+}
+
+struct PrecombatStep {
+    evaluator: RotationEvaluator,
+    /// `wait_until` holds this step instead of moving past it.
+    holds: bool,
+}
+
+impl PrecombatList {
+    /// Compile the precombat actions of a resolved rotation.
+    pub fn compile(
+        rotation: &Rotation,
+        resolver: &SpecResolver,
+        backend: RotationBackend,
+    ) -> Result<Self> {
+        let steps = rotation
+            .precombat
+            .iter()
+            .map(|action| {
+                let single = Rotation {
+                    name: rotation.name.clone(),
+                    variables: rotation.variables.clone(),
+                    lists: rotation.lists.clone(),
+                    actions: vec![action.clone()],
+                    precombat: Vec::new(),
+                };
+                Ok(PrecombatStep {
+                    evaluator: RotationEvaluator::compile(&single, resolver, backend)?,
+                    holds: matches!(action, Action::WaitUntil { .. }),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { steps })
+    }
+
+    /// A list with no actions.
+    pub fn empty() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Next precombat decision, or `None` once every action has been tried.
+    pub fn next(&self, state: &mut SimState) -> Option<EvalResult> {
+        while let Some(step) = self.steps.get(state.precombat_next) {
+            let result = step.evaluator.evaluate(state);
+            if step.holds && result.is_wait() {
+                return Some(result);
+            }
+
+            state.precombat_next += 1;
+            if !result.is_none() {
+                return Some(result);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,497 @@
+//! Pretty-printer from the rotation AST to text syntax.
+//!
+//! Output re-parses with [`Rotation::from_text`] (or `from_text_resolved`)
+//! to the same AST. Lists and variables print in name order so the output is
+//! stable for diffs. Talents are folded to constants at resolve time, so
+//! resolved talent checks print as their values.
+
+use std::fmt::Write;
+
+use wowlab_common::types::{AuraIdx, ResourceType, SpellIdx};
+
+use super::ast::{Action, Expr, Rotation, VarOp};
+use super::expr::{
+    BuffExpr, CombatExpr, CooldownExpr, DebuffExpr, DotExpr, EnemyExpr, GcdExpr, PetExpr,
+    PlayerExpr, ResourceExpr, SpellExpr, TalentExpr, TargetExpr,
+};
+use super::resolver::{resource_type_name, SpecResolver};
+
+impl Rotation {
+    /// Format as rotation text. Resolved spell and aura IDs print numerically.
+    pub fn to_text(&self) -> String {
+        Printer { resolver: None }.rotation(self)
+    }
+
+    /// Format as rotation text, naming resolved IDs through the resolver.
+    pub fn to_text_resolved(&self, resolver: &SpecResolver) -> String {
+        Printer {
+            resolver: Some(resolver),
+        }
+        .rotation(self)
+    }
+}
+
+impl Expr {
+    /// Format as a text-syntax expression.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        Printer { resolver: None }.expr(&mut out, self, 0);
+        out
+    }
+
+    /// Format as a text-syntax expression, naming resolved IDs.
+    pub fn to_text_resolved(&self, resolver: &SpecResolver) -> String {
+        let mut out = String::new();
+        Printer {
+            resolver: Some(resolver),
+        }
+        .expr(&mut out, self, 0);
+        out
+    }
+}
+
+const PREC_OR: u8 = 1;
+const PREC_AND: u8 = 2;
+const PREC_CMP: u8 = 3;
+const PREC_ADD: u8 = 4;
+const PREC_MUL: u8 = 5;
+const PREC_UNARY: u8 = 6;
+const PREC_ATOM: u8 = 7;
+
+struct Printer<'a> {
+    resolver: Option<&'a SpecResolver>,
+}
+
+impl Printer<'_> {
+    fn rotation(&self, rotation: &Rotation) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "name={}", rotation.name);
+
+        let mut variables: Vec<_> = rotation.variables.iter().collect();
+        variables.sort_by_key(|(name, _)| name.as_str());
+        for (name, value) in variables {
+            let _ = write!(out, "variables.{}=", name);
+            self.expr(&mut out, value, 0);
+            out.push('\n');
+        }
+
+        self.list(&mut out, "actions.precombat", &rotation.precombat);
+        self.list(&mut out, "actions", &rotation.actions);
+
+        let mut lists: Vec<_> = rotation.lists.iter().collect();
+        lists.sort_by_key(|(name, _)| name.as_str());
+        for (name, actions) in lists {
+            self.list(&mut out, &format!("actions.{}", name), actions);
+        }
+
+        out
+    }
+
+    fn list(&self, out: &mut String, head: &str, actions: &[Action]) {
+        for (idx, action) in actions.iter().enumerate() {
+            out.push_str(head);
+            out.push_str(if idx == 0 { "=" } else { "+=/" });
+            self.action(out, action);
+            out.push('\n');
+        }
+    }
+
+    fn action(&self, out: &mut String, action: &Action) {
+        let condition = match action {
+            Action::Cast { spell, condition } => {
+                out.push_str(spell);
+                condition
+            }
+            Action::Call { list, condition } => {
+                let _ = write!(out, "call_action_list,name={}", list);
+                condition
+            }
+            Action::Run { list, condition } => {
+                let _ = write!(out, "run_action_list,name={}", list);
+                condition
+            }
+            Action::SetVar {
+                name,
+                value,
+                condition,
+            } => {
+                let _ = write!(out, "variable,name={},value=", name);
+                self.expr(out, value, 0);
+                condition
+            }
+            Action::ModifyVar {
+                name,
+                op,
+                value,
+                condition,
+            } => {
+                let _ = write!(out, "variable,name={},op={},value=", name, var_op(*op));
+                self.expr(out, value, 0);
+                condition
+            }
+            Action::Wait { seconds, condition } => {
+                out.push_str("wait,sec=");
+                float(out, *seconds);
+                condition
+            }
+            Action::WaitUntil { condition } => {
+                out.push_str("wait,until=");
+                self.expr(out, condition, 0);
+                return;
+            }
+            Action::Pool { extra, condition } => {
+                out.push_str("pool");
+                if let Some(extra) = extra {
+                    out.push_str(",extra=");
+                    float(out, *extra);
+                }
+                condition
+            }
+            Action::UseTrinket { slot, condition } => {
+                let _ = write!(out, "use_trinket,slot={}", slot);
+                condition
+            }
+            Action::UseItem { name, condition } => {
+                let _ = write!(out, "use_item,name={}", name);
+                condition
+            }
+        };
+
+        if let Some(condition) = condition {
+            out.push_str(",if=");
+            self.expr(out, condition, 0);
+        }
+    }
+
+    fn expr(&self, out: &mut String, expr: &Expr, min_prec: u8) {
+        let prec = precedence(expr);
+        let parens = prec < min_prec;
+        if parens {
+            out.push('(');
+        }
+
+        match expr {
+            Expr::Bool { value } => out.push_str(if *value { "true" } else { "false" }),
+            Expr::Int { value } => {
+                let _ = write!(out, "{}", value);
+            }
+            Expr::Float { value } => float(out, *value),
+            Expr::UserVar { name } => out.push_str(name),
+
+            Expr::And { operands } | Expr::Or { operands } => {
+                let sep = if matches!(expr, Expr::And { .. }) {
+                    "&"
+                } else {
+                    "|"
+                };
+                for (idx, operand) in operands.iter().enumerate() {
+                    if idx > 0 {
+                        out.push_str(sep);
+                    }
+                    self.expr(out, operand, prec + 1);
+                }
+            }
+            Expr::Not { operand } => {
+                out.push('!');
+                self.expr(out, operand, PREC_UNARY);
+            }
+
+            Expr::Gt { left, right }
+            | Expr::Gte { left, right }
+            | Expr::Lt { left, right }
+            | Expr::Lte { left, right }
+            | Expr::Eq { left, right }
+            | Expr::Ne { left, right } => {
+                self.expr(out, left, prec + 1);
+                out.push_str(match expr {
+                    Expr::Gt { .. } => ">",
+                    Expr::Gte { .. } => ">=",
+                    Expr::Lt { .. } => "<",
+                    Expr::Lte { .. } => "<=",
+                    Expr::Eq { .. } => "==",
+                    _ => "!=",
+                });
+                self.expr(out, right, prec + 1);
+            }
+
+            Expr::Add { left, right }
+            | Expr::Sub { left, right }
+            | Expr::Mul { left, right }
+            | Expr::Div { left, right }
+            | Expr::Mod { left, right } => {
+                self.expr(out, left, prec);
+                out.push_str(match expr {
+                    Expr::Add { .. } => "+",
+                    Expr::Sub { .. } => "-",
+                    Expr::Mul { .. } => "*",
+                    Expr::Div { .. } => "%",
+                    _ => "%%",
+                });
+                self.expr(out, right, prec + 1);
+            }
+
+            Expr::Floor { operand } | Expr::Ceil { operand } | Expr::Abs { operand } => {
+                out.push_str(match expr {
+                    Expr::Floor { .. } => "floor(",
+                    Expr::Ceil { .. } => "ceil(",
+                    _ => "abs(",
+                });
+                self.expr(out, operand, 0);
+                out.push(')');
+            }
+            Expr::Min { left, right } | Expr::Max { left, right } => {
+                out.push_str(if matches!(expr, Expr::Min { .. }) {
+                    "min("
+                } else {
+                    "max("
+                });
+                self.expr(out, left, 0);
+                out.push(',');
+                self.expr(out, right, 0);
+                out.push(')');
+            }
+
+            Expr::Talent(talent) => {
+                let _ = match talent {
+                    TalentExpr::Enabled { value } => write!(out, "{}", value),
+                    TalentExpr::Rank { rank } => write!(out, "{}", rank),
+                    TalentExpr::MaxRank { max_rank } => write!(out, "{}", max_rank),
+                };
+            }
+
+            _ => out.push_str(&self.path(expr)),
+        }
+
+        if parens {
+            out.push(')');
+        }
+    }
+
+    /// Variable path for a domain expression.
+    fn path(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Resource(e) => {
+                let (resource, suffix) = match e {
+                    ResourceExpr::ResourceCurrent { resource } => (resource, ""),
+                    ResourceExpr::ResourceMax { resource } => (resource, ".max"),
+                    ResourceExpr::ResourceDeficit { resource } => (resource, ".deficit"),
+                    ResourceExpr::ResourcePercent { resource } => (resource, ".percent"),
+                    ResourceExpr::ResourceDeficitPercent { resource } => {
+                        (resource, ".deficit_percent")
+                    }
+                    ResourceExpr::ResourceRegen { resource } => (resource, ".regen"),
+                    ResourceExpr::ResourceTimeToMax { resource } => (resource, ".time_to_max"),
+                    ResourceExpr::ResourceTimeTo { resource, amount } => {
+                        return format!("resource.{}.time_to.{}", self.resource(*resource), amount)
+                    }
+                };
+                format!("resource.{}{}", self.resource(*resource), suffix)
+            }
+
+            Expr::Cooldown(e) => {
+                let field = match e {
+                    CooldownExpr::CooldownReady { .. } => "ready",
+                    CooldownExpr::CooldownRemaining { .. } => "remaining",
+                    CooldownExpr::CooldownDuration { .. } => "duration",
+                    CooldownExpr::CooldownBaseDuration { .. } => "base_duration",
+                    CooldownExpr::CooldownCharges { .. } => "charges",
+                    CooldownExpr::CooldownChargesMax { .. } => "charges_max",
+                    CooldownExpr::CooldownChargesFractional { .. } => "charges_fractional",
+                    CooldownExpr::CooldownRechargeTime { .. } => "recharge_time",
+                    CooldownExpr::CooldownFullRechargeTime { .. } => "full_recharge_time",
+                };
+                format!("cd.{}.{}", self.spell(e.spell_id()), field)
+            }
+
+            Expr::Buff(e) => {
+                let field = match e {
+                    BuffExpr::Active { .. } => "active",
+                    BuffExpr::Inactive { .. } => "inactive",
+                    BuffExpr::Remaining { .. } => "remaining",
+                    BuffExpr::Stacks { .. } => "stacks",
+                    BuffExpr::StacksMax { .. } => "stacks_max",
+                    BuffExpr::Duration { .. } => "duration",
+                };
+                format!("buff.{}.{}", self.aura(e.aura_id()), field)
+            }
+
+            Expr::Debuff(e) => {
+                let field = match e {
+                    DebuffExpr::Active { .. } => "active",
+                    DebuffExpr::Inactive { .. } => "inactive",
+                    DebuffExpr::Remaining { .. } => "remaining",
+                    DebuffExpr::Stacks { .. } => "stacks",
+                    DebuffExpr::Refreshable { .. } => "refreshable",
+                };
+                format!("debuff.{}.{}", self.aura(e.aura_id()), field)
+            }
+
+            Expr::Dot(e) => {
+                let field = match e {
+                    DotExpr::Ticking { .. } => "ticking",
+                    DotExpr::Remaining { .. } => "remaining",
+                    DotExpr::Refreshable { .. } => "refreshable",
+                    DotExpr::TicksRemaining { .. } => "ticks_remaining",
+                };
+                format!("dot.{}.{}", self.dot(e.aura_id()), field)
+            }
+
+            Expr::Combat(e) => match e {
+                CombatExpr::Time => "combat.time".into(),
+                CombatExpr::Remaining => "combat.remaining".into(),
+            },
+
+            Expr::Target(e) => match e {
+                TargetExpr::Health => "target.health".into(),
+                TargetExpr::HealthMax => "target.health_max".into(),
+                TargetExpr::HealthPercent => "target.health_percent".into(),
+                TargetExpr::TimeToDie => "target.time_to_die".into(),
+                TargetExpr::TimeToPercent { percent } => {
+                    format!("target.time_to_percent.{}", percent.0)
+                }
+                TargetExpr::Distance => "target.distance".into(),
+                TargetExpr::Casting => "target.casting".into(),
+                TargetExpr::Moving => "target.moving".into(),
+                TargetExpr::EnemyCount => "enemy.count".into(),
+            },
+
+            Expr::Enemy(e) => match e {
+                EnemyExpr::Count => "enemy.count".into(),
+                EnemyExpr::SpellTargetsHit { spell } => {
+                    format!("enemy.spell_targets_hit.{}", self.spell(*spell))
+                }
+            },
+
+            Expr::Player(e) => format!(
+                "player.{}",
+                match e {
+                    PlayerExpr::Health => "health",
+                    PlayerExpr::HealthMax => "health.max",
+                    PlayerExpr::HealthPercent => "health.percent",
+                    PlayerExpr::HealthDeficit => "health.deficit",
+                    PlayerExpr::Haste => "haste",
+                    PlayerExpr::Crit => "crit",
+                    PlayerExpr::Mastery => "mastery",
+                    PlayerExpr::Versatility => "versatility",
+                    PlayerExpr::AttackPower => "attack_power",
+                    PlayerExpr::SpellPower => "spell_power",
+                    PlayerExpr::Level => "level",
+                    PlayerExpr::Armor => "armor",
+                    PlayerExpr::Stamina => "stamina",
+                    PlayerExpr::PrimaryStat => "primary_stat",
+                    PlayerExpr::Moving => "moving",
+                    PlayerExpr::MovementRemaining => "movement_remaining",
+                    PlayerExpr::Alive => "alive",
+                    PlayerExpr::InCombat => "in_combat",
+                    PlayerExpr::Stealthed => "stealthed",
+                    PlayerExpr::Mounted => "mounted",
+                }
+            ),
+
+            Expr::Spell(e) => {
+                let (spell, field) = match e {
+                    SpellExpr::Cost { spell } => (spell, "cost"),
+                    SpellExpr::CastTime { spell } => (spell, "cast_time"),
+                    SpellExpr::Range { spell } => (spell, "range"),
+                    SpellExpr::InRange { spell } => (spell, "in_range"),
+                    SpellExpr::Usable { spell } => (spell, "usable"),
+                };
+                format!("spell.{}.{}", self.spell(*spell), field)
+            }
+
+            Expr::Gcd(e) => match e {
+                GcdExpr::Active => "gcd.active".into(),
+                GcdExpr::Remaining => "gcd.remaining".into(),
+                GcdExpr::Duration => "gcd.duration".into(),
+            },
+
+            Expr::Pet(e) => match e {
+                PetExpr::Active => "pet.active".into(),
+                PetExpr::Count => "pet.count".into(),
+                PetExpr::Remaining => "pet.remaining".into(),
+                PetExpr::BuffActive { aura } => format!("pet.buff.{}.active", self.aura(*aura)),
+            },
+
+            Expr::Equipped { item } => format!("equipped.{}", item),
+            Expr::TrinketReady { slot } => format!("trinket.{}.ready", slot),
+            Expr::TrinketRemaining { slot } => format!("trinket.{}.remaining", slot),
+
+            _ => unreachable!("not a path expression: {:?}", expr),
+        }
+    }
+
+    fn spell(&self, id: SpellIdx) -> String {
+        self.resolver
+            .and_then(|r| r.spell_name(id))
+            .map(str::to_string)
+            .unwrap_or_else(|| id.0.to_string())
+    }
+
+    fn aura(&self, id: AuraIdx) -> String {
+        self.resolver
+            .and_then(|r| r.aura_name(id))
+            .map(str::to_string)
+            .unwrap_or_else(|| id.0.to_string())
+    }
+
+    fn dot(&self, id: AuraIdx) -> String {
+        self.resolver
+            .and_then(|r| r.dot_name(id))
+            .map(str::to_string)
+            .unwrap_or_else(|| self.aura(id))
+    }
+
+    fn resource(&self, resource: ResourceType) -> String {
+        self.resolver
+            .and_then(|r| r.resource_name(resource))
+            .unwrap_or_else(|| resource_type_name(resource))
+            .to_string()
+    }
+}
+
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Or { .. } => PREC_OR,
+        Expr::And { .. } => PREC_AND,
+        Expr::Gt { .. }
+        | Expr::Gte { .. }
+        | Expr::Lt { .. }
+        | Expr::Lte { .. }
+        | Expr::Eq { .. }
+        | Expr::Ne { .. } => PREC_CMP,
+        Expr::Add { .. } | Expr::Sub { .. } => PREC_ADD,
+        Expr::Mul { .. } | Expr::Div { .. } | Expr::Mod { .. } => PREC_MUL,
+        Expr::Not { .. } => PREC_UNARY,
+        Expr::Int { value } if *value < 0 => PREC_UNARY,
+        Expr::Float { value } if value.is_sign_negative() => PREC_UNARY,
+        Expr::Talent(TalentExpr::Rank { rank: value })
+        | Expr::Talent(TalentExpr::MaxRank { max_rank: value })
+            if *value < 0 =>
+        {
+            PREC_UNARY
+        }
+        _ => PREC_ATOM,
+    }
+}
+
+/// Print a float so it re-parses as a float.
+fn float(out: &mut String, value: f64) {
+    let start = out.len();
+    let _ = write!(out, "{}", value);
+    if value.is_finite() && !out[start..].contains('.') {
+        out.push_str(".0");
+    }
+}
+
+fn var_op(op: VarOp) -> &'static str {
+    match op {
+        VarOp::Set => "set",
+        VarOp::Add => "add",
+        VarOp::Sub => "sub",
+        VarOp::Mul => "mul",
+        VarOp::Div => "div",
+        VarOp::Min => "min",
+        VarOp::Max => "max",
+        VarOp::Reset => "reset",
+    }
+}
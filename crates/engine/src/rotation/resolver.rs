@@ -5,7 +5,6 @@
 
 use std::collections::{HashMap, HashSet};
 
-#[cfg(feature = "jit")]
 use crate::specs::SpecData;
 use wowlab_common::types::{AuraIdx, ResourceType, SpellIdx};
 
@@ -76,7 +75,6 @@ impl SpecResolver {
     }
 
     /// Create from a SpecData registry.
-    #[cfg(feature = "jit")]
     pub fn from_spec_data(data: &SpecData) -> Self {
         let mut resolver = Self::new(data.name.clone());
         resolver.resource_type_str = data.primary_resource().map(String::from);
@@ -243,6 +241,33 @@ impl SpecResolver {
             .map(|info| info.enabled)
             .unwrap_or(false)
     }
+
+    /// Reverse lookup of a spell name (first alphabetically if aliased).
+    pub fn spell_name(&self, id: SpellIdx) -> Option<&str> {
+        reverse_lookup(&self.spells, id)
+    }
+
+    /// Reverse lookup of an aura name (first alphabetically if aliased).
+    pub fn aura_name(&self, id: AuraIdx) -> Option<&str> {
+        reverse_lookup(&self.auras, id)
+    }
+
+    /// Reverse lookup of a DoT name (first alphabetically if aliased).
+    pub fn dot_name(&self, id: AuraIdx) -> Option<&str> {
+        reverse_lookup(&self.dots, id)
+    }
+
+    /// Reverse lookup of a registered resource name.
+    pub fn resource_name(&self, resource: ResourceType) -> Option<&str> {
+        reverse_lookup(&self.resources, resource)
+    }
+}
+
+fn reverse_lookup<T: PartialEq>(map: &HashMap<String, T>, id: T) -> Option<&str> {
+    map.iter()
+        .filter(|(_, v)| **v == id)
+        .map(|(k, _)| k.as_str())
+        .min()
 }
 
 /// Convert a resource name string to a ResourceType.
@@ -271,6 +296,29 @@ pub fn resource_name_to_type(name: &str) -> Option<ResourceType> {
     }
 }
 
+/// Canonical name of a ResourceType (inverse of [`resource_name_to_type`]).
+pub fn resource_type_name(resource: ResourceType) -> &'static str {
+    match resource {
+        ResourceType::Mana => "mana",
+        ResourceType::Rage => "rage",
+        ResourceType::Focus => "focus",
+        ResourceType::Energy => "energy",
+        ResourceType::ComboPoints => "combo_points",
+        ResourceType::Runes => "runes",
+        ResourceType::RunicPower => "runic_power",
+        ResourceType::SoulShards => "soul_shards",
+        ResourceType::LunarPower => "lunar_power",
+        ResourceType::HolyPower => "holy_power",
+        ResourceType::Maelstrom => "maelstrom",
+        ResourceType::Chi => "chi",
+        ResourceType::Insanity => "insanity",
+        ResourceType::ArcaneCharges => "arcane_charges",
+        ResourceType::Fury => "fury",
+        ResourceType::Pain => "pain",
+        ResourceType::Essence => "essence",
+    }
+}
+
 /// Check that a resource name is valid (can be resolved).
 pub fn check_resource(r: &str, resolver: &SpecResolver) -> Result<ResourceType> {
     resolver.resolve_resource(r)
@@ -0,0 +1,676 @@
+//! SimulationCraft action priority list import.
+//!
+//! Converts SimC `actions=` / `actions.<list>+=/` lines into a [`Rotation`],
+//! translating SimC expression names onto the rotation vocabulary:
+//!
+//! | SimC                               | Rotation                          |
+//! |------------------------------------|-----------------------------------|
+//! | `buff.X.up` / `.down` / `.stack`   | `buff.X.active` / `.inactive` / `.stacks` |
+//! | `cooldown.Y.remains` / `.charges_fractional` | `cd.Y.remaining` / `.charges_fractional` |
+//! | `dot.Z.refreshable` / `.ticks_left`| `dot.Z.refreshable` / `.ticks_remaining` |
+//! | `active_enemies`                   | `enemy.count`                     |
+//! | `target.time_to_die`               | `target.time_to_die`              |
+//! | `gcd.max` / `gcd.remains`          | `gcd.duration` / `gcd.remaining`  |
+//! | `focus.deficit`                    | `resource.focus.deficit`          |
+//! | `variable.X`                       | `X`                               |
+//! | `charges_fractional` (on a cast)   | `cd.<spell>.charges_fractional`   |
+//!
+//! Import never fails as a whole. Options with no rotation equivalent
+//! (`target_if`, `line_cd`, ...) are dropped from their action, and actions
+//! that cannot be translated (unknown spells, unsupported expressions, lines
+//! that do not parse) are skipped. Both are listed per line in
+//! [`SimcImport::issues`]. Lines that are not `actions` lines (character,
+//! gear and talent settings) are ignored.
+//!
+//! `actions.precombat=` lines become [`Rotation::precombat`].
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+use super::ast::{Action, Rotation};
+use super::error::{Error, Span};
+use super::resolver::SpecResolver;
+use super::syntax::{parse_line, BinaryOp, SynAction, SynExpr, SynLine, SynOption, UnaryOp};
+use super::text::{Lowering, PRECOMBAT_LIST};
+
+/// Action options the importer understands. Anything else is dropped before
+/// parsing, since SimC option values (`target_if=min:...`) are not all
+/// expressions.
+const KNOWN_OPTIONS: &[&str] = &[
+    "if",
+    "name",
+    "value",
+    "op",
+    "condition",
+    "value_else",
+    "sec",
+    "until",
+    "extra_amount",
+    "for_next",
+    "slot",
+];
+
+/// Result of importing a SimC action priority list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct SimcImport {
+    /// The translated rotation.
+    pub rotation: Rotation,
+    /// Constructs that could not be translated, in line order.
+    pub issues: Vec<ImportIssue>,
+}
+
+impl SimcImport {
+    /// Whether every action line was translated without loss.
+    pub fn is_complete(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Number of actions skipped during import.
+    pub fn dropped_actions(&self) -> usize {
+        let mut seen = Vec::new();
+        for issue in self.issues.iter().filter(|i| i.dropped) {
+            if !seen.contains(&(issue.line, issue.action)) {
+                seen.push((issue.line, issue.action));
+            }
+        }
+        seen.len()
+    }
+
+    /// Human-readable report grouped by source line.
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        let mut last_line = None;
+        for issue in &self.issues {
+            if last_line != Some(issue.line) {
+                out.push_str(&format!("line {}: {}\n", issue.line, issue.source));
+                last_line = Some(issue.line);
+            }
+            out.push_str(&format!(
+                "  column {}: {}{}\n",
+                issue.column,
+                issue.message,
+                if issue.dropped {
+                    " (action skipped)"
+                } else {
+                    ""
+                }
+            ));
+        }
+        out
+    }
+}
+
+/// A construct the importer could not translate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ImportIssue {
+    /// 1-based source line.
+    pub line: usize,
+    /// 1-based column of the construct.
+    pub column: usize,
+    /// Index of the action within its line.
+    pub action: usize,
+    /// The source line, trimmed.
+    pub source: String,
+    pub message: String,
+    /// Whether the whole action was skipped (otherwise only an option was).
+    pub dropped: bool,
+}
+
+/// Import a SimC action priority list, resolving names through `resolver`.
+pub fn import_simc(text: &str, resolver: &SpecResolver) -> SimcImport {
+    let mut importer = Importer {
+        lowering: Lowering::new(Some(resolver)),
+        resolver,
+        issues: Vec::new(),
+    };
+
+    let mut lists: HashMap<String, Vec<Action>> = HashMap::new();
+    let mut actions = Vec::new();
+    let mut precombat = Vec::new();
+
+    for (idx, raw) in text.lines().enumerate() {
+        let line = idx + 1;
+        let source = raw.split('#').next().unwrap_or("").trim();
+        if !source.starts_with("actions") {
+            continue;
+        }
+
+        let cleaned = importer.strip_unknown_options(raw, line, source);
+        let parsed = match parse_line(&cleaned, line) {
+            Ok(Some(SynLine::Actions {
+                list,
+                append,
+                actions,
+            })) => (list, append, actions),
+            Ok(_) => continue,
+            Err(err) => {
+                importer.error(line, 0, &err, source);
+                continue;
+            }
+        };
+
+        let (list, append, parsed) = parsed;
+        let target = match list {
+            None => &mut actions,
+            Some(list) if list == PRECOMBAT_LIST => &mut precombat,
+            Some(list) => lists.entry(list).or_default(),
+        };
+        if !append {
+            target.clear();
+        }
+        for (action_idx, action) in parsed.iter().enumerate() {
+            target.extend(importer.action(action, line, action_idx, source));
+        }
+    }
+
+    SimcImport {
+        rotation: Rotation {
+            name: "SimC import".to_string(),
+            variables: HashMap::new(),
+            lists,
+            actions,
+            precombat,
+        },
+        issues: importer.issues,
+    }
+}
+
+struct Importer<'a> {
+    lowering: Lowering<'a>,
+    resolver: &'a SpecResolver,
+    issues: Vec<ImportIssue>,
+}
+
+impl Importer<'_> {
+    /// Blank out options the importer does not understand, keeping columns.
+    fn strip_unknown_options(&mut self, raw: &str, line: usize, source: &str) -> String {
+        let mut out = raw.as_bytes().to_vec();
+        let Some(body) = raw.find('=') else {
+            return raw.to_string();
+        };
+
+        let mut action = 0;
+        let mut depth = 0usize;
+        let mut idx = body + 1;
+        // `actions+=/x` has a leading separator that does not start an action.
+        if raw[idx..].trim_start().starts_with('/') {
+            idx = raw[idx..].find('/').map_or(idx, |slash| idx + slash + 1);
+        }
+        while idx < raw.len() {
+            match raw.as_bytes()[idx] {
+                b'#' => break,
+                b'(' => depth += 1,
+                b')' => depth = depth.saturating_sub(1),
+                b'/' if depth == 0 => action += 1,
+                b',' if depth == 0 => {
+                    let start = idx;
+                    let end = option_end(raw, idx + 1);
+                    let option = &raw[start + 1..end];
+                    let key = option.split('=').next().unwrap_or("").trim();
+                    if !KNOWN_OPTIONS.contains(&key) {
+                        out[start..end].fill(b' ');
+                        self.push(
+                            line,
+                            start + 2,
+                            action,
+                            source,
+                            format!("ignored option '{}'", option.trim()),
+                            false,
+                        );
+                    }
+                    idx = end;
+                    continue;
+                }
+                _ => {}
+            }
+            idx += 1;
+        }
+
+        String::from_utf8(out).unwrap_or_else(|_| raw.to_string())
+    }
+
+    /// Translate one SimC action, recording issues instead of failing.
+    fn action(
+        &mut self,
+        action: &SynAction,
+        line: usize,
+        index: usize,
+        source: &str,
+    ) -> Vec<Action> {
+        let mut translator = Translator {
+            resolver: self.resolver,
+            spell: self
+                .resolver
+                .resolve_spell(&action.name)
+                .ok()
+                .map(|_| &*action.name),
+            errors: Vec::new(),
+        };
+        let translated = translator.action(action);
+
+        if !translator.errors.is_empty() {
+            for (span, message) in translator.errors {
+                self.push(line, span.column, index, source, message, true);
+            }
+            return Vec::new();
+        }
+
+        let mut lowered = Vec::new();
+        for action in &translated {
+            match self.lowering.action(action) {
+                Ok(action) => lowered.push(action),
+                Err(err) => {
+                    self.error(line, index, &err, source);
+                    return Vec::new();
+                }
+            }
+        }
+        lowered
+    }
+
+    fn error(&mut self, line: usize, index: usize, err: &Error, source: &str) {
+        match err {
+            Error::Text { span, message } => {
+                self.push(line, span.column, index, source, message.clone(), true)
+            }
+            other => self.push(line, 1, index, source, other.to_string(), true),
+        }
+    }
+
+    fn push(
+        &mut self,
+        line: usize,
+        column: usize,
+        action: usize,
+        source: &str,
+        message: String,
+        dropped: bool,
+    ) {
+        self.issues.push(ImportIssue {
+            line,
+            column,
+            action,
+            source: source.to_string(),
+            message,
+            dropped,
+        });
+    }
+}
+
+/// End of the option starting at `from` (the next top-level `,` or `/`).
+fn option_end(raw: &str, from: usize) -> usize {
+    let bytes = raw.as_bytes();
+    let mut depth = 0usize;
+    let mut idx = from;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            b',' | b'/' | b'#' if depth == 0 => break,
+            _ => {}
+        }
+        idx += 1;
+    }
+    idx
+}
+
+/// Rewrites SimC action syntax into rotation text syntax.
+struct Translator<'a> {
+    resolver: &'a SpecResolver,
+    /// Spell cast by the action, for SimC's implicit `charges_fractional` etc.
+    spell: Option<&'a str>,
+    errors: Vec<(Span, String)>,
+}
+
+impl Translator<'_> {
+    fn action(&mut self, action: &SynAction) -> Vec<SynAction> {
+        let mut action = SynAction {
+            name: action.name.clone(),
+            span: action.span,
+            options: action
+                .options
+                .iter()
+                .map(|o| SynOption {
+                    key: o.key.clone(),
+                    key_span: o.key_span,
+                    value: if matches!(o.key.as_str(), "name" | "op" | "slot") {
+                        o.value.clone()
+                    } else {
+                        self.expr(&o.value)
+                    },
+                })
+                .collect(),
+        };
+
+        match action.name.as_str() {
+            "variable" => return self.variable(action),
+            "pool_resource" => {
+                action.name = "pool".to_string();
+                action.options.retain(|o| o.key != "for_next");
+                for option in &mut action.options {
+                    if option.key == "extra_amount" {
+                        option.key = "extra".to_string();
+                    }
+                }
+            }
+            "use_item" => {
+                if let Some(idx) = action.options.iter().position(|o| o.key == "slot") {
+                    let option = &action.options[idx];
+                    let slot = match option.value.as_ident() {
+                        Some("trinket1") => 1,
+                        Some("trinket2") => 2,
+                        _ => {
+                            self.errors.push((
+                                option.value.span(),
+                                "only trinket1 and trinket2 slots are supported".to_string(),
+                            ));
+                            return Vec::new();
+                        }
+                    };
+                    action.name = "use_trinket".to_string();
+                    action.options[idx].value = SynExpr::Number {
+                        value: slot as f64,
+                        is_int: true,
+                        span: option.value.span(),
+                    };
+                }
+            }
+            _ => {}
+        }
+
+        vec![action]
+    }
+
+    /// `variable` actions. `op=set` is the default and `op=setif` splits into
+    /// two guarded assignments.
+    fn variable(&mut self, mut action: SynAction) -> Vec<SynAction> {
+        let op = action
+            .option("op")
+            .and_then(|o| o.value.as_ident())
+            .map(str::to_string);
+
+        match op.as_deref() {
+            None | Some("set") => {
+                action.options.retain(|o| o.key != "op");
+                vec![action]
+            }
+            Some("add" | "sub" | "mul" | "div" | "min" | "max" | "reset") => vec![action],
+            Some("setif") => self.setif(action),
+            Some(other) => {
+                let span = action.option("op").map(|o| o.value.span());
+                self.errors.push((
+                    span.unwrap_or(action.span),
+                    format!("unsupported variable op '{}'", other),
+                ));
+                Vec::new()
+            }
+        }
+    }
+
+    fn setif(&mut self, action: SynAction) -> Vec<SynAction> {
+        let (Some(name), Some(condition), Some(value), Some(value_else)) = (
+            action.option("name"),
+            action.option("condition"),
+            action.option("value"),
+            action.option("value_else"),
+        ) else {
+            self.errors.push((
+                action.span,
+                "'op=setif' requires 'name', 'condition', 'value' and 'value_else'".to_string(),
+            ));
+            return Vec::new();
+        };
+
+        let var = name.value.as_ident().unwrap_or_default();
+        if mentions(&condition.value, var) {
+            self.errors.push((
+                condition.value.span(),
+                format!("'op=setif' condition reads '{}' itself", var),
+            ));
+            return Vec::new();
+        }
+
+        let guard = action.option("if").map(|o| &o.value);
+        let span = condition.value.span();
+        let group = |expr: &SynExpr| SynExpr::Group {
+            inner: Box::new(expr.clone()),
+            span: expr.span(),
+        };
+        let when = |cond: SynExpr| match guard {
+            Some(guard) => SynExpr::Binary {
+                op: BinaryOp::And,
+                left: Box::new(cond),
+                right: Box::new(group(guard)),
+                span,
+            },
+            None => cond,
+        };
+
+        let assign = |value: &SynOption, cond: SynExpr| SynAction {
+            name: action.name.clone(),
+            span: action.span,
+            options: vec![
+                name.clone(),
+                SynOption {
+                    key: "value".to_string(),
+                    key_span: value.key_span,
+                    value: value.value.clone(),
+                },
+                SynOption {
+                    key: "if".to_string(),
+                    key_span: condition.key_span,
+                    value: when(cond),
+                },
+            ],
+        };
+
+        vec![
+            assign(value, group(&condition.value)),
+            assign(
+                value_else,
+                SynExpr::Unary {
+                    op: UnaryOp::Not,
+                    operand: Box::new(group(&condition.value)),
+                    span,
+                },
+            ),
+        ]
+    }
+
+    fn expr(&mut self, expr: &SynExpr) -> SynExpr {
+        match expr {
+            SynExpr::Number { .. } => expr.clone(),
+            SynExpr::Path { name, span } => match self.path(name) {
+                Ok(name) => SynExpr::Path { name, span: *span },
+                Err(message) => {
+                    self.errors.push((*span, message));
+                    expr.clone()
+                }
+            },
+            SynExpr::Unary { op, operand, span } => SynExpr::Unary {
+                op: *op,
+                operand: Box::new(self.expr(operand)),
+                span: *span,
+            },
+            SynExpr::Binary {
+                op,
+                left,
+                right,
+                span,
+            } => SynExpr::Binary {
+                op: *op,
+                left: Box::new(self.expr(left)),
+                right: Box::new(self.expr(right)),
+                span: *span,
+            },
+            SynExpr::Group { inner, span } => SynExpr::Group {
+                inner: Box::new(self.expr(inner)),
+                span: *span,
+            },
+            SynExpr::Call { name, args, span } => SynExpr::Call {
+                name: name.clone(),
+                args: args.iter().map(|a| self.expr(a)).collect(),
+                span: *span,
+            },
+        }
+    }
+
+    /// Map a SimC expression name onto a rotation variable path.
+    fn path(&self, name: &str) -> Result<String, String> {
+        let parts: Vec<&str> = name.split('.').collect();
+        let mapped = match parts.as_slice() {
+            ["true" | "false"] => Some(name.to_string()),
+
+            ["buff", aura, field] => buff_field(field).map(|f| format!("buff.{}.{}", aura, f)),
+            ["debuff", aura, field] => match *field {
+                "refreshable" => Some("refreshable"),
+                other => buff_field(other).filter(|f| *f != "stacks_max" && *f != "duration"),
+            }
+            .map(|f| format!("debuff.{}.{}", aura, f)),
+
+            ["cooldown", spell, field] => match *field {
+                "ready" | "up" => Some("ready"),
+                "remains" => Some("remaining"),
+                "charges" => Some("charges"),
+                "charges_fractional" => Some("charges_fractional"),
+                "max_charges" => Some("charges_max"),
+                "recharge_time" => Some("recharge_time"),
+                "full_recharge_time" => Some("full_recharge_time"),
+                "duration" => Some("duration"),
+                _ => None,
+            }
+            .map(|f| format!("cd.{}.{}", spell, f)),
+
+            ["dot", dot, field] => match *field {
+                "ticking" | "up" => Some("ticking"),
+                "remains" => Some("remaining"),
+                "refreshable" => Some("refreshable"),
+                "ticks_left" => Some("ticks_remaining"),
+                _ => None,
+            }
+            .map(|f| format!("dot.{}.{}", dot, f)),
+
+            ["active_enemies"] => Some("enemy.count".to_string()),
+            ["spell_targets", spell] => Some(format!("enemy.spell_targets_hit.{}", spell)),
+
+            ["target", "time_to_die"] => Some("target.time_to_die".to_string()),
+            ["target", "health", "pct"] => Some("target.health_percent".to_string()),
+            ["target", "distance"] => Some("target.distance".to_string()),
+            ["target", field] => field
+                .strip_prefix("time_to_pct_")
+                .filter(|pct| pct.parse::<u8>().is_ok())
+                .map(|pct| format!("target.time_to_percent.{}", pct)),
+
+            ["time"] => Some("combat.time".to_string()),
+            ["fight_remains"] => Some("combat.remaining".to_string()),
+
+            ["gcd"] | ["gcd", "max"] => Some("gcd.duration".to_string()),
+            ["gcd", "remains"] => Some("gcd.remaining".to_string()),
+
+            ["talent", talent] | ["talent", talent, "enabled"] => {
+                Some(format!("talent.{}", talent))
+            }
+            ["talent", talent, "rank"] => Some(format!("talent.{}.rank", talent)),
+
+            ["variable", var] => Some(var.to_string()),
+
+            ["pet", _, "active"] => Some("pet.active".to_string()),
+            ["pet", _, "buff", aura, "up"] => Some(format!("pet.buff.{}.active", aura)),
+
+            ["equipped", item] => Some(format!("equipped.{}", item)),
+            ["trinket", slot @ ("1" | "2"), "cooldown", field] => match *field {
+                "ready" | "up" => Some("ready"),
+                "remains" => Some("remaining"),
+                _ => None,
+            }
+            .map(|f| format!("trinket.{}.{}", slot, f)),
+
+            ["action", spell, field] => match *field {
+                "cost" => Some(format!("spell.{}.cost", spell)),
+                "cast_time" => Some(format!("spell.{}.cast_time", spell)),
+                "in_range" => Some(format!("spell.{}.in_range", spell)),
+                "ready" => Some(format!("cd.{}.ready", spell)),
+                _ => None,
+            },
+
+            [field] if self.spell.is_some() && self.implicit(field).is_some() => {
+                self.implicit(field)
+            }
+
+            [resource] if self.resolver.resolve_resource(resource).is_ok() => {
+                Some(format!("resource.{}", resource))
+            }
+            [resource, field] if self.resolver.resolve_resource(resource).is_ok() => match *field {
+                "deficit" => Some("deficit"),
+                "max" => Some("max"),
+                "pct" => Some("percent"),
+                "regen" => Some("regen"),
+                "time_to_max" => Some("time_to_max"),
+                _ => None,
+            }
+            .map(|f| format!("resource.{}.{}", resource, f)),
+
+            _ => None,
+        };
+
+        mapped.ok_or_else(|| format!("unsupported SimC expression '{}'", name))
+    }
+}
+
+impl Translator<'_> {
+    /// Bare names that refer to the action's own spell.
+    fn implicit(&self, field: &str) -> Option<String> {
+        let spell = self.spell?;
+        let cooldown = match field {
+            "charges" | "charges_fractional" | "recharge_time" | "full_recharge_time" => field,
+            "max_charges" => "charges_max",
+            _ => {
+                let dot = match field {
+                    "ticking" => "ticking",
+                    "remains" => "remaining",
+                    "refreshable" => "refreshable",
+                    "ticks_left" => "ticks_remaining",
+                    _ => return None,
+                };
+                return self
+                    .resolver
+                    .has_dot(spell)
+                    .then(|| format!("dot.{}.{}", spell, dot));
+            }
+        };
+        Some(format!("cd.{}.{}", spell, cooldown))
+    }
+}
+
+fn buff_field(field: &str) -> Option<&'static str> {
+    match field {
+        "up" => Some("active"),
+        "down" => Some("inactive"),
+        "remains" => Some("remaining"),
+        "stack" | "react" => Some("stacks"),
+        "max_stack" => Some("stacks_max"),
+        "duration" => Some("duration"),
+        _ => None,
+    }
+}
+
+/// Whether `expr` reads the (already translated) variable `name`.
+fn mentions(expr: &SynExpr, name: &str) -> bool {
+    match expr {
+        SynExpr::Number { .. } => false,
+        SynExpr::Path { name: path, .. } => path == name,
+        SynExpr::Unary { operand, .. } => mentions(operand, name),
+        SynExpr::Binary { left, right, .. } => mentions(left, name) || mentions(right, name),
+        SynExpr::Group { inner, .. } => mentions(inner, name),
+        SynExpr::Call { args, .. } => args.iter().any(|a| mentions(a, name)),
+    }
+}
@@ -0,0 +1,648 @@
+//! Line-oriented syntax shared by the rotation text formats.
+//!
+//! Rotation text is SimC-shaped: one directive per line, actions separated by
+//! `/`, options by `,`. This module only tokenizes and builds a syntax tree
+//! with source spans; lowering to the AST (and name resolution) happens in the
+//! front ends built on top of it.
+//!
+//! ```text
+//! # comment
+//! name=BM Hunter ST
+//! variables.pool_for_bw=cd.bestial_wrath.remaining<3&resource.focus<70
+//! actions=call_action_list,name=cooldowns
+//! actions+=/kill_command,if=cd.kill_command.ready
+//! actions.cooldowns=bestial_wrath,if=cd.bestial_wrath.ready
+//! ```
+//!
+//! Expression operators follow SimC: `|`, `&`, `!`, comparisons (`=` and `==`
+//! are equivalent), `+`, `-`, `*`, `%` (division), `%%` (modulo), `<?`/`>?`
+//! (min/max) and `@` (abs). `/` is reserved as the action separator.
+
+use super::error::{Error, Result, Span};
+
+/// A parsed line.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SynLine {
+    /// `name=<text>`
+    Name(String),
+    /// `variables.<name>=<expr>`
+    Variable {
+        name: String,
+        name_span: Span,
+        value: SynExpr,
+    },
+    /// `actions[.<list>]=` or `+=` followed by `/`-separated actions.
+    Actions {
+        /// `None` for the default list.
+        list: Option<String>,
+        append: bool,
+        actions: Vec<SynAction>,
+    },
+}
+
+/// An action with its comma-separated options.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SynAction {
+    pub name: String,
+    pub span: Span,
+    pub options: Vec<SynOption>,
+}
+
+impl SynAction {
+    /// Look up an option by key.
+    pub fn option(&self, key: &str) -> Option<&SynOption> {
+        self.options.iter().find(|o| o.key == key)
+    }
+}
+
+/// A `key=value` action option.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SynOption {
+    pub key: String,
+    pub key_span: Span,
+    pub value: SynExpr,
+}
+
+/// Expression syntax tree.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SynExpr {
+    Number {
+        value: f64,
+        is_int: bool,
+        span: Span,
+    },
+    /// Dotted identifier (`cd.kill_command.ready`, `focus`, `true`).
+    Path { name: String, span: Span },
+    Unary {
+        op: UnaryOp,
+        operand: Box<SynExpr>,
+        span: Span,
+    },
+    Binary {
+        op: BinaryOp,
+        left: Box<SynExpr>,
+        right: Box<SynExpr>,
+        span: Span,
+    },
+    /// Parenthesized expression.
+    Group { inner: Box<SynExpr>, span: Span },
+    /// Function call (`floor(x)`, `max(a,b)`).
+    Call {
+        name: String,
+        args: Vec<SynExpr>,
+        span: Span,
+    },
+}
+
+impl SynExpr {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Number { span, .. }
+            | Self::Path { span, .. }
+            | Self::Unary { span, .. }
+            | Self::Binary { span, .. }
+            | Self::Group { span, .. }
+            | Self::Call { span, .. } => *span,
+        }
+    }
+
+    /// Bare identifier, if this expression is one.
+    pub fn as_ident(&self) -> Option<&str> {
+        match self {
+            Self::Path { name, .. } => Some(name),
+            Self::Group { inner, .. } => inner.as_ident(),
+            _ => None,
+        }
+    }
+
+    /// Numeric literal value, folding a leading minus.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number { value, .. } => Some(*value),
+            Self::Unary {
+                op: UnaryOp::Neg,
+                operand,
+                ..
+            } => operand.as_number().map(|v| -v),
+            Self::Group { inner, .. } => inner.as_number(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnaryOp {
+    Not,
+    Neg,
+    Abs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Min,
+    Max,
+}
+
+impl BinaryOp {
+    fn precedence(self) -> u8 {
+        match self {
+            Self::Or => 1,
+            Self::And => 2,
+            Self::Eq | Self::Ne | Self::Gt | Self::Gte | Self::Lt | Self::Lte => 3,
+            Self::Add | Self::Sub => 4,
+            Self::Mul | Self::Div | Self::Mod | Self::Min | Self::Max => 5,
+        }
+    }
+}
+
+/// Parse one line of rotation text. Blank and comment lines yield `None`.
+///
+/// `line` is the 1-based line number used in spans.
+pub(crate) fn parse_line(text: &str, line: usize) -> Result<Option<SynLine>> {
+    let text = strip_comment(text);
+    let trimmed = text.trim_start();
+    if trimmed.trim_end().is_empty() {
+        return Ok(None);
+    }
+    let indent = text.len() - trimmed.len();
+
+    // The rotation name is free text, so it bypasses the tokenizer.
+    if let Some(rest) = trimmed.strip_prefix("name=") {
+        return Ok(Some(SynLine::Name(rest.trim().to_string())));
+    }
+
+    let tokens = tokenize(trimmed, line, indent + 1)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        line,
+        end_column: indent + trimmed.trim_end().len() + 1,
+    };
+    let parsed = parser.directive()?;
+    parser.expect_end()?;
+    Ok(Some(parsed))
+}
+
+fn strip_comment(text: &str) -> &str {
+    match text.find('#') {
+        Some(idx) => &text[..idx],
+        None => text,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Ident(String),
+    Number { value: f64, is_int: bool },
+    Assign,
+    AddAssign,
+    Slash,
+    Comma,
+    LParen,
+    RParen,
+    Bang,
+    At,
+    Op(BinaryOp),
+}
+
+impl Tok {
+    fn describe(&self) -> String {
+        match self {
+            Self::Ident(name) => format!("'{}'", name),
+            Self::Number { value, .. } => format!("number {}", value),
+            Self::Assign => "'='".into(),
+            Self::AddAssign => "'+='".into(),
+            Self::Slash => "'/'".into(),
+            Self::Comma => "','".into(),
+            Self::LParen => "'('".into(),
+            Self::RParen => "')'".into(),
+            Self::Bang => "'!'".into(),
+            Self::At => "'@'".into(),
+            Self::Op(op) => format!("operator '{}'", binary_symbol(*op)),
+        }
+    }
+}
+
+pub(crate) fn binary_symbol(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Or => "|",
+        BinaryOp::And => "&",
+        BinaryOp::Eq => "==",
+        BinaryOp::Ne => "!=",
+        BinaryOp::Gt => ">",
+        BinaryOp::Gte => ">=",
+        BinaryOp::Lt => "<",
+        BinaryOp::Lte => "<=",
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "%",
+        BinaryOp::Mod => "%%",
+        BinaryOp::Min => "<?",
+        BinaryOp::Max => ">?",
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    tok: Tok,
+    span: Span,
+}
+
+fn tokenize(text: &str, line: usize, first_column: usize) -> Result<Vec<Token>> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let tok = if c.is_ascii_alphabetic() || c == b'_' {
+            i += 1;
+            loop {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                // Dotted segments may start with a digit (`trinket.1.ready`).
+                if i + 1 < bytes.len()
+                    && bytes[i] == b'.'
+                    && (bytes[i + 1].is_ascii_alphanumeric() || bytes[i + 1] == b'_')
+                {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            Tok::Ident(text[start..i].to_string())
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            let mut is_int = true;
+            if i + 1 < bytes.len() && bytes[i] == b'.' && bytes[i + 1].is_ascii_digit() {
+                is_int = false;
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let value = text[start..i]
+                .parse()
+                .map_err(|_| text_error(line, first_column + start, i - start, "invalid number"))?;
+            Tok::Number { value, is_int }
+        } else {
+            let next = bytes.get(i + 1).copied();
+            let (tok, len) = match (c, next) {
+                (b'+', Some(b'=')) => (Tok::AddAssign, 2),
+                (b'=', Some(b'=')) => (Tok::Op(BinaryOp::Eq), 2),
+                (b'!', Some(b'=')) => (Tok::Op(BinaryOp::Ne), 2),
+                (b'>', Some(b'=')) => (Tok::Op(BinaryOp::Gte), 2),
+                (b'<', Some(b'=')) => (Tok::Op(BinaryOp::Lte), 2),
+                (b'<', Some(b'?')) => (Tok::Op(BinaryOp::Min), 2),
+                (b'>', Some(b'?')) => (Tok::Op(BinaryOp::Max), 2),
+                (b'%', Some(b'%')) => (Tok::Op(BinaryOp::Mod), 2),
+                (b'&', Some(b'&')) => (Tok::Op(BinaryOp::And), 2),
+                (b'|', Some(b'|')) => (Tok::Op(BinaryOp::Or), 2),
+                (b'=', _) => (Tok::Assign, 1),
+                (b'>', _) => (Tok::Op(BinaryOp::Gt), 1),
+                (b'<', _) => (Tok::Op(BinaryOp::Lt), 1),
+                (b'&', _) => (Tok::Op(BinaryOp::And), 1),
+                (b'|', _) => (Tok::Op(BinaryOp::Or), 1),
+                (b'+', _) => (Tok::Op(BinaryOp::Add), 1),
+                (b'-', _) => (Tok::Op(BinaryOp::Sub), 1),
+                (b'*', _) => (Tok::Op(BinaryOp::Mul), 1),
+                (b'%', _) => (Tok::Op(BinaryOp::Div), 1),
+                (b'!', _) => (Tok::Bang, 1),
+                (b'@', _) => (Tok::At, 1),
+                (b'/', _) => (Tok::Slash, 1),
+                (b',', _) => (Tok::Comma, 1),
+                (b'(', _) => (Tok::LParen, 1),
+                (b')', _) => (Tok::RParen, 1),
+                _ => {
+                    let ch = text[i..].chars().next().unwrap_or('?');
+                    return Err(text_error(
+                        line,
+                        first_column + i,
+                        ch.len_utf8(),
+                        format!("unexpected character '{}'", ch),
+                    ));
+                }
+            };
+            i += len;
+            tok
+        };
+
+        tokens.push(Token {
+            tok,
+            span: Span {
+                line,
+                column: first_column + start,
+                len: i - start,
+            },
+        });
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    line: usize,
+    /// Column just past the end of the line, for "unexpected end" errors.
+    end_column: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|t| &t.tok)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, tok: &Tok) -> bool {
+        if self.peek() == Some(tok) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Span of the current token, or the end of the line.
+    fn here(&self) -> Span {
+        self.tokens.get(self.pos).map(|t| t.span).unwrap_or(Span {
+            line: self.line,
+            column: self.end_column,
+            len: 0,
+        })
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        let found = self
+            .peek()
+            .map(Tok::describe)
+            .unwrap_or_else(|| "end of line".into());
+        Error::Text {
+            span: self.here(),
+            message: format!("expected {}, found {}", expected, found),
+        }
+    }
+
+    fn expect(&mut self, tok: Tok, expected: &str) -> Result<Span> {
+        let span = self.here();
+        if self.eat(&tok) {
+            Ok(span)
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn expect_end(&self) -> Result<()> {
+        if self.pos < self.tokens.len() {
+            Err(self.unexpected("end of line"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn ident(&mut self, expected: &str) -> Result<(String, Span)> {
+        match self.tokens.get(self.pos) {
+            Some(Token {
+                tok: Tok::Ident(name),
+                span,
+            }) => {
+                self.pos += 1;
+                Ok((name.clone(), *span))
+            }
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn directive(&mut self) -> Result<SynLine> {
+        let (head, span) = self.ident("'actions' or 'variables.<name>'")?;
+
+        if let Some(name) = head.strip_prefix("variables.") {
+            if name.contains('.') {
+                return Err(Error::Text {
+                    span,
+                    message: format!("invalid variable name '{}'", name),
+                });
+            }
+            self.expect(Tok::Assign, "'='")?;
+            let value = self.expr()?;
+            return Ok(SynLine::Variable {
+                name: name.to_string(),
+                name_span: Span {
+                    column: span.column + "variables.".len(),
+                    len: name.len(),
+                    ..span
+                },
+                value,
+            });
+        }
+
+        let list = match head.strip_prefix("actions") {
+            Some("") => None,
+            Some(rest) => match rest.strip_prefix('.') {
+                Some(list) if !list.contains('.') => Some(list.to_string()),
+                _ => return Err(unknown_directive(&head, span)),
+            },
+            None => return Err(unknown_directive(&head, span)),
+        };
+
+        let append = if self.eat(&Tok::AddAssign) {
+            true
+        } else {
+            self.expect(Tok::Assign, "'=' or '+='")?;
+            false
+        };
+        self.eat(&Tok::Slash);
+
+        let mut actions = vec![self.action()?];
+        while self.eat(&Tok::Slash) {
+            actions.push(self.action()?);
+        }
+
+        Ok(SynLine::Actions {
+            list,
+            append,
+            actions,
+        })
+    }
+
+    fn action(&mut self) -> Result<SynAction> {
+        let (name, span) = self.ident("action name")?;
+        let mut options: Vec<SynOption> = Vec::new();
+
+        while self.eat(&Tok::Comma) {
+            let (key, key_span) = self.ident("option name")?;
+            if options.iter().any(|o| o.key == key) {
+                return Err(Error::Text {
+                    span: key_span,
+                    message: format!("duplicate option '{}'", key),
+                });
+            }
+            self.expect(Tok::Assign, "'='")?;
+            let value = self.expr()?;
+            options.push(SynOption {
+                key,
+                key_span,
+                value,
+            });
+        }
+
+        Ok(SynAction {
+            name,
+            span,
+            options,
+        })
+    }
+
+    fn expr(&mut self) -> Result<SynExpr> {
+        self.binary(1)
+    }
+
+    fn binary_op(&self) -> Option<BinaryOp> {
+        match self.peek() {
+            Some(Tok::Op(op)) => Some(*op),
+            // A bare `=` inside an expression is SimC equality.
+            Some(Tok::Assign) => Some(BinaryOp::Eq),
+            _ => None,
+        }
+    }
+
+    fn binary(&mut self, min_prec: u8) -> Result<SynExpr> {
+        let mut left = self.unary()?;
+
+        while let Some(op) = self.binary_op() {
+            let prec = op.precedence();
+            if prec < min_prec {
+                break;
+            }
+            self.pos += 1;
+            let right = self.binary(prec + 1)?;
+            let span = join(left.span(), right.span());
+            left = SynExpr::Binary {
+                op,
+                left: Box::new(left),
+                right: Box::new(right),
+                span,
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<SynExpr> {
+        let start = self.here();
+        let op = match self.peek() {
+            Some(Tok::Bang) => UnaryOp::Not,
+            Some(Tok::Op(BinaryOp::Sub)) => UnaryOp::Neg,
+            Some(Tok::At) => UnaryOp::Abs,
+            _ => return self.primary(),
+        };
+        self.pos += 1;
+        let operand = self.unary()?;
+        let span = join(start, operand.span());
+        Ok(SynExpr::Unary {
+            op,
+            operand: Box::new(operand),
+            span,
+        })
+    }
+
+    fn primary(&mut self) -> Result<SynExpr> {
+        let start = self.here();
+        let Some(token) = self.next().cloned() else {
+            return Err(self.unexpected("expression"));
+        };
+
+        match token.tok {
+            Tok::Number { value, is_int } => Ok(SynExpr::Number {
+                value,
+                is_int,
+                span: token.span,
+            }),
+            Tok::Ident(name) => {
+                if !self.eat(&Tok::LParen) {
+                    return Ok(SynExpr::Path {
+                        name,
+                        span: token.span,
+                    });
+                }
+                let mut args = vec![self.expr()?];
+                while self.eat(&Tok::Comma) {
+                    args.push(self.expr()?);
+                }
+                let end = self.expect(Tok::RParen, "')'")?;
+                Ok(SynExpr::Call {
+                    name,
+                    args,
+                    span: join(start, end),
+                })
+            }
+            Tok::LParen => {
+                let inner = self.expr()?;
+                let end = self.expect(Tok::RParen, "')'")?;
+                Ok(SynExpr::Group {
+                    inner: Box::new(inner),
+                    span: join(start, end),
+                })
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected("expression"))
+            }
+        }
+    }
+}
+
+fn unknown_directive(head: &str, span: Span) -> Error {
+    Error::Text {
+        span,
+        message: format!(
+            "unknown directive '{}' (expected 'name', 'actions' or 'variables.<name>')",
+            head
+        ),
+    }
+}
+
+fn join(start: Span, end: Span) -> Span {
+    Span {
+        len: (end.column + end.len).saturating_sub(start.column),
+        ..start
+    }
+}
+
+fn text_error(line: usize, column: usize, len: usize, message: impl Into<String>) -> Error {
+    Error::Text {
+        span: Span { line, column, len },
+        message: message.into(),
+    }
+}
@@ -1,9 +1,12 @@
 //! Rotation system tests.
 
 use super::*;
-use crate::actor::Player;
+use crate::actor::{Equipment, OnUseEffect, Player, Trinket};
+use crate::aura::{AuraFlags, AuraInstance};
+use crate::combat::Cooldown;
+use crate::resource::UnitResources;
 use crate::sim::{SimConfig, SimState};
-use wowlab_common::types::SpecId;
+use wowlab_common::types::{AuraIdx, ResourceType, SimTime, SpecId, TargetIdx};
 
 /// Create a minimal SimState for testing
 fn test_sim_state() -> SimState {
@@ -33,6 +36,100 @@ fn test_resolver() -> SpecResolver {
         .charged_cooldown("barbed_shot")
 }
 
+/// A rotation compiled with both the JIT and the interpreter.
+///
+/// Every rotation in this file is compiled through [`compile_both`], so each
+/// one doubles as a differential test: both backends must accept or reject it
+/// together and return the same `EvalResult` for every state they see. Without
+/// the `jit` feature only the interpreter is built and tested.
+struct BothBackends {
+    #[cfg(feature = "jit")]
+    jit: CompiledRotation,
+    interpreter: InterpretedRotation,
+}
+
+impl BothBackends {
+    fn evaluate(&self, state: &SimState) -> EvalResult {
+        let interpreter = self.interpreter.evaluate(state);
+        #[cfg(feature = "jit")]
+        assert_eq!(
+            self.jit.evaluate(state),
+            interpreter,
+            "JIT and interpreter made different decisions"
+        );
+        interpreter
+    }
+
+    fn schema(&self) -> &ContextSchema {
+        self.interpreter.schema()
+    }
+}
+
+/// Compile with both backends and compare them on a spread of states.
+fn compile_both(json: &str, resolver: &SpecResolver) -> Result<BothBackends> {
+    let interpreter = InterpretedRotation::compile_json(json, resolver);
+
+    #[cfg(feature = "jit")]
+    let both = match (CompiledRotation::compile_json(json, resolver), interpreter) {
+        (Ok(jit), Ok(interpreter)) => BothBackends { jit, interpreter },
+        (Err(e), Err(_)) => return Err(e),
+        (jit, interpreter) => panic!(
+            "backends disagree on compilation: jit={:?}, interpreter={:?}",
+            jit.err(),
+            interpreter.err()
+        ),
+    };
+    #[cfg(not(feature = "jit"))]
+    let both = BothBackends {
+        interpreter: interpreter?,
+    };
+
+    for state in differential_states() {
+        both.evaluate(&state);
+    }
+
+    Ok(both)
+}
+
+/// States varying resources, time and active buffs.
+fn differential_states() -> Vec<SimState> {
+    let mut states = Vec::new();
+
+    for focus in [0.0, 35.0, 100.0] {
+        for secs in [0.0, 4.5] {
+            for buffed in [false, true] {
+                let mut player = Player::new(SpecId::BeastMastery);
+                player.resources = UnitResources::new().with_primary(ResourceType::Focus);
+                if let Some(primary) = player.resources.primary.as_mut() {
+                    primary.set(focus);
+                }
+
+                let config = SimConfig::default().with_duration(10.0);
+                let mut state = SimState::new(config, player);
+                state.advance_time(SimTime::from_secs_f32(secs));
+
+                if buffed {
+                    let now = state.now();
+                    for aura in [100, 101, 19574, 272790] {
+                        let instance = AuraInstance::new(
+                            AuraIdx(aura),
+                            TargetIdx(0),
+                            SimTime::from_secs(3),
+                            now,
+                            AuraFlags::default(),
+                        );
+                        state.player.buffs.apply(instance, now);
+                    }
+                }
+
+                states.push(state);
+            }
+        }
+    }
+
+    states
+}
+
 #[test]
 fn test_parse_simple_rotation() {
     let json = r#"{
@@ -178,7 +275,7 @@ fn test_parse_resource_extended_paths() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
 
     // Should compile without errors
     assert!(compiled.schema().size > 0);
@@ -324,7 +421,7 @@ fn test_compile_simple() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
 
     // Should return spell_a (id 1)
     let result = compiled.evaluate(&test_sim_state());
@@ -343,7 +440,7 @@ fn test_compile_conditional() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
 
     // With default state (low focus), should skip spell_a and return spell_b
     let state = test_sim_state();
@@ -366,7 +463,7 @@ fn test_compile_with_user_variables() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
 
     assert!(compiled.schema().size > 0);
 }
@@ -387,7 +484,7 @@ fn test_compile_with_lists() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
 
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
@@ -406,7 +503,7 @@ fn test_compile_talent_constant_folding() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
 
     // talent_a is true, so should return spell_a (id 1)
     let state = test_sim_state();
@@ -425,7 +522,7 @@ fn test_compile_unknown_spell_error() {
     }"#;
 
     let resolver = test_resolver();
-    let result = CompiledRotation::compile_json(json, &resolver);
+    let result = compile_both(json, &resolver);
     assert!(result.is_err());
 }
 
@@ -439,7 +536,7 @@ fn test_compile_unknown_aura_error() {
     }"#;
 
     let resolver = test_resolver();
-    let result = CompiledRotation::compile_json(json, &resolver);
+    let result = compile_both(json, &resolver);
     assert!(result.is_err());
 }
 
@@ -534,7 +631,7 @@ fn test_bm_hunter_minimal_rotation() {
     use crate::specs::hunter::bm::{default_resolver, MINIMAL_ROTATION_JSON};
 
     let resolver = default_resolver();
-    let compiled = CompiledRotation::compile_json(MINIMAL_ROTATION_JSON, &resolver).unwrap();
+    let compiled = compile_both(MINIMAL_ROTATION_JSON, &resolver).unwrap();
 
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
@@ -551,7 +648,7 @@ fn test_bm_hunter_example_rotation() {
     let resolver = default_resolver();
 
     // Compilation should succeed
-    let compiled = CompiledRotation::compile_json(EXAMPLE_ROTATION_JSON, &resolver).unwrap();
+    let compiled = compile_both(EXAMPLE_ROTATION_JSON, &resolver).unwrap();
 
     // Should have non-zero schema (uses variables)
     assert!(compiled.schema().size > 0);
@@ -685,7 +782,7 @@ fn test_cooldown_ready_with_charges() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -878,7 +975,7 @@ fn test_parse_combat_time_expressions() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1210,7 +1307,7 @@ fn test_division_by_zero_returns_zero() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1234,7 +1331,7 @@ fn test_division_by_zero_float() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1258,7 +1355,7 @@ fn test_true_modulo_negative_dividend() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1282,7 +1379,7 @@ fn test_true_modulo_positive() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1305,7 +1402,7 @@ fn test_modulo_by_zero_returns_zero() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1326,7 +1423,7 @@ fn test_float_equality_with_epsilon() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1347,7 +1444,7 @@ fn test_float_inequality_with_epsilon() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1417,7 +1514,7 @@ fn test_short_circuit_and_early_exit() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1441,7 +1538,7 @@ fn test_short_circuit_or_early_exit() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1466,7 +1563,7 @@ fn test_short_circuit_and_all_true() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1490,7 +1587,7 @@ fn test_short_circuit_or_all_false() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1521,7 +1618,7 @@ fn test_arithmetic_operations() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1554,7 +1651,7 @@ fn test_math_functions() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1581,7 +1678,7 @@ fn test_comparison_operators() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1601,7 +1698,7 @@ fn test_not_operator() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1624,7 +1721,7 @@ fn test_set_var_continues_to_next_action() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1648,7 +1745,7 @@ fn test_set_var_with_condition_false() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1672,7 +1769,7 @@ fn test_modify_var_add_continues() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1702,7 +1799,7 @@ fn test_modify_var_operations() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1722,7 +1819,7 @@ fn test_pool_action_returns_pool_result() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1742,7 +1839,7 @@ fn test_pool_action_default_extra() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1763,7 +1860,7 @@ fn test_pool_with_condition_false() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -1773,8 +1870,8 @@ fn test_pool_with_condition_false() {
 }
 
 #[test]
-fn test_use_trinket_stub_continues() {
-    // UseTrinket stub should continue to next action
+fn test_use_trinket_without_trinket_continues() {
+    // Nothing equipped: use_trinket continues to next action
     let json = r#"{
         "name": "Test UseTrinket",
         "actions": [
@@ -1784,18 +1881,17 @@ fn test_use_trinket_stub_continues() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
-    // Stub should continue to spell_a
     assert!(result.is_cast());
     assert_eq!(result.spell_id, 1);
 }
 
 #[test]
-fn test_use_item_stub_continues() {
-    // UseItem stub should continue to next action
+fn test_use_item_without_item_continues() {
+    // Item not equipped: use_item continues to next action
     let json = r#"{
         "name": "Test UseItem",
         "actions": [
@@ -1805,15 +1901,122 @@ fn test_use_item_stub_continues() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
-    // Stub should continue to spell_a
+    assert!(result.is_cast());
+    assert_eq!(result.spell_id, 1);
+}
+
+/// A trinket with a 2 minute on-use
+fn test_trinket(item_id: u32, name: &str) -> Trinket {
+    Trinket {
+        item_id,
+        name: name.to_string(),
+        on_use: Some(OnUseEffect {
+            spell: wowlab_common::types::SpellIdx(900 + item_id),
+            cooldown: Cooldown::new(120.0),
+            category: 0,
+            category_cooldown: SimTime::ZERO,
+        }),
+        passives: Vec::new(),
+        procs: Vec::new(),
+    }
+}
+
+/// SimState with `puzzle_box` in trinket slot 2
+fn trinket_sim_state() -> SimState {
+    let config = SimConfig::default().with_duration(10.0);
+    let mut player = Player::new(SpecId::BeastMastery);
+    player.equip(Equipment::new().with_trinket(2, test_trinket(1, "puzzle_box")));
+    SimState::new(config, player)
+}
+
+#[test]
+fn test_use_trinket_ready() {
+    let json = r#"{
+        "name": "Test UseTrinket",
+        "actions": [
+            { "use_trinket": 1 },
+            { "use_trinket": 2 },
+            { "cast": "spell_a" }
+        ]
+    }"#;
+
+    let resolver = test_resolver();
+    let compiled = compile_both(json, &resolver).unwrap();
+    let mut state = trinket_sim_state();
+
+    let result = compiled.evaluate(&state);
+    assert_eq!(result.trinket_slot(), Some(2));
+
+    // On cooldown: fall through
+    state.player.equipment.use_trinket(2, SimTime::ZERO);
+    let result = compiled.evaluate(&state);
+    assert!(result.is_cast());
+    assert_eq!(result.spell_id, 1);
+}
+
+#[test]
+fn test_use_trinket_condition() {
+    let json = r#"{
+        "name": "Test UseTrinket",
+        "actions": [
+            { "use_trinket": 2, "if": false },
+            { "cast": "spell_a" }
+        ]
+    }"#;
+
+    let resolver = test_resolver();
+    let compiled = compile_both(json, &resolver).unwrap();
+    let result = compiled.evaluate(&trinket_sim_state());
+
     assert!(result.is_cast());
     assert_eq!(result.spell_id, 1);
 }
 
+#[test]
+fn test_use_item_finds_slot() {
+    let json = r#"{
+        "name": "Test UseItem",
+        "actions": [
+            { "use_item": "puzzle_box", "if": true },
+            { "cast": "spell_a" }
+        ]
+    }"#;
+
+    let resolver = test_resolver();
+    let compiled = compile_both(json, &resolver).unwrap();
+    let result = compiled.evaluate(&trinket_sim_state());
+
+    assert_eq!(result.trinket_slot(), Some(2));
+}
+
+#[test]
+fn test_trinket_expressions() {
+    let json = r#"{
+        "name": "Test Trinket Expressions",
+        "actions": [
+            { "cast": "spell_a", "if": { "and": [
+                "equipped.puzzle_box",
+                { "not": "trinket.1.ready" },
+                "trinket.2.ready"
+            ] } },
+            { "cast": "spell_b", "if": { ">": ["trinket.2.remaining", 100] } },
+            { "cast": "spell_c" }
+        ]
+    }"#;
+
+    let resolver = test_resolver();
+    let compiled = compile_both(json, &resolver).unwrap();
+    let mut state = trinket_sim_state();
+    assert_eq!(compiled.evaluate(&state).spell_id, 1);
+
+    state.player.equipment.use_trinket(2, SimTime::ZERO);
+    assert_eq!(compiled.evaluate(&state).spell_id, 2);
+}
+
 #[test]
 fn test_eval_result_pool() {
     // Test EvalResult::pool constructor and accessors
@@ -2023,8 +2226,7 @@ fn test_bm_hunter_st_rotation_json_roundtrip() {
 fn test_bm_hunter_st_rotation_with_paths_compiles() {
     // Test the rotation with var paths compiles correctly via resolver
     let resolver = test_resolver();
-    let compiled =
-        CompiledRotation::compile_json(BM_HUNTER_ST_ROTATION_WITH_PATHS, &resolver).unwrap();
+    let compiled = compile_both(BM_HUNTER_ST_ROTATION_WITH_PATHS, &resolver).unwrap();
 
     // Schema should have entries for all the expressions used
     assert!(compiled.schema().size > 0, "Schema should be non-empty");
@@ -2068,7 +2270,7 @@ fn test_bm_hunter_st_rotation_validation() {
 #[test]
 fn test_bm_hunter_st_rotation_compilation() {
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(BM_HUNTER_ST_ROTATION, &resolver).unwrap();
+    let compiled = compile_both(BM_HUNTER_ST_ROTATION, &resolver).unwrap();
 
     // Schema should have entries for all the expressions used
     assert!(compiled.schema().size > 0, "Schema should be non-empty");
@@ -2087,7 +2289,7 @@ fn test_bm_hunter_st_rotation_compilation() {
 #[test]
 fn test_bm_hunter_st_rotation_execution() {
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(BM_HUNTER_ST_ROTATION, &resolver).unwrap();
+    let compiled = compile_both(BM_HUNTER_ST_ROTATION, &resolver).unwrap();
 
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
@@ -2688,7 +2890,7 @@ fn test_deeply_nested_and_or() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -2718,7 +2920,7 @@ fn test_complex_arithmetic_expression() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -2745,7 +2947,7 @@ fn test_mixed_comparison_chain() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
     let result = compiled.evaluate(&state);
 
@@ -3010,7 +3212,7 @@ fn test_set_var_bool_runtime_modification() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // The set_var sets should_cast to true, so spell_a should be cast
@@ -3035,7 +3237,7 @@ fn test_set_var_with_condition_false_no_modification() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // The set_var condition is false, so should_cast stays false, spell_b should be cast
@@ -3061,7 +3263,7 @@ fn test_modify_var_add_runtime() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // counter starts at 10, add 5 = 15, condition is >= 15, so spell_a should be cast
@@ -3086,7 +3288,7 @@ fn test_modify_var_sub_runtime() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // counter starts at 20, sub 15 = 5, condition is < 10, so spell_a should be cast
@@ -3111,7 +3313,7 @@ fn test_modify_var_mul_runtime() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // counter starts at 5.0, mul 3.0 = 15.0, condition is == 15.0, so spell_a should be cast
@@ -3136,7 +3338,7 @@ fn test_modify_var_div_runtime() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // counter starts at 20.0, div 4.0 = 5.0, condition is == 5.0, so spell_a should be cast
@@ -3161,7 +3363,7 @@ fn test_modify_var_min_runtime() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // counter starts at 100.0, min(100, 50) = 50, condition is == 50.0, so spell_a should be cast
@@ -3186,7 +3388,7 @@ fn test_modify_var_max_runtime() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // counter starts at 10.0, max(10, 50) = 50, condition is == 50.0, so spell_a should be cast
@@ -3212,7 +3414,7 @@ fn test_modify_var_reset_runtime() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // counter starts at 42.0, set to 999, reset to initial (42), condition is == 42.0, so spell_a should be cast
@@ -3242,7 +3444,7 @@ fn test_multiple_set_var_in_sequence() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // Both x and y should be set correctly
@@ -3267,7 +3469,7 @@ fn test_variable_in_arithmetic_expression() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // base = 25, base + 5 = 30, 30 > 20 is true, so spell_a should be cast
@@ -3292,7 +3494,7 @@ fn test_set_var_without_initial_definition() {
     }"#;
 
     let resolver = test_resolver();
-    let compiled = CompiledRotation::compile_json(json, &resolver).unwrap();
+    let compiled = compile_both(json, &resolver).unwrap();
     let state = test_sim_state();
 
     // dynamic_var is set to true, so spell_a should be cast
@@ -3300,3 +3502,565 @@ fn test_set_var_without_initial_definition() {
     assert!(result.is_cast(), "Expected cast result");
     assert_eq!(result.spell_id, 1, "Expected spell_a (id=1)");
 }
+
+// ============================================================================
+// Text syntax
+// ============================================================================
+
+/// Text form of [`BM_HUNTER_ST_ROTATION_WITH_PATHS`].
+const BM_HUNTER_ST_TEXT: &str = "\
+# Beast Mastery single target
+name=BM Hunter ST With Paths
+variables.pooling=false
+variables.frenzy_up=buff.frenzy.active
+variables.frenzy_low=buff.frenzy.stacks<3
+variables.need_barbed=cd.barbed_shot.ready&(!frenzy_up|frenzy_low|buff.frenzy.remains<2)
+
+actions=call_action_list,name=cooldowns
+actions+=/run_action_list,name=st
+
+actions.cooldowns=bestial_wrath,if=cd.bestial_wrath.ready
+actions.st=barbed_shot,if=need_barbed
+actions.st+=/kill_command,if=cd.kill_command.ready&focus>=30
+actions.st+=/cobra_shot,if=focus>=35
+";
+
+/// Compile a rotation from text with both backends.
+fn compile_both_text(text: &str, resolver: &SpecResolver) -> Result<BothBackends> {
+    let rotation = Rotation::from_text_resolved(text, resolver)?;
+    let both = BothBackends {
+        #[cfg(feature = "jit")]
+        jit: CompiledRotation::compile(&rotation, resolver)?,
+        interpreter: InterpretedRotation::compile(&rotation, resolver)?,
+    };
+    for state in differential_states() {
+        both.evaluate(&state);
+    }
+    Ok(both)
+}
+
+fn ast_json(rotation: &Rotation) -> serde_json::Value {
+    serde_json::to_value(rotation).unwrap()
+}
+
+#[test]
+fn test_text_parse_structure() {
+    let rotation = Rotation::from_text(BM_HUNTER_ST_TEXT).unwrap();
+
+    assert_eq!(rotation.name, "BM Hunter ST With Paths");
+    assert_eq!(rotation.variables.len(), 4);
+    assert_eq!(rotation.actions.len(), 2);
+    assert_eq!(rotation.lists["st"].len(), 3);
+    assert!(matches!(&rotation.actions[0], AstAction::Call { list, .. } if list == "cooldowns"));
+    assert!(matches!(&rotation.actions[1], AstAction::Run { list, .. } if list == "st"));
+}
+
+#[test]
+fn test_text_matches_json() {
+    let resolver = test_resolver();
+    let from_text = Rotation::from_text_resolved(BM_HUNTER_ST_TEXT, &resolver).unwrap();
+    let from_json =
+        Rotation::from_json_resolved(BM_HUNTER_ST_ROTATION_WITH_PATHS, &resolver).unwrap();
+    assert_eq!(ast_json(&from_text), ast_json(&from_json));
+
+    let text = compile_both_text(BM_HUNTER_ST_TEXT, &resolver).unwrap();
+    let json = compile_both(BM_HUNTER_ST_ROTATION_WITH_PATHS, &resolver).unwrap();
+    for state in differential_states() {
+        assert_eq!(text.evaluate(&state), json.evaluate(&state));
+    }
+}
+
+#[test]
+fn test_text_operators() {
+    let rotation = Rotation::from_text(
+        "actions=spell_a,if=a+b*c>=2&!d|e%2<=x%%3&min(a,b)>?floor(c)=1&@y<-1.5",
+    )
+    .unwrap();
+    let AstAction::Cast {
+        condition: Some(Expr::Or { operands }),
+        ..
+    } = &rotation.actions[0]
+    else {
+        panic!("Expected Or condition");
+    };
+    assert_eq!(operands.len(), 2);
+    assert!(matches!(&operands[0], Expr::And { operands } if operands.len() == 2));
+    assert!(matches!(&operands[1], Expr::And { operands } if operands.len() == 3));
+    assert_eq!(
+        operands[0].to_text(),
+        "a+b*c>=2&!d",
+        "multiplication binds tighter than addition"
+    );
+    assert_eq!(
+        operands[1].to_text(),
+        "e%2<=x%%3&max(min(a,b),floor(c))==1&abs(y)<-1.5"
+    );
+}
+
+#[test]
+fn test_text_actions() {
+    let text = "\
+actions=variable,name=count,value=0
+actions+=/variable,name=count,op=add,value=1,if=count<3
+actions+=/variable,name=count,op=reset
+actions+=/wait,sec=0.5,if=gcd.remaining>0.4
+actions+=/wait,until=cd.spell_a.ready
+actions+=/pool,extra=20
+actions+=/use_trinket,slot=1
+actions+=/use_item,name=algethar_puzzle_box
+";
+    let rotation = Rotation::from_text(text).unwrap();
+    let actions = &rotation.actions;
+    assert_eq!(actions.len(), 8);
+    assert!(matches!(&actions[0], AstAction::SetVar { name, .. } if name == "count"));
+    assert!(matches!(
+        &actions[1],
+        AstAction::ModifyVar {
+            op: VarOp::Add,
+            condition: Some(_),
+            ..
+        }
+    ));
+    assert!(matches!(
+        &actions[2],
+        AstAction::ModifyVar {
+            op: VarOp::Reset,
+            value: Expr::Int { value: 0 },
+            ..
+        }
+    ));
+    assert!(
+        matches!(&actions[3], AstAction::Wait { seconds, condition: Some(_) } if *seconds == 0.5)
+    );
+    assert!(matches!(&actions[4], AstAction::WaitUntil { .. }));
+    assert!(matches!(&actions[5], AstAction::Pool { extra: Some(e), .. } if *e == 20.0));
+    assert!(matches!(&actions[6], AstAction::UseTrinket { slot: 1, .. }));
+    assert!(
+        matches!(&actions[7], AstAction::UseItem { name, .. } if name == "algethar_puzzle_box")
+    );
+}
+
+#[test]
+fn test_text_list_reset_and_append() {
+    let text = "\
+actions=spell_a
+actions=spell_b
+actions+=/spell_c/spell_a
+";
+    let rotation = Rotation::from_text(text).unwrap();
+    let spells: Vec<_> = rotation
+        .actions
+        .iter()
+        .map(|a| match a {
+            AstAction::Cast { spell, .. } => spell.as_str(),
+            _ => panic!("Expected Cast action"),
+        })
+        .collect();
+    assert_eq!(spells, ["spell_b", "spell_c", "spell_a"]);
+}
+
+#[test]
+fn test_text_error_spans() {
+    let cases = [
+        ("actions=spell_a,if=(a&b", 1, 24),
+        ("name=x\nactions=spell_a,if=a&&&b", 2, 23),
+        ("actions=spell_a,frob=1", 1, 17),
+        ("\nactions=spell_a,if=a,if=b", 2, 22),
+        ("variables.x=1\nvariables.x=2", 2, 11),
+        ("actions=variable,value=1", 1, 9),
+        ("actions=spell_a,if=floor(a,b)", 1, 20),
+    ];
+
+    for (text, line, column) in cases {
+        match Rotation::from_text(text) {
+            Err(Error::Text { span, .. }) => {
+                assert_eq!(
+                    (span.line, span.column),
+                    (line, column),
+                    "span for {:?}",
+                    text
+                )
+            }
+            other => panic!("Expected text error for {:?}, got {:?}", text, other),
+        }
+    }
+}
+
+#[test]
+fn test_text_resolve_error_span() {
+    let resolver = test_resolver();
+
+    let err =
+        Rotation::from_text_resolved("actions=spell_a\nactions+=/nope", &resolver).unwrap_err();
+    match err {
+        Error::Text { span, message } => {
+            assert_eq!((span.line, span.column), (2, 11));
+            assert!(message.contains("nope"), "{}", message);
+        }
+        other => panic!("Expected text error, got {:?}", other),
+    }
+
+    let err =
+        Rotation::from_text_resolved("actions=spell_a,if=buff.nope.active", &resolver).unwrap_err();
+    assert!(matches!(err, Error::Text { span, .. } if span.column == 20));
+}
+
+#[test]
+fn test_text_aliases() {
+    let resolver = test_resolver();
+    let short = Rotation::from_text_resolved(
+        "actions=spell_a,if=focus>30&buff.buff_a.remains<2&cd.spell_b.remains=0",
+        &resolver,
+    )
+    .unwrap();
+    let long = Rotation::from_text_resolved(
+        "actions=spell_a,if=resource.focus>30&buff.buff_a.remaining<2&cd.spell_b.remaining==0",
+        &resolver,
+    )
+    .unwrap();
+    assert_eq!(ast_json(&short), ast_json(&long));
+}
+
+#[test]
+fn test_text_print_roundtrip() {
+    let resolver = test_resolver();
+    let text = "\
+name=Roundtrip
+variables.a=(1+2)*3
+variables.b=1-(2-3)
+variables.c=!(buff.buff_a.active|buff.buff_b.active)&-2<focus
+variables.d=(a|b)&(c|d)
+actions=spell_a,if=a>b%2
+actions+=/variable,name=x,op=max,value=dot.dot_a.remaining
+actions+=/wait,sec=1
+actions.cds=spell_b,if=cd.barbed_shot.charges_fractional>1.4
+";
+
+    for resolved in [false, true] {
+        let parse = |t: &str| {
+            if resolved {
+                Rotation::from_text_resolved(t, &resolver)
+            } else {
+                Rotation::from_text(t)
+            }
+        };
+        let rotation = parse(text).unwrap();
+        let printed = if resolved {
+            rotation.to_text_resolved(&resolver)
+        } else {
+            rotation.to_text()
+        };
+        let reparsed = parse(&printed).unwrap();
+        assert_eq!(ast_json(&rotation), ast_json(&reparsed), "{}", printed);
+        assert_eq!(printed, reparsed.to_text_resolved(&resolver));
+    }
+}
+
+#[test]
+fn test_text_print_format() {
+    let resolver = test_resolver();
+    let rotation = Rotation::from_text_resolved(BM_HUNTER_ST_TEXT, &resolver).unwrap();
+    let expected = "\
+name=BM Hunter ST With Paths
+variables.frenzy_low=buff.frenzy.stacks<3
+variables.frenzy_up=buff.frenzy.active
+variables.need_barbed=cd.barbed_shot.ready&(!frenzy_up|frenzy_low|buff.frenzy.remaining<2)
+variables.pooling=false
+actions=call_action_list,name=cooldowns
+actions+=/run_action_list,name=st
+actions.cooldowns=bestial_wrath,if=cd.bestial_wrath.ready
+actions.st=barbed_shot,if=need_barbed
+actions.st+=/kill_command,if=cd.kill_command.ready&resource.focus>=30
+actions.st+=/cobra_shot,if=resource.focus>=35
+";
+    assert_eq!(rotation.to_text_resolved(&resolver), expected);
+}
+
+#[test]
+fn test_text_print_json_rotation() {
+    let resolver = test_resolver();
+    let rotation =
+        Rotation::from_json_resolved(BM_HUNTER_ST_ROTATION_WITH_PATHS, &resolver).unwrap();
+    let reparsed =
+        Rotation::from_text_resolved(&rotation.to_text_resolved(&resolver), &resolver).unwrap();
+    assert_eq!(ast_json(&rotation), ast_json(&reparsed));
+}
+
+// ============================================================================
+// SimC import
+// ============================================================================
+
+fn simc_condition(import: &SimcImport, resolver: &SpecResolver, idx: usize) -> String {
+    match &import.rotation.actions[idx] {
+        AstAction::Cast {
+            condition: Some(condition),
+            ..
+        } => condition.to_text_resolved(resolver),
+        other => panic!("Expected conditional cast, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_simc_expression_names() {
+    let resolver = test_resolver();
+    let import = import_simc(
+        "\
+actions=spell_a,if=buff.buff_a.up&buff.buff_b.down&buff.frenzy.stack<3
+actions+=/spell_b,if=cooldown.barbed_shot.charges_fractional>1.4|cooldown.spell_a.remains>gcd.max
+actions+=/spell_c,if=dot.dot_a.refreshable&active_enemies>1&target.time_to_die>10
+actions+=/cobra_shot,if=focus.deficit<20&variable.pool&talent.talent_a
+",
+        &resolver,
+    );
+
+    assert!(import.is_complete(), "{}", import.report());
+    assert_eq!(
+        simc_condition(&import, &resolver, 0),
+        "buff.buff_a.active&buff.buff_b.inactive&buff.frenzy.stacks<3"
+    );
+    assert_eq!(
+        simc_condition(&import, &resolver, 1),
+        "cd.barbed_shot.charges_fractional>1.4|cd.spell_a.remaining>gcd.duration"
+    );
+    assert_eq!(
+        simc_condition(&import, &resolver, 2),
+        "dot.dot_a.refreshable&enemy.count>1&target.time_to_die>10"
+    );
+    assert_eq!(
+        simc_condition(&import, &resolver, 3),
+        "resource.focus.deficit<20&pool&true"
+    );
+}
+
+#[test]
+fn test_simc_resolves_to_domain_exprs() {
+    let resolver = test_resolver();
+    let import = import_simc(
+        "actions=spell_a,if=buff.buff_a.up&cooldown.spell_b.ready&dot.dot_a.ticking&active_enemies>1&target.time_to_die>5&gcd.max<1.5",
+        &resolver,
+    );
+    let AstAction::Cast {
+        condition: Some(Expr::And { operands }),
+        ..
+    } = &import.rotation.actions[0]
+    else {
+        panic!("Expected And condition");
+    };
+    assert!(matches!(operands[0], Expr::Buff(BuffExpr::Active { .. })));
+    assert!(matches!(
+        operands[1],
+        Expr::Cooldown(CooldownExpr::CooldownReady { .. })
+    ));
+    assert!(matches!(operands[2], Expr::Dot(DotExpr::Ticking { .. })));
+    assert!(
+        matches!(&operands[3], Expr::Gt { left, .. } if matches!(**left, Expr::Target(TargetExpr::EnemyCount) | Expr::Enemy(_)))
+    );
+    assert!(
+        matches!(&operands[4], Expr::Gt { left, .. } if matches!(**left, Expr::Target(TargetExpr::TimeToDie)))
+    );
+    assert!(
+        matches!(&operands[5], Expr::Lt { left, .. } if matches!(**left, Expr::Gcd(GcdExpr::Duration)))
+    );
+}
+
+#[test]
+fn test_simc_report_per_line() {
+    let resolver = test_resolver();
+    let text = "\
+hunter=\"Example\"
+level=80
+actions.precombat=summon_pet
+actions.precombat+=/spell_a
+actions=spell_a,target_if=min:dot.dot_a.remains,if=buff.buff_a.up
+actions+=/spell_b,if=prev_gcd.1.spell_a
+actions+=/spell_c,if=buff.buff_a.up&(
+actions+=/call_action_list,name=cds
+actions.cds=spell_b,line_cd=10
+";
+    let import = import_simc(text, &resolver);
+
+    assert_eq!(import.rotation.precombat.len(), 1);
+    assert_eq!(import.rotation.actions.len(), 2);
+    assert_eq!(import.rotation.lists["cds"].len(), 1);
+    assert_eq!(import.dropped_actions(), 3);
+
+    let issues: Vec<_> = import
+        .issues
+        .iter()
+        .map(|i| (i.line, i.column, i.dropped))
+        .collect();
+    assert_eq!(
+        issues,
+        [
+            (3, 19, true),
+            (5, 17, false),
+            (6, 22, true),
+            (7, 38, true),
+            (9, 21, false),
+        ]
+    );
+    assert!(import.issues[1].message.contains("target_if"));
+    assert!(import.issues[2].message.contains("prev_gcd.1.spell_a"));
+
+    let report = import.report();
+    assert!(report.contains("line 6: actions+=/spell_b,if=prev_gcd.1.spell_a\n"));
+    assert!(report.contains("(action skipped)"));
+}
+
+#[test]
+fn test_simc_actions() {
+    let resolver = test_resolver();
+    let import = import_simc(
+        "\
+actions=variable,name=burst,value=buff.buff_a.up,default=0
+actions+=/variable,name=count,op=add,value=1
+actions+=/variable,name=mode,op=setif,condition=active_enemies>2,value=2,value_else=1
+actions+=/pool_resource,for_next=1,extra_amount=20
+actions+=/use_item,slot=trinket2,if=variable.burst
+actions+=/wait,sec=cooldown.spell_a.remains,if=cooldown.spell_a.remains<0.5
+actions+=/variable,name=x,op=pow,value=2
+",
+        &resolver,
+    );
+
+    let actions = &import.rotation.actions;
+    assert_eq!(actions.len(), 6, "{}", import.report());
+    assert!(matches!(&actions[0], AstAction::SetVar { name, .. } if name == "burst"));
+    assert!(matches!(
+        &actions[1],
+        AstAction::ModifyVar { op: VarOp::Add, .. }
+    ));
+    assert!(
+        matches!(&actions[2], AstAction::SetVar { name, condition: Some(_), .. } if name == "mode")
+    );
+    assert!(matches!(
+        &actions[3],
+        AstAction::SetVar {
+            condition: Some(Expr::Not { .. }),
+            ..
+        }
+    ));
+    assert!(matches!(&actions[4], AstAction::Pool { extra: Some(e), .. } if *e == 20.0));
+    assert!(matches!(
+        &actions[5],
+        AstAction::UseTrinket {
+            slot: 2,
+            condition: Some(_)
+        }
+    ));
+
+    // `default=` is dropped, `wait,sec=` needs a literal and `op=pow` is unsupported.
+    let messages: Vec<_> = import.issues.iter().map(|i| i.message.as_str()).collect();
+    assert_eq!(messages.len(), 3, "{}", import.report());
+    assert!(messages[0].contains("default=0"));
+    assert!(messages[1].contains("'sec' must be a number"));
+    assert!(messages[2].contains("pow"));
+}
+
+#[test]
+fn test_simc_import_compiles() {
+    let resolver = test_resolver();
+    let import = import_simc(
+        "\
+actions=call_action_list,name=cds
+actions+=/barbed_shot,if=buff.frenzy.up&buff.frenzy.remains<=gcd.max|cooldown.barbed_shot.charges_fractional>1.8
+actions+=/kill_command,if=focus>=30
+actions+=/cobra_shot
+actions.cds=bestial_wrath,if=cooldown.bestial_wrath.ready&target.time_to_die>15
+",
+        &resolver,
+    );
+    assert!(import.is_complete(), "{}", import.report());
+
+    let interpreter = InterpretedRotation::compile(&import.rotation, &resolver).unwrap();
+    #[cfg(feature = "jit")]
+    {
+        let jit = CompiledRotation::compile(&import.rotation, &resolver).unwrap();
+        for state in differential_states() {
+            assert_eq!(jit.evaluate(&state), interpreter.evaluate(&state));
+        }
+    }
+    #[cfg(not(feature = "jit"))]
+    for state in differential_states() {
+        interpreter.evaluate(&state);
+    }
+}
+
+// ============================================================================
+// Precombat
+// ============================================================================
+
+const PRECOMBAT_JSON: &str = r#"{
+    "name": "prepull",
+    "precombat": [
+        { "cast": "spell_a", "if": "buff.buff_b.active" },
+        { "cast": "spell_b" },
+        { "wait": 0.5 },
+        { "wait_until": "buff.buff_a.active" },
+        { "cast": "spell_c" }
+    ],
+    "actions": [{ "cast": "spell_a" }]
+}"#;
+
+#[test]
+fn test_precombat_json_and_text() {
+    let resolver = test_resolver();
+    let rotation = Rotation::from_json_resolved(PRECOMBAT_JSON, &resolver).unwrap();
+    assert_eq!(rotation.precombat.len(), 5);
+    assert_eq!(rotation.actions.len(), 1);
+
+    let text = rotation.to_text_resolved(&resolver);
+    assert!(text.contains("actions.precombat=spell_a,if=buff.buff_b.active\n"));
+    assert!(text.contains("actions.precombat+=/spell_b\n"));
+
+    let reparsed = Rotation::from_text_resolved(&text, &resolver).unwrap();
+    assert_eq!(ast_json(&reparsed), ast_json(&rotation));
+}
+
+#[test]
+fn test_precombat_runs_once_in_order() {
+    let resolver = test_resolver();
+    let rotation = Rotation::from_json_resolved(PRECOMBAT_JSON, &resolver).unwrap();
+
+    for backend in [RotationBackend::Jit, RotationBackend::Interpreter] {
+        if !backend.is_available() {
+            continue;
+        }
+        let list = PrecombatList::compile(&rotation, &resolver, backend).unwrap();
+        let mut state = test_sim_state();
+        assert_eq!(list.len(), 5);
+
+        // The buff-gated cast is skipped, then spell_b and the fixed wait.
+        assert_eq!(
+            list.next(&mut state),
+            Some(EvalResult::cast(wowlab_common::types::SpellIdx(2)))
+        );
+        assert_eq!(list.next(&mut state), Some(EvalResult::wait(0.5)));
+
+        // `wait_until` holds until its condition is met.
+        assert!(list.next(&mut state).unwrap().is_wait());
+        assert!(list.next(&mut state).unwrap().is_wait());
+        state.player.buffs.apply(
+            AuraInstance::new(
+                AuraIdx(100),
+                TargetIdx(0),
+                SimTime::from_secs(10),
+                SimTime::ZERO,
+                AuraFlags::default(),
+            ),
+            SimTime::ZERO,
+        );
+        assert_eq!(
+            list.next(&mut state),
+            Some(EvalResult::cast(wowlab_common::types::SpellIdx(3)))
+        );
+        assert_eq!(list.next(&mut state), None);
+        assert_eq!(list.next(&mut state), None);
+
+        state.reset(1);
+        assert_eq!(
+            list.next(&mut state),
+            Some(EvalResult::cast(wowlab_common::types::SpellIdx(2)))
+        );
+    }
+}
@@ -0,0 +1,460 @@
+//! Text front end for rotation definitions.
+//!
+//! Lowers the SimC-style line syntax (see [`super::syntax`]) to the same
+//! [`Rotation`] AST the JSON parser produces. Variable paths use the JSON
+//! vocabulary (`cd.kill_command.ready`, `resource.focus`, ...).
+//!
+//! | Text                                           | Action        |
+//! |------------------------------------------------|---------------|
+//! | `kill_command,if=...`                          | `Cast`        |
+//! | `call_action_list,name=cds`                    | `Call`        |
+//! | `run_action_list,name=aoe`                     | `Run`         |
+//! | `variable,name=x,value=...`                    | `SetVar`      |
+//! | `variable,name=x,op=add,value=...`             | `ModifyVar`   |
+//! | `wait,sec=0.5` / `wait,until=...`              | `Wait` / `WaitUntil` |
+//! | `pool,extra=20`                                | `Pool`        |
+//! | `use_trinket,slot=1` / `use_item,name=x`       | `UseTrinket` / `UseItem` |
+//!
+//! Actions under `actions.precombat=` form [`Rotation::precombat`].
+
+use std::collections::HashMap;
+
+use super::ast::{Action, Expr, Rotation, VarOp};
+use super::error::{Error, Result, Span};
+use super::parser::{parse_var_op, parse_var_path_resolved};
+use super::resolver::SpecResolver;
+use super::syntax::{parse_line, BinaryOp, SynAction, SynExpr, SynLine, UnaryOp};
+
+impl Rotation {
+    /// Parse a rotation from text without resolution.
+    /// Variable paths remain as strings for later resolution.
+    pub fn from_text(text: &str) -> Result<Self> {
+        Lowering::new(None).rotation(text)
+    }
+
+    /// Parse a rotation from text and resolve names using the provided resolver.
+    pub fn from_text_resolved(text: &str, resolver: &SpecResolver) -> Result<Self> {
+        Lowering::new(Some(resolver)).rotation(text)
+    }
+}
+
+/// List name that holds [`Rotation::precombat`] (`actions.precombat=`).
+pub(crate) const PRECOMBAT_LIST: &str = "precombat";
+
+/// Lowers syntax trees to the AST, optionally resolving names.
+pub(crate) struct Lowering<'a> {
+    resolver: Option<&'a SpecResolver>,
+}
+
+impl<'a> Lowering<'a> {
+    pub fn new(resolver: Option<&'a SpecResolver>) -> Self {
+        Self { resolver }
+    }
+
+    fn rotation(&self, text: &str) -> Result<Rotation> {
+        let mut name = None;
+        let mut variables = HashMap::new();
+        let mut lists: HashMap<String, Vec<Action>> = HashMap::new();
+        let mut actions = Vec::new();
+        let mut precombat = Vec::new();
+
+        for (idx, line) in text.lines().enumerate() {
+            let Some(parsed) = parse_line(line, idx + 1)? else {
+                continue;
+            };
+
+            match parsed {
+                SynLine::Name(value) => name = Some(value),
+                SynLine::Variable {
+                    name,
+                    name_span,
+                    value,
+                } => {
+                    if variables.contains_key(&name) {
+                        return Err(Error::Text {
+                            span: name_span,
+                            message: format!("variable '{}' is defined twice", name),
+                        });
+                    }
+                    variables.insert(name, self.expr(&value)?);
+                }
+                SynLine::Actions {
+                    list,
+                    append,
+                    actions: parsed,
+                } => {
+                    let target = match list {
+                        None => &mut actions,
+                        Some(list) if list == PRECOMBAT_LIST => &mut precombat,
+                        Some(list) => lists.entry(list).or_default(),
+                    };
+                    if !append {
+                        target.clear();
+                    }
+                    for action in &parsed {
+                        target.push(self.action(action)?);
+                    }
+                }
+            }
+        }
+
+        Ok(Rotation {
+            name: name.unwrap_or_else(|| "Unnamed".to_string()),
+            variables,
+            lists,
+            actions,
+            precombat,
+        })
+    }
+
+    pub fn action(&self, action: &SynAction) -> Result<Action> {
+        let condition = self.optional_expr(action, "if")?;
+
+        let lowered = match action.name.as_str() {
+            "call_action_list" => Action::Call {
+                list: ident_option(action, "name")?,
+                condition,
+            },
+            "run_action_list" => Action::Run {
+                list: ident_option(action, "name")?,
+                condition,
+            },
+            "variable" => {
+                let name = ident_option(action, "name")?;
+                match action.option("op") {
+                    None => Action::SetVar {
+                        name,
+                        value: self.expr(&required(action, "value")?.value)?,
+                        condition,
+                    },
+                    Some(op) => {
+                        let op = lower_var_op(&op.value)?;
+                        let value = match (op, action.option("value")) {
+                            (_, Some(value)) => self.expr(&value.value)?,
+                            (VarOp::Reset, None) => Expr::Int { value: 0 },
+                            (_, None) => return Err(missing_option(action, "value")),
+                        };
+                        Action::ModifyVar {
+                            name,
+                            op,
+                            value,
+                            condition,
+                        }
+                    }
+                }
+            }
+            "wait" => match (action.option("sec"), action.option("until")) {
+                (Some(sec), None) => Action::Wait {
+                    seconds: number_option(sec.value.as_number(), sec.value.span(), "sec")?,
+                    condition,
+                },
+                (None, Some(until)) => {
+                    if let Some(cond) = action.option("if") {
+                        return Err(Error::Text {
+                            span: cond.key_span,
+                            message: "'wait,until=' does not take 'if'".into(),
+                        });
+                    }
+                    Action::WaitUntil {
+                        condition: self.expr(&until.value)?,
+                    }
+                }
+                (Some(_), Some(until)) => {
+                    return Err(Error::Text {
+                        span: until.key_span,
+                        message: "'wait' takes either 'sec' or 'until', not both".into(),
+                    })
+                }
+                (None, None) => return Err(missing_option(action, "sec")),
+            },
+            "pool" => Action::Pool {
+                extra: action
+                    .option("extra")
+                    .map(|o| number_option(o.value.as_number(), o.value.span(), "extra"))
+                    .transpose()?,
+                condition,
+            },
+            "use_trinket" => {
+                let slot = required(action, "slot")?;
+                let value = number_option(slot.value.as_number(), slot.value.span(), "slot")?;
+                if value.fract() != 0.0 || !(0.0..=255.0).contains(&value) {
+                    return Err(Error::Text {
+                        span: slot.value.span(),
+                        message: format!("invalid trinket slot: {}", value),
+                    });
+                }
+                Action::UseTrinket {
+                    slot: value as u8,
+                    condition,
+                }
+            }
+            "use_item" => Action::UseItem {
+                name: ident_option(action, "name")?,
+                condition,
+            },
+            spell => {
+                if let Some(resolver) = self.resolver {
+                    resolver
+                        .resolve_spell(spell)
+                        .map_err(|e| at(action.span, e))?;
+                }
+                Action::Cast {
+                    spell: spell.to_string(),
+                    condition,
+                }
+            }
+        };
+
+        check_options(action, &lowered)?;
+        Ok(lowered)
+    }
+
+    fn optional_expr(&self, action: &SynAction, key: &str) -> Result<Option<Expr>> {
+        action.option(key).map(|o| self.expr(&o.value)).transpose()
+    }
+
+    pub fn expr(&self, expr: &SynExpr) -> Result<Expr> {
+        match expr {
+            SynExpr::Number { value, is_int, .. } => Ok(number(*value, *is_int)),
+
+            SynExpr::Path { name, span } => match name.as_str() {
+                "true" => Ok(Expr::Bool { value: true }),
+                "false" => Ok(Expr::Bool { value: false }),
+                _ => self.path(name, *span),
+            },
+
+            SynExpr::Unary { op, operand, .. } => {
+                // Fold negative literals so they print back unchanged.
+                if let (UnaryOp::Neg, SynExpr::Number { value, is_int, .. }) =
+                    (op, operand.as_ref())
+                {
+                    return Ok(number(-value, *is_int));
+                }
+                let operand = Box::new(self.expr(operand)?);
+                Ok(match op {
+                    UnaryOp::Not => Expr::Not { operand },
+                    UnaryOp::Abs => Expr::Abs { operand },
+                    UnaryOp::Neg => Expr::Sub {
+                        left: Box::new(Expr::Int { value: 0 }),
+                        right: operand,
+                    },
+                })
+            }
+
+            SynExpr::Binary {
+                op: op @ (BinaryOp::And | BinaryOp::Or),
+                ..
+            } => {
+                let mut operands = Vec::new();
+                self.logic_operands(*op, expr, &mut operands)?;
+                Ok(match op {
+                    BinaryOp::And => Expr::And { operands },
+                    _ => Expr::Or { operands },
+                })
+            }
+
+            SynExpr::Binary {
+                op, left, right, ..
+            } => Ok(binary(*op, self.expr(left)?, self.expr(right)?)),
+
+            SynExpr::Group { inner, .. } => self.expr(inner),
+
+            SynExpr::Call { name, args, span } => {
+                let arity = match name.as_str() {
+                    "floor" | "ceil" | "abs" => 1,
+                    "min" | "max" => 2,
+                    _ => {
+                        return Err(Error::Text {
+                            span: *span,
+                            message: format!("unknown function '{}'", name),
+                        })
+                    }
+                };
+                if args.len() != arity {
+                    return Err(Error::Text {
+                        span: *span,
+                        message: format!(
+                            "{} takes {} argument{}, got {}",
+                            name,
+                            arity,
+                            if arity == 1 { "" } else { "s" },
+                            args.len()
+                        ),
+                    });
+                }
+                let mut args = args
+                    .iter()
+                    .map(|a| self.expr(a).map(Box::new))
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter();
+                let mut arg = || args.next().expect("arity checked");
+                Ok(match name.as_str() {
+                    "floor" => Expr::Floor { operand: arg() },
+                    "ceil" => Expr::Ceil { operand: arg() },
+                    "abs" => Expr::Abs { operand: arg() },
+                    "min" => Expr::Min {
+                        left: arg(),
+                        right: arg(),
+                    },
+                    _ => Expr::Max {
+                        left: arg(),
+                        right: arg(),
+                    },
+                })
+            }
+        }
+    }
+
+    /// Flatten an unparenthesized `a & b & c` chain into one n-ary node.
+    fn logic_operands(&self, op: BinaryOp, expr: &SynExpr, out: &mut Vec<Expr>) -> Result<()> {
+        match expr {
+            SynExpr::Binary {
+                op: inner,
+                left,
+                right,
+                ..
+            } if *inner == op => {
+                self.logic_operands(op, left, out)?;
+                out.push(self.expr(right)?);
+            }
+            other => out.push(self.expr(other)?),
+        }
+        Ok(())
+    }
+
+    fn path(&self, name: &str, span: Span) -> Result<Expr> {
+        let Some(resolver) = self.resolver else {
+            return Ok(Expr::UserVar {
+                name: name.to_string(),
+            });
+        };
+
+        let canonical = canonical_path(name, resolver);
+        parse_var_path_resolved(&canonical, resolver).map_err(|e| at(span, e))
+    }
+}
+
+/// Accept the short forms from SimC habits: `focus` for `resource.focus`
+/// and `remains` for `remaining`.
+fn canonical_path(name: &str, resolver: &SpecResolver) -> String {
+    if !name.contains('.') && resolver.resolve_resource(name).is_ok() {
+        return format!("resource.{}", name);
+    }
+    match name.strip_suffix(".remains") {
+        Some(prefix)
+            if ["cd.", "buff.", "debuff.", "dot."]
+                .iter()
+                .any(|p| prefix.starts_with(p)) =>
+        {
+            format!("{}.remaining", prefix)
+        }
+        _ => name.to_string(),
+    }
+}
+
+fn number(value: f64, is_int: bool) -> Expr {
+    if is_int && value.abs() <= i64::MAX as f64 {
+        Expr::Int {
+            value: value as i64,
+        }
+    } else {
+        Expr::Float { value }
+    }
+}
+
+fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+    let left = Box::new(left);
+    let right = Box::new(right);
+    match op {
+        BinaryOp::Eq => Expr::Eq { left, right },
+        BinaryOp::Ne => Expr::Ne { left, right },
+        BinaryOp::Gt => Expr::Gt { left, right },
+        BinaryOp::Gte => Expr::Gte { left, right },
+        BinaryOp::Lt => Expr::Lt { left, right },
+        BinaryOp::Lte => Expr::Lte { left, right },
+        BinaryOp::Add => Expr::Add { left, right },
+        BinaryOp::Sub => Expr::Sub { left, right },
+        BinaryOp::Mul => Expr::Mul { left, right },
+        BinaryOp::Div => Expr::Div { left, right },
+        BinaryOp::Mod => Expr::Mod { left, right },
+        BinaryOp::Min => Expr::Min { left, right },
+        BinaryOp::Max => Expr::Max { left, right },
+        BinaryOp::And | BinaryOp::Or => unreachable!("logical operators are n-ary"),
+    }
+}
+
+fn lower_var_op(value: &SynExpr) -> Result<VarOp> {
+    let name = value.as_ident().ok_or_else(|| Error::Text {
+        span: value.span(),
+        message: "'op' must be a name".into(),
+    })?;
+    parse_var_op(name).map_err(|e| at(value.span(), e))
+}
+
+fn required<'s>(action: &'s SynAction, key: &str) -> Result<&'s super::syntax::SynOption> {
+    action
+        .option(key)
+        .ok_or_else(|| missing_option(action, key))
+}
+
+fn ident_option(action: &SynAction, key: &str) -> Result<String> {
+    let option = required(action, key)?;
+    option
+        .value
+        .as_ident()
+        .map(str::to_string)
+        .ok_or_else(|| Error::Text {
+            span: option.value.span(),
+            message: format!("'{}' must be a name", key),
+        })
+}
+
+fn number_option(value: Option<f64>, span: Span, key: &str) -> Result<f64> {
+    value.ok_or_else(|| Error::Text {
+        span,
+        message: format!("'{}' must be a number", key),
+    })
+}
+
+fn missing_option(action: &SynAction, key: &str) -> Error {
+    Error::Text {
+        span: action.span,
+        message: format!("'{}' requires '{}'", action.name, key),
+    }
+}
+
+/// Reject options the lowered action has no use for.
+fn check_options(action: &SynAction, lowered: &Action) -> Result<()> {
+    let allowed: &[&str] = match lowered {
+        Action::Cast { .. } => &["if"],
+        Action::Call { .. } | Action::Run { .. } | Action::UseItem { .. } => &["name", "if"],
+        Action::SetVar { .. } | Action::ModifyVar { .. } => &["name", "op", "value", "if"],
+        Action::Wait { .. } => &["sec", "if"],
+        Action::WaitUntil { .. } => &["until"],
+        Action::Pool { .. } => &["extra", "if"],
+        Action::UseTrinket { .. } => &["slot", "if"],
+    };
+
+    match action
+        .options
+        .iter()
+        .find(|o| !allowed.contains(&o.key.as_str()))
+    {
+        Some(option) => Err(Error::Text {
+            span: option.key_span,
+            message: format!("unknown option '{}' for '{}'", option.key, action.name),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Attach a source location to an error raised while lowering.
+fn at(span: Span, error: Error) -> Error {
+    match error {
+        Error::Text { .. } => error,
+        other => Error::Text {
+            span,
+            message: other.to_string(),
+        },
+    }
+}
@@ -121,8 +121,8 @@ pub fn validate_rotation(rotation: &Rotation) -> ValidationResult {
         }
     }
 
-    // Validate main actions
-    for action in &rotation.actions {
+    // Validate main and precombat actions
+    for action in rotation.actions.iter().chain(&rotation.precombat) {
         validate_action(
             action,
             &variable_names,
@@ -2,14 +2,25 @@ use super::{SimConfig, Simulation};
 use crate::actor::Player;
 use crate::handler::SpecHandler;
 use crate::math::Summary;
+use crate::results::{DamageBreakdown, StatsCollector};
+#[cfg(feature = "parallel")]
 use parking_lot::Mutex;
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
+use serde::Serialize;
+#[cfg(feature = "parallel")]
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+#[cfg(feature = "parallel")]
 use std::time::Instant;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
 
 /// Results from a batch of iterations
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
 pub struct BatchResults {
     /// Number of iterations
     pub iterations: u32,
@@ -23,6 +34,9 @@ pub struct BatchResults {
     pub max_dps: f64,
     /// All DPS values (for percentile calculations)
     pub dps_values: Vec<f64>,
+    /// Per-ability damage merged across iterations, if collected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<DamageBreakdown>,
 }
 
 impl BatchResults {
@@ -36,9 +50,16 @@ impl BatchResults {
             min_dps: stats.min(),
             max_dps: stats.max(),
             dps_values: values,
+            breakdown: None,
         }
     }
 
+    /// Attach a merged damage breakdown
+    pub fn with_breakdown(mut self, breakdown: DamageBreakdown) -> Self {
+        self.breakdown = Some(breakdown);
+        self
+    }
+
     /// Get DPS at percentile (0-100)
     pub fn percentile(&self, p: f64) -> f64 {
         if self.dps_values.is_empty() {
@@ -67,12 +88,17 @@ impl BatchResults {
     }
 }
 
-/// Runs multiple simulation iterations in parallel
+/// Runs multiple simulation iterations.
+///
+/// Iterations run in parallel with the `parallel` feature and sequentially
+/// otherwise (e.g. on wasm32).
+#[derive(Clone)]
 pub struct BatchRunner {
     handler: Arc<dyn SpecHandler>,
     config: SimConfig,
     player_template: Player,
     iterations: u32,
+    collect_breakdown: bool,
 }
 
 impl BatchRunner {
@@ -82,6 +108,7 @@ impl BatchRunner {
             config,
             player_template: player,
             iterations: 1000,
+            collect_breakdown: false,
         }
     }
 
@@ -90,45 +117,184 @@ impl BatchRunner {
         self
     }
 
+    /// Merge per-ability damage from every iteration into the results
+    pub fn with_breakdown(mut self) -> Self {
+        self.collect_breakdown = true;
+        self
+    }
+
+    pub fn handler(&self) -> &Arc<dyn SpecHandler> {
+        &self.handler
+    }
+
+    /// Player cloned into every iteration
+    pub(crate) fn player_mut(&mut self) -> &mut Player {
+        &mut self.player_template
+    }
+
     /// Run all iterations in parallel using rayon
+    #[cfg(feature = "parallel")]
     pub fn run(&self) -> BatchResults {
         self.run_internal(None)
     }
 
+    /// Run all iterations sequentially
+    #[cfg(not(feature = "parallel"))]
+    pub fn run(&self) -> BatchResults {
+        let (dps_values, stats) = (0..self.iterations).fold(
+            (
+                Vec::with_capacity(self.iterations as usize),
+                StatsCollector::accumulator(),
+            ),
+            |acc, i| self.accumulate(acc, i, |_| {}),
+        );
+
+        self.results(dps_values, &stats)
+    }
+
     /// Run with progress tracking (parallel)
+    #[cfg(feature = "parallel")]
     pub fn run_with_progress(&self, progress: &ExactProgress) -> BatchResults {
         self.run_internal(Some(progress))
     }
 
+    /// Run iterations in caller-driven chunks on the current thread.
+    ///
+    /// Lets single-threaded hosts (the browser) yield between chunks and
+    /// report progress. Also collects the merged damage breakdown.
+    pub fn chunked(self) -> ChunkedBatch {
+        ChunkedBatch {
+            dps_values: Vec::with_capacity(self.iterations as usize),
+            stats: StatsCollector::accumulator(),
+            runner: self,
+        }
+    }
+
+    /// Each rayon job folds its iterations into its own collector; the
+    /// collectors are then reduced pairwise, so no lock is shared.
+    #[cfg(feature = "parallel")]
     fn run_internal(&self, progress: Option<&ExactProgress>) -> BatchResults {
-        let dps_values: Vec<f64> = (0..self.iterations)
+        let empty = || (Vec::new(), StatsCollector::accumulator());
+        let (dps_values, stats) = (0..self.iterations)
             .into_par_iter()
-            .map(|i| {
-                let mut config = self.config.clone();
-                config.seed = config.seed.wrapping_add(i as u64);
-
-                let mut sim = Simulation::new(
-                    Arc::clone(&self.handler),
-                    config,
-                    self.player_template.clone(),
-                );
-                sim.run();
-                let dps = sim.dps();
-
-                if let Some(p) = progress {
-                    p.record_iteration(dps);
-                }
-
-                dps
+            .fold(empty, |acc, i| {
+                self.accumulate(acc, i, |dps| {
+                    if let Some(p) = progress {
+                        p.record_iteration(dps);
+                    }
+                })
             })
-            .collect();
+            .reduce(
+                empty,
+                |(mut values, mut stats), (other_values, other_stats)| {
+                    values.extend(other_values);
+                    stats.merge(&other_stats);
+                    (values, stats)
+                },
+            );
+
+        self.results(dps_values, &stats)
+    }
+
+    /// Run iteration `i` and fold its DPS and damage stats into the running totals.
+    fn accumulate(
+        &self,
+        (mut values, mut stats): (Vec<f64>, StatsCollector),
+        i: u32,
+        on_dps: impl Fn(f64),
+    ) -> (Vec<f64>, StatsCollector) {
+        let sim = self.run_iteration(i);
+        let dps = sim.dps();
+        on_dps(dps);
+        values.push(dps);
+        if self.collect_breakdown {
+            stats.merge(&sim.state.stats);
+        }
+        (values, stats)
+    }
+
+    fn results(&self, dps_values: Vec<f64>, stats: &StatsCollector) -> BatchResults {
+        let results = BatchResults::from_values(dps_values);
+        if self.collect_breakdown {
+            results.with_breakdown(self.breakdown(stats))
+        } else {
+            results
+        }
+    }
+
+    fn breakdown(&self, stats: &StatsCollector) -> DamageBreakdown {
+        DamageBreakdown::from_collector(stats, &self.handler.spell_names())
+    }
+
+    fn run_iteration(&self, i: u32) -> Simulation {
+        let mut config = self.config.clone();
+        config.seed = config.seed.wrapping_add(i as u64);
+
+        let mut sim = Simulation::new(
+            Arc::clone(&self.handler),
+            config,
+            self.player_template.clone(),
+        );
+        sim.run();
+        sim
+    }
+}
+
+/// Batch run advanced a chunk of iterations at a time.
+pub struct ChunkedBatch {
+    runner: BatchRunner,
+    dps_values: Vec<f64>,
+    stats: StatsCollector,
+}
+
+impl ChunkedBatch {
+    /// Run up to `max` more iterations. Returns the number run.
+    pub fn step(&mut self, max: u32) -> u32 {
+        let start = self.completed();
+        let end = start.saturating_add(max).min(self.total());
+
+        for i in start..end {
+            let sim = self.runner.run_iteration(i);
+            self.dps_values.push(sim.dps());
+            self.stats.merge(&sim.state.stats);
+        }
+
+        end - start
+    }
+
+    pub fn completed(&self) -> u32 {
+        self.dps_values.len() as u32
+    }
+
+    pub fn total(&self) -> u32 {
+        self.runner.iterations
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.completed() >= self.total()
+    }
+
+    /// Current mean of completed iterations
+    pub fn running_mean(&self) -> f64 {
+        if self.dps_values.is_empty() {
+            return 0.0;
+        }
+        Summary::new(self.dps_values.clone()).mean()
+    }
+
+    /// Summarize completed iterations.
+    ///
+    /// The breakdown is averaged over all completed iterations.
+    pub fn finish(self) -> (BatchResults, DamageBreakdown) {
+        let breakdown = self.runner.breakdown(&self.stats);
 
-        BatchResults::from_values(dps_values)
+        (BatchResults::from_values(self.dps_values), breakdown)
     }
 }
 
 /// Progress tracking with batch statistics for live display.
 /// Collects values in a Vec and computes stats on demand.
+#[cfg(feature = "parallel")]
 pub struct ExactProgress {
     completed: AtomicU32,
     total: u32,
@@ -137,6 +303,7 @@ pub struct ExactProgress {
     num_threads: usize,
 }
 
+#[cfg(feature = "parallel")]
 impl ExactProgress {
     pub fn new(total: u32) -> Self {
         Self {
@@ -1,14 +1,20 @@
-#[cfg(feature = "parallel")]
 mod batch;
 mod executor;
+mod raid_events;
+mod request;
 mod simulation;
 mod state;
+mod trace;
+mod weights;
 
-#[cfg(feature = "parallel")]
 pub use batch::*;
 pub use executor::*;
+pub use raid_events::{FightStyle, RaidEvent, RaidEventKind};
+pub use request::*;
 pub use simulation::*;
 pub use state::*;
+pub use trace::*;
+pub use weights::*;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,337 @@
+//! Scripted raid events and fight style presets.
+//!
+//! A fight style is a declarative timeline of [`RaidEvent`]s. Each event is
+//! scheduled through the [`EventQueue`](crate::core::EventQueue) as a
+//! `RaidEventStart`/`RaidEventEnd` pair and updates player movement and the
+//! [`EnemyManager`](crate::actor::EnemyManager), which is what `player.moving`,
+//! `target.distance` and `enemy.count` read in rotations.
+
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+use crate::actor::{Enemy, DEFAULT_ENEMY_DISTANCE};
+use crate::aura::TargetAuras;
+use crate::core::SimEvent;
+use wowlab_common::types::{SimTime, TargetIdx};
+
+use super::{SimConfig, SimState};
+
+/// Player run speed in yards per second, used to turn SimC movement
+/// distances into durations.
+const RUN_SPEED: f32 = 7.0;
+
+/// What a raid event does while it is active.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum RaidEventKind {
+    /// The player is moving.
+    Movement,
+    /// `count` adds with `health` each join the fight, and despawn at the end.
+    Adds { count: u16, health: f32 },
+    /// Bosses take no damage; the player retargets to an attackable add.
+    Invulnerable,
+    /// Bosses stand `distance` yards away, returning to melee at the end.
+    Distance { distance: f32 },
+}
+
+/// One entry of a raid event timeline. Times are seconds since the pull.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct RaidEvent {
+    #[serde(flatten)]
+    pub kind: RaidEventKind,
+    /// First occurrence
+    #[serde(default)]
+    pub first: f32,
+    /// How long each occurrence lasts
+    pub duration: f32,
+    /// Time between occurrences (0 = only once)
+    #[serde(default)]
+    pub cooldown: f32,
+    /// No occurrence starts after this time
+    #[serde(default)]
+    pub last: Option<f32>,
+}
+
+impl RaidEvent {
+    pub fn new(kind: RaidEventKind, first: f32, duration: f32) -> Self {
+        Self {
+            kind,
+            first,
+            duration,
+            cooldown: 0.0,
+            last: None,
+        }
+    }
+
+    /// Repeat every `cooldown` seconds.
+    pub fn every(mut self, cooldown: f32) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Stop repeating after `last` seconds.
+    pub fn until(mut self, last: f32) -> Self {
+        self.last = Some(last);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.first < 0.0 || self.duration < 0.0 || self.cooldown < 0.0 {
+            return Err("raid event times must not be negative".to_string());
+        }
+        if self.cooldown > 0.0 && self.cooldown < 1.0 {
+            return Err("raid event cooldown must be at least 1 second".to_string());
+        }
+        match self.kind {
+            RaidEventKind::Adds { count: 0, .. } => {
+                Err("adds raid event needs at least one add".to_string())
+            }
+            RaidEventKind::Adds { health, .. } if health <= 0.0 => {
+                Err("adds raid event needs positive health".to_string())
+            }
+            RaidEventKind::Distance { distance } if distance < 0.0 => {
+                Err("distance raid event needs a non-negative distance".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Fight style presets, mirroring SimC's fight styles of the same name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum FightStyle {
+    /// Single target, no raid events.
+    #[default]
+    Patchwerk,
+    /// Waves of five adds with movement to and from them.
+    HecticAddCleave,
+    /// A 7 second movement window every 85 seconds.
+    LightMovement,
+    /// An invulnerable dummy boss with a boss add followed by add waves.
+    DungeonSlice,
+}
+
+impl FightStyle {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Patchwerk => "Patchwerk",
+            Self::HecticAddCleave => "HecticAddCleave",
+            Self::LightMovement => "LightMovement",
+            Self::DungeonSlice => "DungeonSlice",
+        }
+    }
+
+    /// The raid event timeline for a fight of `duration` seconds.
+    pub fn raid_events(self, duration: f32) -> Vec<RaidEvent> {
+        let at = |fraction: f32| (duration * fraction).floor();
+        match self {
+            Self::Patchwerk => Vec::new(),
+            Self::HecticAddCleave => {
+                let adds = RaidEventKind::Adds {
+                    count: 5,
+                    health: 1_000_000.0,
+                };
+                vec![
+                    RaidEvent::new(adds, at(0.05), at(0.05))
+                        .every(at(0.075))
+                        .until(at(0.75)),
+                    // Move out to the adds, then back with the boss.
+                    RaidEvent::new(RaidEventKind::Movement, at(0.05), 25.0 / RUN_SPEED)
+                        .every(at(0.075))
+                        .until(at(0.75)),
+                    RaidEvent::new(RaidEventKind::Movement, at(0.03), 8.0 / RUN_SPEED)
+                        .every(at(0.075))
+                        .until(at(0.75)),
+                ]
+            }
+            Self::LightMovement => vec![RaidEvent::new(RaidEventKind::Movement, at(0.1), 7.0)
+                .every(85.0)
+                .until(at(0.8))],
+            Self::DungeonSlice => {
+                let boss = RaidEventKind::Adds {
+                    count: 1,
+                    health: 2_000_000.0,
+                };
+                let small = RaidEventKind::Adds {
+                    count: 5,
+                    health: 200_000.0,
+                };
+                let big = RaidEventKind::Adds {
+                    count: 2,
+                    health: 600_000.0,
+                };
+                vec![
+                    RaidEvent::new(RaidEventKind::Invulnerable, 0.0, duration),
+                    RaidEvent::new(boss, 0.0, 135.0),
+                    RaidEvent::new(small, 140.0, 15.0).every(45.0),
+                    RaidEvent::new(big, 160.0, 30.0).every(50.0),
+                ]
+            }
+        }
+    }
+}
+
+impl fmt::Display for FightStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FightStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "patchwerk" => Ok(Self::Patchwerk),
+            "hecticaddcleave" => Ok(Self::HecticAddCleave),
+            "lightmovement" => Ok(Self::LightMovement),
+            "dungeonslice" => Ok(Self::DungeonSlice),
+            _ => Err(format!(
+                "unknown fight style '{}' (expected Patchwerk, HecticAddCleave, LightMovement or DungeonSlice)",
+                s
+            )),
+        }
+    }
+}
+
+/// Target slots reserved for the adds of raid event `index`. Each adds event
+/// owns its own slots after the bosses, reused by every wave.
+fn add_slots(config: &SimConfig, index: usize) -> Range<usize> {
+    let mut start = config.target_count;
+    for (i, event) in config.raid_events.iter().enumerate() {
+        if let RaidEventKind::Adds { count, .. } = event.kind {
+            if i == index {
+                return start..start + count as usize;
+            }
+            start += count as usize;
+        }
+    }
+    start..start
+}
+
+/// Add the (despawned) enemies for every adds event.
+pub(crate) fn add_enemies(state: &mut SimState) {
+    for (index, event) in state.config.raid_events.iter().enumerate() {
+        if let RaidEventKind::Adds { health, .. } = event.kind {
+            for slot in add_slots(&state.config, index) {
+                let id = TargetIdx(slot as u16);
+                state
+                    .enemies
+                    .add(Enemy::add(id, format!("Add {}", slot + 1), health));
+            }
+        }
+    }
+}
+
+/// Schedule the first occurrence of every raid event.
+pub(crate) fn schedule_raid_events(state: &mut SimState) {
+    let start = state.config.combat_start();
+    for (index, event) in state.config.raid_events.iter().enumerate() {
+        let time = start + SimTime::from_secs_f32(event.first);
+        if time < state.fight_end {
+            state.events.schedule(
+                time,
+                SimEvent::RaidEventStart {
+                    index: index as u16,
+                },
+            );
+        }
+    }
+}
+
+pub(crate) fn start_raid_event(state: &mut SimState, index: usize) {
+    let Some(event) = state.config.raid_events.get(index).cloned() else {
+        return;
+    };
+    let now = state.now();
+    let end = now + SimTime::from_secs_f32(event.duration);
+
+    match event.kind {
+        RaidEventKind::Movement => {
+            state.player.is_moving = true;
+            state.player.movement_end = state.player.movement_end.max(end);
+        }
+        RaidEventKind::Adds { .. } => {
+            for slot in add_slots(&state.config, index) {
+                if let Some(enemy) = state.enemies.get_mut(TargetIdx(slot as u16)) {
+                    enemy.spawn();
+                }
+                if let Some(auras) = state.auras.target_mut(TargetIdx(slot as u16)) {
+                    *auras = TargetAuras::new();
+                }
+            }
+        }
+        RaidEventKind::Invulnerable => {
+            for enemy in state.enemies.alive_mut().filter(|e| !e.is_add) {
+                enemy.invulnerable = true;
+            }
+        }
+        RaidEventKind::Distance { distance } => {
+            for enemy in state.enemies.alive_mut().filter(|e| !e.is_add) {
+                enemy.distance = distance;
+            }
+        }
+    }
+    state.enemies.retarget();
+
+    let index = index as u16;
+    state.events.schedule(end, SimEvent::RaidEventEnd { index });
+
+    if event.cooldown > 0.0 {
+        let next = now + SimTime::from_secs_f32(event.cooldown);
+        let last = event
+            .last
+            .map(|last| state.config.combat_start() + SimTime::from_secs_f32(last));
+        if next < state.fight_end && last.is_none_or(|last| next <= last) {
+            state
+                .events
+                .schedule(next, SimEvent::RaidEventStart { index });
+        }
+    }
+}
+
+pub(crate) fn end_raid_event(state: &mut SimState, index: usize) {
+    let Some(event) = state.config.raid_events.get(index) else {
+        return;
+    };
+
+    match event.kind {
+        RaidEventKind::Movement => {
+            // An overlapping movement window may still be running.
+            if state.player.movement_end <= state.now() {
+                state.player.is_moving = false;
+            }
+        }
+        RaidEventKind::Adds { .. } => {
+            for slot in add_slots(&state.config, index) {
+                if let Some(enemy) = state.enemies.get_mut(TargetIdx(slot as u16)) {
+                    enemy.despawn();
+                }
+            }
+        }
+        RaidEventKind::Invulnerable => {
+            for enemy in state.enemies.alive_mut().filter(|e| !e.is_add) {
+                enemy.invulnerable = false;
+            }
+        }
+        RaidEventKind::Distance { .. } => {
+            for enemy in state.enemies.alive_mut().filter(|e| !e.is_add) {
+                enemy.distance = DEFAULT_ENEMY_DISTANCE;
+            }
+        }
+    }
+    state.enemies.retarget();
+}
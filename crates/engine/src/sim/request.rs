@@ -0,0 +1,239 @@
+//! Self-contained simulation requests.
+//!
+//! A request bundles everything needed to run a batch (spec, player stats,
+//! fight settings and rotation) so it can cross a serialization boundary
+//! such as the WASM bindings.
+
+use super::{
+    BatchResults, BatchRunner, ChunkedBatch, FightStyle, RaidEvent, SimConfig, StatWeightRunner,
+    StatWeights,
+};
+use crate::actor::{Player, DEFAULT_ENEMY_LEVEL};
+use crate::combat::AttackPosition;
+use crate::handler::{create_handler_with_backend, create_handler_with_loadout};
+use crate::results::DamageBreakdown;
+use crate::rotation::RotationBackend;
+use crate::spec::TalentLoadout;
+use crate::stats::primary_stat_for_spec;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+use wowlab_common::types::{RatingType, SpecId};
+
+/// Player stat totals for a simulation request.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct PlayerStats {
+    /// Primary stat (agi/str/int, picked by spec)
+    pub primary_stat: u32,
+    pub crit_rating: u32,
+    pub haste_rating: u32,
+    pub mastery_rating: u32,
+    pub versatility_rating: u32,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self {
+            primary_stat: 15000,
+            crit_rating: 3000,
+            haste_rating: 3000,
+            mastery_rating: 2000,
+            versatility_rating: 1500,
+        }
+    }
+}
+
+/// Fight settings for a simulation request.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct FightSettings {
+    /// Duration in seconds
+    pub duration: f32,
+    /// Number of targets
+    pub targets: usize,
+    /// Base RNG seed (iteration `i` uses `seed + i`)
+    pub seed: Option<u64>,
+    /// Prepull window in seconds, excluded from DPS
+    pub prepull: f32,
+    /// Preset raid event timeline
+    pub fight_style: FightStyle,
+    /// Extra raid events on top of the fight style's
+    pub raid_events: Vec<RaidEvent>,
+    /// Boss health for a health-based fight (duration becomes a cap)
+    pub target_health: Option<f32>,
+    /// Per-iteration fight length (or boss health) variance, 0 to 1
+    pub vary_combat_length: f32,
+    /// Where the player attacks from
+    pub position: AttackPosition,
+    /// Boss level
+    pub target_level: u8,
+}
+
+impl Default for FightSettings {
+    fn default() -> Self {
+        Self {
+            duration: 300.0,
+            targets: 1,
+            seed: None,
+            prepull: 0.0,
+            fight_style: FightStyle::default(),
+            raid_events: Vec::new(),
+            target_health: None,
+            vary_combat_length: 0.0,
+            position: AttackPosition::default(),
+            target_level: DEFAULT_ENEMY_LEVEL,
+        }
+    }
+}
+
+/// Everything needed to run a batch of iterations.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct SimulationRequest {
+    /// WoW spec ID (e.g. 253 for Beast Mastery)
+    pub spec_id: u32,
+    #[serde(default)]
+    pub stats: PlayerStats,
+    #[serde(default)]
+    pub fight: FightSettings,
+    /// Rotation JSON
+    pub rotation: String,
+    pub iterations: u32,
+    /// Purchased talents (none if omitted)
+    #[serde(default)]
+    pub talents: Option<TalentLoadout>,
+}
+
+/// Result of a finished simulation request.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+pub struct SimulationOutput {
+    pub results: BatchResults,
+    pub breakdown: DamageBreakdown,
+}
+
+impl SimulationRequest {
+    /// Build a runner for this request.
+    pub fn runner(&self) -> Result<BatchRunner, String> {
+        if self.iterations == 0 {
+            return Err("iterations must be at least 1".to_string());
+        }
+        if self.fight.duration <= 0.0 {
+            return Err("fight duration must be positive".to_string());
+        }
+        if self.fight.targets == 0 {
+            return Err("fight must have at least one target".to_string());
+        }
+        if self.fight.prepull < 0.0 {
+            return Err("prepull must not be negative".to_string());
+        }
+        if self.fight.target_health.is_some_and(|health| health <= 0.0) {
+            return Err("target health must be positive".to_string());
+        }
+        if !(0.0..1.0).contains(&self.fight.vary_combat_length) {
+            return Err("vary combat length must be between 0 and 1".to_string());
+        }
+        if self.fight.target_level == 0 {
+            return Err("target level must be at least 1".to_string());
+        }
+        for event in &self.fight.raid_events {
+            event.validate()?;
+        }
+
+        let spec = SpecId::from_wow_spec_id(self.spec_id)
+            .ok_or_else(|| format!("Unknown spec ID: {}", self.spec_id))?;
+        let handler = match self.talents {
+            Some(ref loadout) => {
+                let (handler, report) = create_handler_with_loadout(
+                    spec,
+                    &self.rotation,
+                    RotationBackend::default(),
+                    loadout,
+                )?;
+                for talent in &report.unimplemented {
+                    warn!(spell_id = talent.spell_id, name = %talent.name, "Talent not implemented");
+                }
+                handler
+            }
+            None => create_handler_with_backend(spec, &self.rotation, RotationBackend::default())?,
+        };
+
+        let mut config = SimConfig::default()
+            .with_duration(self.fight.duration)
+            .with_prepull(self.fight.prepull)
+            .with_fight_style(self.fight.fight_style)
+            .with_vary_combat_length(self.fight.vary_combat_length)
+            .with_position(self.fight.position)
+            .with_target_level(self.fight.target_level);
+        if let Some(health) = self.fight.target_health {
+            config = config.with_target_health(health);
+        }
+        config
+            .raid_events
+            .extend(self.fight.raid_events.iter().cloned());
+        config.target_count = self.fight.targets;
+        if let Some(seed) = self.fight.seed {
+            config = config.with_seed(seed);
+        }
+
+        let mut player = Player::new(spec);
+        self.apply_stats(&mut player, spec);
+
+        Ok(BatchRunner::with_handler(handler, config, player).with_iterations(self.iterations))
+    }
+
+    /// Prepare a chunked run for this request.
+    pub fn chunked(&self) -> Result<ChunkedBatch, String> {
+        Ok(self.runner()?.chunked())
+    }
+
+    /// Run the request to completion on the current thread.
+    pub fn run(&self) -> Result<SimulationOutput, String> {
+        let mut batch = self.chunked()?;
+        batch.step(self.iterations);
+        let (results, breakdown) = batch.finish();
+        Ok(SimulationOutput { results, breakdown })
+    }
+
+    /// Scale factors for this request, adding `delta` to each stat.
+    pub fn stat_weights(&self, delta: f32) -> Result<StatWeights, String> {
+        if delta <= 0.0 {
+            return Err("stat weight delta must be positive".to_string());
+        }
+        Ok(StatWeightRunner::new(self.runner()?)
+            .with_delta(delta)
+            .run())
+    }
+
+    fn apply_stats(&self, player: &mut Player, spec: SpecId) {
+        let stats = &mut player.stats;
+        stats
+            .primary
+            .set(primary_stat_for_spec(spec), self.stats.primary_stat as f32);
+        stats
+            .ratings
+            .set(RatingType::Crit, self.stats.crit_rating as f32);
+        stats
+            .ratings
+            .set(RatingType::Haste, self.stats.haste_rating as f32);
+        stats
+            .ratings
+            .set(RatingType::Mastery, self.stats.mastery_rating as f32);
+        stats.ratings.set(
+            RatingType::Versatility,
+            self.stats.versatility_rating as f32,
+        );
+        stats.invalidate();
+        stats.update(1.0);
+    }
+}
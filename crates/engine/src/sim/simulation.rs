@@ -3,14 +3,17 @@
 //! This struct solves the borrow checker issue where we need to call
 //! `handler.on_gcd(&mut state)`. By owning both, we can borrow them separately.
 
-use super::{SimConfig, SimState};
+use super::{raid_events, SimConfig, SimState, TraceEventType};
 use crate::actor::Player;
+use crate::aura::AuraInstance;
+use crate::combat::MitigationAuras;
 use crate::core::{ScheduledEvent, SimEvent};
 use crate::handler::SpecHandler;
 use crate::resource::ResourceRegen;
+use crate::spec::{AuraDef, AuraEffect};
 use std::sync::Arc;
 use tracing::{debug, trace};
-use wowlab_common::types::SimTime;
+use wowlab_common::types::{SimTime, TargetIdx};
 
 /// Simulation combining handler and state.
 ///
@@ -32,9 +35,11 @@ impl Simulation {
 
         // Create state
         let mut state = SimState::new(config, player);
+        state.mitigation_auras = mitigation_auras(handler.aura_definitions());
 
         // Initialize simulation with spec-specific setup (pets, events, etc.)
         handler.init(&mut state);
+        apply_passive_auras(handler.as_ref(), &mut state);
 
         Self { handler, state }
     }
@@ -82,6 +87,9 @@ impl Simulation {
 
             // Advance simulation time to the event time
             self.state.advance_time(event_time);
+            if self.state.tracing() {
+                self.state.trace_expired_auras(prev_time);
+            }
             prev_time = event_time;
             event_count += 1;
 
@@ -112,6 +120,7 @@ impl Simulation {
     pub fn reset(&mut self, iteration: u32) {
         self.state.reset(iteration);
         self.handler.init(&mut self.state);
+        apply_passive_auras(self.handler.as_ref(), &mut self.state);
     }
 
     /// Get final DPS.
@@ -129,6 +138,7 @@ impl Simulation {
         match event.event {
             SimEvent::SimEnd => {
                 self.state.finished = true;
+                self.state.stats.set_end(self.state.now());
             }
 
             SimEvent::GcdEnd => {
@@ -191,6 +201,14 @@ impl Simulation {
             SimEvent::ProcIcdEnd { proc: _ } => {
                 // Proc ICD end events are informational
             }
+
+            SimEvent::RaidEventStart { index } => {
+                raid_events::start_raid_event(&mut self.state, index as usize);
+            }
+
+            SimEvent::RaidEventEnd { index } => {
+                raid_events::end_raid_event(&mut self.state, index as usize);
+            }
         }
     }
 
@@ -204,3 +222,50 @@ impl Simulation {
         }
     }
 }
+
+/// Collect the spec's auras that change the target's armor.
+fn mitigation_auras(auras: &[AuraDef]) -> MitigationAuras {
+    let mut mitigation = MitigationAuras::default();
+    for aura in auras {
+        for effect in &aura.effects {
+            match *effect {
+                AuraEffect::ArmorReduction { amount } => {
+                    mitigation.armor_reduction.push((aura.id, amount))
+                }
+                AuraEffect::ArmorPenetration { amount } => {
+                    mitigation.armor_penetration.push((aura.id, amount))
+                }
+                _ => {}
+            }
+        }
+    }
+    mitigation
+}
+
+/// Apply the player's set bonus and on-equip auras for the whole fight.
+///
+/// Auras the spec handler doesn't define are skipped.
+fn apply_passive_auras(handler: &dyn SpecHandler, state: &mut SimState) {
+    let now = state.now();
+    let auras: Vec<_> = state
+        .player
+        .set_auras
+        .iter()
+        .copied()
+        .chain(state.player.equipment.passive_auras())
+        .collect();
+    for aura_id in auras {
+        let Some(aura) = handler.get_aura(aura_id) else {
+            continue;
+        };
+        state.trace(TraceEventType::AuraApply {
+            aura: aura_id,
+            target: TargetIdx(0),
+            is_debuff: false,
+        });
+        let instance =
+            AuraInstance::new(aura_id, TargetIdx(0), SimTime::MAX - now, now, aura.flags)
+                .with_stacks(aura.max_stacks);
+        state.player.buffs.apply(instance, now);
+    }
+}
@@ -1,8 +1,17 @@
-use crate::actor::{EnemyManager, PetManager, Player};
+use crate::actor::{Enemy, EnemyManager, PetManager, Player, DEFAULT_ENEMY_LEVEL};
 use crate::aura::AuraTracker;
-use crate::combat::DamageMultipliers;
+use crate::combat::{
+    ArmorConstants, Attack, AttackPosition, AttackTable, DamageMultipliers, Mitigation,
+    MitigationAuras, MultiplierEntry, MultiplierSource,
+};
 use crate::core::{EventQueue, FastRng};
-use wowlab_common::types::SimTime;
+use crate::results::{DamageSource, StatsCollector};
+use crate::stats::{MasteryHit, SpecCoefficients};
+use smallvec::SmallVec;
+use wowlab_common::types::{DamageSchool, HitResult, SimTime, SpellIdx, TargetIdx};
+
+use super::raid_events::{self, FightStyle, RaidEvent};
+use super::{TraceEvent, TraceEventType};
 
 /// Configuration for simulation
 #[derive(Clone, Debug)]
@@ -19,6 +28,24 @@ pub struct SimConfig {
     pub initial_distance: f32,
     /// Whether targets are stacked
     pub targets_stacked: bool,
+    /// Prepull window before combat starts (runs the precombat list)
+    pub prepull: SimTime,
+    /// Scripted raid events (movement, adds, invulnerability, distance)
+    pub raid_events: Vec<RaidEvent>,
+    /// Boss health for a health-based fight; the fight ends when every boss
+    /// dies and `duration` is only an upper bound. `None` is a fixed-length fight.
+    pub target_health: Option<f32>,
+    /// Random +/- fraction applied to each iteration's fight length, or to
+    /// boss health in a health-based fight (SimC's `vary_combat_length`)
+    pub vary_combat_length: f32,
+    /// Where the player attacks from
+    pub position: AttackPosition,
+    /// Boss level (adds keep their own)
+    pub target_level: u8,
+    /// Boss armor, if not the default
+    pub target_armor: Option<f32>,
+    /// Armor constants by target level
+    pub armor_constants: ArmorConstants,
 }
 
 impl Default for SimConfig {
@@ -30,6 +57,14 @@ impl Default for SimConfig {
             trace_events: false,
             initial_distance: 30.0,
             targets_stacked: true,
+            prepull: SimTime::ZERO,
+            raid_events: Vec::new(),
+            target_health: None,
+            vary_combat_length: 0.0,
+            position: AttackPosition::default(),
+            target_level: DEFAULT_ENEMY_LEVEL,
+            target_armor: None,
+            armor_constants: ArmorConstants::default(),
         }
     }
 }
@@ -66,6 +101,65 @@ impl SimConfig {
         self.trace_events = true;
         self
     }
+
+    pub fn with_prepull(mut self, secs: f32) -> Self {
+        self.prepull = SimTime::from_secs_f32(secs);
+        self
+    }
+
+    /// Use a fight style preset's raid events (set the duration first).
+    pub fn with_fight_style(mut self, style: FightStyle) -> Self {
+        self.raid_events = style.raid_events(self.duration.as_secs_f32());
+        self
+    }
+
+    /// End the fight when every boss is dead instead of after `duration`.
+    pub fn with_target_health(mut self, health: f32) -> Self {
+        self.target_health = Some(health);
+        self
+    }
+
+    pub fn with_vary_combat_length(mut self, fraction: f32) -> Self {
+        self.vary_combat_length = fraction;
+        self
+    }
+
+    pub fn with_position(mut self, position: AttackPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_target_level(mut self, level: u8) -> Self {
+        self.target_level = level;
+        self
+    }
+
+    pub fn with_target_armor(mut self, armor: f32) -> Self {
+        self.target_armor = Some(armor);
+        self
+    }
+
+    pub fn with_armor_constants(mut self, constants: ArmorConstants) -> Self {
+        self.armor_constants = constants;
+        self
+    }
+
+    #[inline]
+    pub fn is_health_based(&self) -> bool {
+        self.target_health.is_some()
+    }
+
+    /// Sim time at which combat starts (the pull).
+    #[inline]
+    pub fn combat_start(&self) -> SimTime {
+        self.prepull
+    }
+
+    /// Sim time at which a fight of the configured duration ends.
+    #[inline]
+    pub fn fight_end(&self) -> SimTime {
+        self.prepull + self.duration
+    }
 }
 
 /// Rolling window for DPS calculation (used for TTD estimates)
@@ -162,88 +256,65 @@ pub struct SimState {
     pub auras: AuraTracker,
     /// Global damage multipliers
     pub multipliers: DamageMultipliers,
+    /// Spec auras that reduce or bypass the target's armor
+    pub mitigation_auras: MitigationAuras,
+    /// Multipliers behind the next recorded hit
+    audit: SmallVec<[MultiplierEntry; 4]>,
     /// Iteration number (for batch runs)
     pub iteration: u32,
     /// Is simulation complete
     pub finished: bool,
     /// Accumulated damage
     pub total_damage: f64,
+    /// Per-spell damage statistics
+    pub stats: StatsCollector,
     /// Event trace (if enabled)
     pub trace: Vec<TraceEvent>,
     /// Rolling DPS window for TTD calculations (damage in last N seconds)
     dps_window: DpsWindow,
-}
-
-/// Traced event for debugging
-#[derive(Clone, Debug)]
-pub struct TraceEvent {
-    pub time: SimTime,
-    pub event_type: TraceEventType,
-}
-
-#[derive(Clone, Debug)]
-pub enum TraceEventType {
-    SpellCast {
-        spell: wowlab_common::types::SpellIdx,
-        target: wowlab_common::types::TargetIdx,
-    },
-    Damage {
-        spell: wowlab_common::types::SpellIdx,
-        amount: f32,
-        is_crit: bool,
-    },
-    AuraApply {
-        aura: wowlab_common::types::AuraIdx,
-        target: wowlab_common::types::TargetIdx,
-    },
-    AuraExpire {
-        aura: wowlab_common::types::AuraIdx,
-        target: wowlab_common::types::TargetIdx,
-    },
-    ResourceGain {
-        resource: wowlab_common::types::ResourceType,
-        amount: f32,
-    },
-    CooldownStart {
-        spell: wowlab_common::types::SpellIdx,
-    },
-    ProcTrigger {
-        proc: wowlab_common::types::ProcIdx,
-    },
+    /// Next precombat action to run during the prepull window
+    pub precombat_next: usize,
+    /// Time this iteration ends at the latest (varies per iteration)
+    pub fight_end: SimTime,
 }
 
 impl SimState {
     pub fn new(config: SimConfig, player: Player) -> Self {
-        let mut events = EventQueue::new();
-
-        // Schedule simulation end
-        events.schedule(config.duration, crate::core::SimEvent::SimEnd);
+        let mut stats = StatsCollector::new();
+        stats.set_start(config.combat_start());
 
-        // Schedule resource ticks (every 100ms for energy/focus)
-        events.schedule(
-            SimTime::from_millis(100),
-            crate::core::SimEvent::ResourceTick,
-        );
-
-        // Schedule initial GCD end to start rotation
-        events.schedule(SimTime::ZERO, crate::core::SimEvent::GcdEnd);
-
-        Self {
+        let mut state = Self {
             rng: FastRng::new(config.seed),
             enemies: EnemyManager::with_bosses(config.target_count),
-            auras: AuraTracker::new().with_targets(config.target_count),
+            auras: AuraTracker::new(),
+            fight_end: config.fight_end(),
             config,
-            events,
+            events: EventQueue::new(),
             current_time: SimTime::ZERO,
             player,
             pets: PetManager::new(),
             multipliers: DamageMultipliers::default(),
+            mitigation_auras: MitigationAuras::default(),
+            audit: SmallVec::new(),
             iteration: 0,
             finished: false,
             total_damage: 0.0,
+            stats,
             trace: Vec::new(),
             dps_window: DpsWindow::default(),
+            precombat_next: 0,
+        };
+
+        for boss in state.enemies.alive_mut() {
+            boss.level = state.config.target_level;
+            if let Some(armor) = state.config.target_armor {
+                boss.armor = armor;
+            }
         }
+        raid_events::add_enemies(&mut state);
+        state.auras = AuraTracker::new().with_targets(state.enemies.count());
+        state.start_iteration();
+        state
     }
 
     /// Reset for new iteration
@@ -251,22 +322,17 @@ impl SimState {
         self.iteration = iteration;
         self.finished = false;
         self.total_damage = 0.0;
+        self.stats.reset();
+        self.stats.set_start(self.config.combat_start());
         self.trace.clear();
         self.current_time = SimTime::ZERO;
+        self.precombat_next = 0;
 
         // Reset RNG with new seed based on iteration
         self.rng = FastRng::new(self.config.seed.wrapping_add(iteration as u64));
 
         // Reset event queue
         self.events.clear();
-        self.events
-            .schedule(self.config.duration, crate::core::SimEvent::SimEnd);
-        self.events.schedule(
-            SimTime::from_millis(100),
-            crate::core::SimEvent::ResourceTick,
-        );
-        self.events
-            .schedule(SimTime::ZERO, crate::core::SimEvent::GcdEnd);
 
         // Reset actors
         self.player.reset();
@@ -274,7 +340,47 @@ impl SimState {
         self.enemies.reset();
         self.auras.reset();
         self.multipliers = DamageMultipliers::default();
+        self.audit.clear();
         self.dps_window.reset();
+
+        self.start_iteration();
+    }
+
+    /// Roll this iteration's fight length and schedule the opening events.
+    fn start_iteration(&mut self) {
+        let vary = self.config.vary_combat_length;
+        let factor = if vary > 0.0 {
+            self.rng.range(1.0 - vary, 1.0 + vary)
+        } else {
+            1.0
+        };
+
+        if let Some(health) = self.config.target_health {
+            self.fight_end = self.config.fight_end();
+            for boss in self.enemies.alive_mut().filter(|e| !e.is_add) {
+                boss.max_health = health * factor;
+                boss.current_health = boss.max_health;
+            }
+        } else {
+            let duration = self.config.duration.as_secs_f32() * factor;
+            self.fight_end = self.config.combat_start() + SimTime::from_secs_f32(duration);
+        }
+
+        // Schedule simulation end
+        self.events
+            .schedule(self.fight_end, crate::core::SimEvent::SimEnd);
+
+        // Schedule resource ticks (every 100ms for energy/focus)
+        self.events.schedule(
+            SimTime::from_millis(100),
+            crate::core::SimEvent::ResourceTick,
+        );
+
+        // Schedule initial GCD end to start rotation (or the precombat list)
+        self.events
+            .schedule(SimTime::ZERO, crate::core::SimEvent::GcdEnd);
+
+        raid_events::schedule_raid_events(self);
     }
 
     /// Current simulation time
@@ -289,16 +395,142 @@ impl SimState {
         self.current_time = time;
     }
 
+    /// Attack table for the player's `attack` on `target`, without crit chance
+    pub fn attack_table(&self, attack: Attack, target: TargetIdx) -> AttackTable {
+        AttackTable::new(attack, self.level_diff(target), self.config.position)
+    }
+
+    /// Attack table for a pet's `attack` on `target` (pets attack from behind)
+    pub fn pet_attack_table(&self, attack: Attack, target: TargetIdx) -> AttackTable {
+        AttackTable::new(attack, self.level_diff(target), AttackPosition::Back)
+    }
+
+    /// Armor and resistances the player's attacks on `target` go through,
+    /// including armor reduction debuffs and armor penetration buffs.
+    pub fn mitigation(&self, target: TargetIdx) -> Mitigation {
+        let Some(enemy) = self.enemies.get(target) else {
+            return Mitigation::none();
+        };
+        let now = self.now();
+        let mut mitigation = enemy.mitigation(&self.config.armor_constants);
+        if let Some(debuffs) = self.auras.target(target) {
+            for &(aura, amount) in &self.mitigation_auras.armor_reduction {
+                let stacks = debuffs.stacks(aura, now);
+                if stacks > 0 {
+                    mitigation = mitigation.with_armor_reduction(amount * stacks as f32);
+                }
+            }
+        }
+        for &(aura, amount) in &self.mitigation_auras.armor_penetration {
+            let stacks = self.player.buffs.stacks(aura, now);
+            if stacks > 0 {
+                mitigation = mitigation.with_armor_penetration(amount * stacks as f32);
+            }
+        }
+        mitigation
+    }
+
+    /// Multipliers for a hit on `target`: the global ones plus versatility
+    /// and the spec's mastery.
+    pub fn damage_multipliers(
+        &self,
+        school: DamageSchool,
+        periodic: bool,
+        pet: bool,
+        target: TargetIdx,
+    ) -> DamageMultipliers {
+        let stats = &self.player.stats;
+        let mut multipliers = self
+            .multipliers
+            .clone()
+            .with_versatility(stats.versatility());
+
+        let hit = MasteryHit {
+            school,
+            periodic,
+            pet,
+            target_health: self.enemies.get(target).map_or(1.0, |e| e.health_percent()),
+        };
+        let mastery =
+            SpecCoefficients::for_spec(self.player.spec).mastery_multiplier(stats.mastery(), &hit);
+        if pet {
+            multipliers.apply_pet(MultiplierSource::Mastery, mastery);
+        } else {
+            multipliers.apply(MultiplierSource::Mastery, mastery);
+        }
+        multipliers
+    }
+
+    /// Remember the multipliers behind the hit about to be recorded, so the
+    /// breakdown can explain it.
+    pub fn audit_multipliers(&mut self, multipliers: &DamageMultipliers) {
+        self.audit.clear();
+        self.audit.extend(multipliers.active().iter().cloned());
+    }
+
+    /// Levels `target` has over the player
+    fn level_diff(&self, target: TargetIdx) -> i32 {
+        self.enemies
+            .get(target)
+            .map_or(0, |e| e.level as i32 - self.player.level as i32)
+    }
+
+    /// Whether the pull has happened (always true without a prepull window)
+    #[inline]
+    pub fn in_combat(&self) -> bool {
+        self.now() >= self.config.combat_start()
+    }
+
+    /// Time since the pull (zero during the prepull window)
+    #[inline]
+    pub fn combat_time(&self) -> SimTime {
+        self.now().saturating_sub(self.config.combat_start())
+    }
+
     /// Fight progress (0.0 to 1.0)
     #[inline]
     pub fn progress(&self) -> f32 {
-        self.now().as_secs_f32() / self.config.duration.as_secs_f32()
+        let elapsed = self.combat_time().as_secs_f32();
+        (elapsed / (elapsed + self.remaining().as_secs_f32())).min(1.0)
     }
 
-    /// Time remaining in fight
-    #[inline]
+    /// Time remaining in fight (the slowest boss's time to die in a
+    /// health-based fight)
     pub fn remaining(&self) -> SimTime {
-        self.config.duration.saturating_sub(self.now())
+        let cap = self.fight_end.saturating_sub(self.now());
+        if !self.config.is_health_based() {
+            return cap;
+        }
+        self.enemies
+            .alive()
+            .filter(|e| !e.is_add)
+            .map(|e| self.time_to_die(e.id))
+            .max()
+            .unwrap_or(SimTime::ZERO)
+            .min(cap)
+    }
+
+    /// Estimated time until `target` dies, from the rate it has been losing
+    /// health. Enemies that cannot die live until the fight ends.
+    pub fn time_to_die(&self, target: TargetIdx) -> SimTime {
+        let cap = self.fight_end.saturating_sub(self.now());
+        let Some(enemy) = self.enemies.get(target) else {
+            return cap;
+        };
+        if !enemy.is_alive() {
+            return SimTime::ZERO;
+        }
+        if !self.takes_health_damage(enemy) {
+            return cap;
+        }
+        enemy.time_to_die(enemy.damage_rate(self.now())).min(cap)
+    }
+
+    /// Whether damage lowers this enemy's health. Bosses in a fixed-length
+    /// fight never die; adds always can.
+    #[inline]
+    fn takes_health_damage(&self, enemy: &Enemy) -> bool {
+        enemy.is_add || self.config.is_health_based()
     }
 
     /// Record damage
@@ -307,6 +539,112 @@ impl SimState {
         self.dps_window.record(self.current_time, amount);
     }
 
+    /// Record damage attributed to a spell (feeds the damage breakdown)
+    pub fn record_spell_damage(
+        &mut self,
+        spell: SpellIdx,
+        target: TargetIdx,
+        amount: f32,
+        result: HitResult,
+        is_periodic: bool,
+    ) {
+        self.record_damage_from(
+            DamageSource::Player,
+            spell,
+            target,
+            amount,
+            result,
+            is_periodic,
+        );
+    }
+
+    /// Record spell damage dealt by the player's pet
+    pub fn record_pet_damage(
+        &mut self,
+        spell: SpellIdx,
+        target: TargetIdx,
+        amount: f32,
+        result: HitResult,
+        is_periodic: bool,
+    ) {
+        self.record_damage_from(
+            DamageSource::Pet,
+            spell,
+            target,
+            amount,
+            result,
+            is_periodic,
+        );
+    }
+
+    fn record_damage_from(
+        &mut self,
+        source: DamageSource,
+        spell: SpellIdx,
+        target: TargetIdx,
+        amount: f32,
+        result: HitResult,
+        is_periodic: bool,
+    ) {
+        let audit = std::mem::take(&mut self.audit);
+        // Damage to invulnerable or despawned enemies is lost
+        if self.enemies.get(target).is_some_and(|e| !e.is_attackable()) {
+            return;
+        }
+        if !is_periodic {
+            self.stats.record_outcome(source, spell, result);
+        }
+        if !result.is_hit() {
+            return;
+        }
+        let is_crit = result.is_crit();
+        self.damage_enemy(target, amount);
+        self.record_damage(amount);
+        self.trace(TraceEventType::Damage {
+            spell,
+            target,
+            source,
+            amount,
+            is_crit,
+            is_periodic,
+        });
+        self.stats.record_damage_from(
+            source,
+            self.current_time,
+            spell,
+            target,
+            amount,
+            is_crit,
+            is_periodic,
+        );
+        self.stats.record_multipliers(spell, &audit);
+    }
+
+    /// Take health from an enemy, handling its death.
+    fn damage_enemy(&mut self, target: TargetIdx, amount: f32) {
+        let now = self.now();
+        let health_based = self.config.is_health_based();
+        let Some(enemy) = self.enemies.get_mut(target) else {
+            return;
+        };
+        if !(enemy.is_add || health_based) {
+            return;
+        }
+
+        enemy.record_damage_taken(now, amount);
+        if enemy.is_alive() {
+            return;
+        }
+
+        enemy.dies_at = Some(now);
+        self.enemies.retarget();
+
+        // Health-based fights end once every boss is dead
+        if health_based && !self.enemies.alive().any(|e| !e.is_add) {
+            self.events.schedule(now, crate::core::SimEvent::SimEnd);
+        }
+    }
+
     /// Get rolling DPS (for TTD calculations)
     pub fn rolling_dps(&mut self) -> f32 {
         self.dps_window.current_dps(self.current_time)
@@ -328,9 +666,9 @@ impl SimState {
         }
     }
 
-    /// Calculate DPS so far
+    /// Calculate DPS so far. Prepull time is excluded; prepull damage is not.
     pub fn current_dps(&self) -> f64 {
-        let seconds = self.now().as_secs_f32() as f64;
+        let seconds = self.combat_time().as_secs_f32() as f64;
         if seconds > 0.0 {
             self.total_damage / seconds
         } else {
@@ -1,7 +1,14 @@
 use super::*;
-use crate::actor::Player;
+use crate::actor::{Player, DEFAULT_ENEMY_LEVEL};
+use crate::aura::{AuraFlags, AuraInstance};
+use crate::combat::{
+    ArmorConstants, Attack, AttackPosition, MultiplierSource, AVOIDANCE_PER_LEVEL,
+};
 use crate::handler::SpecHandler;
+use crate::results::DamageSource;
+use crate::specs::hunter::bm::TalentFlags;
 use crate::specs::BmHunter;
+use crate::stats::SpecCoefficients;
 use std::sync::Arc;
 use wowlab_common::types::*;
 
@@ -123,6 +130,7 @@ fn batch_runner_basic() {
 }
 
 #[test]
+#[cfg(feature = "parallel")]
 fn progress_tracking() {
     let progress = ExactProgress::new(100);
 
@@ -173,6 +181,367 @@ fn sim_config_builders() {
     assert!(config.trace_events);
 }
 
+#[test]
+fn sim_config_prepull() {
+    let config = SimConfig::default().with_duration(60.0).with_prepull(2.0);
+
+    assert_eq!(config.combat_start(), SimTime::from_secs(2));
+    assert_eq!(config.fight_end(), SimTime::from_secs(62));
+    assert_eq!(SimConfig::default().combat_start(), SimTime::ZERO);
+}
+
+#[test]
+fn sim_state_prepull_window() {
+    let config = SimConfig::default().with_duration(10.0).with_prepull(2.0);
+    let player = Player::new(SpecId::BeastMastery);
+    let mut state = SimState::new(config, player);
+
+    assert!(!state.in_combat());
+    assert_eq!(state.combat_time(), SimTime::ZERO);
+    assert!((state.remaining().as_secs_f32() - 12.0).abs() < 0.01);
+
+    // Prepull damage counts, prepull time does not.
+    state.record_damage(500.0);
+    state.advance_time(SimTime::from_secs(7));
+    assert!(state.in_combat());
+    assert!((state.progress() - 0.5).abs() < 0.01);
+    assert!((state.current_dps() - 100.0).abs() < 0.01);
+}
+
+fn cobra_shot_handler() -> Arc<dyn SpecHandler> {
+    let rotation = r#"{"actions":[{"cast":"cobra_shot"}]}"#;
+    Arc::new(BmHunter::with_talents(rotation, TalentFlags::empty()).unwrap())
+}
+
+fn geared_player() -> Player {
+    let mut player = Player::new(SpecId::BeastMastery);
+    player.stats.primary.set(Attribute::Agility, 15000.0);
+    player.stats.update(1.0);
+    player
+}
+
+fn run_with_events(duration: f32, raid_events: Vec<RaidEvent>) -> Simulation {
+    let mut config = SimConfig::default().with_duration(duration);
+    config.raid_events = raid_events;
+    let mut sim = Simulation::new(cobra_shot_handler(), config, geared_player());
+    sim.run();
+    sim
+}
+
+#[test]
+fn raid_event_movement() {
+    let movement = || vec![RaidEvent::new(RaidEventKind::Movement, 1.0, 5.0)];
+
+    let sim = run_with_events(3.0, movement());
+    assert!(sim.state.player.is_moving);
+    assert_eq!(sim.state.player.movement_end, SimTime::from_secs(6));
+
+    let sim = run_with_events(10.0, movement());
+    assert!(!sim.state.player.is_moving);
+}
+
+#[test]
+fn raid_event_adds_and_distance() {
+    let events = || {
+        vec![
+            RaidEvent::new(
+                RaidEventKind::Adds {
+                    count: 3,
+                    health: 100_000.0,
+                },
+                1.0,
+                5.0,
+            ),
+            RaidEvent::new(RaidEventKind::Distance { distance: 30.0 }, 1.0, 5.0),
+        ]
+    };
+
+    let sim = run_with_events(3.0, events());
+    assert_eq!(sim.state.enemies.count(), 4);
+    assert_eq!(sim.state.enemies.alive_count(), 4);
+    assert_eq!(sim.state.enemies.primary().unwrap().distance, 30.0);
+
+    let sim = run_with_events(10.0, events());
+    assert_eq!(sim.state.enemies.alive_count(), 1);
+    assert_eq!(sim.state.enemies.primary().unwrap().distance, 5.0);
+}
+
+#[test]
+fn raid_event_repeats_until_last() {
+    let event = RaidEvent::new(RaidEventKind::Movement, 10.0, 2.0)
+        .every(10.0)
+        .until(30.0);
+
+    // Occurrences at 10, 20 and 30; the 40s one is past `last`.
+    let sim = run_with_events(41.0, vec![event.clone()]);
+    assert!(!sim.state.player.is_moving);
+    assert_eq!(sim.state.player.movement_end, SimTime::from_secs(32));
+
+    let sim = run_with_events(31.0, vec![event]);
+    assert!(sim.state.player.is_moving);
+}
+
+#[test]
+fn raid_event_invulnerable_retargets() {
+    let invulnerable = RaidEvent::new(RaidEventKind::Invulnerable, 0.0, 60.0);
+    let sim = run_with_events(10.0, vec![invulnerable.clone()]);
+    assert_eq!(sim.total_damage(), 0.0);
+
+    let adds = RaidEvent::new(
+        RaidEventKind::Adds {
+            count: 1,
+            health: 100_000.0,
+        },
+        0.0,
+        60.0,
+    );
+    let sim = run_with_events(10.0, vec![invulnerable, adds]);
+    assert_eq!(sim.state.enemies.primary, TargetIdx(1));
+    assert!(sim.total_damage() > 0.0);
+}
+
+#[test]
+fn fight_style_presets() {
+    assert!(FightStyle::Patchwerk.raid_events(300.0).is_empty());
+
+    let hectic = FightStyle::HecticAddCleave.raid_events(300.0);
+    assert!(matches!(
+        hectic[0].kind,
+        RaidEventKind::Adds { count: 5, .. }
+    ));
+    assert_eq!(hectic[0].first, 15.0);
+    assert_eq!(hectic[0].cooldown, 22.0);
+
+    let light = FightStyle::LightMovement.raid_events(300.0);
+    assert_eq!(light.len(), 1);
+    assert_eq!(light[0].duration, 7.0);
+    assert_eq!(light[0].cooldown, 85.0);
+
+    let dungeon = FightStyle::DungeonSlice.raid_events(360.0);
+    assert_eq!(dungeon[0].kind, RaidEventKind::Invulnerable);
+    for event in &dungeon {
+        event.validate().unwrap();
+    }
+
+    assert_eq!(
+        "hectic-add-cleave".parse::<FightStyle>(),
+        Ok(FightStyle::HecticAddCleave)
+    );
+    assert!("Mythic".parse::<FightStyle>().is_err());
+}
+
+#[test]
+fn fight_style_sim_runs() {
+    let config = SimConfig::default()
+        .with_duration(120.0)
+        .with_fight_style(FightStyle::DungeonSlice);
+    let mut sim = Simulation::new(cobra_shot_handler(), config, geared_player());
+    sim.run();
+
+    // The dummy boss stays invulnerable; the boss add took the damage.
+    assert!(sim.state.enemies.get(TargetIdx(0)).unwrap().invulnerable);
+    assert!(sim.total_damage() > 0.0);
+}
+
+#[test]
+fn raid_events_request_json() {
+    let fight: FightSettings = serde_json::from_str(
+        r#"{
+            "fightStyle": "LightMovement",
+            "raidEvents": [
+                { "type": "adds", "count": 2, "health": 50000, "first": 30, "duration": 10 }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(fight.fight_style, FightStyle::LightMovement);
+    assert_eq!(
+        fight.raid_events[0].kind,
+        RaidEventKind::Adds {
+            count: 2,
+            health: 50000.0
+        }
+    );
+    assert_eq!(fight.raid_events[0].cooldown, 0.0);
+}
+
+#[test]
+fn health_based_fight_ends_on_boss_death() {
+    let config = SimConfig::default()
+        .with_duration(300.0)
+        .with_target_health(200_000.0);
+    let mut sim = Simulation::new(cobra_shot_handler(), config, geared_player());
+    sim.run();
+
+    let boss = sim.state.enemies.get(TargetIdx(0)).unwrap();
+    assert!(!boss.is_alive());
+    assert_eq!(boss.dies_at, Some(sim.state.now()));
+    assert!(sim.state.combat_time() < SimTime::from_secs(300));
+    assert!((sim.dps() - sim.total_damage() / sim.state.combat_time().as_secs_f64()).abs() < 0.01);
+}
+
+#[test]
+fn fixed_length_bosses_do_not_die() {
+    let config = SimConfig::default().with_duration(30.0);
+    let mut sim = Simulation::new(cobra_shot_handler(), config, geared_player());
+    sim.run();
+
+    let boss = sim.state.enemies.get(TargetIdx(0)).unwrap();
+    assert_eq!(boss.current_health, boss.max_health);
+    assert_eq!(sim.state.combat_time(), SimTime::from_secs(30));
+}
+
+#[test]
+fn adds_die_and_retarget() {
+    let adds = RaidEvent::new(
+        RaidEventKind::Adds {
+            count: 1,
+            health: 1_000.0,
+        },
+        0.0,
+        60.0,
+    );
+    let invulnerable = RaidEvent::new(RaidEventKind::Invulnerable, 0.0, 60.0);
+    let sim = run_with_events(10.0, vec![invulnerable, adds]);
+
+    let add = sim.state.enemies.get(TargetIdx(1)).unwrap();
+    assert!(!add.is_alive());
+    assert!(add.dies_at.is_some());
+    assert_eq!(sim.state.enemies.primary, TargetIdx(0));
+}
+
+#[test]
+fn vary_combat_length() {
+    let config = SimConfig::default()
+        .with_duration(100.0)
+        .with_vary_combat_length(0.2);
+    let mut state = SimState::new(config, Player::new(SpecId::BeastMastery));
+
+    let mut ends = Vec::new();
+    for iteration in 0..20 {
+        state.reset(iteration);
+        let secs = state.fight_end.as_secs_f32();
+        assert!((80.0..=120.0).contains(&secs));
+        ends.push(state.fight_end);
+    }
+    assert!(ends.iter().any(|&end| end != ends[0]));
+
+    // The same iteration always rolls the same length.
+    state.reset(3);
+    assert_eq!(state.fight_end, ends[3]);
+}
+
+#[test]
+fn time_to_die_from_health() {
+    let config = SimConfig::default()
+        .with_duration(300.0)
+        .with_target_health(100_000.0);
+    let mut state = SimState::new(config, Player::new(SpecId::BeastMastery));
+
+    // No damage yet: assume the boss lasts the fight.
+    assert_eq!(state.time_to_die(TargetIdx(0)), SimTime::from_secs(300));
+
+    state.record_spell_damage(SpellIdx(1), TargetIdx(0), 10_000.0, HitResult::Hit, false);
+    state.advance_time(SimTime::from_secs(10));
+    state.record_spell_damage(SpellIdx(1), TargetIdx(0), 10_000.0, HitResult::Hit, false);
+
+    // 20k over 10s leaves 80k at 2k/s.
+    assert_eq!(state.time_to_die(TargetIdx(0)), SimTime::from_secs(40));
+    assert_eq!(state.remaining(), SimTime::from_secs(40));
+}
+
+#[test]
+fn avoided_attacks_record_outcome_only() {
+    let config = SimConfig::default().with_target_level(90);
+    let mut state = SimState::new(config, Player::new(SpecId::BeastMastery));
+    let spell = SpellIdx(1);
+
+    state.record_spell_damage(spell, TargetIdx(0), 10_000.0, HitResult::Miss, false);
+    state.record_spell_damage(spell, TargetIdx(0), 5_000.0, HitResult::Glancing, false);
+
+    assert_eq!(state.stats.total_damage, 5_000.0);
+    let stats = state.stats.spell(spell).unwrap();
+    assert_eq!(stats.count, 1);
+    assert_eq!(stats.outcomes.miss, 1);
+    assert_eq!(stats.outcomes.glancing, 1);
+}
+
+#[test]
+fn attack_table_uses_target_level_and_position() {
+    let config = SimConfig::default()
+        .with_target_level(DEFAULT_ENEMY_LEVEL + 2)
+        .with_position(AttackPosition::Front);
+    let state = SimState::new(config, Player::new(SpecId::BeastMastery));
+    let target = state.enemies.primary;
+    assert_eq!(
+        state.enemies.get(target).unwrap().level,
+        DEFAULT_ENEMY_LEVEL + 2
+    );
+
+    let table = state.attack_table(Attack::melee(), target);
+    assert!((table.miss - 2.0 * AVOIDANCE_PER_LEVEL).abs() < 1e-6);
+    assert!(table.parry > 0.0);
+
+    // Pets always attack from behind.
+    let pet = state.pet_attack_table(Attack::melee().auto(), target);
+    assert_eq!(pet.parry, 0.0);
+    assert!(pet.glance > 0.0);
+}
+
+#[test]
+fn mitigation_from_target_level_and_auras() {
+    let constants = ArmorConstants::new()
+        .with_level(83, 10_000.0)
+        .with_level(85, 20_000.0);
+    let config = SimConfig::default()
+        .with_target_armor(10_000.0)
+        .with_armor_constants(constants);
+    let mut state = SimState::new(config, Player::new(SpecId::BeastMastery));
+    let target = state.enemies.primary;
+    assert!((state.mitigation(target).armor_mitigation() - 0.5).abs() < 1e-6);
+
+    let sunder = AuraIdx(1);
+    let focus = AuraIdx(2);
+    state.mitigation_auras.armor_reduction.push((sunder, 0.1));
+    state.mitigation_auras.armor_penetration.push((focus, 0.5));
+
+    let now = state.now();
+    let mut debuff = AuraInstance::new(
+        sunder,
+        target,
+        SimTime::from_secs(10),
+        now,
+        AuraFlags::default(),
+    )
+    .with_stacks(5);
+    debuff.stacks = 2;
+    state.auras.target_mut(target).unwrap().apply(debuff, now);
+    state.player.buffs.apply(
+        AuraInstance::new(
+            focus,
+            target,
+            SimTime::from_secs(10),
+            now,
+            AuraFlags::default(),
+        ),
+        now,
+    );
+
+    // 10000 * (1 - 2 * 0.1) * (1 - 0.5) = 4000 armor
+    let mitigation = state.mitigation(target);
+    assert!((mitigation.effective_armor() - 4000.0).abs() < 1e-3);
+
+    // A higher-level target uses its level's constant.
+    let config = SimConfig::default()
+        .with_target_level(85)
+        .with_target_armor(20_000.0)
+        .with_armor_constants(state.config.armor_constants.clone());
+    let state = SimState::new(config, Player::new(SpecId::BeastMastery));
+    let mitigation = state.mitigation(state.enemies.primary);
+    assert!((mitigation.armor_mitigation() - 0.5).abs() < 1e-6);
+}
+
 #[test]
 fn simulation_current_dps() {
     let handler = create_handler();
@@ -185,3 +554,299 @@ fn simulation_current_dps() {
     // DPS should be 0 since empty rotation does nothing
     assert_eq!(sim.dps(), 0.0);
 }
+
+fn bm_request(iterations: u32) -> SimulationRequest {
+    SimulationRequest {
+        spec_id: 253,
+        stats: PlayerStats::default(),
+        fight: FightSettings {
+            duration: 30.0,
+            targets: 1,
+            seed: Some(7),
+            ..Default::default()
+        },
+        rotation: include_str!("../../rotations/bm_hunter.json").to_string(),
+        iterations,
+        talents: None,
+    }
+}
+
+#[test]
+fn simulation_request_from_json() {
+    let request: SimulationRequest = serde_json::from_str(
+        r#"{ "specId": 253, "rotation": "{}", "iterations": 5, "fight": { "duration": 60 } }"#,
+    )
+    .unwrap();
+
+    assert_eq!(request.spec_id, 253);
+    assert_eq!(request.iterations, 5);
+    assert!((request.fight.duration - 60.0).abs() < 0.01);
+    assert_eq!(request.fight.targets, 1);
+    assert_eq!(
+        request.stats.primary_stat,
+        PlayerStats::default().primary_stat
+    );
+}
+
+#[test]
+fn simulation_request_rejects_bad_input() {
+    let mut request = bm_request(0);
+    assert!(request.runner().is_err());
+
+    request.iterations = 1;
+    request.spec_id = 0;
+    assert!(request.runner().is_err());
+}
+
+#[test]
+fn simulation_request_run() {
+    let output = bm_request(4).run().unwrap();
+
+    assert_eq!(output.results.iterations, 4);
+    assert!(output.results.mean_dps > 0.0);
+    assert!(!output.breakdown.entries.is_empty());
+    assert!((output.breakdown.total_dps - output.results.mean_dps).abs() < 1.0);
+}
+
+#[test]
+fn chunked_batch_matches_full_run() {
+    let request = bm_request(5);
+    let full = request.run().unwrap();
+
+    let mut batch = request.chunked().unwrap();
+    assert_eq!(batch.step(2), 2);
+    assert_eq!(batch.completed(), 2);
+    assert!(!batch.is_done());
+    assert!(batch.running_mean() > 0.0);
+    assert_eq!(batch.step(10), 3);
+    assert!(batch.is_done());
+    assert_eq!(batch.step(1), 0);
+
+    let (results, breakdown) = batch.finish();
+    assert_eq!(results.dps_values, full.results.dps_values);
+    assert!((breakdown.total_damage - full.breakdown.total_damage).abs() < 1e-6);
+}
+
+#[test]
+fn batch_runner_merges_breakdown() {
+    let request = bm_request(6);
+    let results = request.runner().unwrap().with_breakdown().run();
+    let (chunked, expected) = {
+        let mut batch = request.chunked().unwrap();
+        batch.step(6);
+        batch.finish()
+    };
+
+    assert_eq!(results.dps_values, chunked.dps_values);
+    let breakdown = results.breakdown.expect("breakdown collected");
+    assert_eq!(breakdown.iterations, 6);
+    assert!((breakdown.total_damage - expected.total_damage).abs() < 1e-6);
+    assert!((breakdown.total_dps - results.mean_dps).abs() < 1.0);
+
+    let entry = |name: &str| breakdown.entries.iter().find(|e| e.name == name).unwrap();
+    assert_eq!(entry("Pet Melee").source, DamageSource::Pet);
+    assert_eq!(entry("Kill Command").source, DamageSource::Pet);
+    assert_eq!(entry("Auto Shot").source, DamageSource::Player);
+    assert!(entry("Kill Command").casts > 0.0);
+    assert!(breakdown.source_percent(DamageSource::Pet) > 0.0);
+    assert!(breakdown.source_percent(DamageSource::Player) > 0.0);
+    assert_eq!(breakdown.targets.len(), 1);
+}
+
+#[test]
+fn batch_runner_breakdown_is_opt_in() {
+    let results = bm_request(2).runner().unwrap().run();
+    assert!(results.breakdown.is_none());
+}
+
+#[test]
+fn breakdown_names_spells() {
+    let (_, breakdown) = {
+        let mut batch = bm_request(1).chunked().unwrap();
+        batch.step(1);
+        batch.finish()
+    };
+
+    let names: Vec<_> = breakdown.entries.iter().map(|e| e.name.as_str()).collect();
+    assert!(names.contains(&"Kill Command"), "{:?}", names);
+}
+
+fn traced_bm_sim(duration: f32) -> Simulation {
+    let handler = crate::handler::create_handler_with_backend(
+        SpecId::BeastMastery,
+        include_str!("../../rotations/bm_hunter.json"),
+        crate::rotation::RotationBackend::default(),
+    )
+    .unwrap();
+    let config = SimConfig::default()
+        .with_duration(duration)
+        .with_seed(3)
+        .with_trace();
+    let mut sim = Simulation::new(handler, config, geared_player());
+    sim.run();
+    sim
+}
+
+#[test]
+fn trace_is_off_by_default() {
+    let mut sim = Simulation::new(
+        cobra_shot_handler(),
+        SimConfig::default().with_duration(10.0),
+        geared_player(),
+    );
+    sim.run();
+
+    assert!(sim.state.trace.is_empty());
+}
+
+#[test]
+fn trace_records_combat_log() {
+    let sim = traced_bm_sim(40.0);
+    let trace = &sim.state.trace;
+
+    assert!(trace.windows(2).all(|w| w[0].time <= w[1].time));
+
+    let traced_damage: f64 = trace
+        .iter()
+        .filter_map(|e| match e.event_type {
+            TraceEventType::Damage { amount, .. } => Some(amount as f64),
+            _ => None,
+        })
+        .sum();
+    assert!((traced_damage - sim.state.total_damage).abs() < 1.0);
+
+    let casts = trace.iter().filter_map(|e| match &e.event_type {
+        TraceEventType::SpellCast {
+            resources, auras, ..
+        } => Some((resources, auras)),
+        _ => None,
+    });
+    let (mut cast_count, mut casts_with_auras) = (0, 0);
+    for (resources, auras) in casts {
+        cast_count += 1;
+        assert_eq!(resources[0].resource, ResourceType::Focus);
+        if !auras.is_empty() {
+            casts_with_auras += 1;
+        }
+    }
+    assert!(cast_count > 10);
+    assert!(casts_with_auras > 0);
+
+    let has = |f: fn(&TraceEventType) -> bool| trace.iter().any(|e| f(&e.event_type));
+    assert!(has(|e| matches!(e, TraceEventType::AuraApply { .. })));
+    assert!(has(|e| matches!(e, TraceEventType::AuraExpire { .. })));
+    assert!(has(|e| matches!(e, TraceEventType::CooldownStart { .. })));
+    assert!(has(|e| matches!(e, TraceEventType::ResourceGain { .. })));
+    assert!(has(|e| matches!(
+        e,
+        TraceEventType::Damage {
+            source: DamageSource::Pet,
+            ..
+        }
+    )));
+}
+
+#[test]
+fn trace_expiry_matches_aura_end() {
+    let sim = traced_bm_sim(40.0);
+
+    for event in &sim.state.trace {
+        if let TraceEventType::AuraExpire { aura, .. } = event.event_type {
+            let applied = sim.state.trace.iter().any(|e| {
+                e.time <= event.time
+                    && matches!(e.event_type, TraceEventType::AuraApply { aura: a, .. } if a == aura)
+            });
+            assert!(applied, "aura {} expired without being applied", aura.0);
+        }
+    }
+}
+
+#[test]
+fn stat_weights_normalize_to_primary() {
+    let weights = bm_request(20).stat_weights(1000.0).unwrap();
+
+    assert_eq!(weights.factors.len(), ScaleStat::ALL.len());
+    let primary = weights.factor(ScaleStat::Primary).unwrap();
+    assert!(primary.factor > 0.0, "{:?}", primary);
+    assert!((primary.weight - 1.0).abs() < 1e-9);
+    for f in &weights.factors {
+        assert!(f.factor.is_finite() && f.std_error >= 0.0, "{:?}", f);
+    }
+}
+
+#[test]
+fn stat_weights_pair_iterations() {
+    let runner = bm_request(20).runner().unwrap();
+    let weights = StatWeightRunner::new(runner)
+        .with_stats(vec![ScaleStat::Primary])
+        .run();
+
+    // Common random numbers keep the paired error well below the raw spread
+    let primary = weights.factor(ScaleStat::Primary).unwrap();
+    assert!(primary.std_error * 1000.0 < weights.base_std_error);
+}
+
+#[test]
+fn stat_weights_reject_bad_delta() {
+    assert!(bm_request(2).stat_weights(0.0).is_err());
+}
+
+#[test]
+fn pawn_string_format() {
+    let factors = vec![
+        ScaleFactor {
+            stat: ScaleStat::Primary,
+            factor: 2.0,
+            std_error: 0.0,
+            weight: 1.0,
+        },
+        ScaleFactor {
+            stat: ScaleStat::Crit,
+            factor: 1.0,
+            std_error: 0.0,
+            weight: 0.5,
+        },
+    ];
+
+    assert_eq!(
+        pawn_string(SpecId::BeastMastery, &factors),
+        "( Pawn: v1: \"WoW Lab BeastMastery\": Class=Hunter, Spec=BeastMastery, Agility=1.00, CritRating=0.50 )"
+    );
+}
+
+#[test]
+fn damage_multipliers_include_versatility_and_mastery() {
+    let mut player = Player::new(SpecId::BeastMastery);
+    player.stats.ratings.versatility = 1000.0;
+    player.stats.ratings.mastery = 1000.0;
+    player.stats.update(1.0);
+    let versatility = player.stats.versatility();
+    let mastery =
+        SpecCoefficients::for_spec(SpecId::BeastMastery).mastery_bonus(player.stats.mastery());
+    let mut state = SimState::new(SimConfig::default(), player);
+    let target = state.enemies.primary;
+
+    // BM mastery only boosts the pet
+    let owner = state.damage_multipliers(DamageSchool::Physical, false, false, target);
+    assert!((owner.total_da(false) - (1.0 + versatility)).abs() < 1e-5);
+    assert_eq!(owner.active().len(), 1);
+
+    let pet = state.damage_multipliers(DamageSchool::Physical, false, true, target);
+    let expected = (1.0 + versatility) * (1.0 + mastery);
+    assert!((pet.total_da(false) - expected).abs() < 1e-5);
+
+    // Landed hits credit their multipliers to the spell
+    state.audit_multipliers(&pet);
+    state.record_pet_damage(SpellIdx(1), target, 100.0, HitResult::Hit, false);
+    let stats = state.stats.spell(SpellIdx(1)).unwrap();
+    assert_eq!(stats.multipliers.len(), 2);
+    assert_eq!(stats.multipliers[1].source, MultiplierSource::Mastery);
+    assert!((stats.multipliers[1].average() - (1.0 + mastery)).abs() < 1e-5);
+
+    // Missed ones don't, and don't leak into the next hit
+    state.audit_multipliers(&pet);
+    state.record_pet_damage(SpellIdx(1), target, 0.0, HitResult::Miss, false);
+    state.record_pet_damage(SpellIdx(1), target, 100.0, HitResult::Hit, false);
+    let stats = state.stats.spell(SpellIdx(1)).unwrap();
+    assert_eq!(stats.multipliers[1].hits, 1);
+}
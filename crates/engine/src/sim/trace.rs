@@ -0,0 +1,177 @@
+//! Combat trace recorded when `SimConfig::trace_events` is set.
+//!
+//! Events are appended to `SimState::trace` as they happen; casts also
+//! snapshot the player's resources and every active aura so rotation
+//! decisions can be replayed from the log.
+
+use super::SimState;
+use crate::aura::TargetAuras;
+use crate::results::DamageSource;
+use serde::Serialize;
+use wowlab_common::types::{AuraIdx, ProcIdx, ResourceType, SimTime, SpellIdx, TargetIdx};
+
+/// Traced event for debugging
+#[derive(Clone, Debug, Serialize)]
+pub struct TraceEvent {
+    pub time: SimTime,
+    #[serde(flatten)]
+    pub event_type: TraceEventType,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum TraceEventType {
+    SpellCast {
+        spell: SpellIdx,
+        target: TargetIdx,
+        /// Player resources before paying the cast's cost
+        resources: Vec<ResourceSnapshot>,
+        /// Buffs on the player and debuffs on enemies
+        auras: Vec<AuraSnapshot>,
+    },
+    Damage {
+        spell: SpellIdx,
+        target: TargetIdx,
+        source: DamageSource,
+        amount: f32,
+        is_crit: bool,
+        is_periodic: bool,
+    },
+    AuraApply {
+        aura: AuraIdx,
+        target: TargetIdx,
+        is_debuff: bool,
+    },
+    AuraExpire {
+        aura: AuraIdx,
+        target: TargetIdx,
+        is_debuff: bool,
+    },
+    ResourceGain {
+        resource: ResourceType,
+        amount: f32,
+    },
+    CooldownStart {
+        spell: SpellIdx,
+    },
+    ProcTrigger {
+        proc: ProcIdx,
+    },
+}
+
+/// A player resource pool at one point in the trace
+#[derive(Clone, Debug, Serialize)]
+pub struct ResourceSnapshot {
+    pub resource: ResourceType,
+    pub current: f32,
+    pub max: f32,
+}
+
+/// An active aura at one point in the trace
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuraSnapshot {
+    pub aura: AuraIdx,
+    pub target: TargetIdx,
+    pub is_debuff: bool,
+    pub stacks: u8,
+    /// Seconds until it expires
+    pub remaining: f32,
+}
+
+impl SimState {
+    /// Whether trace events are being recorded
+    #[inline]
+    pub fn tracing(&self) -> bool {
+        self.config.trace_events
+    }
+
+    /// Trace a cast along with the player's resources and active auras.
+    pub fn trace_cast(&mut self, spell: SpellIdx, target: TargetIdx) {
+        if !self.tracing() {
+            return;
+        }
+
+        let resources = [
+            &self.player.resources.primary,
+            &self.player.resources.secondary,
+            &self.player.resources.mana,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|pool| ResourceSnapshot {
+            resource: pool.resource_type,
+            current: pool.current,
+            max: pool.max,
+        })
+        .collect();
+
+        let now = self.now();
+        let snapshot = |auras: &TargetAuras| -> Vec<AuraSnapshot> {
+            auras
+                .iter()
+                .filter(|a| a.is_active(now))
+                .map(|a| AuraSnapshot {
+                    aura: a.aura_id,
+                    target: a.target,
+                    is_debuff: a.flags.is_debuff,
+                    stacks: a.stacks,
+                    remaining: a.remaining(now).as_secs_f32(),
+                })
+                .collect()
+        };
+        let mut auras = snapshot(&self.player.buffs);
+        for i in 0..self.enemies.count() {
+            if let Some(target_auras) = self.auras.target(TargetIdx(i as u16)) {
+                auras.extend(snapshot(target_auras));
+            }
+        }
+
+        self.trace(TraceEventType::SpellCast {
+            spell,
+            target,
+            resources,
+            auras,
+        });
+    }
+
+    /// Trace auras that ran out after `since` and no later than now.
+    ///
+    /// Player buffs expire lazily, so expiry is found by scanning rather
+    /// than from events. Called before each event is handled.
+    pub(crate) fn trace_expired_auras(&mut self, since: SimTime) {
+        let now = self.now();
+        let expired = |auras: &TargetAuras| -> Vec<(SimTime, AuraIdx, TargetIdx, bool)> {
+            auras
+                .iter()
+                .filter(|a| a.expires_at > since && a.expires_at <= now)
+                .map(|a| (a.expires_at, a.aura_id, a.target, a.flags.is_debuff))
+                .collect()
+        };
+
+        let mut events = expired(&self.player.buffs);
+        for i in 0..self.enemies.count() {
+            if let Some(target_auras) = self.auras.target(TargetIdx(i as u16)) {
+                events.extend(expired(target_auras));
+            }
+        }
+        events.sort_by_key(|&(time, ..)| time);
+
+        self.trace.extend(
+            events
+                .into_iter()
+                .map(|(time, aura, target, is_debuff)| TraceEvent {
+                    time,
+                    event_type: TraceEventType::AuraExpire {
+                        aura,
+                        target,
+                        is_debuff,
+                    },
+                }),
+        );
+    }
+}
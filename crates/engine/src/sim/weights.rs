@@ -0,0 +1,245 @@
+//! Stat weights from paired delta simulations.
+//!
+//! Each stat is raised by a fixed amount and simulated with the same seeds
+//! as the base run (common random numbers), so the per-iteration DPS
+//! differences cancel most of the run-to-run noise.
+
+use super::{BatchResults, BatchRunner};
+use crate::math::Summary;
+use crate::stats::{primary_stat_for_spec, StatCache};
+use serde::Serialize;
+use std::fmt;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+use wowlab_common::types::{Attribute, RatingType, SpecId};
+
+/// Stat raised for a scale factor
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+pub enum ScaleStat {
+    /// Agility, Strength or Intellect, picked by spec
+    Primary,
+    Crit,
+    Haste,
+    Mastery,
+    Versatility,
+}
+
+impl ScaleStat {
+    pub const ALL: [Self; 5] = [
+        Self::Primary,
+        Self::Crit,
+        Self::Haste,
+        Self::Mastery,
+        Self::Versatility,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Primary => "primary",
+            Self::Crit => "crit",
+            Self::Haste => "haste",
+            Self::Mastery => "mastery",
+            Self::Versatility => "versatility",
+        }
+    }
+
+    /// Stat name used in Pawn strings
+    pub fn pawn_name(self, spec: SpecId) -> &'static str {
+        match self {
+            Self::Primary => match primary_stat_for_spec(spec) {
+                Attribute::Strength => "Strength",
+                Attribute::Agility => "Agility",
+                Attribute::Intellect => "Intellect",
+                Attribute::Stamina => "Stamina",
+            },
+            Self::Crit => "CritRating",
+            Self::Haste => "HasteRating",
+            Self::Mastery => "MasteryRating",
+            Self::Versatility => "Versatility",
+        }
+    }
+
+    /// Add `amount` of this stat and recompute derived stats
+    fn apply(self, stats: &mut StatCache, amount: f32) {
+        match self {
+            Self::Primary => stats.primary.add(primary_stat_for_spec(stats.spec), amount),
+            Self::Crit => stats.ratings.add(RatingType::Crit, amount),
+            Self::Haste => stats.ratings.add(RatingType::Haste, amount),
+            Self::Mastery => stats.ratings.add(RatingType::Mastery, amount),
+            Self::Versatility => stats.ratings.add(RatingType::Versatility, amount),
+        }
+        stats.invalidate();
+        stats.update(1.0);
+    }
+}
+
+impl fmt::Display for ScaleStat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// DPS gained per point of one stat
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+pub struct ScaleFactor {
+    pub stat: ScaleStat,
+    /// DPS per point
+    pub factor: f64,
+    /// Standard error of `factor`
+    pub std_error: f64,
+    /// Factor relative to the primary stat's
+    pub weight: f64,
+}
+
+/// Scale factors for every simulated stat
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+pub struct StatWeights {
+    pub spec: SpecId,
+    /// Points added to each stat
+    pub delta: f32,
+    pub iterations: u32,
+    pub base_dps: f64,
+    /// Standard error of `base_dps`
+    pub base_std_error: f64,
+    pub factors: Vec<ScaleFactor>,
+    /// Pawn import string of the normalized weights
+    pub pawn: String,
+}
+
+impl StatWeights {
+    /// Scale factor for `stat`, if it was simulated
+    pub fn factor(&self, stat: ScaleStat) -> Option<&ScaleFactor> {
+        self.factors.iter().find(|f| f.stat == stat)
+    }
+
+    /// Format as table
+    pub fn to_table(&self) -> String {
+        let mut output = format!(
+            "\n{:12} {:>10} {:>10} {:>8}\n",
+            "Stat", "DPS/pt", "Error", "Weight"
+        );
+        output.push_str(&"-".repeat(43));
+        output.push('\n');
+        for f in &self.factors {
+            output.push_str(&format!(
+                "{:12} {:>10.4} {:>10.4} {:>8.3}\n",
+                f.stat, f.factor, f.std_error, f.weight
+            ));
+        }
+        output
+    }
+}
+
+/// Build a Pawn import string from normalized weights.
+pub fn pawn_string(spec: SpecId, factors: &[ScaleFactor]) -> String {
+    let weights: Vec<_> = factors
+        .iter()
+        .map(|f| format!("{}={:.2}", f.stat.pawn_name(spec), f.weight))
+        .collect();
+    format!(
+        "( Pawn: v1: \"WoW Lab {:?}\": Class={:?}, Spec={:?}, {} )",
+        spec,
+        spec.class(),
+        spec,
+        weights.join(", ")
+    )
+}
+
+/// Runs the base simulation and one delta simulation per stat.
+pub struct StatWeightRunner {
+    base: BatchRunner,
+    delta: f32,
+    stats: Vec<ScaleStat>,
+}
+
+impl StatWeightRunner {
+    /// Scale factors for the player and fight configured on `base`.
+    pub fn new(base: BatchRunner) -> Self {
+        Self {
+            base,
+            delta: 1000.0,
+            stats: ScaleStat::ALL.to_vec(),
+        }
+    }
+
+    /// Points added to each stat (default 1000)
+    pub fn with_delta(mut self, delta: f32) -> Self {
+        self.delta = delta;
+        self
+    }
+
+    /// Only simulate these stats
+    pub fn with_stats(mut self, stats: Vec<ScaleStat>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    pub fn run(&self) -> StatWeights {
+        let base = self.base.run();
+        let spec = self.base.handler().spec_id();
+
+        let mut factors: Vec<_> = self
+            .stats
+            .iter()
+            .map(|&stat| {
+                let mut runner = self.base.clone();
+                stat.apply(&mut runner.player_mut().stats, self.delta);
+                self.scale_factor(stat, &base, &runner.run())
+            })
+            .collect();
+
+        let primary = factors
+            .iter()
+            .find(|f| f.stat == ScaleStat::Primary)
+            .map(|f| f.factor)
+            .filter(|&factor| factor > 0.0)
+            .unwrap_or(1.0);
+        for f in &mut factors {
+            f.weight = f.factor / primary;
+        }
+
+        StatWeights {
+            spec,
+            delta: self.delta,
+            iterations: base.iterations,
+            base_dps: base.mean_dps,
+            base_std_error: base.std_dev / (base.iterations.max(1) as f64).sqrt(),
+            pawn: pawn_string(spec, &factors),
+            factors,
+        }
+    }
+
+    /// Scale factor from the paired per-iteration DPS differences
+    fn scale_factor(
+        &self,
+        stat: ScaleStat,
+        base: &BatchResults,
+        scaled: &BatchResults,
+    ) -> ScaleFactor {
+        let diffs: Vec<f64> = scaled
+            .dps_values
+            .iter()
+            .zip(&base.dps_values)
+            .map(|(scaled, base)| scaled - base)
+            .collect();
+        let n = diffs.len().max(1) as f64;
+        let summary = Summary::new(diffs);
+        let delta = self.delta as f64;
+
+        ScaleFactor {
+            stat,
+            factor: summary.mean() / delta,
+            std_error: summary.std_dev() / n.sqrt() / delta,
+            weight: 0.0,
+        }
+    }
+}
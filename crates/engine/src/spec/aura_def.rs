@@ -17,6 +17,10 @@ pub enum AuraEffect {
     RatingFlat { rating: RatingType, amount: f32 },
     /// Percentage increase to a derived stat (crit chance, haste mult, etc.)
     DerivedPercent { stat: DerivedStat, amount: f32 },
+    /// Reduces the target's armor by `amount` (0 to 1) per stack
+    ArmorReduction { amount: f32 },
+    /// Ignores `amount` (0 to 1) of the target's armor per stack
+    ArmorPenetration { amount: f32 },
     /// Damage multiplier
     DamageMultiplier {
         amount: f32,
@@ -382,6 +382,20 @@ impl AuraBuilder {
         self
     }
 
+    pub fn armor_reduction(mut self, amount: f32) -> Self {
+        self.aura
+            .effects
+            .push(AuraEffect::ArmorReduction { amount });
+        self
+    }
+
+    pub fn armor_penetration(mut self, amount: f32) -> Self {
+        self.aura
+            .effects
+            .push(AuraEffect::ArmorPenetration { amount });
+        self
+    }
+
     pub fn haste(mut self, amount: f32) -> Self {
         self.aura.effects.push(AuraEffect::DerivedPercent {
             stat: DerivedStat::Haste,
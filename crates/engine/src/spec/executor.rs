@@ -4,9 +4,9 @@
 //! replacing scattered if/else chains in spec handlers.
 
 use crate::aura::AuraInstance;
-use crate::combat::DamagePipeline;
+use crate::combat::{Attack, AttackType, DamagePipeline, MultiplierSource};
 use crate::core::SimEvent;
-use crate::sim::SimState;
+use crate::sim::{SimState, TraceEventType};
 use crate::spec::{
     AuraDef, DamageMod, EffectCondition, ModCondition, SpellDef, SpellEffect, SpellFlags,
 };
@@ -218,6 +218,11 @@ fn apply_buff(ctx: &mut EffectContext<'_>, aura_id: AuraIdx, stacks: u8) {
         }
 
         ctx.state.player.buffs.apply(instance, now);
+        ctx.state.trace(TraceEventType::AuraApply {
+            aura: aura_id,
+            target: TargetIdx(0),
+            is_debuff: false,
+        });
         debug!(aura = aura_id.0, stacks, "Applied buff");
     }
 }
@@ -241,6 +246,11 @@ fn apply_debuff(ctx: &mut EffectContext<'_>, aura_id: AuraIdx, target: TargetIdx
         if let Some(target_auras) = ctx.state.auras.target_mut(target) {
             target_auras.apply(instance, now);
         }
+        ctx.state.trace(TraceEventType::AuraApply {
+            aura: aura_id,
+            target,
+            is_debuff: true,
+        });
 
         // Schedule first tick for periodic effects
         if let Some(ref periodic) = aura_def.periodic {
@@ -276,8 +286,10 @@ pub struct DamageContext<'a> {
     pub talents: &'a [&'static str],
     /// Active damage modifiers.
     pub modifiers: &'a [DamageMod],
-    /// Was this a critical hit?
-    pub is_crit: bool,
+    /// How the attack is delivered.
+    pub attack: Attack,
+    /// Outcome of the attack roll.
+    pub result: HitResult,
 }
 
 /// Calculate damage with all modifiers applied.
@@ -291,7 +303,21 @@ pub fn calculate_damage(
     let ap = ctx.state.player.stats.attack_power();
     let sp = ctx.state.player.stats.spell_power();
     let crit = ctx.state.player.stats.crit_chance();
-    let armor = ctx.state.enemies.primary().map(|e| e.armor).unwrap_or(0.0);
+    let mitigation = ctx.state.mitigation(ctx.target);
+    let from_pet = ctx
+        .spell
+        .is_some_and(|s| s.flags.contains(SpellFlags::PET_ABILITY));
+    let table = if from_pet {
+        ctx.state.pet_attack_table(ctx.attack, ctx.target)
+    } else {
+        ctx.state.attack_table(ctx.attack, ctx.target)
+    }
+    .with_crit(crit);
+
+    let periodic = ctx.attack.kind == AttackType::Periodic;
+    let mut multipliers = ctx
+        .state
+        .damage_multipliers(school, periodic, from_pet, ctx.target);
 
     // Calculate base damage through pipeline
     let result = DamagePipeline::calculate(
@@ -300,17 +326,17 @@ pub fn calculate_damage(
         sp_coef,
         ap,
         sp,
-        &ctx.state.multipliers,
-        crit,
+        &multipliers,
+        &table,
         school,
-        armor,
+        &mitigation,
         &mut ctx.state.rng,
     );
 
     let mut damage = result.final_amount;
-    ctx.is_crit = result.hit_result == HitResult::Crit;
+    ctx.result = result.hit_result;
 
-    // Apply all active modifiers
+    // Apply all active modifiers (after the roll, as some only apply to crits)
     let mut sorted_mods: Vec<_> = ctx
         .modifiers
         .iter()
@@ -321,15 +347,32 @@ pub fn calculate_damage(
     for modifier in sorted_mods {
         let mult = get_modifier_value(ctx, modifier);
         damage *= mult;
+        let source = modifier_source(modifier);
+        if from_pet {
+            multipliers.apply_pet(source, mult);
+        } else {
+            multipliers.apply(source, mult);
+        }
         debug!(
             name = modifier.name,
             mult, damage, "Applied damage modifier"
         );
     }
+    ctx.state.audit_multipliers(&multipliers);
 
     damage
 }
 
+/// Aura a modifier depends on, or its name.
+fn modifier_source(modifier: &DamageMod) -> MultiplierSource {
+    match modifier.condition {
+        ModCondition::BuffActive(aura)
+        | ModCondition::DebuffActive(aura)
+        | ModCondition::PerStack { aura, .. } => MultiplierSource::Aura(aura),
+        _ => MultiplierSource::named(modifier.name.clone()),
+    }
+}
+
 /// Check if a damage modifier condition is met.
 fn check_mod_condition(ctx: &DamageContext<'_>, condition: &ModCondition) -> bool {
     let now = ctx.state.now();
@@ -360,7 +403,7 @@ fn check_mod_condition(ctx: &DamageContext<'_>, condition: &ModCondition) -> boo
             .map(|e| (e.current_health / e.max_health) < *threshold)
             .unwrap_or(false),
 
-        ModCondition::OnCrit => ctx.is_crit,
+        ModCondition::OnCrit => ctx.result.is_crit(),
 
         ModCondition::PerStack { aura, .. } => ctx.state.player.buffs.stacks(*aura, now) > 0,
 
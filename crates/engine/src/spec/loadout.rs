@@ -0,0 +1,139 @@
+//! Talent loadouts from in-game loadout strings.
+//!
+//! A loadout string only records per-node selections. The spec's trait tree
+//! turns those into definition spell IDs, which each spec matches against
+//! its talent map.
+
+use crate::rotation::SpecResolver;
+use bitflags::Flags;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+use wowlab_common::parsers::{apply_decoded_traits, decode_trait_loadout, validate_trait_loadout};
+use wowlab_common::types::data::TraitTreeFlat;
+
+/// A talent purchased in a loadout
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct LoadoutTalent {
+    /// Spell ID of the trait definition (the chosen entry for choice nodes)
+    pub spell_id: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_rank")]
+    pub rank: u8,
+    #[serde(default = "default_rank")]
+    pub max_rank: u8,
+}
+
+fn default_rank() -> u8 {
+    1
+}
+
+/// Purchased talents for one spec.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct TalentLoadout {
+    /// WoW spec ID the loadout was built for (0 if unknown)
+    #[serde(default)]
+    pub spec_id: u32,
+    pub talents: Vec<LoadoutTalent>,
+}
+
+impl TalentLoadout {
+    /// Decode an in-game loadout string against the spec's trait tree.
+    ///
+    /// Builds the tree doesn't allow are rejected with every violation listed.
+    pub fn decode(loadout: &str, tree: &TraitTreeFlat) -> Result<Self, String> {
+        let decoded = decode_trait_loadout(loadout.trim())
+            .map_err(|e| format!("Invalid loadout string: {}", e))?;
+        let violations = validate_trait_loadout(tree, &decoded);
+        if !violations.is_empty() {
+            let messages: Vec<_> = violations.iter().map(|v| v.to_string()).collect();
+            return Err(format!("Invalid loadout: {}", messages.join("; ")));
+        }
+
+        let applied = apply_decoded_traits(tree.clone(), &decoded);
+        let talents = applied
+            .selections
+            .iter()
+            .filter(|s| s.ranks_purchased > 0)
+            .filter_map(|s| {
+                let node = tree.nodes.iter().find(|n| n.id == s.node_id)?;
+                let entry = node.entries.get(s.choice_index.unwrap_or(0) as usize)?;
+                Some(LoadoutTalent {
+                    spell_id: entry.spell_id as u32,
+                    name: entry.name.clone(),
+                    rank: s.ranks_purchased as u8,
+                    max_rank: node.max_ranks.max(1) as u8,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            spec_id: decoded.spec_id as u32,
+            talents,
+        })
+    }
+
+    /// Match talents against a spec's `(flag, name, spell ID)` talent map.
+    pub fn apply<F: Flags + Copy>(&self, map: &[(F, &'static str, u32)]) -> AppliedTalents<F> {
+        let mut flags = F::empty();
+        let mut report = LoadoutReport::default();
+
+        for talent in &self.talents {
+            match map.iter().find(|(_, _, id)| *id == talent.spell_id) {
+                Some(&(flag, name, _)) if talent.rank > 0 => {
+                    flags.insert(flag);
+                    report.applied.push(TalentRank {
+                        name,
+                        rank: talent.rank,
+                        max_rank: talent.max_rank.max(talent.rank),
+                    });
+                }
+                Some(_) => {}
+                None => report.unimplemented.push(talent.clone()),
+            }
+        }
+
+        AppliedTalents { flags, report }
+    }
+}
+
+/// Rank of a talent the spec implements
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TalentRank {
+    pub name: &'static str,
+    pub rank: u8,
+    pub max_rank: u8,
+}
+
+/// What a loadout changed in a spec handler
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadoutReport {
+    pub applied: Vec<TalentRank>,
+    /// Talents the spec has no implementation for
+    pub unimplemented: Vec<LoadoutTalent>,
+}
+
+/// A loadout matched against one spec's talents.
+#[derive(Clone, Debug)]
+pub struct AppliedTalents<F> {
+    pub flags: F,
+    pub report: LoadoutReport,
+}
+
+impl<F> AppliedTalents<F> {
+    /// Register talent ranks so `talent.X.rank` matches the loadout
+    pub fn register(&self, resolver: SpecResolver) -> SpecResolver {
+        self.report.applied.iter().fold(resolver, |resolver, t| {
+            resolver.talent_ranked(t.name, t.rank as i32, t.max_rank as i32)
+        })
+    }
+}
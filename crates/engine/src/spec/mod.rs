@@ -3,6 +3,8 @@ mod builder;
 mod context;
 pub mod effect;
 pub mod executor;
+mod loadout;
+mod sets;
 mod spell;
 
 pub use aura_def::*;
@@ -12,6 +14,8 @@ pub use effect::{
     ChargeMod, CooldownMod, DamageMod, EffectCondition, ModCondition, SpellEffect, TalentDef,
 };
 pub use executor::{calculate_damage, execute_effects, DamageContext, EffectContext};
+pub use loadout::*;
+pub use sets::*;
 pub use spell::*;
 
 #[cfg(test)]
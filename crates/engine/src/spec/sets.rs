@@ -0,0 +1,127 @@
+//! Item set bonuses from equipped items.
+//!
+//! Pieces are counted per item set and every bonus whose threshold is met
+//! (and that isn't limited to another spec) becomes active. Specs map bonus
+//! spells onto their tier set flags by name; a bonus whose spell is one of
+//! the spec's auras is applied for the whole fight instead.
+
+use super::AuraDef;
+use bitflags::Flags;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use wowlab_common::types::data::ItemDataFlat;
+use wowlab_common::types::AuraIdx;
+
+/// A set bonus whose piece threshold is met
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSetBonus {
+    pub set_id: i32,
+    pub set_name: String,
+    /// Pieces of the set equipped
+    pub pieces: i32,
+    pub threshold: i32,
+    pub spell_id: i32,
+    /// Name of the bonus spell, if known
+    pub name: String,
+}
+
+/// Set bonuses active for one spec's equipment.
+#[derive(Clone, Debug, Default)]
+pub struct SetBonuses {
+    pub active: Vec<ActiveSetBonus>,
+}
+
+impl SetBonuses {
+    /// Count set pieces in `items` and collect the bonuses `wow_spec_id` gets.
+    pub fn from_items(items: &[ItemDataFlat], wow_spec_id: u32) -> Self {
+        let mut pieces: BTreeMap<i32, (i32, &ItemDataFlat)> = BTreeMap::new();
+        for item in items
+            .iter()
+            .filter(|i| i.item_set_id > 0 && i.set_info.is_some())
+        {
+            pieces.entry(item.item_set_id).or_insert((0, item)).0 += 1;
+        }
+
+        let mut active = Vec::new();
+        for (count, item) in pieces.into_values() {
+            let Some(set) = &item.set_info else {
+                continue;
+            };
+            for bonus in &set.bonuses {
+                let for_spec = bonus.spec_id == 0 || bonus.spec_id == wow_spec_id as i32;
+                if for_spec && bonus.threshold <= count {
+                    active.push(ActiveSetBonus {
+                        set_id: set.set_id,
+                        set_name: set.set_name.clone(),
+                        pieces: count,
+                        threshold: bonus.threshold,
+                        spell_id: bonus.spell_id,
+                        name: String::new(),
+                    });
+                }
+            }
+        }
+
+        Self { active }
+    }
+
+    /// Fill in bonus spell names from `spell_name`.
+    pub fn with_names(mut self, spell_name: impl Fn(i32) -> Option<String>) -> Self {
+        for bonus in &mut self.active {
+            if let Some(name) = spell_name(bonus.spell_id) {
+                bonus.name = name;
+            }
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Match bonuses against a spec's `(flag, spell name)` tier set map.
+    ///
+    /// Bonuses not in the map whose spell is one of `auras` are granted as
+    /// fight-long auras.
+    pub fn apply<F: Flags + Copy>(
+        &self,
+        map: &[(F, &'static str)],
+        auras: &[AuraDef],
+    ) -> AppliedSetBonuses<F> {
+        let mut flags = F::empty();
+        let mut report = SetBonusReport::default();
+
+        for bonus in &self.active {
+            if let Some(&(flag, _)) = map.iter().find(|(_, name)| *name == bonus.name) {
+                flags.insert(flag);
+            } else if let Some(aura) = auras.iter().find(|a| a.id.0 == bonus.spell_id as u32) {
+                report.auras.push(aura.id);
+            } else {
+                report.unimplemented.push(bonus.clone());
+                continue;
+            }
+            report.applied.push(bonus.clone());
+        }
+
+        AppliedSetBonuses { flags, report }
+    }
+}
+
+/// What a spec made of its set bonuses
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetBonusReport {
+    pub applied: Vec<ActiveSetBonus>,
+    /// Auras granted for the whole fight
+    pub auras: Vec<AuraIdx>,
+    /// Bonuses the spec has no implementation for
+    pub unimplemented: Vec<ActiveSetBonus>,
+}
+
+/// Set bonuses matched against one spec's tier sets.
+#[derive(Clone, Debug)]
+pub struct AppliedSetBonuses<F> {
+    pub flags: F,
+    pub report: SetBonusReport,
+}
@@ -137,3 +137,198 @@ fn spell_target_variants() {
     assert!(matches!(cleave, SpellTarget::Cleave { max_targets: 3 }));
     assert!(matches!(ground, SpellTarget::Ground { radius: _ }));
 }
+
+fn trait_tree() -> wowlab_common::types::data::TraitTreeFlat {
+    use wowlab_common::types::data::{PointLimits, TraitNode, TraitNodeEntry, TraitTreeFlat};
+
+    let node = |id: i32, max_ranks: i32, spells: &[(i32, &str)]| TraitNode {
+        id,
+        pos_x: 0,
+        pos_y: 0,
+        max_ranks,
+        node_type: 0,
+        tree_index: 0,
+        order_index: 0,
+        sub_tree_id: 0,
+        required_points: 0,
+        entries: spells
+            .iter()
+            .map(|&(spell_id, name)| TraitNodeEntry {
+                id: spell_id,
+                definition_id: spell_id,
+                spell_id,
+                name: name.to_string(),
+                description: String::new(),
+                icon_file_name: String::new(),
+            })
+            .collect(),
+    };
+
+    TraitTreeFlat {
+        spec_id: 253,
+        spec_name: "Beast Mastery".to_string(),
+        class_name: "Hunter".to_string(),
+        tree_id: 1,
+        all_node_ids: vec![10, 20, 30, 40],
+        nodes: vec![
+            node(10, 1, &[(269737, "Alpha Predator")]),
+            node(20, 2, &[(378244, "Cobra Senses")]),
+            node(
+                30,
+                1,
+                &[(267116, "Animal Companion"), (474746, "Solitary Companion")],
+            ),
+            node(40, 1, &[(1, "Not Implemented")]),
+        ],
+        edges: Vec::new(),
+        sub_trees: Vec::new(),
+        point_limits: PointLimits::default(),
+        tree_hash: None,
+    }
+}
+
+fn encoded_loadout(spec_id: u16) -> String {
+    use wowlab_common::parsers::{encode_trait_loadout, DecodedTraitLoadout, DecodedTraitNode};
+
+    let node = |ranks: Option<u8>, choice: Option<u8>| DecodedTraitNode {
+        selected: true,
+        purchased: true,
+        partially_ranked: ranks.is_some(),
+        ranks_purchased: ranks,
+        choice_node: choice.is_some(),
+        choice_index: choice,
+    };
+    encode_trait_loadout(&DecodedTraitLoadout {
+        version: 1,
+        spec_id,
+        tree_hash: [0; 16],
+        nodes: vec![
+            node(None, None),
+            node(Some(1), None),
+            node(None, Some(1)),
+            node(None, None),
+        ],
+    })
+}
+
+#[test]
+fn loadout_decodes_ranks_and_choices() {
+    let loadout = TalentLoadout::decode(&encoded_loadout(253), &trait_tree()).unwrap();
+
+    assert_eq!(loadout.spec_id, 253);
+    let talents: Vec<_> = loadout
+        .talents
+        .iter()
+        .map(|t| (t.spell_id, t.rank, t.max_rank))
+        .collect();
+    assert_eq!(
+        talents,
+        vec![(269737, 1, 1), (378244, 1, 2), (474746, 1, 1), (1, 1, 1)]
+    );
+}
+
+#[test]
+fn loadout_rejects_other_spec() {
+    assert!(TalentLoadout::decode(&encoded_loadout(254), &trait_tree()).is_err());
+    assert!(TalentLoadout::decode("not a loadout!", &trait_tree()).is_err());
+}
+
+#[test]
+fn loadout_rejects_illegal_build() {
+    use wowlab_common::parsers::{encode_trait_loadout, DecodedTraitLoadout, DecodedTraitNode};
+
+    // Three ranks in a two-rank node
+    let loadout = encode_trait_loadout(&DecodedTraitLoadout {
+        version: 1,
+        spec_id: 253,
+        tree_hash: [0; 16],
+        nodes: vec![
+            DecodedTraitNode {
+                selected: false,
+                purchased: false,
+                partially_ranked: false,
+                ranks_purchased: None,
+                choice_node: false,
+                choice_index: None,
+            },
+            DecodedTraitNode {
+                selected: true,
+                purchased: true,
+                partially_ranked: true,
+                ranks_purchased: Some(3),
+                choice_node: false,
+                choice_index: None,
+            },
+        ],
+    });
+
+    let err = TalentLoadout::decode(&loadout, &trait_tree()).unwrap_err();
+    assert!(err.contains("Node 20 has 3 ranks"), "{err}");
+}
+
+#[test]
+fn loadout_applies_talent_map() {
+    use crate::specs::hunter::bm::{talents_from_loadout, TalentFlags};
+
+    let loadout = TalentLoadout::decode(&encoded_loadout(253), &trait_tree()).unwrap();
+    let applied = talents_from_loadout(&loadout);
+
+    assert_eq!(
+        applied.flags,
+        TalentFlags::ALPHA_PREDATOR | TalentFlags::COBRA_SENSES | TalentFlags::SOLITARY_COMPANION
+    );
+    let cobra_senses = applied
+        .report
+        .applied
+        .iter()
+        .find(|t| t.name == "cobra_senses")
+        .unwrap();
+    assert_eq!((cobra_senses.rank, cobra_senses.max_rank), (1, 2));
+    assert_eq!(applied.report.unimplemented.len(), 1);
+    assert_eq!(applied.report.unimplemented[0].name, "Not Implemented");
+}
+
+fn set_item(
+    item_set_id: i32,
+    bonuses: &[(i32, i32, i32)],
+) -> wowlab_common::types::data::ItemDataFlat {
+    use wowlab_common::types::data::{ItemDataFlat, ItemSetBonus, ItemSetInfo};
+
+    ItemDataFlat {
+        item_set_id,
+        set_info: Some(ItemSetInfo {
+            set_id: item_set_id,
+            set_name: format!("Set {}", item_set_id),
+            item_ids: Vec::new(),
+            bonuses: bonuses
+                .iter()
+                .map(|&(threshold, spell_id, spec_id)| ItemSetBonus {
+                    threshold,
+                    spell_id,
+                    spec_id,
+                })
+                .collect(),
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn set_bonuses_count_pieces_per_set() {
+    let tier = [(2, 1002, 0), (4, 1004, 0), (2, 2002, 254)];
+    let items = vec![
+        set_item(100, &tier),
+        set_item(100, &tier),
+        set_item(100, &tier),
+        set_item(200, &[(2, 3002, 0)]),
+        Default::default(),
+    ];
+
+    let sets = SetBonuses::from_items(&items, 253)
+        .with_names(|id| (id == 1002).then(|| "Tier 2pc".to_string()));
+
+    assert_eq!(sets.active.len(), 1);
+    let bonus = &sets.active[0];
+    assert_eq!((bonus.set_id, bonus.pieces, bonus.threshold), (100, 3, 2));
+    assert_eq!(bonus.name, "Tier 2pc");
+}
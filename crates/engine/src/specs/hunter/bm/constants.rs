@@ -14,6 +14,8 @@ pub const MULTI_SHOT: SpellIdx = SpellIdx(2643);
 pub const BEAST_CLEAVE_SPELL: SpellIdx = SpellIdx(115939);
 /// Kill Shot
 pub const KILL_SHOT: SpellIdx = SpellIdx(53351);
+/// Auto Shot
+pub const AUTO_SHOT: SpellIdx = SpellIdx(75);
 
 /// A Murder of Crows
 pub const MURDER_OF_CROWS: SpellIdx = SpellIdx(131894);
@@ -607,32 +609,23 @@ impl TalentFlags {
     pub const TWW_S1_2PC_SET: TalentFlags = TalentFlags::from_bits_truncate(1 << 63);
 }
 
-/// Extended tier set flags (separate since we ran out of bits)
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
-pub struct TierSetFlags(u16);
-
-impl TierSetFlags {
-    pub const NONE: Self = Self(0);
-    pub const TWW_S1_2PC: Self = Self(1 << 0);
-    pub const TWW_S1_4PC: Self = Self(1 << 1);
-    pub const TWW_S2_2PC: Self = Self(1 << 2);
-    pub const TWW_S2_4PC: Self = Self(1 << 3);
-    pub const TWW_S3_PL_2PC: Self = Self(1 << 4);
-    pub const TWW_S3_PL_4PC: Self = Self(1 << 5);
-    pub const TWW_S3_DR_2PC: Self = Self(1 << 6);
-    pub const TWW_S3_DR_4PC: Self = Self(1 << 7);
-    pub const TWW_S3_SENTINEL_2PC: Self = Self(1 << 8);
-    pub const TWW_S3_SENTINEL_4PC: Self = Self(1 << 9);
-
-    pub fn contains(&self, other: Self) -> bool {
-        (self.0 & other.0) == other.0
-    }
-
-    pub fn insert(&mut self, other: Self) {
-        self.0 |= other.0;
+bitflags::bitflags! {
+    /// Extended tier set flags (separate since we ran out of bits)
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct TierSetFlags: u16 {
+        const TWW_S1_2PC = 1 << 0;
+        const TWW_S1_4PC = 1 << 1;
+        const TWW_S2_2PC = 1 << 2;
+        const TWW_S2_4PC = 1 << 3;
+        const TWW_S3_PL_2PC = 1 << 4;
+        const TWW_S3_PL_4PC = 1 << 5;
+        const TWW_S3_DR_2PC = 1 << 6;
+        const TWW_S3_DR_4PC = 1 << 7;
+        const TWW_S3_SENTINEL_2PC = 1 << 8;
+        const TWW_S3_SENTINEL_4PC = 1 << 9;
     }
+}
 
-    pub fn is_empty(&self) -> bool {
-        self.0 == 0
-    }
+impl TierSetFlags {
+    pub const NONE: Self = Self::empty();
 }
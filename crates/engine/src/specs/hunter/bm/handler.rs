@@ -12,19 +12,23 @@ use super::spells::spell_definitions;
 use super::talents::{active_talents, collect_damage_mods};
 use crate::actor::Player;
 use crate::aura::AuraInstance;
+use crate::class::hunter::HUNTER_DAMAGE_NAMES;
 use crate::class::HunterClass;
-use crate::combat::{ChargedCooldown, Cooldown};
+use crate::combat::{Attack, ChargedCooldown, Cooldown, DamageMultipliers, MultiplierSource};
 use crate::core::SimEvent;
 use crate::handler::SpecHandler;
-use crate::rotation::{Action, CompiledRotation};
-use crate::sim::SimState;
+use crate::rotation::{
+    Action, PrecombatList, Rotation, RotationBackend, RotationEvaluator, SpecResolver,
+};
+use crate::sim::{SimState, TraceEventType};
 use crate::spec::{
-    calculate_damage, execute_effects, AuraDef, DamageContext, EffectContext, GcdType, SpellDef,
-    SpellFlags,
+    calculate_damage, execute_effects, AppliedTalents, AuraDef, DamageContext, EffectContext,
+    GcdType, SpellDef, SpellFlags,
 };
 use tracing::debug;
 use wowlab_common::types::{
-    AuraIdx, ClassId, DamageSchool, PetKind, SimTime, SpecId, SpellIdx, TargetIdx, UnitIdx,
+    AuraIdx, ClassId, DamageSchool, HitResult, PetKind, SimTime, SpecId, SpellIdx, TargetIdx,
+    UnitIdx,
 };
 
 static SPELL_DEFS: std::sync::OnceLock<Vec<SpellDef>> = std::sync::OnceLock::new();
@@ -60,7 +64,8 @@ fn get_aura_defs() -> &'static [AuraDef] {
 pub struct BmHunter {
     talents: TalentFlags,
     tier_sets: TierSetFlags,
-    rotation: CompiledRotation,
+    rotation: RotationEvaluator,
+    precombat: PrecombatList,
 }
 
 impl BmHunter {
@@ -69,17 +74,71 @@ impl BmHunter {
         rotation_json: &str,
         talents: TalentFlags,
         tier_sets: TierSetFlags,
+    ) -> Result<Self, String> {
+        Self::with_backend(
+            rotation_json,
+            talents,
+            tier_sets,
+            RotationBackend::default(),
+        )
+    }
+
+    /// Create a new BM Hunter handler using a specific rotation backend.
+    pub fn with_backend(
+        rotation_json: &str,
+        talents: TalentFlags,
+        tier_sets: TierSetFlags,
+        backend: RotationBackend,
+    ) -> Result<Self, String> {
+        Self::compile(
+            rotation_json,
+            talents,
+            tier_sets,
+            backend,
+            spec_resolver(talents),
+        )
+    }
+
+    /// Create a BM Hunter handler from a loadout's talents.
+    ///
+    /// Talent ranks are registered with the resolver so `talent.X.rank`
+    /// rotation expressions match the loadout.
+    pub fn with_loadout(
+        rotation_json: &str,
+        talents: &AppliedTalents<TalentFlags>,
+        tier_sets: TierSetFlags,
+        backend: RotationBackend,
+    ) -> Result<Self, String> {
+        Self::compile(
+            rotation_json,
+            talents.flags,
+            tier_sets,
+            backend,
+            talents.register(spec_resolver(talents.flags)),
+        )
+    }
+
+    fn compile(
+        rotation_json: &str,
+        talents: TalentFlags,
+        tier_sets: TierSetFlags,
+        backend: RotationBackend,
+        resolver: SpecResolver,
     ) -> Result<Self, String> {
         ensure_definitions();
 
-        let resolver = spec_resolver(talents);
-        let rotation = CompiledRotation::compile_json(rotation_json, &resolver)
+        let rotation = Rotation::from_json_resolved(rotation_json, &resolver)
+            .map_err(|e| format!("Compile error: {}", e))?;
+        let precombat = PrecombatList::compile(&rotation, &resolver, backend)
+            .map_err(|e| format!("Compile error: {}", e))?;
+        let rotation = RotationEvaluator::compile(&rotation, &resolver, backend)
             .map_err(|e| format!("Compile error: {}", e))?;
 
         Ok(Self {
             talents,
             tier_sets,
             rotation,
+            precombat,
         })
     }
 
@@ -117,6 +176,8 @@ impl BmHunter {
         };
         let now = state.now();
         let haste = state.player.stats.haste();
+        state.stats.record_cast(spell_id);
+        state.trace_cast(spell_id, target);
 
         // Pay costs
         for cost in &spell.costs {
@@ -129,6 +190,11 @@ impl BmHunter {
         for gain in &spell.gains {
             if let Some(ref mut primary) = state.player.resources.primary {
                 primary.gain(gain.amount);
+                let resource = primary.resource_type;
+                state.trace(TraceEventType::ResourceGain {
+                    resource,
+                    amount: gain.amount,
+                });
             }
         }
 
@@ -136,10 +202,12 @@ impl BmHunter {
         if spell.charges > 0 {
             if let Some(cd) = state.player.charged_cooldown_mut(spell_id) {
                 cd.spend(now, haste);
+                state.trace(TraceEventType::CooldownStart { spell: spell_id });
             }
         } else if spell.cooldown > SimTime::ZERO {
             if let Some(cd) = state.player.cooldown_mut(spell_id) {
                 cd.start(now, haste);
+                state.trace(TraceEventType::CooldownStart { spell: spell_id });
             }
         }
 
@@ -185,6 +253,12 @@ impl BmHunter {
             return;
         };
 
+        state.trace(TraceEventType::AuraApply {
+            aura: aura_id,
+            target,
+            is_debuff: aura.flags.is_debuff,
+        });
+
         let mut instance = AuraInstance::new(aura_id, target, aura.duration, now, aura.flags);
         if aura.max_stacks > 1 {
             instance = instance.with_stacks(aura.max_stacks);
@@ -212,6 +286,9 @@ impl BmHunter {
     }
 
     /// Calculate damage using the modifier system.
+    ///
+    /// Returns the damage and the attack table outcome.
+    #[allow(clippy::too_many_arguments)]
     fn do_damage(
         &self,
         state: &mut SimState,
@@ -220,7 +297,8 @@ impl BmHunter {
         ap_coef: f32,
         sp_coef: f32,
         school: DamageSchool,
-    ) -> f32 {
+        attack: Attack,
+    ) -> (f32, HitResult) {
         let talents = self.talent_names();
         let talents_slice: Vec<&'static str> = talents;
         let damage_mods = collect_damage_mods(self.talents);
@@ -234,14 +312,12 @@ impl BmHunter {
             target,
             talents: &talents_slice,
             modifiers: &damage_mods,
-            is_crit: false,
+            attack,
+            result: HitResult::Hit,
         };
 
-        calculate_damage(&mut ctx, 0.0, ap_coef, sp_coef, school)
-    }
-
-    fn mastery_pet_damage_bonus(&self, state: &SimState) -> f32 {
-        state.player.stats.mastery()
+        let damage = calculate_damage(&mut ctx, 0.0, ap_coef, sp_coef, school);
+        (damage, ctx.result)
     }
 }
 
@@ -265,6 +341,10 @@ impl SpecHandler for BmHunter {
         get_aura_defs()
     }
 
+    fn damage_names(&self) -> &'static [(SpellIdx, &'static str)] {
+        HUNTER_DAMAGE_NAMES
+    }
+
     fn talent_names(&self) -> Vec<String> {
         super::talents::talent_definitions()
             .into_iter()
@@ -273,18 +353,20 @@ impl SpecHandler for BmHunter {
     }
 
     fn init(&self, state: &mut SimState) {
+        // Auto attacks start at the pull
+        let pull = state.config.combat_start();
         let pet_id = state
             .pets
             .summon(state.player.id, PetKind::Permanent, "Pet");
         state.events.schedule(
-            SimTime::ZERO,
+            pull,
             SimEvent::AutoAttack {
                 unit: state.player.id,
             },
         );
         state
             .events
-            .schedule(SimTime::ZERO, SimEvent::PetAttack { pet: pet_id });
+            .schedule(pull, SimEvent::PetAttack { pet: pet_id });
 
         if self.has_talent(TalentFlags::ANIMAL_COMPANION) {
             let ac_pet = state
@@ -292,7 +374,7 @@ impl SpecHandler for BmHunter {
                 .summon(state.player.id, PetKind::Permanent, "Animal Companion");
             state
                 .events
-                .schedule(SimTime::ZERO, SimEvent::PetAttack { pet: ac_pet });
+                .schedule(pull, SimEvent::PetAttack { pet: ac_pet });
         }
     }
 
@@ -332,14 +414,33 @@ impl SpecHandler for BmHunter {
             return;
         }
 
-        let result = self.rotation.evaluate(state);
+        let result = if state.in_combat() {
+            self.rotation.evaluate(state)
+        } else if let Some(result) = self.precombat.next(state) {
+            result
+        } else {
+            // Precombat list done: idle until the pull
+            state
+                .events
+                .schedule(state.config.combat_start(), SimEvent::GcdEnd);
+            return;
+        };
 
         if result.is_cast() {
             if let Some(spell) = spell_id_to_idx(result.spell_id) {
-                self.do_cast(state, spell, TargetIdx(0));
+                let target = state.enemies.primary;
+                self.do_cast(state, spell, target);
             } else {
                 state.schedule_in(SimTime::from_millis(100), SimEvent::GcdEnd);
             }
+        } else if let Some(slot) = result.trinket_slot() {
+            // Trinkets are off the GCD
+            let delay = if self.use_trinket(state, slot) {
+                SimTime::ZERO
+            } else {
+                SimTime::from_millis(100)
+            };
+            state.schedule_in(delay, SimEvent::GcdEnd);
         } else if result.is_wait() {
             let wait_ms = (result.wait_time * 1000.0) as u32;
             state.schedule_in(SimTime::from_millis(wait_ms.max(100)), SimEvent::GcdEnd);
@@ -358,31 +459,44 @@ impl SpecHandler for BmHunter {
         };
         let Some(ref dmg) = spell.damage else { return };
 
-        let damage = self.do_damage(
+        let from_pet = spell.flags.contains(SpellFlags::PET_ABILITY);
+        let attack = if from_pet {
+            Attack::melee()
+        } else {
+            Attack::ranged()
+        };
+        let (damage, result) = self.do_damage(
             state,
             Some(spell_id),
             target,
             dmg.ap_coefficient,
             dmg.sp_coefficient,
             dmg.school,
+            attack,
         );
-        state.record_damage(damage);
+        if from_pet {
+            state.record_pet_damage(spell_id, target, damage, result, false);
+        } else {
+            state.record_spell_damage(spell_id, target, damage, result, false);
+        }
         debug!(spell = spell_id.0, damage, "Spell damage");
     }
 
     fn on_auto_attack(&self, state: &mut SimState, unit: UnitIdx) {
         let now = state.now();
         let haste = state.player.stats.haste();
+        let target = state.enemies.primary;
 
-        let damage = self.do_damage(
+        let (damage, result) = self.do_damage(
             state,
             None,
-            TargetIdx(0),
+            target,
             PetDamage::AUTO_ATTACK_COEF,
             0.0,
             DamageSchool::Physical,
+            Attack::ranged().auto(),
         );
-        state.record_damage(damage);
+        state.record_spell_damage(AUTO_SHOT, target, damage, result, false);
 
         // Wild Call proc
         let crit = state.player.stats.crit_chance();
@@ -432,15 +546,16 @@ impl SpecHandler for BmHunter {
 
         if let Some(aura) = get_aura(aura_id) {
             if let Some(ref periodic) = aura.periodic {
-                let damage = self.do_damage(
+                let (damage, result) = self.do_damage(
                     state,
                     None,
                     target,
                     periodic.ap_coefficient,
                     periodic.sp_coefficient,
                     DamageSchool::Physical,
+                    Attack::periodic(),
                 );
-                state.record_damage(damage);
+                state.record_spell_damage(SpellIdx(aura_id.0), target, damage, result, true);
 
                 // Master Handler: Barbed Shot ticks reduce KC CD
                 if aura_id == BARBED_SHOT_DOT && self.has_talent(TalentFlags::MASTER_HANDLER) {
@@ -470,6 +585,8 @@ impl SpecHandler for BmHunter {
             spell_id_to_idx(result.spell_id)
                 .map(Action::Cast)
                 .unwrap_or(Action::WaitGcd)
+        } else if let Some(slot) = result.trinket_slot() {
+            Action::UseTrinket(slot)
         } else if result.is_wait() {
             Action::Wait(result.wait_time as f64)
         } else {
@@ -505,32 +622,39 @@ impl SpecHandler for BmHunter {
         sp_coef: f32,
         school: DamageSchool,
     ) -> f32 {
-        self.do_damage(state, None, TargetIdx(0), ap_coef, sp_coef, school)
+        self.do_damage(
+            state,
+            None,
+            TargetIdx(0),
+            ap_coef,
+            sp_coef,
+            school,
+            Attack::ranged(),
+        )
+        .0
     }
 }
 
 impl HunterClass for BmHunter {
-    fn pet_damage_modifier(&self, state: &SimState) -> f32 {
-        let now = state.now();
-        let mut modifier = 1.0;
-
-        // Mastery: Master of Beasts
-        modifier *= 1.0 + self.mastery_pet_damage_bonus(state);
-
+    fn pet_damage_multipliers(&self, state: &SimState, multipliers: &mut DamageMultipliers) {
         // Bestial Wrath
-        if state.player.buffs.has(BESTIAL_WRATH_BUFF, now) {
-            modifier *= 1.25;
+        if state.player.buffs.has(BESTIAL_WRATH_BUFF, state.now()) {
+            multipliers.apply_pet(MultiplierSource::Aura(BESTIAL_WRATH_BUFF), 1.25);
         }
 
         // Training Expert
         if self.has_talent(TalentFlags::TRAINING_EXPERT) {
-            modifier *= 1.0 + TRAINING_EXPERT_BONUS;
+            multipliers.apply_pet(
+                MultiplierSource::named("Training Expert"),
+                1.0 + TRAINING_EXPERT_BONUS,
+            );
         }
 
         // Wild Hunt
-        modifier *= 1.0 + WILD_HUNT_DAMAGE_BONUS;
-
-        modifier
+        multipliers.apply_pet(
+            MultiplierSource::named("Wild Hunt"),
+            1.0 + WILD_HUNT_DAMAGE_BONUS,
+        );
     }
 
     fn pet_attack_speed_modifier(&self, state: &SimState) -> f32 {
@@ -14,7 +14,10 @@ pub use pet::*;
 pub use procs::*;
 pub use rotation::*;
 pub use spells::*;
-pub use talents::{active_talents, collect_damage_mods, talent_definitions};
+pub use talents::{
+    active_talents, collect_damage_mods, talent_definitions, talents_from_loadout,
+    tier_sets_from_gear, TALENT_MAP, TIER_SET_MAP,
+};
 
 #[cfg(test)]
 mod tests;
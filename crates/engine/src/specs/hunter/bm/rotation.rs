@@ -3,6 +3,7 @@
 //! Provides name resolution for BM Hunter rotations and context building.
 
 use super::constants::*;
+use super::talents::TALENT_MAP;
 use crate::rotation::SpecResolver;
 use wowlab_common::types::SpellIdx;
 
@@ -10,7 +11,7 @@ use wowlab_common::types::SpellIdx;
 ///
 /// This maps human-readable spell/aura names to game IDs.
 pub fn spec_resolver(talents: TalentFlags) -> SpecResolver {
-    SpecResolver::new("bm_hunter")
+    let resolver = SpecResolver::new("bm_hunter")
         .resource("focus")
         // Core spells
         .spell("kill_command", KILL_COMMAND.0)
@@ -46,44 +47,14 @@ pub fn spec_resolver(talents: TalentFlags) -> SpecResolver {
         .aura("bloodshed", BLOODSHED_DEBUFF.0)
         .aura("wild_instincts", WILD_INSTINCTS.0)
         // Charged cooldowns
-        .charged_cooldown("barbed_shot")
-        // Talents
-        .talent(
-            "animal_companion",
-            talents.contains(TalentFlags::ANIMAL_COMPANION),
-        )
-        .talent(
-            "solitary_companion",
-            talents.contains(TalentFlags::SOLITARY_COMPANION),
-        )
-        .talent(
-            "thrill_of_the_hunt",
-            talents.contains(TalentFlags::THRILL_OF_THE_HUNT),
-        )
-        .talent(
-            "go_for_the_throat",
-            talents.contains(TalentFlags::GO_FOR_THE_THROAT),
-        )
-        .talent(
-            "alpha_predator",
-            talents.contains(TalentFlags::ALPHA_PREDATOR),
-        )
-        .talent(
-            "killer_instinct",
-            talents.contains(TalentFlags::KILLER_INSTINCT),
-        )
-        .talent("killer_cobra", talents.contains(TalentFlags::KILLER_COBRA))
-        .talent("bloodshed", talents.contains(TalentFlags::BLOODSHED))
-        .talent(
-            "call_of_the_wild",
-            talents.contains(TalentFlags::CALL_OF_THE_WILD),
-        )
-        .talent("dire_beast", talents.contains(TalentFlags::DIRE_BEAST))
-        .talent(
-            "murder_of_crows",
-            talents.contains(TalentFlags::MURDER_OF_CROWS),
-        )
-        .talent("black_arrow", talents.contains(TalentFlags::BLACK_ARROW))
+        .charged_cooldown("barbed_shot");
+
+    // Talents
+    TALENT_MAP
+        .iter()
+        .fold(resolver, |resolver, &(flag, name, _)| {
+            resolver.talent(name, talents.contains(flag))
+        })
 }
 
 /// Default spec resolver (no talents).
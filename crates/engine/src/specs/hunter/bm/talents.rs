@@ -2,29 +2,80 @@
 //!
 //! Each talent defines its damage modifiers, cooldown changes, and effects.
 
+use super::auras::aura_definitions;
 use super::constants::*;
-use crate::spec::{DamageMod, ModCondition, TalentDef};
-
-/// Single source of truth for talent flag to name mapping.
-const TALENT_MAP: &[(TalentFlags, &str)] = &[
-    (TalentFlags::ALPHA_PREDATOR, "alpha_predator"),
-    (TalentFlags::KILLER_INSTINCT, "killer_instinct"),
-    (TalentFlags::KILLER_COBRA, "killer_cobra"),
-    (TalentFlags::TRAINING_EXPERT, "training_expert"),
-    (TalentFlags::ANIMAL_COMPANION, "animal_companion"),
-    (TalentFlags::SOLITARY_COMPANION, "solitary_companion"),
-    (TalentFlags::BRUTAL_COMPANION, "brutal_companion"),
-    (TalentFlags::PIERCING_FANGS, "piercing_fangs"),
-    (TalentFlags::SERPENTINE_RHYTHM, "serpentine_rhythm"),
-    (TalentFlags::GO_FOR_THE_THROAT, "go_for_the_throat"),
-    (TalentFlags::PACK_MENTALITY, "pack_mentality"),
-    (TalentFlags::WILD_INSTINCTS, "wild_instincts"),
-    (TalentFlags::KILL_CLEAVE, "kill_cleave"),
-    (TalentFlags::BLOODY_FRENZY, "bloody_frenzy"),
-    (TalentFlags::THUNDERING_HOOVES, "thundering_hooves"),
-    (TalentFlags::MASTER_HANDLER, "master_handler"),
-    (TalentFlags::DIRE_FRENZY, "dire_frenzy"),
-    (TalentFlags::WITHERING_FIRE, "withering_fire"),
+use crate::spec::{
+    AppliedSetBonuses, AppliedTalents, DamageMod, ModCondition, SetBonuses, TalentDef,
+    TalentLoadout,
+};
+
+/// Single source of truth for talent flag, name and trait definition spell ID.
+pub const TALENT_MAP: &[(TalentFlags, &str, u32)] = &[
+    (TalentFlags::ANIMAL_COMPANION, "animal_companion", 267116),
+    (
+        TalentFlags::SOLITARY_COMPANION,
+        "solitary_companion",
+        474746,
+    ),
+    (TalentFlags::PACK_TACTICS, "pack_tactics", 321014),
+    (
+        TalentFlags::ASPECT_OF_THE_BEAST,
+        "aspect_of_the_beast",
+        191384,
+    ),
+    (TalentFlags::WAR_ORDERS, "war_orders", 1217730),
+    (
+        TalentFlags::THRILL_OF_THE_HUNT,
+        "thrill_of_the_hunt",
+        257944,
+    ),
+    (TalentFlags::GO_FOR_THE_THROAT, "go_for_the_throat", 459550),
+    (TalentFlags::LACERATION, "laceration", 459552),
+    (TalentFlags::BARBED_SCALES, "barbed_scales", 469880),
+    (TalentFlags::SNAKESKIN_QUIVER, "snakeskin_quiver", 468695),
+    (TalentFlags::COBRA_SENSES, "cobra_senses", 378244),
+    (TalentFlags::ALPHA_PREDATOR, "alpha_predator", 269737),
+    (TalentFlags::HUNTERS_PREY, "hunters_prey", 378210),
+    (TalentFlags::POISONED_BARBS, "poisoned_barbs", 1217535),
+    (TalentFlags::STOMP, "stomp", 199530),
+    (TalentFlags::SERPENTINE_RHYTHM, "serpentine_rhythm", 468701),
+    (TalentFlags::KILL_CLEAVE, "kill_cleave", 378207),
+    (TalentFlags::TRAINING_EXPERT, "training_expert", 378209),
+    (TalentFlags::DIRE_COMMAND, "dire_command", 378743),
+    (TalentFlags::HUNTMASTERS_CALL, "huntmasters_call", 459730),
+    (TalentFlags::DIRE_CLEAVE, "dire_cleave", 1217524),
+    (TalentFlags::KILLER_INSTINCT, "killer_instinct", 273887),
+    (TalentFlags::MASTER_HANDLER, "master_handler", 424558),
+    (TalentFlags::THUNDERING_HOOVES, "thundering_hooves", 459693),
+    (TalentFlags::DIRE_FRENZY, "dire_frenzy", 385810),
+    (TalentFlags::KILLER_COBRA, "killer_cobra", 199532),
+    (TalentFlags::SCENT_OF_BLOOD, "scent_of_blood", 193532),
+    (TalentFlags::BRUTAL_COMPANION, "brutal_companion", 386870),
+    (TalentFlags::WILD_INSTINCTS, "wild_instincts", 378442),
+    (TalentFlags::BLOODY_FRENZY, "bloody_frenzy", 407412),
+    (TalentFlags::PIERCING_FANGS, "piercing_fangs", 392053),
+    (TalentFlags::WILDSPEAKER, "wildspeaker", 1232739),
+    (TalentFlags::BLOODSHED, "bloodshed", 321530),
+    (TalentFlags::CALL_OF_THE_WILD, "call_of_the_wild", 359844),
+    (TalentFlags::DIRE_BEAST, "dire_beast", 120679),
+    (TalentFlags::MURDER_OF_CROWS, "murder_of_crows", 131894),
+    (
+        TalentFlags::HOWL_OF_THE_PACK_LEADER,
+        "howl_of_the_pack_leader",
+        471876,
+    ),
+    (TalentFlags::PACK_MENTALITY, "pack_mentality", 472358),
+    (TalentFlags::URSINE_FURY, "ursine_fury", 472476),
+    (TalentFlags::ENVENOMED_FANGS, "envenomed_fangs", 472524),
+    (
+        TalentFlags::LEAD_FROM_THE_FRONT,
+        "lead_from_the_front",
+        472741,
+    ),
+    (TalentFlags::BLACK_ARROW, "black_arrow", 466932),
+    (TalentFlags::PHANTOM_PAIN, "phantom_pain", 467941),
+    (TalentFlags::WITHERING_FIRE, "withering_fire", 466990),
+    (TalentFlags::BLEAK_POWDER, "bleak_powder", 467911),
 ];
 
 /// Get all BM Hunter talent definitions.
@@ -60,14 +111,68 @@ pub fn talent_definitions() -> Vec<TalentDef> {
 pub fn active_talents(flags: TalentFlags) -> Vec<&'static str> {
     let mut talents: Vec<&'static str> = TALENT_MAP
         .iter()
-        .filter(|(flag, _)| flags.contains(*flag))
-        .map(|(_, name)| *name)
+        .filter(|(flag, ..)| flags.contains(*flag))
+        .map(|(_, name, _)| *name)
         .collect();
     // Wild Hunt is always active (passive with no flag)
     talents.push("wild_hunt");
     talents
 }
 
+/// Match a loadout's talents against the BM talent map.
+pub fn talents_from_loadout(loadout: &TalentLoadout) -> AppliedTalents<TalentFlags> {
+    loadout.apply(TALENT_MAP)
+}
+
+/// Tier set flag and set bonus spell name.
+pub const TIER_SET_MAP: &[(TierSetFlags, &str)] = &[
+    (
+        TierSetFlags::TWW_S1_2PC,
+        "Hunter Beast Mastery 11.0 Class Set 2pc",
+    ),
+    (
+        TierSetFlags::TWW_S1_4PC,
+        "Hunter Beast Mastery 11.0 Class Set 4pc",
+    ),
+    (
+        TierSetFlags::TWW_S2_2PC,
+        "Hunter Beast Mastery 11.1 Class Set 2pc",
+    ),
+    (
+        TierSetFlags::TWW_S2_4PC,
+        "Hunter Beast Mastery 11.1 Class Set 4pc",
+    ),
+    (
+        TierSetFlags::TWW_S3_PL_2PC,
+        "Hunter Pack Leader 11.2 Class Set 2pc",
+    ),
+    (
+        TierSetFlags::TWW_S3_PL_4PC,
+        "Hunter Pack Leader 11.2 Class Set 4pc",
+    ),
+    (
+        TierSetFlags::TWW_S3_DR_2PC,
+        "Hunter Dark Ranger 11.2 Class Set 2pc",
+    ),
+    (
+        TierSetFlags::TWW_S3_DR_4PC,
+        "Hunter Dark Ranger 11.2 Class Set 4pc",
+    ),
+    (
+        TierSetFlags::TWW_S3_SENTINEL_2PC,
+        "Hunter Sentinel 11.2 Class Set 2pc",
+    ),
+    (
+        TierSetFlags::TWW_S3_SENTINEL_4PC,
+        "Hunter Sentinel 11.2 Class Set 4pc",
+    ),
+];
+
+/// Match equipped set bonuses against the BM tier sets.
+pub fn tier_sets_from_gear(sets: &SetBonuses) -> AppliedSetBonuses<TierSetFlags> {
+    sets.apply(TIER_SET_MAP, &aura_definitions())
+}
+
 /// Collect all damage modifiers from active talents.
 pub fn collect_damage_mods(flags: TalentFlags) -> Vec<DamageMod> {
     talent_definitions()
@@ -83,8 +188,8 @@ fn is_talent_active(flags: TalentFlags, name: &str) -> bool {
     }
     TALENT_MAP
         .iter()
-        .find(|(_, n)| *n == name)
-        .map(|(flag, _)| flags.contains(*flag))
+        .find(|(_, n, _)| *n == name)
+        .map(|(flag, ..)| flags.contains(*flag))
         .unwrap_or(false)
 }
 
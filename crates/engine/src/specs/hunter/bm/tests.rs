@@ -1,8 +1,8 @@
 use super::*;
 use crate::actor::Player;
 use crate::handler::SpecHandler;
-use crate::rotation::{CompiledRotation, Rotation};
-use crate::sim::{SimConfig, SimState};
+use crate::rotation::{Rotation, RotationBackend, RotationEvaluator};
+use crate::sim::{SimConfig, SimState, Simulation};
 use wowlab_common::types::*;
 
 fn create_handler() -> BmHunter {
@@ -146,8 +146,8 @@ fn rotation_compile_basic() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 
     // Verify it compiled successfully (the schema should have the right size)
     assert!(compiled.schema().size > 0);
@@ -164,8 +164,8 @@ fn rotation_compile_with_condition() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 }
 
 #[test]
@@ -179,8 +179,8 @@ fn rotation_compile_with_resource_check() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 }
 
 #[test]
@@ -194,8 +194,8 @@ fn rotation_compile_with_buff_check() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 }
 
 #[test]
@@ -215,8 +215,8 @@ fn rotation_compile_with_and_condition() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 }
 
 #[test]
@@ -236,8 +236,8 @@ fn rotation_compile_with_or_condition() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 }
 
 #[test]
@@ -257,8 +257,8 @@ fn rotation_compile_with_lists() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 }
 
 #[test]
@@ -279,8 +279,8 @@ fn rotation_compile_with_variables() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 }
 
 #[test]
@@ -294,8 +294,8 @@ fn rotation_compile_with_dot_check() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 }
 
 #[test]
@@ -309,8 +309,8 @@ fn rotation_compile_with_charges() {
     }"#;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled =
-        CompiledRotation::compile_json(json, &resolver).expect("Failed to compile rotation");
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
+        .expect("Failed to compile rotation");
 }
 
 #[test]
@@ -319,7 +319,7 @@ fn rotation_compile_full_example() {
     let json = EXAMPLE_ROTATION_JSON;
 
     let resolver = spec_resolver(TalentFlags::empty());
-    let _compiled = CompiledRotation::compile_json(json, &resolver)
+    let _compiled = RotationEvaluator::compile_json(json, &resolver, RotationBackend::default())
         .expect("Failed to compile example rotation");
 }
 
@@ -360,3 +360,264 @@ fn spec_resolver_with_talents() {
     assert!(resolver.has_talent("killer_cobra"));
     assert!(!resolver.has_talent("bloodshed")); // Not enabled
 }
+
+#[test]
+fn simc_apl_import() {
+    let apl = "\
+actions.precombat=summon_pet
+actions.precombat+=/snapshot_stats
+actions=auto_shot
+actions+=/call_action_list,name=cds
+actions+=/call_action_list,name=st,if=active_enemies<2|!talent.beast_cleave&active_enemies<3
+actions.cds=invoke_external_buff,name=power_infusion,if=buff.call_of_the_wild.up|!talent.call_of_the_wild&buff.bestial_wrath.up
+actions.st=bestial_wrath
+actions.st+=/barbed_shot,target_if=min:dot.barbed_shot.remains,if=full_recharge_time<gcd|charges_fractional>=cooldown.kill_command.charges_fractional
+actions.st+=/kill_command,if=charges_fractional>=cooldown.barbed_shot.charges_fractional
+actions.st+=/barbed_shot,target_if=min:dot.barbed_shot.remains,if=buff.frenzy.up&buff.frenzy.remains<=gcd.max
+actions.st+=/call_of_the_wild
+actions.st+=/bloodshed
+actions.st+=/kill_shot
+actions.st+=/cobra_shot,if=focus.deficit<30|buff.bestial_wrath.up
+";
+
+    let resolver = spec_resolver(TalentFlags::empty());
+    let import = crate::rotation::import_simc(apl, &resolver);
+
+    let st = &import.rotation.lists["st"];
+    assert_eq!(st.len(), 8, "{}", import.report());
+    assert_eq!(import.rotation.actions.len(), 1);
+    assert!(import.rotation.lists["cds"].is_empty());
+    assert!(import.rotation.precombat.is_empty());
+
+    // Unknown spells, the unregistered `talent.beast_cleave` and `target_if`.
+    let lines: Vec<_> = import.issues.iter().map(|i| i.line).collect();
+    assert_eq!(lines, [1, 2, 3, 5, 6, 8, 10], "{}", import.report());
+    assert_eq!(import.dropped_actions(), 5);
+
+    RotationEvaluator::compile(&import.rotation, &resolver, RotationBackend::default())
+        .expect("imported rotation should compile");
+}
+
+#[test]
+fn precombat_cast_carries_into_combat() {
+    let json = r#"{
+        "precombat": [
+            { "cast": "bestial_wrath" },
+            { "cast": "bestial_wrath" }
+        ],
+        "actions": []
+    }"#;
+    let handler = BmHunter::with_talents(json, TalentFlags::empty()).unwrap();
+    let config = SimConfig::default().with_duration(1.0).with_prepull(2.0);
+
+    let mut sim = Simulation::new(
+        std::sync::Arc::new(handler),
+        config,
+        Player::new(SpecId::BeastMastery),
+    );
+    sim.run();
+
+    // Cast once at the start of the prepull window; the second entry is
+    // tried once and skipped while on cooldown.
+    let cooldown = sim.state.player.cooldown(BESTIAL_WRATH).unwrap();
+    assert_eq!(cooldown.ready_at, cooldown.duration);
+    assert!(sim
+        .state
+        .player
+        .buffs
+        .has(BESTIAL_WRATH_BUFF, sim.state.now()));
+    assert_eq!(sim.state.combat_time(), SimTime::from_secs(1));
+}
+
+fn bm_loadout(talents: &[(u32, u8, u8)]) -> crate::spec::TalentLoadout {
+    crate::spec::TalentLoadout {
+        spec_id: 253,
+        talents: talents
+            .iter()
+            .map(|&(spell_id, rank, max_rank)| crate::spec::LoadoutTalent {
+                spell_id,
+                name: String::new(),
+                rank,
+                max_rank,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn loadout_sets_resolver_talents() {
+    let json = r#"{
+        "actions": [
+            { "cast": "kill_command", "if": "talent.alpha_predator" },
+            { "cast": "cobra_shot", "if": "talent.cobra_senses" }
+        ]
+    }"#;
+    let applied = talents_from_loadout(&bm_loadout(&[(269737, 1, 1), (378244, 2, 2)]));
+    let handler = BmHunter::with_loadout(
+        json,
+        &applied,
+        TierSetFlags::NONE,
+        crate::rotation::RotationBackend::default(),
+    )
+    .unwrap();
+
+    assert!(handler.has_talent(TalentFlags::ALPHA_PREDATOR | TalentFlags::COBRA_SENSES));
+    let resolver = applied.register(spec_resolver(applied.flags));
+    assert_eq!(
+        resolver.resolve_talent_info("cobra_senses").unwrap().rank,
+        2
+    );
+    assert!(!resolver.resolve_talent("killer_cobra").unwrap());
+}
+
+#[test]
+fn create_handler_with_loadout_reports_unimplemented() {
+    use crate::handler::create_handler_with_loadout;
+    use crate::rotation::RotationBackend;
+
+    let loadout = bm_loadout(&[(269737, 1, 1), (999999, 1, 1)]);
+    let (_, report) = create_handler_with_loadout(
+        SpecId::BeastMastery,
+        r#"{"actions":[]}"#,
+        RotationBackend::default(),
+        &loadout,
+    )
+    .unwrap();
+    assert_eq!(report.applied.len(), 1);
+    assert_eq!(report.unimplemented[0].spell_id, 999999);
+
+    let result = create_handler_with_loadout(
+        SpecId::Marksmanship,
+        r#"{"actions":[]}"#,
+        RotationBackend::default(),
+        &loadout,
+    );
+    assert!(result.is_err());
+}
+
+fn set_bonus(spell_id: i32, name: &str) -> crate::spec::ActiveSetBonus {
+    crate::spec::ActiveSetBonus {
+        set_id: 1,
+        set_name: "Tier".to_string(),
+        pieces: 4,
+        threshold: 2,
+        spell_id,
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn tier_sets_from_gear_maps_flags_and_auras() {
+    let sets = crate::spec::SetBonuses {
+        active: vec![
+            set_bonus(1, "Hunter Beast Mastery 11.1 Class Set 2pc"),
+            set_bonus(POTENT_MUTAGEN.0 as i32, "Potent Mutagen"),
+            set_bonus(2, "Something Else"),
+        ],
+    };
+
+    let applied = tier_sets_from_gear(&sets);
+    assert_eq!(applied.flags, TierSetFlags::TWW_S2_2PC);
+    assert_eq!(applied.report.auras, vec![POTENT_MUTAGEN]);
+    assert_eq!(applied.report.applied.len(), 2);
+    assert_eq!(applied.report.unimplemented[0].spell_id, 2);
+}
+
+#[test]
+fn set_bonus_auras_last_the_whole_fight() {
+    use crate::handler::create_handler_with_gear;
+    use crate::rotation::RotationBackend;
+
+    let sets = crate::spec::SetBonuses {
+        active: vec![set_bonus(POTENT_MUTAGEN.0 as i32, "Potent Mutagen")],
+    };
+    let (handler, _, report) = create_handler_with_gear(
+        SpecId::BeastMastery,
+        r#"{"actions":[]}"#,
+        RotationBackend::default(),
+        &crate::spec::TalentLoadout::default(),
+        &sets,
+    )
+    .unwrap();
+
+    let mut player = Player::new(SpecId::BeastMastery);
+    player.set_auras = report.auras;
+    let config = SimConfig::default().with_duration(30.0);
+    let mut sim = Simulation::new(handler, config, player);
+    sim.run();
+
+    assert!(sim.state.player.buffs.has(POTENT_MUTAGEN, sim.state.now()));
+    sim.reset(1);
+    assert!(sim.state.player.buffs.has(POTENT_MUTAGEN, SimTime::ZERO));
+}
+
+#[test]
+fn use_trinket_applies_on_use_buff() {
+    use crate::actor::{Equipment, OnUseEffect, Trinket};
+    use crate::combat::Cooldown;
+    use crate::handler::create_handler_with_backend;
+    use crate::rotation::RotationBackend;
+
+    let handler = create_handler_with_backend(
+        SpecId::BeastMastery,
+        r#"{"actions":[{"use_trinket":1}]}"#,
+        RotationBackend::default(),
+    )
+    .unwrap();
+
+    let trinket = Trinket {
+        item_id: 1,
+        name: "test_trinket".to_string(),
+        on_use: Some(OnUseEffect {
+            spell: SpellIdx(BESTIAL_WRATH_BUFF.0),
+            cooldown: Cooldown::new(120.0),
+            category: 0,
+            category_cooldown: SimTime::ZERO,
+        }),
+        passives: Vec::new(),
+        procs: Vec::new(),
+    };
+    let mut player = Player::new(SpecId::BeastMastery);
+    player.equip(Equipment::new().with_trinket(1, trinket));
+    let config = SimConfig::default().with_duration(5.0);
+    let mut sim = Simulation::new(handler, config, player);
+    sim.run();
+
+    let now = sim.state.now();
+    assert!(sim.state.player.buffs.has(BESTIAL_WRATH_BUFF, now));
+    assert!(!sim.state.player.equipment.trinket_ready(1, now));
+}
+
+#[test]
+fn on_equip_trinket_aura_lasts_whole_fight() {
+    use crate::actor::{Equipment, Trinket};
+    use crate::handler::create_handler_with_backend;
+    use crate::rotation::RotationBackend;
+
+    let handler = create_handler_with_backend(
+        SpecId::BeastMastery,
+        r#"{"actions":[]}"#,
+        RotationBackend::default(),
+    )
+    .unwrap();
+
+    let trinket = Trinket {
+        item_id: 1,
+        name: "test_trinket".to_string(),
+        on_use: None,
+        passives: vec![BESTIAL_WRATH_BUFF],
+        procs: Vec::new(),
+    };
+    let mut player = Player::new(SpecId::BeastMastery);
+    player.equip(Equipment::new().with_trinket(1, trinket));
+    let config = SimConfig::default().with_duration(60.0);
+    let mut sim = Simulation::new(handler, config, player);
+    sim.run();
+
+    assert!(sim
+        .state
+        .player
+        .buffs
+        .has(BESTIAL_WRATH_BUFF, sim.state.now()));
+    assert!(sim.state.player.procs.get_handler(ProcIdx(1000)).is_none());
+}
@@ -10,6 +10,8 @@ pub const STEADY_SHOT: SpellIdx = SpellIdx(56641);
 pub const ARCANE_SHOT: SpellIdx = SpellIdx(185358);
 /// Kill Shot - Execute ability (shared)
 pub const KILL_SHOT: SpellIdx = SpellIdx(53351);
+/// Auto Shot - Ranged auto-attack (shared)
+pub const AUTO_SHOT: SpellIdx = SpellIdx(75);
 /// Trueshot - Major cooldown
 pub const TRUESHOT: SpellIdx = SpellIdx(288613);
 /// Multi-Shot - AoE ability
@@ -7,16 +7,19 @@ use super::rotation::{spec_resolver, spell_id_to_idx, spell_name_to_idx};
 use super::spells::spell_definitions;
 use crate::actor::Player;
 use crate::aura::{AuraFlags, AuraInstance};
+use crate::class::hunter::HUNTER_DAMAGE_NAMES;
 use crate::class::HunterClass;
-use crate::combat::{Cooldown, DamagePipeline};
+use crate::combat::{Attack, AttackType, Cooldown, DamagePipeline, MultiplierSource};
 use crate::core::SimEvent;
 use crate::handler::SpecHandler;
-use crate::rotation::{Action, CompiledRotation, Rotation};
-use crate::sim::SimState;
-use crate::spec::{AuraDef, AuraEffect, GcdType, SpellDef, SpellFlags};
+use crate::rotation::{
+    Action, PrecombatList, Rotation, RotationBackend, RotationEvaluator, SpecResolver,
+};
+use crate::sim::{SimState, TraceEventType};
+use crate::spec::{AppliedTalents, AuraDef, AuraEffect, GcdType, SpellDef, SpellFlags};
 use tracing::debug;
 use wowlab_common::types::{
-    AuraIdx, ClassId, DamageSchool, SimTime, SpecId, SpellIdx, TargetIdx, UnitIdx,
+    AuraIdx, ClassId, DamageSchool, HitResult, SimTime, SpecId, SpellIdx, TargetIdx, UnitIdx,
 };
 
 static SPELL_DEFS: std::sync::OnceLock<Vec<SpellDef>> = std::sync::OnceLock::new();
@@ -66,21 +69,61 @@ fn aura_damage_multiplier(aura: &AuraDef) -> f32 {
 /// Marksmanship focuses on ranged damage with careful shot placement.
 /// Unlike BM, MM can operate without a pet using Lone Wolf.
 pub struct MmHunter {
-    rotation: CompiledRotation,
+    talents: TalentFlags,
+    rotation: RotationEvaluator,
+    precombat: PrecombatList,
 }
 
 impl MmHunter {
     /// Create a new MM Hunter handler with the given rotation.
     pub fn new(rotation_json: &str) -> Result<Self, String> {
+        Self::with_backend(rotation_json, RotationBackend::default())
+    }
+
+    /// Create a new MM Hunter handler using a specific rotation backend.
+    pub fn with_backend(rotation_json: &str, backend: RotationBackend) -> Result<Self, String> {
+        Self::compile(
+            rotation_json,
+            TalentFlags::empty(),
+            backend,
+            spec_resolver(TalentFlags::empty()),
+        )
+    }
+
+    /// Create a MM Hunter handler from a loadout's talents.
+    pub fn with_loadout(
+        rotation_json: &str,
+        talents: &AppliedTalents<TalentFlags>,
+        backend: RotationBackend,
+    ) -> Result<Self, String> {
+        Self::compile(
+            rotation_json,
+            talents.flags,
+            backend,
+            talents.register(spec_resolver(talents.flags)),
+        )
+    }
+
+    fn compile(
+        rotation_json: &str,
+        talents: TalentFlags,
+        backend: RotationBackend,
+        resolver: SpecResolver,
+    ) -> Result<Self, String> {
         ensure_definitions();
 
-        let rotation = Rotation::from_json(rotation_json)
+        let rotation = Rotation::from_json_resolved(rotation_json, &resolver)
             .map_err(|e| format!("Failed to parse rotation: {}", e))?;
-        let resolver = spec_resolver(TalentFlags::empty());
-        let compiled = CompiledRotation::compile(&rotation, &resolver)
+        let precombat = PrecombatList::compile(&rotation, &resolver, backend)
+            .map_err(|e| format!("Failed to compile rotation: {}", e))?;
+        let compiled = RotationEvaluator::compile(&rotation, &resolver, backend)
             .map_err(|e| format!("Failed to compile rotation: {}", e))?;
 
-        Ok(Self { rotation: compiled })
+        Ok(Self {
+            talents,
+            rotation: compiled,
+            precombat,
+        })
     }
 
     /// Create with default empty rotation (for tests/simple cases).
@@ -88,6 +131,10 @@ impl MmHunter {
         Self::new(r#"{"actions":[]}"#)
     }
 
+    pub fn has_talent(&self, talent: TalentFlags) -> bool {
+        self.talents.contains(talent)
+    }
+
     /// Internal helper to cast a spell
     fn do_cast_spell(&self, state: &mut SimState, spell_id: SpellIdx, target: TargetIdx) {
         let Some(spell) = get_spell(spell_id) else {
@@ -95,6 +142,8 @@ impl MmHunter {
         };
         let now = state.now();
         let haste = state.player.stats.haste();
+        state.stats.record_cast(spell_id);
+        state.trace_cast(spell_id, target);
 
         // Handle Lock and Load: free instant Aimed Shot
         let is_free = spell_id == AIMED_SHOT && state.player.buffs.has(LOCK_AND_LOAD, now);
@@ -110,6 +159,11 @@ impl MmHunter {
         for gain in &spell.gains {
             if let Some(ref mut primary) = state.player.resources.primary {
                 primary.gain(gain.amount);
+                let resource = primary.resource_type;
+                state.trace(TraceEventType::ResourceGain {
+                    resource,
+                    amount: gain.amount,
+                });
             }
         }
 
@@ -129,6 +183,7 @@ impl MmHunter {
         if spell.cooldown > SimTime::ZERO {
             if let Some(cd) = state.player.cooldown_mut(spell_id) {
                 cd.start(now, haste);
+                state.trace(TraceEventType::CooldownStart { spell: spell_id });
             }
         }
 
@@ -203,6 +258,12 @@ impl MmHunter {
             return;
         };
 
+        state.trace(TraceEventType::AuraApply {
+            aura: aura_id,
+            target,
+            is_debuff: aura.flags.is_debuff,
+        });
+
         let mut instance = AuraInstance::new(aura_id, target, aura.duration, now, aura.flags);
         if aura.max_stacks > 1 {
             instance = instance.with_stacks(aura.max_stacks);
@@ -217,6 +278,7 @@ impl MmHunter {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn do_calculate_damage(
         &self,
         state: &mut SimState,
@@ -225,37 +287,33 @@ impl MmHunter {
         sp_coef: f32,
         school: DamageSchool,
         spell_id: Option<SpellIdx>,
-    ) -> f32 {
+        attack: Attack,
+    ) -> (f32, HitResult) {
         let ap = state.player.stats.attack_power();
         let sp = state.player.stats.spell_power();
         let crit = state.player.stats.crit_chance();
-        let armor = state.enemies.primary().map(|e| e.armor).unwrap_or(0.0);
+        let mitigation = state.mitigation(state.enemies.primary);
+        let table = state
+            .attack_table(attack, state.enemies.primary)
+            .with_crit(crit);
         let now = state.now();
-
-        let result = DamagePipeline::calculate(
-            base,
-            ap_coef,
-            sp_coef,
-            ap,
-            sp,
-            &state.multipliers,
-            crit,
-            school,
-            armor,
-            &mut state.rng,
-        );
-        let mut damage = result.final_amount;
+        let target = state.enemies.primary;
+        let periodic = attack.kind == AttackType::Periodic;
+        let mut multipliers = state.damage_multipliers(school, periodic, false, target);
 
         // Trueshot: Bonus damage during cooldown
         if state.player.buffs.has(TRUESHOT_BUFF, now) {
             if let Some(ts) = get_aura(TRUESHOT_BUFF) {
-                damage *= aura_damage_multiplier(ts);
+                multipliers.apply(
+                    MultiplierSource::Aura(TRUESHOT_BUFF),
+                    aura_damage_multiplier(ts),
+                );
             }
         }
 
         // Lone Wolf: 10% damage bonus when no pet active
         if state.player.buffs.has(LONE_WOLF, now) {
-            damage *= 1.0 + LONE_WOLF_DAMAGE;
+            multipliers.apply(MultiplierSource::Aura(LONE_WOLF), 1.0 + LONE_WOLF_DAMAGE);
         }
 
         // Precise Shots: Arcane Shot and Multi-Shot deal bonus damage
@@ -263,11 +321,29 @@ impl MmHunter {
             if (spell == ARCANE_SHOT || spell == MULTI_SHOT)
                 && state.player.buffs.has(PRECISE_SHOTS, now)
             {
-                damage *= 1.0 + PRECISE_SHOTS_DAMAGE;
+                multipliers.apply(
+                    MultiplierSource::Aura(PRECISE_SHOTS),
+                    1.0 + PRECISE_SHOTS_DAMAGE,
+                );
             }
         }
+        state.audit_multipliers(&multipliers);
+
+        let result = DamagePipeline::calculate(
+            base,
+            ap_coef,
+            sp_coef,
+            ap,
+            sp,
+            &multipliers,
+            &table,
+            school,
+            &mitigation,
+            &mut state.rng,
+        );
+        let damage = result.final_amount;
 
-        damage
+        (damage, result.hit_result)
     }
 }
 
@@ -292,6 +368,10 @@ impl SpecHandler for MmHunter {
         get_aura_defs()
     }
 
+    fn damage_names(&self) -> &'static [(SpellIdx, &'static str)] {
+        HUNTER_DAMAGE_NAMES
+    }
+
     fn talent_names(&self) -> Vec<String> {
         // MM Hunter has basic talent flags but no full talent definitions yet
         vec![
@@ -308,9 +388,9 @@ impl SpecHandler for MmHunter {
         // If pet is desired, summon it and don't apply Lone Wolf
         self.apply_aura(state, LONE_WOLF, TargetIdx(0));
 
-        // Schedule first auto-attack
+        // Schedule first auto-attack at the pull
         state.events.schedule(
-            SimTime::ZERO,
+            state.config.combat_start(),
             SimEvent::AutoAttack {
                 unit: state.player.id,
             },
@@ -335,14 +415,33 @@ impl SpecHandler for MmHunter {
             return;
         }
 
-        let result = self.rotation.evaluate(state);
+        let result = if state.in_combat() {
+            self.rotation.evaluate(state)
+        } else if let Some(result) = self.precombat.next(state) {
+            result
+        } else {
+            // Precombat list done: idle until the pull
+            state
+                .events
+                .schedule(state.config.combat_start(), SimEvent::GcdEnd);
+            return;
+        };
 
         if result.is_cast() {
             if let Some(spell) = spell_id_to_idx(result.spell_id) {
-                self.cast_spell(state, spell, TargetIdx(0));
+                let target = state.enemies.primary;
+                self.cast_spell(state, spell, target);
             } else {
                 state.schedule_in(SimTime::from_millis(100), SimEvent::GcdEnd);
             }
+        } else if let Some(slot) = result.trinket_slot() {
+            // Trinkets are off the GCD
+            let delay = if self.use_trinket(state, slot) {
+                SimTime::ZERO
+            } else {
+                SimTime::from_millis(100)
+            };
+            state.schedule_in(delay, SimEvent::GcdEnd);
         } else if result.is_wait() {
             let wait_ms = (result.wait_time * 1000.0) as u32;
             state.schedule_in(SimTime::from_millis(wait_ms.max(100)), SimEvent::GcdEnd);
@@ -355,27 +454,37 @@ impl SpecHandler for MmHunter {
         self.on_spell_damage(state, spell, target);
     }
 
-    fn on_spell_damage(&self, state: &mut SimState, spell_id: SpellIdx, _target: TargetIdx) {
+    fn on_spell_damage(&self, state: &mut SimState, spell_id: SpellIdx, target: TargetIdx) {
         let Some(spell) = get_spell(spell_id) else {
             return;
         };
         let Some(ref dmg) = spell.damage else { return };
 
-        let damage = self.do_calculate_damage(
+        let (damage, result) = self.do_calculate_damage(
             state,
             dmg.base_damage,
             dmg.ap_coefficient,
             dmg.sp_coefficient,
             dmg.school,
             Some(spell_id),
+            Attack::ranged(),
         );
-        state.record_damage(damage);
+        state.record_spell_damage(spell_id, target, damage, result, false);
         debug!(spell = spell_id.0, damage, "Spell damage");
     }
 
     fn on_auto_attack(&self, state: &mut SimState, unit: UnitIdx) {
-        let damage = self.do_calculate_damage(state, 0.0, 0.8, 0.0, DamageSchool::Physical, None);
-        state.record_damage(damage);
+        let (damage, result) = self.do_calculate_damage(
+            state,
+            0.0,
+            0.8,
+            0.0,
+            DamageSchool::Physical,
+            None,
+            Attack::ranged().auto(),
+        );
+        let target = state.enemies.primary;
+        state.record_spell_damage(AUTO_SHOT, target, damage, result, false);
 
         // Schedule next auto-attack using class method
         if !state.finished {
@@ -407,15 +516,16 @@ impl SpecHandler for MmHunter {
 
         if let Some(aura) = get_aura(aura_id) {
             if let Some(ref periodic) = aura.periodic {
-                let damage = self.do_calculate_damage(
+                let (damage, result) = self.do_calculate_damage(
                     state,
                     0.0,
                     periodic.ap_coefficient,
                     periodic.sp_coefficient,
                     DamageSchool::Physical,
                     None,
+                    Attack::periodic(),
                 );
-                state.record_damage(damage);
+                state.record_spell_damage(SpellIdx(aura_id.0), target, damage, result, true);
                 state.schedule_in(
                     periodic.interval,
                     SimEvent::AuraTick {
@@ -439,6 +549,8 @@ impl SpecHandler for MmHunter {
             } else {
                 Action::WaitGcd
             }
+        } else if let Some(slot) = result.trinket_slot() {
+            Action::UseTrinket(slot)
         } else if result.is_wait() {
             Action::Wait(result.wait_time as f64)
         } else {
@@ -478,19 +590,20 @@ impl SpecHandler for MmHunter {
         sp_coef: f32,
         school: DamageSchool,
     ) -> f32 {
-        self.do_calculate_damage(state, base, ap_coef, sp_coef, school, None)
+        self.do_calculate_damage(
+            state,
+            base,
+            ap_coef,
+            sp_coef,
+            school,
+            None,
+            Attack::ranged(),
+        )
+        .0
     }
 }
 
 impl HunterClass for MmHunter {
-    /// MM Hunter pet damage modifier.
-    ///
-    /// MM typically uses Lone Wolf (no pet), but if pet is active,
-    /// use base damage without special modifiers.
-    fn pet_damage_modifier(&self, _state: &SimState) -> f32 {
-        1.0
-    }
-
     /// MM Hunter pet attack speed modifier.
     ///
     /// No special pet attack speed bonuses for MM.
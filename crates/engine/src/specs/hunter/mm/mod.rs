@@ -4,6 +4,7 @@ mod handler;
 mod procs;
 mod rotation;
 mod spells;
+mod talents;
 
 pub use auras::*;
 pub use constants::*;
@@ -11,6 +12,7 @@ pub use handler::MmHunter;
 pub use procs::*;
 pub use rotation::*;
 pub use spells::*;
+pub use talents::{set_bonuses_from_gear, talents_from_loadout, TALENT_MAP};
 
 #[cfg(test)]
 mod tests;
@@ -3,12 +3,13 @@
 //! Provides name resolution for MM Hunter rotations.
 
 use super::constants::*;
+use super::talents::TALENT_MAP;
 use crate::rotation::SpecResolver;
 use wowlab_common::types::SpellIdx;
 
 /// Create a spec resolver for MM Hunter.
 pub fn spec_resolver(talents: TalentFlags) -> SpecResolver {
-    SpecResolver::new("mm_hunter")
+    let resolver = SpecResolver::new("mm_hunter")
         .resource("focus")
         // Core spells
         .spell("aimed_shot", AIMED_SHOT.0)
@@ -23,13 +24,14 @@ pub fn spec_resolver(talents: TalentFlags) -> SpecResolver {
         .aura("precise_shots", PRECISE_SHOTS.0)
         .aura("steady_focus", STEADY_FOCUS.0)
         .aura("trick_shots", TRICK_SHOTS.0)
-        .aura("lock_and_load", LOCK_AND_LOAD.0)
-        // Talents
-        .talent("trueshot", talents.contains(TalentFlags::TRUESHOT))
-        .talent(
-            "lock_and_load",
-            talents.contains(TalentFlags::LOCK_AND_LOAD),
-        )
+        .aura("lock_and_load", LOCK_AND_LOAD.0);
+
+    // Talents
+    TALENT_MAP
+        .iter()
+        .fold(resolver, |resolver, &(flag, name, _)| {
+            resolver.talent(name, talents.contains(flag))
+        })
 }
 
 /// Default spec resolver (no talents).
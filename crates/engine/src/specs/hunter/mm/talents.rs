@@ -0,0 +1,24 @@
+//! MM Hunter talent mapping.
+
+use super::auras::aura_definitions;
+use super::constants::TalentFlags;
+use crate::spec::{AppliedTalents, SetBonusReport, SetBonuses, TalentLoadout};
+
+/// Talent flag, name and trait definition spell ID.
+pub const TALENT_MAP: &[(TalentFlags, &str, u32)] = &[
+    (TalentFlags::TRUESHOT, "trueshot", 288613),
+    (TalentFlags::LOCK_AND_LOAD, "lock_and_load", 194595),
+    (TalentFlags::STEADY_FOCUS, "steady_focus", 193533),
+    (TalentFlags::TRICK_SHOTS, "trick_shots", 257621),
+    (TalentFlags::VOLLEY, "volley", 260243),
+];
+
+/// Match a loadout's talents against the MM talent map.
+pub fn talents_from_loadout(loadout: &TalentLoadout) -> AppliedTalents<TalentFlags> {
+    loadout.apply(TALENT_MAP)
+}
+
+/// Match equipped set bonuses; MM has no tier set procs, so only aura bonuses apply.
+pub fn set_bonuses_from_gear(sets: &SetBonuses) -> SetBonusReport {
+    sets.apply::<TalentFlags>(&[], &aura_definitions()).report
+}
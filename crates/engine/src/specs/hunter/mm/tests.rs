@@ -93,19 +93,22 @@ fn test_kill_shot_shared_with_bm() {
 #[test]
 fn test_mm_lone_wolf_by_default() {
     // MM Hunter uses Lone Wolf (no pet) by default
-    // Pet damage modifier should be 1.0 (no special bonus)
+    // Pet damage gets no multipliers on top of versatility and mastery
     // Since SimState requires parameters, we test the trait method directly
     let handler = create_handler();
 
     // Create a minimal SimState for testing
     use crate::actor::Player;
+    use crate::combat::DamageMultipliers;
     use crate::sim::{SimConfig, SimState};
 
     let config = SimConfig::default();
     let player = Player::new(SpecId::Marksmanship);
     let state = SimState::new(config, player);
 
-    assert_eq!(handler.pet_damage_modifier(&state), 1.0);
+    let mut multipliers = DamageMultipliers::default();
+    handler.pet_damage_multipliers(&state, &mut multipliers);
+    assert!(multipliers.active().is_empty());
 }
 
 #[test]
@@ -1,4 +1,4 @@
-use super::{CombatStats, PrimaryStats, Ratings};
+use super::{CombatStats, PrimaryStats, Ratings, WeaponStats};
 use wowlab_common::types::SpecId;
 
 /// Cached stat calculations to avoid recomputation
@@ -8,6 +8,10 @@ pub struct StatCache {
     pub primary: PrimaryStats,
     /// Rating values
     pub ratings: Ratings,
+    /// Armor from gear
+    pub armor: f32,
+    /// Main-hand weapon
+    pub weapon: WeaponStats,
     /// Computed combat stats
     pub combat: CombatStats,
     /// Spec (for mastery interpretation)
@@ -22,6 +26,8 @@ impl StatCache {
         Self {
             primary: PrimaryStats::default(),
             ratings: Ratings::default(),
+            armor: 0.0,
+            weapon: WeaponStats::default(),
             combat: CombatStats::new(),
             spec: SpecId::BeastMastery, // Default
             dirty: true,
@@ -33,6 +39,8 @@ impl StatCache {
         Self {
             primary: PrimaryStats::default(),
             ratings: Ratings::default(),
+            armor: 0.0,
+            weapon: WeaponStats::default(),
             combat: CombatStats::default(),
             spec,
             dirty: true,
@@ -66,6 +74,7 @@ impl StatCache {
         // Most specs: 1 primary = 1 AP/SP
         self.combat.attack_power = primary;
         self.combat.spell_power = primary;
+        self.combat.weapon_dps = self.weapon.dps;
 
         // Crit: base + rating
         let crit_from_rating = rating_to_percent(self.ratings.crit, RatingType::Crit);
@@ -1,4 +1,15 @@
-use wowlab_common::types::{MasteryEffect, SpecId};
+use wowlab_common::types::{DamageSchool, MasteryEffect, SpecId};
+
+/// What mastery needs to know about a hit
+#[derive(Clone, Copy, Debug)]
+pub struct MasteryHit {
+    pub school: DamageSchool,
+    pub periodic: bool,
+    /// Dealt by the player's pet
+    pub pet: bool,
+    /// Target health remaining (0.0 to 1.0)
+    pub target_health: f32,
+}
 
 /// Class/spec coefficients for stat calculations
 #[derive(Clone, Debug)]
@@ -66,4 +77,38 @@ impl SpecCoefficients {
             },
         }
     }
+
+    /// Mastery bonus (as a fraction) from `mastery` points
+    pub fn mastery_bonus(&self, mastery: f32) -> f32 {
+        (self.mastery_base + mastery * self.mastery_coeff) / 100.0
+    }
+
+    /// Damage multiplier mastery gives `hit`.
+    ///
+    /// Player-only effects don't touch pet damage; `Custom` masteries are
+    /// left to the spec.
+    pub fn mastery_multiplier(&self, mastery: f32, hit: &MasteryHit) -> f32 {
+        use MasteryEffect::*;
+
+        let bonus = self.mastery_bonus(mastery);
+        let applies = match self.mastery_effect {
+            AllDamage => !hit.pet,
+            PhysicalDamage => !hit.pet && hit.school.is_physical(),
+            MagicDamage => !hit.pet && !hit.school.is_physical(),
+            PetDamage => hit.pet,
+            PetAndOwnerDamage { owner_pct } => {
+                let share = if hit.pet { 1.0 } else { owner_pct };
+                return 1.0 + bonus * share;
+            }
+            DotDamage => !hit.pet && hit.periodic,
+            ExecuteDamage { threshold } => !hit.pet && hit.target_health < threshold,
+            SchoolDamage(school) => !hit.pet && hit.school == school,
+            Custom => false,
+        };
+        if applies {
+            1.0 + bonus
+        } else {
+            1.0
+        }
+    }
 }
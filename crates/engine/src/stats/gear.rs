@@ -0,0 +1,219 @@
+//! Player stats from equipped items.
+//!
+//! Items from a SimC profile are scaled with their bonus IDs (item level,
+//! stat mods, curves) against their slot's stat budget and summed. Enchant
+//! stats, and gems without item stats, aren't in the game data the engine
+//! loads, so they're reported as unresolved.
+
+use super::{primary_stat_for_spec, ItemLevelScaling, PrimaryStats, Ratings, StatCache};
+use wowlab_common::parsers::{
+    apply_item_bonuses_for_slot, get_item_armor, slot_budget_index, Item, Profile, Slot,
+};
+use wowlab_common::types::data::{ItemDataFlat, ItemScalingData, ItemStat, ScaledStat};
+use wowlab_common::types::{Attribute, RatingType, SpecId};
+
+/// Item class ID for weapons
+const ITEM_CLASS_WEAPON: i32 = 2;
+
+/// Main-hand weapon damage
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WeaponStats {
+    pub dps: f32,
+    /// Swing time in seconds
+    pub speed: f32,
+}
+
+/// An equipped item after scaling
+#[derive(Clone, Debug)]
+pub struct EquippedItem {
+    pub slot: Slot,
+    pub item_id: u32,
+    pub name: String,
+    pub item_level: i32,
+    pub stats: Vec<ScaledStat>,
+}
+
+/// Stat totals of a profile's equipment.
+#[derive(Clone, Debug, Default)]
+pub struct GearStats {
+    pub items: Vec<EquippedItem>,
+    pub primary: PrimaryStats,
+    pub ratings: Ratings,
+    pub armor: f32,
+    pub weapon: Option<WeaponStats>,
+    /// Items, gems and enchants whose stats couldn't be resolved
+    pub unresolved: Vec<String>,
+}
+
+impl GearStats {
+    /// Scale and sum a profile's equipment for `spec`.
+    ///
+    /// `item` looks up item data by ID. Hybrid primary stats (e.g. "Agility
+    /// or Intellect") count as the spec's primary stat when it's one of them.
+    pub fn from_profile(
+        profile: &Profile,
+        spec: SpecId,
+        scaling: &ItemScalingData,
+        item: impl Fn(u32) -> Option<ItemDataFlat>,
+    ) -> Self {
+        let mut gear = Self::default();
+        let level = profile.character.level as i32;
+
+        for equipped in &profile.equipment {
+            let Some(data) = item(equipped.id) else {
+                gear.unresolved
+                    .push(format!("item {} ({:?})", equipped.id, equipped.slot));
+                continue;
+            };
+            gear.equip(equipped, &data, spec, scaling, level);
+
+            for &gem_id in equipped.gem_ids.iter().flatten() {
+                match item(gem_id).filter(|gem| !gem.stats.is_empty()) {
+                    Some(gem) => gear.add_gem(&gem, spec, scaling),
+                    None => gear.unresolved.push(format!("gem {}", gem_id)),
+                }
+            }
+            if let Some(enchant_id) = equipped.enchant_id {
+                gear.unresolved.push(format!("enchant {}", enchant_id));
+            }
+        }
+
+        gear
+    }
+
+    /// Mean item level of the equipped items
+    pub fn average_item_level(&self) -> f32 {
+        if self.items.is_empty() {
+            return 0.0;
+        }
+        let total: i32 = self.items.iter().map(|i| i.item_level).sum();
+        total as f32 / self.items.len() as f32
+    }
+
+    /// Replace the stat cache's gear stats with these totals
+    pub fn apply_to(&self, stats: &mut StatCache) {
+        stats.primary = self.primary.clone();
+        stats.ratings = self.ratings.clone();
+        stats.armor = self.armor;
+        stats.weapon = self.weapon.unwrap_or_default();
+        stats.invalidate();
+    }
+
+    fn equip(
+        &mut self,
+        equipped: &Item,
+        data: &ItemDataFlat,
+        spec: SpecId,
+        scaling: &ItemScalingData,
+        level: i32,
+    ) {
+        let base_stats = crafted_stats(&data.stats, equipped.crafted_stats.as_deref());
+        let bonus_ids: Vec<i32> = equipped
+            .bonus_ids
+            .iter()
+            .flatten()
+            .map(|&id| id as i32)
+            .collect();
+        let scaled = apply_item_bonuses_for_slot(
+            data.item_level,
+            &base_stats,
+            data.quality,
+            &bonus_ids,
+            scaling,
+            Some(level),
+            slot_budget_index(data.inventory_type),
+        );
+
+        for stat in &scaled.stats {
+            self.add_stat(stat.stat_type, stat.value as f32, spec);
+        }
+        if let Some(armor) = get_item_armor(
+            scaling,
+            scaled.item_level,
+            data.quality,
+            data.class_id,
+            data.subclass_id,
+            data.inventory_type,
+        ) {
+            self.armor += armor as f32;
+        }
+
+        if equipped.slot == Slot::MainHand && data.class_id == ITEM_CLASS_WEAPON && data.speed > 0 {
+            let speed = data.speed as f32 / 1000.0;
+            self.weapon = Some(WeaponStats {
+                dps: ItemLevelScaling::weapon_dps(scaled.item_level.max(0) as u32, speed),
+                speed,
+            });
+        }
+
+        self.items.push(EquippedItem {
+            slot: equipped.slot,
+            item_id: equipped.id,
+            name: data.name.clone(),
+            item_level: scaled.item_level,
+            stats: scaled.stats,
+        });
+    }
+
+    fn add_gem(&mut self, gem: &ItemDataFlat, spec: SpecId, scaling: &ItemScalingData) {
+        let scaled = apply_item_bonuses_for_slot(
+            gem.item_level,
+            &gem.stats,
+            gem.quality,
+            &[],
+            scaling,
+            None,
+            slot_budget_index(gem.inventory_type),
+        );
+        for stat in &scaled.stats {
+            self.add_stat(stat.stat_type, stat.value as f32, spec);
+        }
+    }
+
+    fn add_stat(&mut self, stat_type: i32, value: f32, spec: SpecId) {
+        let primary = primary_stat_for_spec(spec);
+        match stat_type {
+            3 => self.primary.add(Attribute::Agility, value),
+            4 => self.primary.add(Attribute::Strength, value),
+            5 => self.primary.add(Attribute::Intellect, value),
+            7 => self.primary.add(Attribute::Stamina, value),
+            71 => self.primary.add(primary, value),
+            72 if matches!(primary, Attribute::Agility | Attribute::Strength) => {
+                self.primary.add(primary, value)
+            }
+            73 if matches!(primary, Attribute::Agility | Attribute::Intellect) => {
+                self.primary.add(primary, value)
+            }
+            74 if matches!(primary, Attribute::Strength | Attribute::Intellect) => {
+                self.primary.add(primary, value)
+            }
+            32 => self.ratings.add(RatingType::Crit, value),
+            36 => self.ratings.add(RatingType::Haste, value),
+            40 => self.ratings.add(RatingType::Versatility, value),
+            49 => self.ratings.add(RatingType::Mastery, value),
+            91 => self.ratings.add(RatingType::Avoidance, value),
+            93 => self.ratings.add(RatingType::Leech, value),
+            94 => self.ratings.add(RatingType::Speed, value),
+            62 => self.armor += value,
+            _ => {}
+        }
+    }
+}
+
+/// Swap a crafted item's secondary stats for the ones picked when crafting.
+fn crafted_stats(stats: &[ItemStat], crafted: Option<&[u32]>) -> Vec<ItemStat> {
+    let mut picks = crafted.unwrap_or_default().iter();
+    stats
+        .iter()
+        .map(|stat| match stat.stat_type {
+            32 | 36 | 40 | 49 => match picks.next() {
+                Some(&stat_type) => ItemStat {
+                    stat_type: stat_type as i32,
+                    value: stat.value,
+                },
+                None => stat.clone(),
+            },
+            _ => stat.clone(),
+        })
+        .collect()
+}
@@ -2,6 +2,7 @@ mod attributes;
 mod cache;
 mod coefficients;
 mod combat;
+mod gear;
 mod modifiers;
 mod ratings;
 mod scaling;
@@ -10,6 +11,7 @@ pub use attributes::*;
 pub use cache::*;
 pub use coefficients::*;
 pub use combat::*;
+pub use gear::*;
 pub use modifiers::*;
 pub use ratings::*;
 pub use scaling::*;
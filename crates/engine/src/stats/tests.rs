@@ -79,6 +79,65 @@ fn spec_coefficients() {
     ));
 }
 
+#[test]
+fn mastery_effect_by_variant() {
+    let hit = MasteryHit {
+        school: DamageSchool::Physical,
+        periodic: false,
+        pet: false,
+        target_health: 1.0,
+    };
+    let pet = MasteryHit { pet: true, ..hit };
+    let mut coeff = SpecCoefficients::for_spec(SpecId::BeastMastery);
+    coeff.mastery_base = 10.0;
+    coeff.mastery_coeff = 2.0;
+    // (10 + 5 * 2)% = 20%
+    assert!((coeff.mastery_bonus(5.0) - 0.2).abs() < 1e-6);
+
+    let cases = [
+        (MasteryEffect::AllDamage, hit, 1.2),
+        (MasteryEffect::AllDamage, pet, 1.0),
+        (MasteryEffect::MagicDamage, hit, 1.0),
+        (MasteryEffect::PhysicalDamage, hit, 1.2),
+        (MasteryEffect::PetDamage, pet, 1.2),
+        (
+            MasteryEffect::PetAndOwnerDamage { owner_pct: 0.5 },
+            hit,
+            1.1,
+        ),
+        (
+            MasteryEffect::PetAndOwnerDamage { owner_pct: 0.5 },
+            pet,
+            1.2,
+        ),
+        (MasteryEffect::DotDamage, hit, 1.0),
+        (
+            MasteryEffect::DotDamage,
+            MasteryHit {
+                periodic: true,
+                ..hit
+            },
+            1.2,
+        ),
+        (MasteryEffect::ExecuteDamage { threshold: 0.2 }, hit, 1.0),
+        (
+            MasteryEffect::ExecuteDamage { threshold: 0.2 },
+            MasteryHit {
+                target_health: 0.1,
+                ..hit
+            },
+            1.2,
+        ),
+        (MasteryEffect::SchoolDamage(DamageSchool::Fire), hit, 1.0),
+        (MasteryEffect::Custom, hit, 1.0),
+    ];
+    for (effect, hit, expected) in cases {
+        coeff.mastery_effect = effect;
+        let mult = coeff.mastery_multiplier(5.0, &hit);
+        assert!((mult - expected).abs() < 1e-6, "{:?}: {}", effect, mult);
+    }
+}
+
 #[test]
 fn primary_stat_for_spec_check() {
     assert_eq!(
@@ -88,3 +147,140 @@ fn primary_stat_for_spec_check() {
     assert_eq!(primary_stat_for_spec(SpecId::Fury), Attribute::Strength);
     assert_eq!(primary_stat_for_spec(SpecId::Fire), Attribute::Intellect);
 }
+
+fn gear_item(id: i32, inventory_type: i32, stats: &[(i32, i32)]) -> data::ItemDataFlat {
+    data::ItemDataFlat {
+        id,
+        item_level: 600,
+        quality: 4,
+        inventory_type,
+        stats: stats
+            .iter()
+            .map(|&(stat_type, value)| data::ItemStat { stat_type, value })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn gear_scaling() -> data::ItemScalingData {
+    let mut scaling = data::ItemScalingData::default();
+    scaling.rand_prop_points.insert(
+        610,
+        data::RandPropPointsFlat {
+            id: 610,
+            epic_f_0: 1000.0,
+            epic_f_2: 500.0,
+            ..Default::default()
+        },
+    );
+    scaling.bonuses.insert(
+        1,
+        vec![data::ItemBonusFlat {
+            id: 1,
+            parent_item_bonus_list_id: 1,
+            bonus_type: 1, // Item level +10
+            value_0: 10,
+            ..Default::default()
+        }],
+    );
+    scaling
+        .armor
+        .total
+        .insert(610, [1000.0, 2000.0, 3000.0, 4000.0]);
+    scaling
+        .armor
+        .quality
+        .insert(610, [1.0, 1.0, 1.0, 1.0, 1.1, 1.0, 1.0]);
+    scaling.armor.location.insert(5, [0.16, 0.16, 0.16, 0.16]);
+    scaling
+}
+
+fn gear_profile() -> wowlab_common::parsers::Profile {
+    wowlab_common::parsers::parse_simc(
+        r#"hunter="Gear"
+level=80
+spec=beast_mastery
+chest=,id=1,bonus_id=1,enchant_id=7000
+finger1=,id=2,bonus_id=1,crafted_stats=36
+main_hand=,id=3,bonus_id=1
+"#,
+    )
+    .unwrap()
+}
+
+fn gear_lookup(id: u32) -> Option<data::ItemDataFlat> {
+    match id {
+        // Plate chest: agility/intellect, stamina and crit
+        1 => Some(data::ItemDataFlat {
+            class_id: 4,
+            subclass_id: 4,
+            ..gear_item(1, 5, &[(73, 5000), (7, 8000), (32, 3000)])
+        }),
+        // Ring: crafted secondary slot, mastery
+        2 => Some(gear_item(2, 11, &[(32, 4000), (49, 4000)])),
+        // Two-handed bow
+        3 => Some(data::ItemDataFlat {
+            class_id: 2,
+            speed: 3000,
+            ..gear_item(3, 15, &[(3, 2000)])
+        }),
+        _ => None,
+    }
+}
+
+#[test]
+fn gear_stats_scale_by_slot() {
+    let gear = GearStats::from_profile(
+        &gear_profile(),
+        SpecId::BeastMastery,
+        &gear_scaling(),
+        gear_lookup,
+    );
+
+    assert_eq!(gear.items.len(), 3);
+    assert_eq!(gear.average_item_level(), 610.0);
+    // Chest and bow use the large budget, the ring the small one
+    assert_eq!(gear.primary.agility, 700.0);
+    assert_eq!(gear.primary.stamina, 800.0);
+    assert_eq!(gear.ratings.crit, 300.0);
+    assert_eq!(gear.ratings.haste, 200.0);
+    assert_eq!(gear.ratings.mastery, 200.0);
+    assert_eq!(gear.unresolved, vec!["enchant 7000".to_string()]);
+    // Base armor of the plate chest at item level 610: 4000 * 1.1 * 0.16
+    assert_eq!(gear.armor, 704.0);
+
+    let weapon = gear.weapon.unwrap();
+    assert_eq!(weapon.speed, 3.0);
+    assert!(weapon.dps > 0.0);
+}
+
+#[test]
+fn gear_stats_fill_stat_cache() {
+    let gear = GearStats::from_profile(
+        &gear_profile(),
+        SpecId::Marksmanship,
+        &gear_scaling(),
+        gear_lookup,
+    );
+    let mut stats = StatCache::with_spec(SpecId::Marksmanship);
+    gear.apply_to(&mut stats);
+    stats.update(1.0);
+
+    assert_eq!(stats.attack_power(), 700.0);
+    assert_eq!(stats.weapon, gear.weapon.unwrap());
+    assert_eq!(stats.combat.weapon_dps, stats.weapon.dps);
+    assert!(stats.crit_chance() > CombatStats::BASE_CRIT);
+}
+
+#[test]
+fn gear_stats_report_missing_items() {
+    let gear = GearStats::from_profile(
+        &gear_profile(),
+        SpecId::BeastMastery,
+        &gear_scaling(),
+        |_| None,
+    );
+
+    assert!(gear.items.is_empty());
+    assert_eq!(gear.unresolved.len(), 3);
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 use crate::rotation::{get_var_path_schema, validate_rotation, Rotation};
+use crate::sim::{ChunkedBatch, SimulationOutput, SimulationRequest};
 use wowlab_common::types::{Attribute, DamageSchool, RatingType, ResourceType};
 
 #[cfg(feature = "jit")]
@@ -635,8 +636,97 @@ pub fn validate_rotation_json(json: &str) -> Result<JsValue, JsValue> {
     serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
+#[wasm_bindgen(js_name = parseRotationText)]
+pub fn parse_rotation_text(text: &str) -> Result<JsValue, JsValue> {
+    let rotation = Rotation::from_text(text).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    serde_wasm_bindgen::to_value(&rotation).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = formatRotation)]
+pub fn format_rotation(json: &str) -> Result<String, JsValue> {
+    let rotation: Rotation = serde_json::from_str(json)
+        .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+    Ok(rotation.to_text())
+}
+
 #[wasm_bindgen(js_name = getVarPathSchema)]
 pub fn get_var_path_schema_wasm() -> Result<JsValue, JsValue> {
     let schema = get_var_path_schema();
     serde_wasm_bindgen::to_value(&schema).map_err(|e| JsValue::from_str(&e.to_string()))
 }
+
+/// Progress of a chunked simulation run.
+#[derive(Clone, Debug, Serialize, Deserialize, tsify::Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct SimulationProgress {
+    pub completed: u32,
+    pub total: u32,
+    pub mean_dps: f64,
+}
+
+fn parse_simulation_request(request: JsValue) -> Result<SimulationRequest, JsValue> {
+    serde_wasm_bindgen::from_value(request)
+        .map_err(|e| JsValue::from_str(&format!("Invalid simulation request: {}", e)))
+}
+
+/// Run a full simulation to completion.
+///
+/// Blocks until all iterations finish; call from a Web Worker. Use
+/// `SimulationRun` to report progress between chunks instead.
+#[wasm_bindgen(js_name = runSimulation)]
+pub fn run_simulation(request: JsValue) -> Result<JsValue, JsValue> {
+    let request = parse_simulation_request(request)?;
+    let output = request.run().map_err(|e| JsValue::from_str(&e))?;
+    serde_wasm_bindgen::to_value(&output).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// A simulation advanced in chunks so the caller can yield between them.
+#[wasm_bindgen]
+pub struct SimulationRun {
+    batch: Option<ChunkedBatch>,
+}
+
+#[wasm_bindgen]
+impl SimulationRun {
+    #[wasm_bindgen(constructor)]
+    pub fn new(request: JsValue) -> Result<SimulationRun, JsValue> {
+        let request = parse_simulation_request(request)?;
+        let batch = request.chunked().map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self { batch: Some(batch) })
+    }
+
+    /// Run up to `chunk` more iterations and return the progress.
+    pub fn step(&mut self, chunk: u32) -> Result<JsValue, JsValue> {
+        let batch = self.batch_mut()?;
+        batch.step(chunk);
+
+        let progress = SimulationProgress {
+            completed: batch.completed(),
+            total: batch.total(),
+            mean_dps: batch.running_mean(),
+        };
+        serde_wasm_bindgen::to_value(&progress).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(getter, js_name = isDone)]
+    pub fn is_done(&self) -> bool {
+        self.batch.as_ref().is_none_or(ChunkedBatch::is_done)
+    }
+
+    /// Summarize completed iterations. The run cannot be stepped afterwards.
+    pub fn finish(&mut self) -> Result<JsValue, JsValue> {
+        let batch = self
+            .batch
+            .take()
+            .ok_or_else(|| JsValue::from_str("Simulation already finished"))?;
+        let (results, breakdown) = batch.finish();
+        let output = SimulationOutput { results, breakdown };
+        serde_wasm_bindgen::to_value(&output).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    fn batch_mut(&mut self) -> Result<&mut ChunkedBatch, JsValue> {
+        self.batch
+            .as_mut()
+            .ok_or_else(|| JsValue::from_str("Simulation already finished"))
+    }
+}
@@ -3,8 +3,9 @@ use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
 use directories::ProjectDirs;
 use ed25519_dalek::{Signer, SigningKey};
-use sha2::{Digest, Sha256};
 use std::path::PathBuf;
+use uuid::Uuid;
+use wowlab_common::build_sign_message;
 
 /// Ed25519 keypair for node authentication.
 #[derive(Clone)]
@@ -74,8 +75,8 @@ impl NodeKeypair {
             .unwrap_or_default()
             .as_secs();
 
-        let body_hash = hex::encode(Sha256::digest(body));
-        let message = format!("{}\0{}\0{}\0{}", timestamp, method, path, body_hash);
+        let nonce = Uuid::new_v4().simple().to_string();
+        let message = build_sign_message(timestamp, &nonce, method, path, body);
 
         let signature = self.signing_key.sign(message.as_bytes());
         let sig_b64 = BASE64.encode(signature.to_bytes());
@@ -84,6 +85,7 @@ impl NodeKeypair {
             key: self.public_key_b64.clone(),
             signature: sig_b64,
             timestamp: timestamp.to_string(),
+            nonce,
         }
     }
 
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 use std::path::PathBuf;
 use uuid::Uuid;
+use wowlab_engine::data::ResolverConfig;
 
 const DEFAULT_API_URL: &str = "https://api.wowlab.gg";
 const DEFAULT_SENTINEL_URL: &str = "https://sentinel.wowlab.gg";
@@ -25,6 +26,12 @@ pub struct NodeConfig {
     pub sentinel_url: String,
     #[serde(default = "default_anon_key")]
     pub anon_key: String,
+    /// Local game data directory, used for armor constants
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+    /// Game data snapshot, used instead of `data_dir`
+    #[serde(default)]
+    pub snapshot: Option<PathBuf>,
 }
 
 fn default_api_url() -> String {
@@ -46,6 +53,8 @@ impl Default for NodeConfig {
             api_url: default_api_url(),
             sentinel_url: default_sentinel_url(),
             anon_key: default_anon_key(),
+            data_dir: None,
+            snapshot: None,
         }
     }
 }
@@ -115,11 +124,33 @@ impl NodeConfig {
         let _ = writeln!(content, "sentinel_url = {}", self.sentinel_url);
         let _ = writeln!(content, "anon_key = {}", self.anon_key);
 
+        if let Some(dir) = &self.data_dir {
+            let _ = writeln!(content, "data_dir = {}", dir.display());
+        }
+        if let Some(path) = &self.snapshot {
+            let _ = writeln!(content, "snapshot = {}", path.display());
+        }
+
         if let Err(e) = std::fs::write(&path, content) {
             tracing::error!("Failed to save config: {}", e);
         }
     }
 
+    /// Where to read game data from: a snapshot (`$WOWLAB_SNAPSHOT` or
+    /// `snapshot`) wins over a data directory (`$WOWLAB_DATA_DIR` or `data_dir`)
+    pub fn game_data(&self) -> Option<ResolverConfig> {
+        let snapshot = std::env::var_os("WOWLAB_SNAPSHOT")
+            .map(PathBuf::from)
+            .or_else(|| self.snapshot.clone());
+        if let Some(path) = snapshot {
+            return Some(ResolverConfig::Snapshot { path });
+        }
+        std::env::var_os("WOWLAB_DATA_DIR")
+            .map(PathBuf::from)
+            .or_else(|| self.data_dir.clone())
+            .map(|data_dir| ResolverConfig::Local { data_dir })
+    }
+
     pub fn set_node_id(&mut self, id: Uuid) {
         self.node_id = Some(id);
         self.save();
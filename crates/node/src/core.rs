@@ -8,8 +8,8 @@ use crate::{
     queries,
     realtime::NodeRealtime,
     utils::backoff::ExponentialBackoff,
-    ChunkPayload, ConnectionStatus, NodePayload, NodeState, NodeStats, RealtimeEvent, WorkItem,
-    WorkResult, WorkerPool,
+    ChunkPayload, ConnectionStatus, NodePayload, NodeState, NodeStats, RealtimeEvent, SimData,
+    WorkItem, WorkResult, WorkerPool,
 };
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -17,6 +17,8 @@ use tokio::runtime::Handle;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
+use wowlab_common::types::SpecId;
+use wowlab_engine::data::{create_resolver, load_armor_constants};
 use wowlab_supabase::SupabaseClient;
 
 const CLAIM_POLL_INTERVAL: Duration = Duration::from_secs(3);
@@ -102,13 +104,14 @@ impl NodeCore {
         };
 
         let (event_tx, event_rx) = mpsc::channel(32);
+        let sim_data = sim_data(&runtime, &config);
 
         let core = Self {
             runtime,
             state,
             sentinel,
             supabase,
-            worker_pool: WorkerPool::new(enabled_cores as usize),
+            worker_pool: WorkerPool::new(enabled_cores as usize).with_sim_data(sim_data),
             node_id: config.node_id,
             node_name: claim::default_name(),
             max_parallel: enabled_cores,
@@ -645,15 +648,35 @@ impl NodeCore {
         let sentinel = self.sentinel.clone();
         let event_tx = self.event_tx.clone();
 
+        let result = match result.result {
+            Ok(result) => result,
+            Err(failure) => {
+                self.runtime.spawn(async move {
+                    if let Err(e) = sentinel
+                        .fail_chunk(chunk_id, failure.class, &failure.message)
+                        .await
+                    {
+                        tracing::error!("Failed to report chunk {} failure: {}", chunk_id, e);
+                    }
+                    let _ = event_tx
+                        .send(NodeCoreEvent::ChunkFailed {
+                            id: chunk_id,
+                            error: failure.message,
+                        })
+                        .await;
+                });
+                return;
+            }
+        };
+
         // Extract mean_dps from result for the event
         let mean_dps = result
-            .result
             .get("meanDps")
             .and_then(|v| v.as_f64())
             .unwrap_or(0.0) as f32;
 
         self.runtime.spawn(async move {
-            match sentinel.complete_chunk(chunk_id, result.result).await {
+            match sentinel.complete_chunk(chunk_id, result).await {
                 Ok(()) => {
                     tracing::info!("Chunk {} completed: {:.0} DPS", chunk_id, mean_dps);
                     let _ = event_tx
@@ -676,3 +699,32 @@ impl NodeCore {
         });
     }
 }
+
+/// Armor constants from the configured game data, or the level 80 default
+/// without it.
+fn sim_data(runtime: &tokio::runtime::Runtime, config: &NodeConfig) -> SimData {
+    let Some(game_data) = config.game_data() else {
+        tracing::warn!("No game data configured, using the default armor constant and no talents");
+        return SimData::default();
+    };
+    let resolver = match create_resolver(game_data) {
+        Ok(resolver) => resolver,
+        Err(e) => {
+            tracing::warn!("Failed to open game data: {}", e);
+            return SimData::default();
+        }
+    };
+
+    let mut data = SimData::new();
+    match runtime.block_on(load_armor_constants(resolver.as_ref())) {
+        Ok(constants) => data = data.with_armor_constants(constants),
+        Err(e) => tracing::warn!("Failed to load armor constants: {}", e),
+    }
+    for spec_id in [SpecId::BeastMastery, SpecId::Marksmanship] {
+        match runtime.block_on(resolver.get_trait_tree(spec_id.wow_spec_id() as i32)) {
+            Ok(tree) => data = data.with_trait_tree(spec_id, tree),
+            Err(e) => tracing::warn!("Failed to load {:?} trait tree: {}", spec_id, e),
+        }
+    }
+    data
+}
@@ -21,7 +21,7 @@ pub use config::NodeConfig;
 pub use queries::{ConfigRow, RotationRow};
 pub use realtime::{ChunkPayload, NodePayload, NodeRealtime, RealtimeEvent};
 pub use sentinel::{RegisterResponse, SentinelClient, SentinelError};
-pub use worker::{WorkItem, WorkResult, WorkerPool};
+pub use worker::{ChunkFailure, SimData, WorkItem, WorkResult, WorkerPool};
 
 use std::time::Instant;
 
@@ -13,6 +13,7 @@ pub struct SignedHeaders {
     pub key: String,
     pub signature: String,
     pub timestamp: String,
+    pub nonce: String,
 }
 
 /// Trait for signing HTTP requests to the sentinel.
@@ -78,6 +79,7 @@ impl SentinelClient {
             .header("X-Node-Key", &headers.key)
             .header("X-Node-Sig", &headers.signature)
             .header("X-Node-Ts", &headers.timestamp)
+            .header("X-Node-Nonce", &headers.nonce)
             .body(body.to_vec())
             .send()
             .await?;
@@ -161,4 +163,36 @@ impl SentinelClient {
 
         Ok(())
     }
+
+    /// Report a chunk that could not be simulated, so the sentinel can
+    /// reassign it without waiting for the chunk to go stale.
+    pub async fn fail_chunk(
+        &self,
+        chunk_id: Uuid,
+        error_class: &str,
+        message: &str,
+    ) -> Result<(), SentinelError> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            chunk_id: Uuid,
+            error_class: &'a str,
+            message: &'a str,
+        }
+
+        let body = serde_json::to_vec(&Request {
+            chunk_id,
+            error_class,
+            message,
+        })
+        .unwrap();
+        let response = self.signed_post("/chunks/fail", &body).await?;
+
+        if !response.status().is_success() {
+            let error = response.text().await.unwrap_or_default();
+            return Err(SentinelError::Api(error));
+        }
+
+        Ok(())
+    }
 }
@@ -1,5 +1,5 @@
 mod pool;
 mod runner;
 
-pub use pool::{WorkItem, WorkResult, WorkerPool};
-pub use runner::SimRunner;
+pub use pool::{ChunkFailure, WorkItem, WorkResult, WorkerPool};
+pub use runner::{SimData, SimError, SimRunner};
@@ -1,4 +1,4 @@
-use super::runner::SimRunner;
+use super::runner::{SimData, SimRunner};
 use crate::NodeStats;
 use std::sync::{
     atomic::{AtomicU32, AtomicU64, Ordering::SeqCst},
@@ -17,10 +17,18 @@ pub struct WorkItem {
 
 pub struct WorkResult {
     pub chunk_id: Uuid,
-    pub result: serde_json::Value,
+    pub result: Result<serde_json::Value, ChunkFailure>,
     pub elapsed_ms: u64,
 }
 
+/// Why a chunk could not be simulated.
+#[derive(Debug, Clone)]
+pub struct ChunkFailure {
+    /// `config`, `engine`, `serialization` or `panic`
+    pub class: &'static str,
+    pub message: String,
+}
+
 pub struct WorkerPool {
     max_workers: usize,
     active_workers: Arc<AtomicU32>,
@@ -28,6 +36,7 @@ pub struct WorkerPool {
     sims_completed: Arc<AtomicU64>,
     work_tx: Option<mpsc::Sender<WorkItem>>,
     result_rx: Option<mpsc::Receiver<WorkResult>>,
+    data: Arc<SimData>,
 }
 
 impl WorkerPool {
@@ -39,9 +48,15 @@ impl WorkerPool {
             sims_completed: Arc::new(AtomicU64::new(0)),
             work_tx: None,
             result_rx: None,
+            data: Arc::new(SimData::default()),
         }
     }
 
+    pub fn with_sim_data(mut self, data: SimData) -> Self {
+        self.data = Arc::new(data);
+        self
+    }
+
     pub fn start(&mut self, handle: &Handle) {
         let (work_tx, mut work_rx) = mpsc::channel::<WorkItem>(100);
         let (result_tx, result_rx) = mpsc::channel::<WorkResult>(100);
@@ -53,6 +68,7 @@ impl WorkerPool {
         let active = Arc::clone(&self.active_workers);
         let completed = Arc::clone(&self.completed_chunks);
         let sims = Arc::clone(&self.sims_completed);
+        let data = Arc::clone(&self.data);
 
         handle.spawn(async move {
             while let Some(item) = work_rx.recv().await {
@@ -63,6 +79,7 @@ impl WorkerPool {
                 let completed = Arc::clone(&completed);
                 let sims = Arc::clone(&sims);
                 let result_tx = result_tx.clone();
+                let data = Arc::clone(&data);
 
                 tokio::spawn(async move {
                     active.fetch_add(1, SeqCst);
@@ -73,30 +90,43 @@ impl WorkerPool {
                     let seed = item.seed_offset;
 
                     let result = tokio::task::spawn_blocking(move || {
-                        SimRunner::run(&config, iterations, seed)
+                        SimRunner::run(&config, iterations, seed, &data)
                     })
                     .await;
 
                     active.fetch_sub(1, SeqCst);
 
-                    match result {
+                    #[allow(clippy::cast_possible_truncation)]
+                    let elapsed_ms = start.elapsed().as_millis() as u64;
+                    let result = match result {
                         Ok(Ok(sim_result)) => {
-                            #[allow(clippy::cast_possible_truncation)]
-                            let elapsed_ms = start.elapsed().as_millis() as u64;
                             sims.fetch_add(u64::from(item.iterations), SeqCst);
                             completed.fetch_add(1, SeqCst);
-
-                            let _ = result_tx
-                                .send(WorkResult {
-                                    chunk_id: item.chunk_id,
-                                    result: sim_result,
-                                    elapsed_ms,
-                                })
-                                .await;
+                            Ok(sim_result)
+                        }
+                        Ok(Err(e)) => {
+                            tracing::error!("Simulation failed: {}", e);
+                            Err(ChunkFailure {
+                                class: e.class(),
+                                message: e.to_string(),
+                            })
+                        }
+                        Err(e) => {
+                            tracing::error!("Task panicked: {}", e);
+                            Err(ChunkFailure {
+                                class: "panic",
+                                message: e.to_string(),
+                            })
                         }
-                        Ok(Err(e)) => tracing::error!("Simulation failed: {}", e),
-                        Err(e) => tracing::error!("Task panicked: {}", e),
-                    }
+                    };
+
+                    let _ = result_tx
+                        .send(WorkResult {
+                            chunk_id: item.chunk_id,
+                            result,
+                            elapsed_ms,
+                        })
+                        .await;
                 });
             }
         });
@@ -1,13 +1,16 @@
 //! Simulation runner that integrates with the engine crate.
 
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::sync::Arc;
+use wowlab_common::types::data::TraitTreeFlat;
 use wowlab_common::types::{ChunkResult, SpecId};
 use wowlab_engine::actor::Player;
-use wowlab_engine::handler::SpecHandler;
+use wowlab_engine::combat::ArmorConstants;
+use wowlab_engine::handler::{create_handler_with_loadout, SpecHandler};
+use wowlab_engine::rotation::RotationBackend;
 use wowlab_engine::sim::{BatchResults, SimConfig, Simulation};
-use wowlab_engine::specs::hunter::bm::{BmHunter, TalentFlags, TierSetFlags};
-use wowlab_engine::specs::hunter::mm::MmHunter;
+use wowlab_engine::spec::TalentLoadout;
 
 /// JSON request format for distributed simulation.
 #[derive(Debug, Clone, Deserialize)]
@@ -49,6 +52,9 @@ struct PlayerConfig {
     weapon_speed: f32,
     #[serde(default)]
     weapon_damage: [f32; 2],
+    /// In-game talent loadout string
+    #[serde(default)]
+    talents: Option<String>,
 }
 
 /// Stats configuration from JSON.
@@ -99,12 +105,65 @@ struct TargetConfig {
     armor: f32,
 }
 
+impl TargetConfig {
+    /// Target level from the player's level and the configured offset
+    fn level(&self, player_level: u8) -> Result<u8, SimError> {
+        u8::try_from(player_level as i32 + self.level_diff)
+            .ok()
+            .filter(|&level| level > 0)
+            .ok_or_else(|| {
+                SimError::Config(format!("Invalid target level_diff: {}", self.level_diff))
+            })
+    }
+}
+
+/// Game data simulations need beyond their request.
+#[derive(Debug, Clone, Default)]
+pub struct SimData {
+    armor_constants: ArmorConstants,
+    trait_trees: HashMap<SpecId, TraitTreeFlat>,
+}
+
+impl SimData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Armor constants by target level
+    pub fn with_armor_constants(mut self, constants: ArmorConstants) -> Self {
+        self.armor_constants = constants;
+        self
+    }
+
+    /// Trait tree used to decode a spec's loadout strings
+    pub fn with_trait_tree(mut self, spec_id: SpecId, tree: TraitTreeFlat) -> Self {
+        self.trait_trees.insert(spec_id, tree);
+        self
+    }
+
+    /// Decode a loadout string against the spec's trait tree
+    fn decode_loadout(&self, spec_id: SpecId, loadout: &str) -> Result<TalentLoadout, SimError> {
+        let tree = self.trait_trees.get(&spec_id).ok_or_else(|| {
+            SimError::Engine(format!(
+                "No trait tree loaded for {:?}; configure game data to use talents",
+                spec_id
+            ))
+        })?;
+        TalentLoadout::decode(loadout, tree).map_err(SimError::Config)
+    }
+}
+
 /// Convert BatchResults to ChunkResult
 fn to_chunk_result(result: BatchResults) -> ChunkResult {
     ChunkResult {
         iterations: result.iterations,
         mean_dps: result.mean_dps,
-        std_dps: result.std_dev,
+        // A single iteration has no variance; NaN isn't valid JSON
+        std_dps: if result.std_dev.is_finite() {
+            result.std_dev
+        } else {
+            0.0
+        },
         min_dps: result.min_dps,
         max_dps: result.max_dps,
     }
@@ -119,6 +178,7 @@ impl SimRunner {
     /// * `config_json` - JSON string containing SimRequest
     /// * `iterations` - Number of simulation iterations to run
     /// * `base_seed` - Base seed for RNG (offset by chunk for distribution)
+    /// * `data` - Armor constants and trait trees from game data
     ///
     /// # Returns
     /// JSON value containing simulation results
@@ -126,6 +186,7 @@ impl SimRunner {
         config_json: &str,
         iterations: u32,
         base_seed: u64,
+        data: &SimData,
     ) -> Result<serde_json::Value, SimError> {
         // Parse the request
         let request: SimRequest =
@@ -146,33 +207,38 @@ impl SimRunner {
                 request.rotation.clone()
             };
 
-        // Create spec handler with rotation
-        let handler: Arc<dyn SpecHandler> = match spec_id {
-            SpecId::BeastMastery => {
-                let h = BmHunter::new(&rotation_json, TalentFlags::empty(), TierSetFlags::NONE)
-                    .map_err(|e| SimError::Engine(format!("Failed to create BM handler: {}", e)))?;
-                Arc::new(h)
-            }
-            SpecId::Marksmanship => {
-                let h = MmHunter::new(&rotation_json)
-                    .map_err(|e| SimError::Engine(format!("Failed to create MM handler: {}", e)))?;
-                Arc::new(h)
-            }
-            _ => {
-                return Err(SimError::Engine(format!(
-                    "Spec {:?} not implemented",
-                    spec_id
-                )))
-            }
+        // Create spec handler with rotation and talents
+        let loadout = match request.player.talents.as_deref().map(str::trim) {
+            Some(talents) if !talents.is_empty() => data.decode_loadout(spec_id, talents)?,
+            _ => TalentLoadout::default(),
         };
+        let (handler, report) = create_handler_with_loadout(
+            spec_id,
+            &rotation_json,
+            RotationBackend::default(),
+            &loadout,
+        )
+        .map_err(|e| SimError::Config(format!("Failed to create handler: {}", e)))?;
+        for talent in &report.unimplemented {
+            tracing::warn!(
+                "Talent {} ({}) is not implemented",
+                talent.name,
+                talent.spell_id
+            );
+        }
 
         // Initialize spec-specific abilities
         handler.init_player(&mut player);
 
         // Create sim config
-        let config = SimConfig::default()
+        let mut config = SimConfig::default()
             .with_duration(request.duration)
-            .with_seed(base_seed);
+            .with_seed(base_seed)
+            .with_target_level(request.target.level(player.level)?)
+            .with_armor_constants(data.armor_constants.clone());
+        if request.target.armor > 0.0 {
+            config = config.with_target_armor(request.target.armor);
+        }
 
         // Run batch simulation
         let results = run_batch(handler, config, player, iterations);
@@ -240,7 +306,7 @@ fn create_player(spec_id: SpecId, config: &PlayerConfig) -> Result<Player, SimEr
 
 /// Run batch simulation with proper spec initialization per iteration
 fn run_batch(
-    handler: Arc<dyn wowlab_engine::handler::SpecHandler>,
+    handler: Arc<dyn SpecHandler>,
     config: SimConfig,
     player_template: Player,
     iterations: u32,
@@ -270,3 +336,15 @@ pub enum SimError {
     #[error("Serialization error: {0}")]
     Serialization(String),
 }
+
+impl SimError {
+    /// Error class reported to the sentinel. Config errors are deterministic
+    /// and will fail the same way on every node.
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "config",
+            Self::Engine(_) => "engine",
+            Self::Serialization(_) => "serialization",
+        }
+    }
+}
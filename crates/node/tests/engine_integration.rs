@@ -1,6 +1,9 @@
 //! Integration test for engine integration.
 
-use wowlab_node::worker::SimRunner;
+use wowlab_common::types::data::{PointLimits, TraitTreeFlat};
+use wowlab_common::types::SpecId;
+use wowlab_engine::combat::ArmorConstants;
+use wowlab_node::worker::{SimData, SimError, SimRunner};
 
 const TEST_CONFIG: &str = r#"{
     "player": {
@@ -42,7 +45,7 @@ const TEST_CONFIG: &str = r#"{
 
 #[test]
 fn test_engine_integration() {
-    let result = SimRunner::run(TEST_CONFIG, 100, 12345);
+    let result = SimRunner::run(TEST_CONFIG, 100, 12345, &SimData::default());
 
     match result {
         Ok(value) => {
@@ -74,8 +77,8 @@ fn test_engine_integration() {
 
 #[test]
 fn test_invalid_config() {
-    let result = SimRunner::run("not valid json", 100, 12345);
-    assert!(result.is_err());
+    let result = SimRunner::run("not valid json", 100, 12345, &SimData::default());
+    assert_eq!(result.unwrap_err().class(), "config");
 }
 
 #[test]
@@ -99,10 +102,21 @@ fn test_unknown_spec() {
         }
     }"#;
 
-    let result = SimRunner::run(bad_config, 100, 12345);
+    let result = SimRunner::run(bad_config, 100, 12345, &SimData::default());
     assert!(result.is_err(), "Should fail with unknown spec");
 }
 
+#[test]
+fn test_broken_rotation_is_config_error() {
+    let mut config: serde_json::Value = serde_json::from_str(TEST_CONFIG).unwrap();
+    config["rotation"] = r#"{"actions": [{"cast": "no_such_spell"}]}"#.into();
+    let config = config.to_string();
+
+    // Fails the same way on every node, so the sentinel stops retrying it
+    let result = SimRunner::run(&config, 10, 42, &SimData::default());
+    assert_eq!(result.unwrap_err().class(), "config");
+}
+
 #[test]
 fn test_minimal_config() {
     // Test with minimal required fields
@@ -121,6 +135,76 @@ fn test_minimal_config() {
         }
     }"#;
 
-    let result = SimRunner::run(minimal_config, 10, 42);
+    let result = SimRunner::run(minimal_config, 10, 42, &SimData::default());
     assert!(result.is_ok(), "Minimal config should work: {:?}", result);
 }
+
+#[test]
+fn test_invalid_level_diff() {
+    let config = r#"{
+        "player": {
+            "spec": "beast_mastery",
+            "stats": {
+                "strength": 0,
+                "agility": 5000,
+                "intellect": 0
+            }
+        },
+        "duration": 5.0,
+        "target": {
+            "level_diff": -100,
+            "max_health": 1000000.0
+        }
+    }"#;
+
+    let result = SimRunner::run(config, 10, 42, &SimData::default());
+    assert!(result.is_err(), "Should fail with a target below level 1");
+}
+
+#[test]
+fn test_armor_constants_reach_the_sim() {
+    let mean_dps = |constants: ArmorConstants| {
+        let data = SimData::new().with_armor_constants(constants);
+        let value = SimRunner::run(TEST_CONFIG, 20, 12345, &data).unwrap();
+        value["meanDps"].as_f64().unwrap()
+    };
+
+    // A huge constant for the level 83 target leaves its armor with no effect
+    let default = mean_dps(ArmorConstants::default());
+    let weak_armor = mean_dps(ArmorConstants::new().with_level(83, 1.0e9));
+    assert!(
+        weak_armor > default,
+        "expected more DPS with weaker armor: {} vs {}",
+        weak_armor,
+        default
+    );
+}
+
+#[test]
+fn test_talents_need_a_trait_tree() {
+    let config = TEST_CONFIG.replace(
+        r#""weapon_damage": [500, 700]"#,
+        r#""weapon_damage": [500, 700], "talents": "not a loadout!""#,
+    );
+
+    // Without game data the loadout can't be decoded on this node
+    let result = SimRunner::run(&config, 10, 42, &SimData::default());
+    assert!(matches!(result, Err(SimError::Engine(_))), "{:?}", result);
+
+    // With the tree, a malformed string fails the same way everywhere
+    let tree = TraitTreeFlat {
+        spec_id: 253,
+        spec_name: "Beast Mastery".to_string(),
+        class_name: "Hunter".to_string(),
+        tree_id: 1,
+        all_node_ids: Vec::new(),
+        nodes: Vec::new(),
+        edges: Vec::new(),
+        sub_trees: Vec::new(),
+        point_limits: PointLimits::default(),
+        tree_hash: None,
+    };
+    let data = SimData::new().with_trait_tree(SpecId::BeastMastery, tree);
+    let result = SimRunner::run(&config, 10, 42, &data);
+    assert!(matches!(result, Err(SimError::Config(_))), "{:?}", result);
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use tokio_cron_scheduler::{Job, JobScheduler};
 
-use crate::scheduler::{maintenance, reclaim};
+use crate::scheduler::{maintenance, reclaim, verify};
 use crate::state::ServerState;
 use crate::telemetry;
 
@@ -27,6 +27,7 @@ pub async fn run(state: Arc<ServerState>) -> Result<(), Box<dyn std::error::Erro
 
     let jobs: Vec<Arc<dyn CronJob>> = vec![
         Arc::new(reclaim::ReclaimChunksJob),
+        Arc::new(verify::ExpireReplicasJob),
         Arc::new(maintenance::CleanupStaleDataJob),
         Arc::new(telemetry::RecordGaugesJob),
     ];
@@ -1,12 +1,17 @@
+use std::sync::Arc;
+
 use axum::body::Body;
-use axum::extract::Request;
+use axum::extract::{Request, State};
 use axum::http::StatusCode;
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
-use sha2::{Digest, Sha256};
+use wowlab_common::build_sign_message;
+
+use crate::state::ServerState;
+use crate::utils::nonce::NonceError;
 
 /// Verified node identity, inserted into request extensions by auth middleware.
 #[derive(Clone, Debug)]
@@ -15,14 +20,45 @@ pub struct VerifiedNode {
     pub public_key: String,
 }
 
-const MAX_CLOCK_SKEW: u64 = 300; // 5 minutes
+pub const MAX_CLOCK_SKEW: u64 = 300; // 5 minutes
+/// Live nonces each registered node may hold
+pub const MAX_NONCES_PER_KEY: usize = 5_000;
+/// Live nonces each key may hold on the register route
+pub const MAX_REGISTER_NONCES_PER_KEY: usize = 10;
+/// Live nonces across all keys on the register route
+pub const MAX_REGISTER_NONCES: usize = 1_000;
 const MAX_BODY_SIZE: usize = 1024 * 1024; // 1 MB
+const MAX_NONCE_LEN: usize = 64;
 
-/// Axum middleware that verifies Ed25519 node signatures.
+/// Axum middleware that verifies Ed25519 signatures from registered nodes.
 ///
-/// Expects headers: X-Node-Key (base64), X-Node-Sig (base64), X-Node-Ts (unix seconds).
-/// Message format: "{timestamp}\0{method}\0{pathname}\0{body_sha256_hex}"
-pub async fn verify_node(request: Request, next: Next) -> Response {
+/// Expects headers: X-Node-Key (base64), X-Node-Sig (base64), X-Node-Ts (unix seconds),
+/// X-Node-Nonce (unique per request).
+/// Message format: "{timestamp}\0{nonce}\0{method}\0{pathname}\0{body_sha256_hex}"
+pub async fn verify_node(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    verify(state, request, next, true).await
+}
+
+/// Like [`verify_node`], but for the register route, which accepts keys that
+/// aren't known yet and tracks their nonces in a separate, smaller store.
+pub async fn verify_new_node(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    verify(state, request, next, false).await
+}
+
+async fn verify(
+    state: Arc<ServerState>,
+    request: Request,
+    next: Next,
+    registered: bool,
+) -> Response {
     let (parts, body) = request.into_parts();
 
     let pubkey_b64 = match parts.headers.get("X-Node-Key") {
@@ -37,6 +73,13 @@ pub async fn verify_node(request: Request, next: Next) -> Response {
         Some(v) => v.to_str().unwrap_or("").to_string(),
         None => return auth_error("Missing X-Node-Ts"),
     };
+    let nonce = match parts.headers.get("X-Node-Nonce") {
+        Some(v) => v.to_str().unwrap_or("").to_string(),
+        None => return auth_error("Missing X-Node-Nonce"),
+    };
+    if nonce.is_empty() || nonce.len() > MAX_NONCE_LEN {
+        return auth_error("Invalid nonce");
+    }
 
     // Validate timestamp freshness
     let timestamp: u64 = match ts_str.parse() {
@@ -73,16 +116,13 @@ pub async fn verify_node(request: Request, next: Next) -> Response {
         Err(_) => return auth_error("Body too large"),
     };
 
-    // Hash body with SHA-256
-    let body_hash = hex::encode(Sha256::digest(&body_bytes));
-
-    // Build canonical message: "timestamp\0METHOD\0/path\0bodyHash"
-    let message = format!(
-        "{}\0{}\0{}\0{}",
-        ts_str,
-        parts.method,
+    // Build canonical message: "timestamp\0nonce\0METHOD\0/path\0bodyHash"
+    let message = build_sign_message(
+        timestamp,
+        &nonce,
+        parts.method.as_str(),
         parts.uri.path(),
-        body_hash,
+        &body_bytes,
     );
 
     // Verify Ed25519 signature
@@ -99,6 +139,58 @@ pub async fn verify_node(request: Request, next: Next) -> Response {
         return auth_error("Invalid signature");
     }
 
+    // Only registered keys may take up room in the main nonce store
+    let nonces = if registered {
+        let known = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM nodes WHERE public_key = $1)",
+        )
+        .bind(&pubkey_b64)
+        .fetch_one(&state.db)
+        .await;
+        match known {
+            Ok(true) => &state.nonces,
+            Ok(false) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    axum::Json(serde_json::json!({ "error": "Node not found" })),
+                )
+                    .into_response()
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to look up node key");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(serde_json::json!({ "error": "Database error" })),
+                )
+                    .into_response();
+            }
+        }
+    } else {
+        &state.register_nonces
+    };
+
+    // Reject replays; only checked once the signature proves the key owner sent it
+    match nonces.check(&pubkey_b64, &nonce, timestamp, now) {
+        Ok(()) => {}
+        Err(NonceError::Replayed) => return auth_error("Nonce already used"),
+        Err(NonceError::KeyFull) => {
+            tracing::warn!(key = %pubkey_b64, "Nonce quota reached for key, rejecting signed request");
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                axum::Json(serde_json::json!({ "error": "Too many requests" })),
+            )
+                .into_response();
+        }
+        Err(NonceError::Full) => {
+            tracing::warn!("Nonce store full, rejecting signed request");
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                axum::Json(serde_json::json!({ "error": "Too many requests" })),
+            )
+                .into_response();
+        }
+    }
+
     // Rebuild request with body restored and verified node in extensions
     let mut request = Request::from_parts(parts, Body::from(body_bytes));
     request.extensions_mut().insert(VerifiedNode {
@@ -15,18 +15,30 @@ pub async fn run(
     state: Arc<ServerState>,
     shutdown: CancellationToken,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Node API routes (require Ed25519 auth)
-    let node_api = Router::new()
+    // Registration accepts any signed key
+    let register = Router::new()
         .route("/nodes/register", post(routes::nodes::register))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::verify_new_node,
+        ));
+
+    // Node API routes (require Ed25519 auth from a registered node)
+    let node_api = Router::new()
         .route("/nodes/heartbeat", post(routes::nodes::heartbeat))
         .route("/chunks/complete", post(routes::chunks::complete))
-        .layer(middleware::from_fn(auth::verify_node));
+        .route("/chunks/fail", post(routes::chunks::fail))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::verify_node,
+        ));
 
     let app = Router::new()
         .route("/", get(routes::index::handler))
         .route("/favicon.ico", get(routes::favicon::handler))
         .route("/status", get(routes::status::handler))
         .route("/metrics", get(routes::metrics::handler))
+        .merge(register)
         .merge(node_api)
         .layer(TraceLayer::new_for_http())
         .with_state(state);
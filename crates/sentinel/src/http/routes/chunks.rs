@@ -5,11 +5,24 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
 use serde::Deserialize;
-use serde_json::{json, Value};
+use serde_json::json;
+use wowlab_common::ChunkResult;
 
 use crate::http::auth::VerifiedNode;
+use crate::scheduler::{completion, verify};
 use crate::state::ServerState;
 
+/// Config failures on a single chunk before its whole job is failed.
+/// Config errors are deterministic, so retrying elsewhere won't help.
+const MAX_CONFIG_FAILURES: i32 = 3;
+
+/// Failed attempts of any kind on a single chunk before its whole job is
+/// failed, so engine errors and panics that follow the chunk around stop too.
+const MAX_ATTEMPTS: i32 = 10;
+
+/// Longest error message stored on a chunk.
+const MAX_ERROR_LEN: usize = 2000;
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CompleteRequest {
@@ -17,17 +30,6 @@ pub struct CompleteRequest {
     result: ChunkResult,
 }
 
-#[derive(Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ChunkResult {
-    mean_dps: f64,
-    #[allow(dead_code)]
-    std_dps: f64,
-    min_dps: f64,
-    max_dps: f64,
-    iterations: i64,
-}
-
 pub async fn complete(
     State(state): State<Arc<ServerState>>,
     Extension(node): Extension<VerifiedNode>,
@@ -59,20 +61,14 @@ pub async fn complete(
     };
 
     // Store the full result as JSON for the chunk
-    let result_json = json!({
-        "meanDps": payload.result.mean_dps,
-        "stdDps": payload.result.std_dps,
-        "minDps": payload.result.min_dps,
-        "maxDps": payload.result.max_dps,
-        "iterations": payload.result.iterations,
-    });
+    let result_json = serde_json::to_value(&payload.result).unwrap_or_default();
 
     // Update chunk status
-    let chunk_row = sqlx::query_as::<_, (uuid::Uuid, uuid::Uuid)>(
+    let chunk_row = sqlx::query_as::<_, (uuid::Uuid, uuid::Uuid, Option<uuid::Uuid>)>(
         r#"UPDATE jobs_chunks
            SET status = 'completed', result = $1, completed_at = now()
            WHERE id = $2 AND node_id = $3 AND status = 'running'
-           RETURNING id, job_id"#,
+           RETURNING id, job_id, verifies_chunk_id"#,
     )
     .bind(&result_json)
     .bind(payload.chunk_id)
@@ -81,7 +77,10 @@ pub async fn complete(
     .await;
 
     let job_id = match chunk_row {
-        Ok(Some((_, job_id))) => job_id,
+        Ok(Some((_, job_id, Some(original_id)))) => {
+            return verify_replica(&state, job_id, original_id, node_id).await;
+        }
+        Ok(Some((_, job_id, None))) => job_id,
         Ok(None) => {
             // Check if chunk exists for better error message
             let existing = sqlx::query_as::<_, (String, uuid::Uuid)>(
@@ -105,6 +104,15 @@ pub async fn complete(
                             Json(json!({ "success": true, "alreadyCompleted": true, "jobComplete": false })),
                         )
                             .into_response()
+                    } else if status == "cancelled" {
+                        // Target-error job finished before this chunk did
+                        (
+                            StatusCode::OK,
+                            Json(
+                                json!({ "success": true, "cancelled": true, "jobComplete": true }),
+                            ),
+                        )
+                            .into_response()
                     } else {
                         (
                             StatusCode::BAD_REQUEST,
@@ -138,18 +146,127 @@ pub async fn complete(
         }
     };
 
-    // Query all completed chunks for this job
-    let completed_chunks = sqlx::query_as::<_, (Value,)>(
-        "SELECT result FROM jobs_chunks WHERE job_id = $1 AND status = 'completed'",
-    )
-    .bind(job_id)
-    .fetch_all(&state.db)
-    .await;
+    // Spot-check results from untrusted nodes
+    match verify::maybe_replicate(&state.db, payload.chunk_id, node_id, state.verify_fraction).await
+    {
+        Ok(true) => tracing::debug!(chunk_id = %payload.chunk_id, "Queued verification replica"),
+        Ok(false) => {}
+        Err(e) => tracing::error!(error = %e, "Failed to queue verification replica"),
+    }
+
+    match completion::settle_job(&state.db, job_id).await {
+        Ok(job_complete) => {
+            metrics::counter!("chunks_completed").increment(1);
+            (
+                StatusCode::OK,
+                Json(json!({ "success": true, "jobComplete": job_complete })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to settle job");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Database error" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Compare a completed verification replica with the chunk it re-ran, then
+/// settle the job it was holding up.
+async fn verify_replica(
+    state: &ServerState,
+    job_id: uuid::Uuid,
+    original_id: uuid::Uuid,
+    node_id: uuid::Uuid,
+) -> Response {
+    let settled = async {
+        let verdict = verify::on_replica_completed(&state.db, original_id).await?;
+        let job_complete = completion::settle_job(&state.db, job_id).await?;
+        Ok::<_, sqlx::Error>((verdict, job_complete))
+    };
+    match settled.await {
+        Ok((verdict, job_complete)) => {
+            metrics::counter!(crate::telemetry::CHUNKS_VERIFIED).increment(1);
+            if matches!(verdict, Some(v) if v != verify::Verdict::Agreed) {
+                tracing::warn!(chunk_id = %original_id, %node_id, ?verdict, "Verification mismatch");
+                metrics::counter!(crate::telemetry::CHUNK_MISMATCHES).increment(1);
+            }
+            (
+                StatusCode::OK,
+                Json(json!({ "success": true, "jobComplete": job_complete })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to verify chunk");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Database error" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailRequest {
+    chunk_id: uuid::Uuid,
+    error_class: ErrorClass,
+    message: String,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum ErrorClass {
+    Config,
+    Engine,
+    Serialization,
+    Panic,
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorClass {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Engine => "engine",
+            Self::Serialization => "serialization",
+            Self::Panic => "panic",
+            Self::Unknown => "unknown",
+        }
+    }
+}
 
-    let chunks = match completed_chunks {
-        Ok(rows) => rows,
+/// Record a failed chunk and put it back in the queue.
+///
+/// Fails the whole job once a chunk has hit `MAX_CONFIG_FAILURES` config
+/// errors or `MAX_ATTEMPTS` failures in total.
+pub async fn fail(
+    State(state): State<Arc<ServerState>>,
+    Extension(node): Extension<VerifiedNode>,
+    Json(payload): Json<FailRequest>,
+) -> Response {
+    let node_row = sqlx::query_as::<_, (uuid::Uuid,)>("SELECT id FROM nodes WHERE public_key = $1")
+        .bind(&node.public_key)
+        .fetch_optional(&state.db)
+        .await;
+
+    let node_id = match node_row {
+        Ok(Some((id,))) => id,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "Node not found" })),
+            )
+                .into_response()
+        }
         Err(e) => {
-            tracing::error!(error = %e, "Failed to query completed chunks");
+            tracing::error!(error = %e, "Failed to query node");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({ "error": "Database error" })),
@@ -158,91 +275,107 @@ pub async fn complete(
         }
     };
 
-    // Compute total iterations from completed chunks
-    let total_iterations: i64 = chunks
-        .iter()
-        .map(|(r,)| r.get("iterations").and_then(|v| v.as_i64()).unwrap_or(0))
-        .sum();
+    let message: String = payload.message.chars().take(MAX_ERROR_LEN).collect();
+    let class = payload.error_class;
 
-    // Check if all chunks are done
-    let pending_count: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM jobs_chunks WHERE job_id = $1 AND status != 'completed'",
+    match record_failure(&state, payload.chunk_id, node_id, class, &message).await {
+        Ok(Some(job_failed)) => {
+            tracing::warn!(
+                chunk_id = %payload.chunk_id,
+                node_id = %node_id,
+                class = class.as_str(),
+                error = %message,
+                "Chunk failed"
+            );
+            metrics::counter!(crate::telemetry::CHUNKS_FAILED).increment(1);
+            if job_failed {
+                metrics::counter!(crate::telemetry::JOBS_FAILED).increment(1);
+            }
+            (
+                StatusCode::OK,
+                Json(json!({ "success": true, "jobFailed": job_failed })),
+            )
+                .into_response()
+        }
+        Ok(None) => (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": "Chunk is not running on this node" })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to record chunk failure");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Database error" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Requeue a failed chunk, failing its job on repeated config errors or too
+/// many attempts.
+///
+/// Returns whether the job was failed, or `None` if the chunk isn't running
+/// on this node.
+async fn record_failure(
+    state: &ServerState,
+    chunk_id: uuid::Uuid,
+    node_id: uuid::Uuid,
+    class: ErrorClass,
+    message: &str,
+) -> Result<Option<bool>, sqlx::Error> {
+    // One transaction, so the scheduler never sees the chunk as pending
+    // if its job is about to be failed
+    let mut tx = state.db.begin().await?;
+
+    let row = sqlx::query_as::<_, (uuid::Uuid, i32, i32)>(
+        r#"UPDATE jobs_chunks
+           SET status = 'pending', node_id = NULL, claimed_at = NULL,
+               attempts = attempts + 1,
+               config_failures = config_failures + CASE WHEN $1 = 'config' THEN 1 ELSE 0 END,
+               last_error_class = $1, last_error = $2, last_failed_node_id = $4
+           WHERE id = $3 AND node_id = $4 AND status = 'running'
+           RETURNING job_id, attempts, config_failures"#,
     )
-    .bind(job_id)
-    .fetch_one(&state.db)
-    .await
-    .unwrap_or(1);
-
-    if pending_count == 0 && !chunks.is_empty() {
-        // All chunks complete — aggregate results
-        let mean_dps = if total_iterations > 0 {
-            chunks
-                .iter()
-                .map(|(r,)| {
-                    let m = r.get("meanDps").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                    let i = r.get("iterations").and_then(|v| v.as_i64()).unwrap_or(0) as f64;
-                    m * i
-                })
-                .sum::<f64>()
-                / total_iterations as f64
-        } else {
-            0.0
-        };
-
-        let min_dps = chunks
-            .iter()
-            .filter_map(|(r,)| r.get("minDps").and_then(|v| v.as_f64()))
-            .fold(f64::INFINITY, f64::min);
-
-        let max_dps = chunks
-            .iter()
-            .filter_map(|(r,)| r.get("maxDps").and_then(|v| v.as_f64()))
-            .fold(f64::NEG_INFINITY, f64::max);
-
-        let aggregated = json!({
-            "meanDps": mean_dps,
-            "minDps": min_dps,
-            "maxDps": max_dps,
-            "totalIterations": total_iterations,
-            "chunksCompleted": chunks.len(),
-        });
+    .bind(class.as_str())
+    .bind(message)
+    .bind(chunk_id)
+    .bind(node_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((job_id, attempts, config_failures)) = row else {
+        return Ok(None);
+    };
 
-        let _ = sqlx::query(
+    let job_failed = config_failures >= MAX_CONFIG_FAILURES || attempts >= MAX_ATTEMPTS;
+    if job_failed {
+        let error = json!({
+            "error": message,
+            "errorClass": class.as_str(),
+            "attempts": attempts,
+        });
+        sqlx::query(
             r#"UPDATE jobs
-               SET status = 'completed', result = $1, completed_iterations = $2, completed_at = now()
-               WHERE id = $3"#,
+               SET status = 'failed', result = $1, completed_at = now()
+               WHERE id = $2 AND status IN ('pending', 'running')"#,
         )
-        .bind(&aggregated)
-        .bind(total_iterations)
+        .bind(&error)
         .bind(job_id)
-        .execute(&state.db)
-        .await;
-
-        metrics::counter!("chunks_completed").increment(1);
+        .execute(&mut *tx)
+        .await?;
 
-        return (
-            StatusCode::OK,
-            Json(json!({ "success": true, "jobComplete": true })),
+        sqlx::query(
+            r#"UPDATE jobs_chunks
+               SET status = 'failed'
+               WHERE job_id = $1 AND status IN ('pending', 'running')"#,
         )
-            .into_response();
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await?;
     }
 
-    // Job still in progress — update iterations and ensure status is running
-    let _ = sqlx::query(
-        r#"UPDATE jobs
-           SET status = 'running', completed_iterations = $1
-           WHERE id = $2 AND status IN ('pending', 'running')"#,
-    )
-    .bind(total_iterations)
-    .bind(job_id)
-    .execute(&state.db)
-    .await;
-
-    metrics::counter!("chunks_completed").increment(1);
-
-    (
-        StatusCode::OK,
-        Json(json!({ "success": true, "jobComplete": false })),
-    )
-        .into_response()
+    tx.commit().await?;
+    Ok(Some(job_failed))
 }
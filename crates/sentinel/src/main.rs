@@ -9,6 +9,7 @@ use tokio::sync::RwLock;
 use tokio_util::sync::CancellationToken;
 
 use wowlab_sentinel::state::ServerState;
+use wowlab_sentinel::utils::nonce::NonceStore;
 use wowlab_sentinel::{bot, cron, http, presence, scheduler};
 
 fn load_env() {
@@ -42,6 +43,12 @@ async fn main() {
         .await
         .expect("Failed to connect to database");
 
+    let verify_fraction = std::env::var("VERIFY_FRACTION")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(scheduler::verify::DEFAULT_VERIFY_FRACTION)
+        .clamp(0.0, 1.0);
+
     let filters = Arc::new(RwLock::new(HashMap::new()));
     let state = Arc::new(ServerState {
         db,
@@ -50,6 +57,13 @@ async fn main() {
         prometheus,
         shard_manager: OnceLock::new(),
         last_scheduler_tick: AtomicU64::new(0),
+        verify_fraction,
+        nonces: NonceStore::new(http::auth::MAX_CLOCK_SKEW, http::auth::MAX_NONCES_PER_KEY),
+        register_nonces: NonceStore::new(
+            http::auth::MAX_CLOCK_SKEW,
+            http::auth::MAX_REGISTER_NONCES_PER_KEY,
+        )
+        .with_capacity(http::auth::MAX_REGISTER_NONCES),
     });
 
     wowlab_sentinel::telemetry::init();
@@ -48,11 +48,7 @@ pub async fn assign_pending_chunks(
             None => continue,
         };
 
-        let target = nodes
-            .iter_mut()
-            .filter(|n| is_eligible(n, job, &permissions, &state.filters))
-            .filter(|n| n.backlog < n.capacity)
-            .max_by_key(|n| n.capacity - n.backlog);
+        let target = pick_node(&mut nodes, chunk, job, &permissions, &state.filters);
 
         if let Some(node) = target {
             assignments.push(Assignment {
@@ -76,6 +72,31 @@ pub async fn assign_pending_chunks(
     Ok(())
 }
 
+/// Pick the eligible node with the most available capacity for a chunk.
+///
+/// Nodes that just reported the chunk as failed are only used when no other
+/// node can take it. Verification replicas never go to a node owned by a
+/// user whose result they check.
+pub fn pick_node<'a>(
+    nodes: &'a mut [OnlineNode],
+    chunk: &PendingChunk,
+    job: &JobInfo,
+    permissions: &[NodePermission],
+    filters: &crate::utils::filter_refresh::FilterMap,
+) -> Option<&'a mut OnlineNode> {
+    nodes
+        .iter_mut()
+        .filter(|n| is_eligible(n, job, permissions, filters))
+        .filter(|n| n.backlog < n.capacity)
+        .filter(|n| !chunk.excluded_user_ids.contains(&n.user_id))
+        .max_by_key(|n| {
+            (
+                chunk.last_failed_node_id != Some(n.id),
+                n.capacity - n.backlog,
+            )
+        })
+}
+
 /// Check if a node is eligible to run a chunk based on the job's access settings.
 pub fn is_eligible(
     node: &OnlineNode,
@@ -141,7 +162,7 @@ async fn fetch_online_nodes(db: &PgPool) -> Result<Vec<OnlineNode>, sqlx::Error>
          FROM public.nodes n
          LEFT JOIN auth.identities i
            ON i.user_id = n.user_id AND i.provider = 'discord'
-         WHERE n.status = 'online'",
+         WHERE n.status = 'online' AND n.quarantined_at IS NULL",
     )
     .fetch_all(db)
     .await?;
@@ -0,0 +1,76 @@
+//! Job completion once its chunks, and any verification replicas of them, are
+//! done.
+//!
+//! Jobs don't finalize while a replica is still pending or running, so a
+//! verification mismatch can still correct the chunk results the job result
+//! is pooled from.
+
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::precision;
+
+/// Pool a job's results after one of its chunks or replicas finished, and
+/// finalize it once nothing is outstanding.
+///
+/// Returns whether the job is complete.
+pub async fn settle_job(db: &PgPool, job_id: Uuid) -> Result<bool, sqlx::Error> {
+    // Target-error jobs decide for themselves when they're done
+    if let Some(job_complete) = precision::on_chunk_completed(db, job_id).await? {
+        return Ok(job_complete);
+    }
+
+    let chunks = sqlx::query_as::<_, (Value,)>(
+        "SELECT result FROM public.jobs_chunks
+         WHERE job_id = $1 AND status = 'completed' AND verifies_chunk_id IS NULL",
+    )
+    .bind(job_id)
+    .fetch_all(db)
+    .await?;
+
+    // Pool all completed chunks into the job result
+    let aggregated = precision::pool_chunk_results(chunks.iter().map(|(r,)| r));
+    let total_iterations = i64::from(aggregated.total_iterations);
+
+    // Unfinished chunks, and replicas still waiting to check one
+    let outstanding: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM public.jobs_chunks
+         WHERE job_id = $1
+           AND CASE WHEN verifies_chunk_id IS NULL THEN status != 'completed'
+                    ELSE status IN ('pending', 'running') END",
+    )
+    .bind(job_id)
+    .fetch_one(db)
+    .await?;
+
+    if outstanding == 0 && !chunks.is_empty() {
+        // A completed job is pooled again in case verification corrected
+        // one of its chunks
+        sqlx::query(
+            "UPDATE public.jobs
+             SET status = 'completed', result = $1, completed_iterations = $2,
+                 completed_at = COALESCE(completed_at, now())
+             WHERE id = $3 AND status IN ('pending', 'running', 'completed')",
+        )
+        .bind(serde_json::to_value(&aggregated).unwrap_or_default())
+        .bind(total_iterations)
+        .bind(job_id)
+        .execute(db)
+        .await?;
+        return Ok(true);
+    }
+
+    // Job still in progress — update iterations and ensure status is running
+    sqlx::query(
+        "UPDATE public.jobs
+         SET status = 'running', completed_iterations = $1
+         WHERE id = $2 AND status IN ('pending', 'running')",
+    )
+    .bind(total_iterations)
+    .bind(job_id)
+    .execute(db)
+    .await?;
+
+    Ok(false)
+}
@@ -1,6 +1,9 @@
 pub mod assign;
+pub mod completion;
 pub mod maintenance;
+pub mod precision;
 pub mod reclaim;
+pub mod verify;
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -61,6 +64,10 @@ async fn listen_and_assign(
 }
 
 async fn process_pending(state: &ServerState) {
+    if let Err(e) = precision::dispatch_initial_waves(state).await {
+        tracing::error!(error = %e, "Failed to dispatch target-error waves");
+    }
+
     match fetch_pending_chunks(state).await {
         Ok(pending) if !pending.is_empty() => {
             tracing::debug!(count = pending.len(), "Found pending chunks");
@@ -80,9 +87,18 @@ async fn process_pending(state: &ServerState) {
 
 async fn fetch_pending_chunks(state: &ServerState) -> Result<Vec<PendingChunk>, sqlx::Error> {
     sqlx::query_as::<_, PendingChunk>(
-        "SELECT id, job_id FROM public.jobs_chunks
-         WHERE status = 'pending' AND node_id IS NULL
-         ORDER BY created_at ASC
+        "SELECT c.id, c.job_id, c.last_failed_node_id,
+                ARRAY(
+                    SELECT n.user_id
+                    FROM public.jobs_chunks r
+                    JOIN public.nodes n ON n.id = r.node_id
+                    WHERE n.user_id IS NOT NULL
+                      AND (r.id = c.verifies_chunk_id
+                           OR (r.verifies_chunk_id = c.verifies_chunk_id AND r.id <> c.id))
+                ) AS excluded_user_ids
+         FROM public.jobs_chunks c
+         WHERE c.status = 'pending' AND c.node_id IS NULL
+         ORDER BY c.verifies_chunk_id IS NOT NULL, c.created_at ASC
          LIMIT 100",
     )
     .fetch_all(&state.db)
@@ -93,4 +109,9 @@ async fn fetch_pending_chunks(state: &ServerState) -> Result<Vec<PendingChunk>,
 pub struct PendingChunk {
     pub id: Uuid,
     pub job_id: Uuid,
+    /// Node that most recently reported this chunk as failed
+    pub last_failed_node_id: Option<Uuid>,
+    /// For verification replicas, owners of the nodes that already ran the
+    /// chunk
+    pub excluded_user_ids: Vec<Uuid>,
 }
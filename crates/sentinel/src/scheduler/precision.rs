@@ -0,0 +1,291 @@
+//! Target-error jobs: dispatch chunks in waves until the mean DPS is precise enough.
+//!
+//! A job with `target_error` set gets no chunks up front. The scheduler creates
+//! an initial wave, and every chunk completion pools the results so far to
+//! decide whether to finalize, wait for in-flight chunks or dispatch another
+//! wave. `total_iterations` caps how many iterations are ever dispatched.
+
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use wowlab_common::{ChunkResult, SimulationResult};
+
+use crate::state::ServerState;
+
+/// Iterations per chunk dispatched for target-error jobs
+pub const CHUNK_ITERATIONS: u32 = 1000;
+
+/// Iterations in the first wave, before there's any variance to go on
+pub const INITIAL_WAVE_ITERATIONS: u32 = 10_000;
+
+/// What to do with a target-error job after a chunk completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// Precision reached or iteration cap used up
+    Finalize,
+    /// Not precise yet, but chunks are still in flight
+    Wait,
+    /// Dispatch this many more iterations
+    Dispatch(u32),
+}
+
+/// Decide the next step for a target-error job.
+///
+/// `dispatched` counts every iteration handed out so far, `outstanding` the
+/// chunks still pending or running.
+pub fn decide(
+    result: &SimulationResult,
+    target_error: f64,
+    dispatched: u32,
+    outstanding: u32,
+    max_iterations: u32,
+) -> Decision {
+    let error = result.relative_error();
+    if result.total_iterations > 1 && error <= target_error {
+        return Decision::Finalize;
+    }
+    if outstanding > 0 {
+        return Decision::Wait;
+    }
+    let remaining = max_iterations.saturating_sub(dispatched);
+    if remaining == 0 {
+        return Decision::Finalize;
+    }
+
+    // The error shrinks with the square root of the iteration count
+    let completed = f64::from(result.total_iterations);
+    let needed = if error.is_finite() && completed > 0.0 {
+        ((error / target_error).powi(2) * completed - completed).ceil()
+    } else {
+        0.0
+    };
+    let needed = (needed.min(f64::from(u32::MAX)) as u32).max(CHUNK_ITERATIONS);
+    Decision::Dispatch(needed.min(remaining))
+}
+
+/// Split a wave into `(iterations, seed_offset)` chunks, with seeds
+/// continuing from the iterations already dispatched.
+pub fn wave_chunks(dispatched: u32, iterations: u32) -> Vec<(i32, i32)> {
+    (0..iterations.div_ceil(CHUNK_ITERATIONS))
+        .map(|i| {
+            let offset = i * CHUNK_ITERATIONS;
+            (
+                CHUNK_ITERATIONS.min(iterations - offset) as i32,
+                (dispatched + offset) as i32,
+            )
+        })
+        .collect()
+}
+
+/// Pool stored chunk results into a job result.
+pub fn pool_chunk_results<'a>(results: impl IntoIterator<Item = &'a Value>) -> SimulationResult {
+    let mut pooled = SimulationResult::new();
+    for result in results {
+        match ChunkResult::deserialize(result) {
+            Ok(chunk) => pooled.merge_chunk(&chunk),
+            Err(e) => tracing::warn!(error = %e, "Skipping malformed chunk result"),
+        }
+    }
+    pooled
+}
+
+/// Create the first wave of chunks for new target-error jobs.
+///
+/// Each job is claimed by moving it to running in the same transaction that
+/// inserts its wave, so concurrent passes never dispatch a job twice.
+pub async fn dispatch_initial_waves(state: &ServerState) -> Result<usize, sqlx::Error> {
+    let mut tx = state.db.begin().await?;
+
+    let jobs = sqlx::query_as::<_, TargetErrorJob>(
+        "UPDATE public.jobs
+         SET status = 'running'
+         WHERE id IN (
+             SELECT j.id FROM public.jobs j
+             WHERE j.target_error IS NOT NULL AND j.status = 'pending'
+               AND NOT EXISTS (SELECT 1 FROM public.jobs_chunks c WHERE c.job_id = j.id)
+             FOR UPDATE SKIP LOCKED
+         ) AND status = 'pending'
+         RETURNING id, config_hash, total_iterations, target_error, status",
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for job in &jobs {
+        let iterations = INITIAL_WAVE_ITERATIONS.min(job.max_iterations());
+        insert_wave(&mut *tx, job, 0, iterations).await?;
+        tracing::debug!(job_id = %job.id, iterations, "Dispatched initial wave");
+    }
+
+    tx.commit().await?;
+    Ok(jobs.len())
+}
+
+/// Advance a target-error job after one of its chunks or replicas finished.
+///
+/// Returns `None` for fixed-iteration jobs, otherwise whether the job is now
+/// complete.
+pub async fn on_chunk_completed(db: &PgPool, job_id: Uuid) -> Result<Option<bool>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    // Lock the job so concurrent completions can't both dispatch a wave
+    let job = sqlx::query_as::<_, TargetErrorJob>(
+        "SELECT id, config_hash, total_iterations, target_error, status
+         FROM public.jobs
+         WHERE id = $1 AND target_error IS NOT NULL
+         FOR UPDATE",
+    )
+    .bind(job_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(job) = job else {
+        return Ok(None);
+    };
+    if !matches!(job.status.as_str(), "pending" | "running") {
+        return Ok(Some(job.status == "completed"));
+    }
+
+    let chunks = sqlx::query_as::<_, (String, i32, Option<Value>, bool)>(
+        "SELECT status, iterations, result, verifies_chunk_id IS NOT NULL
+         FROM public.jobs_chunks
+         WHERE job_id = $1",
+    )
+    .bind(job_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let in_flight = |status: &str| matches!(status, "pending" | "running");
+    let (replicas, chunks): (Vec<_>, Vec<_>) =
+        chunks.into_iter().partition(|(_, _, _, replica)| *replica);
+
+    let result = pool_chunk_results(
+        chunks
+            .iter()
+            .filter(|(status, _, _, _)| status == "completed")
+            .filter_map(|(_, _, result, _)| result.as_ref()),
+    );
+    let dispatched: i64 = chunks.iter().map(|(_, n, _, _)| i64::from(*n)).sum();
+    let outstanding = chunks
+        .iter()
+        .filter(|(status, ..)| in_flight(status))
+        .count();
+
+    let decision = decide(
+        &result,
+        job.target_error,
+        u32::try_from(dispatched).unwrap_or(u32::MAX),
+        outstanding as u32,
+        job.max_iterations(),
+    );
+    // Replicas may still correct a chunk the result is pooled from
+    let decision = if decision == Decision::Finalize
+        && replicas.iter().any(|(status, ..)| in_flight(status))
+    {
+        Decision::Wait
+    } else {
+        decision
+    };
+
+    let job_complete = match decision {
+        Decision::Finalize => {
+            finalize(&mut tx, job_id, &result).await?;
+            tracing::info!(
+                %job_id,
+                iterations = result.total_iterations,
+                error = result.relative_error(),
+                "Target-error job finished"
+            );
+            true
+        }
+        Decision::Wait | Decision::Dispatch(_) => {
+            if let Decision::Dispatch(iterations) = decision {
+                insert_wave(&mut *tx, &job, dispatched as u32, iterations).await?;
+                tracing::debug!(%job_id, iterations, "Dispatched wave");
+            }
+            sqlx::query(
+                "UPDATE public.jobs
+                 SET status = 'running', completed_iterations = $1
+                 WHERE id = $2",
+            )
+            .bind(i64::from(result.total_iterations))
+            .bind(job_id)
+            .execute(&mut *tx)
+            .await?;
+            false
+        }
+    };
+
+    tx.commit().await?;
+    Ok(Some(job_complete))
+}
+
+/// Store the job result and cancel chunks that are no longer needed.
+async fn finalize(
+    tx: &mut Transaction<'_, Postgres>,
+    job_id: Uuid,
+    result: &SimulationResult,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE public.jobs
+         SET status = 'completed', result = $1, completed_iterations = $2, completed_at = now()
+         WHERE id = $3",
+    )
+    .bind(serde_json::to_value(result).unwrap_or_default())
+    .bind(i64::from(result.total_iterations))
+    .bind(job_id)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE public.jobs_chunks
+         SET status = 'cancelled'
+         WHERE job_id = $1 AND status IN ('pending', 'running')",
+    )
+    .bind(job_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+async fn insert_wave<'e, E>(
+    executor: E,
+    job: &TargetErrorJob,
+    dispatched: u32,
+    iterations: u32,
+) -> Result<(), sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let (counts, offsets): (Vec<i32>, Vec<i32>) =
+        wave_chunks(dispatched, iterations).into_iter().unzip();
+
+    sqlx::query(
+        "INSERT INTO public.jobs_chunks (job_id, config_hash, iterations, seed_offset, status)
+         SELECT $1, $2, data.iterations, data.seed_offset, 'pending'
+         FROM unnest($3::int[], $4::int[]) AS data(iterations, seed_offset)",
+    )
+    .bind(job.id)
+    .bind(&job.config_hash)
+    .bind(&counts)
+    .bind(&offsets)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct TargetErrorJob {
+    id: Uuid,
+    config_hash: String,
+    total_iterations: i32,
+    target_error: f64,
+    status: String,
+}
+
+impl TargetErrorJob {
+    fn max_iterations(&self) -> u32 {
+        u32::try_from(self.total_iterations).unwrap_or(0)
+    }
+}
@@ -0,0 +1,368 @@
+//! Spot-check verification of chunk results from untrusted nodes.
+//!
+//! Simulations are deterministic for a given config, iteration count and seed,
+//! so a chunk re-run on a second node must produce the same result. When a
+//! node runs someone else's chunk, a fraction of its results (all of them for
+//! low-reputation nodes) are replicated onto a node from a different user.
+//! Matches raise both nodes' reputation. A mismatch alone can't tell which
+//! node is wrong, so it sends the chunk to a third user's node and only the
+//! node that loses the vote is penalised, eventually quarantining it.
+//!
+//! Replicas hold up their job until they finish, and expire after
+//! `REPLICA_TIMEOUT` or once their job has ended.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use wowlab_common::ChunkResult;
+
+use super::completion;
+use crate::cron::CronJob;
+use crate::state::ServerState;
+
+/// Fraction of untrusted chunks replicated when `VERIFY_FRACTION` isn't set
+pub const DEFAULT_VERIFY_FRACTION: f64 = 0.05;
+
+/// Nodes below this reputation have every untrusted chunk verified
+pub const LOW_REPUTATION: f64 = 0.8;
+
+/// Reputation gained per matching verification
+const MATCH_REWARD: f64 = 0.05;
+
+/// Reputation kept after a mismatch
+const MISMATCH_FACTOR: f64 = 0.5;
+
+/// Mismatches before a low-reputation node is quarantined
+pub const QUARANTINE_MISMATCHES: i32 = 3;
+
+/// Relative tolerance when comparing replicated results
+const TOLERANCE: f64 = 1e-9;
+
+/// How long a replica may wait for a node before it's dropped
+const REPLICA_TIMEOUT: &str = "15 minutes";
+
+/// Outcome of comparing a chunk's result with its replicas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Every run agrees
+    Agreed,
+    /// Two runs disagree; a third decides
+    Disputed,
+    /// Every run but this one (0 is the original) agrees
+    Outvoted(usize),
+    /// No majority
+    Inconclusive,
+}
+
+/// Whether a chunk completed by a node should be replicated.
+///
+/// Nodes are trusted with their own users' jobs. `chunk_id` picks the
+/// chunks to spot-check, so the choice is stable across retries.
+pub fn should_verify(own_job: bool, reputation: f64, chunk_id: Uuid, fraction: f64) -> bool {
+    if own_job {
+        return false;
+    }
+    if reputation < LOW_REPUTATION {
+        return true;
+    }
+    let roll = (chunk_id.as_u128() % 1_000_000) as f64 / 1_000_000.0;
+    roll < fraction
+}
+
+/// Whether two runs of the same chunk agree.
+pub fn results_match(a: &ChunkResult, b: &ChunkResult) -> bool {
+    let close = |x: f64, y: f64| (x - y).abs() <= TOLERANCE * x.abs().max(y.abs()).max(1.0);
+    a.iterations == b.iterations
+        && close(a.mean_dps, b.mean_dps)
+        && close(a.min_dps, b.min_dps)
+        && close(a.max_dps, b.max_dps)
+}
+
+/// Reputation after a verification, in `[0, 1]`.
+pub fn updated_reputation(reputation: f64, matched: bool) -> f64 {
+    if matched {
+        (reputation + MATCH_REWARD).min(1.0)
+    } else {
+        reputation * MISMATCH_FACTOR
+    }
+}
+
+/// Compare the runs of a chunk: the original result first, then its replicas
+/// in the order they finished.
+pub fn tally(runs: &[ChunkResult]) -> Verdict {
+    let Some((original, replicas)) = runs.split_first() else {
+        return Verdict::Agreed;
+    };
+    if replicas.iter().all(|run| results_match(original, run)) {
+        return Verdict::Agreed;
+    }
+    if runs.len() < 3 {
+        return Verdict::Disputed;
+    }
+    let outvoted = (0..runs.len()).find(|&i| {
+        let mut others = runs.iter().enumerate().filter(|&(j, _)| j != i);
+        let (_, first) = others.next().unwrap();
+        others.all(|(_, run)| results_match(first, run)) && !results_match(first, &runs[i])
+    });
+    outvoted.map_or(Verdict::Inconclusive, Verdict::Outvoted)
+}
+
+/// Whether a node has disagreed often enough to stop receiving work.
+pub fn should_quarantine(reputation: f64, mismatches: i32) -> bool {
+    mismatches >= QUARANTINE_MISMATCHES && reputation < LOW_REPUTATION
+}
+
+/// Queue a replica of a freshly completed chunk if it should be verified.
+///
+/// Returns whether a replica was queued.
+pub async fn maybe_replicate(
+    db: &PgPool,
+    chunk_id: Uuid,
+    node_id: Uuid,
+    fraction: f64,
+) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query_as::<_, (f64, bool)>(
+        "SELECT n.reputation, n.user_id IS NOT DISTINCT FROM j.user_id
+         FROM public.jobs_chunks c
+         JOIN public.jobs j ON j.id = c.job_id
+         JOIN public.nodes n ON n.id = $2
+         WHERE c.id = $1",
+    )
+    .bind(chunk_id)
+    .bind(node_id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some((reputation, own_job)) = row else {
+        return Ok(false);
+    };
+    if !should_verify(own_job, reputation, chunk_id, fraction) {
+        return Ok(false);
+    }
+
+    Ok(queue_replica(db, chunk_id).await? > 0)
+}
+
+/// Queue another run of a chunk, unless its job has already ended.
+async fn queue_replica<'e, E>(executor: E, chunk_id: Uuid) -> Result<u64, sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let result = sqlx::query(
+        "INSERT INTO public.jobs_chunks
+             (job_id, config_hash, iterations, seed_offset, status, verifies_chunk_id)
+         SELECT c.job_id, c.config_hash, c.iterations, c.seed_offset, 'pending', c.id
+         FROM public.jobs_chunks c
+         JOIN public.jobs j ON j.id = c.job_id
+         WHERE c.id = $1 AND j.status IN ('pending', 'running')",
+    )
+    .bind(chunk_id)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Compare a completed replica with the chunk it verifies and every earlier
+/// replica, then settle the vote.
+///
+/// Returns the verdict, or `None` if the original result is gone (for example
+/// because it was already requeued).
+pub async fn on_replica_completed(
+    db: &PgPool,
+    original_id: Uuid,
+) -> Result<Option<Verdict>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let original = sqlx::query_as::<_, (Option<Uuid>, Option<Value>)>(
+        "SELECT node_id, result FROM public.jobs_chunks
+         WHERE id = $1 AND status = 'completed'
+         FOR UPDATE",
+    )
+    .bind(original_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((Some(original_node_id), Some(original_result))) = original else {
+        return Ok(None);
+    };
+    let replicas = sqlx::query_as::<_, (Uuid, Option<Uuid>, Option<Value>)>(
+        "SELECT id, node_id, result FROM public.jobs_chunks
+         WHERE verifies_chunk_id = $1 AND status = 'completed'
+         ORDER BY completed_at, id",
+    )
+    .bind(original_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    // (node, result) of every run, the original first
+    let mut runs = Vec::with_capacity(replicas.len() + 1);
+    for (node_id, result) in std::iter::once((Some(original_node_id), Some(original_result)))
+        .chain(replicas.iter().map(|(_, n, r)| (*n, r.clone())))
+    {
+        let result = result.and_then(|r| serde_json::from_value::<ChunkResult>(r).ok());
+        if let (Some(node_id), Some(result)) = (node_id, result) {
+            runs.push((node_id, result));
+        }
+    }
+    if runs.len() < 2 {
+        return Ok(None);
+    }
+    let results: Vec<ChunkResult> = runs.iter().map(|(_, r)| r.clone()).collect();
+    let verdict = tally(&results);
+
+    match verdict {
+        Verdict::Agreed => {
+            for (node_id, _) in &runs {
+                record_verification(&mut tx, *node_id, true).await?;
+            }
+        }
+        Verdict::Disputed => {
+            // Reputation is left alone until a third node breaks the tie
+            queue_replica(&mut *tx, original_id).await?;
+        }
+        Verdict::Outvoted(loser) => {
+            for (i, (node_id, _)) in runs.iter().enumerate() {
+                record_verification(&mut tx, *node_id, i != loser).await?;
+            }
+            if loser == 0 {
+                // The job result is pooled from the majority's result
+                let winner = serde_json::to_value(&results[1]).unwrap_or_default();
+                sqlx::query("UPDATE public.jobs_chunks SET result = $1 WHERE id = $2")
+                    .bind(winner)
+                    .bind(original_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        Verdict::Inconclusive => {
+            // No run can be trusted: drop them and recompute the chunk while
+            // its job is still collecting results
+            let replica_ids: Vec<Uuid> = replicas.iter().map(|(id, _, _)| *id).collect();
+            sqlx::query("UPDATE public.jobs_chunks SET status = 'cancelled' WHERE id = ANY($1)")
+                .bind(&replica_ids)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "UPDATE public.jobs_chunks
+                 SET status = 'pending', node_id = NULL, claimed_at = NULL,
+                     result = NULL, completed_at = NULL, last_failed_node_id = $2
+                 WHERE id = $1
+                   AND EXISTS (SELECT 1 FROM public.jobs j
+                               WHERE j.id = jobs_chunks.job_id
+                                 AND j.status IN ('pending', 'running'))",
+            )
+            .bind(original_id)
+            .bind(original_node_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(Some(verdict))
+}
+
+/// Update a node's reputation after one of its results was checked, and
+/// quarantine it if it keeps losing.
+async fn record_verification(
+    tx: &mut Transaction<'_, Postgres>,
+    node_id: Uuid,
+    matched: bool,
+) -> Result<(), sqlx::Error> {
+    let row = sqlx::query_as::<_, (f64, i32, bool)>(
+        "SELECT reputation, mismatched_chunks, quarantined_at IS NOT NULL
+         FROM public.nodes
+         WHERE id = $1
+         FOR UPDATE",
+    )
+    .bind(node_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+    let Some((reputation, mismatches, quarantined)) = row else {
+        return Ok(());
+    };
+
+    let reputation = updated_reputation(reputation, matched);
+    let mismatches = mismatches + i32::from(!matched);
+    let quarantine = should_quarantine(reputation, mismatches);
+
+    sqlx::query(
+        "UPDATE public.nodes
+         SET reputation = $1,
+             verified_chunks = verified_chunks + 1,
+             mismatched_chunks = $2,
+             quarantined_at = CASE WHEN $3 THEN COALESCE(quarantined_at, now())
+                                   ELSE quarantined_at END
+         WHERE id = $4",
+    )
+    .bind(reputation)
+    .bind(mismatches)
+    .bind(quarantine)
+    .bind(node_id)
+    .execute(&mut **tx)
+    .await?;
+
+    if quarantine && !quarantined {
+        tracing::warn!(%node_id, reputation, mismatches, "Node quarantined");
+        metrics::counter!(crate::telemetry::NODES_QUARANTINED).increment(1);
+    }
+    Ok(())
+}
+
+pub struct ExpireReplicasJob;
+
+#[async_trait]
+impl CronJob for ExpireReplicasJob {
+    fn name(&self) -> &'static str {
+        "expire_replicas"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "*/30 * * * * *"
+    }
+
+    async fn run(&self, state: &ServerState) {
+        expire_replicas(&state.db).await;
+    }
+}
+
+/// Cancel replicas whose job has ended or that no node picked up in time, and
+/// settle the jobs they were holding up.
+pub async fn expire_replicas(db: &PgPool) {
+    match do_expire(db).await {
+        Ok(count) if count > 0 => {
+            tracing::info!(count, "Expired verification replicas");
+            metrics::counter!(crate::telemetry::REPLICAS_EXPIRED).increment(count);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to expire verification replicas");
+        }
+    }
+}
+
+async fn do_expire(db: &PgPool) -> Result<u64, sqlx::Error> {
+    let expired = sqlx::query_as::<_, (Uuid,)>(&format!(
+        "UPDATE public.jobs_chunks c
+         SET status = 'cancelled'
+         FROM public.jobs j
+         WHERE j.id = c.job_id
+           AND c.verifies_chunk_id IS NOT NULL
+           AND c.status IN ('pending', 'running')
+           AND (j.status NOT IN ('pending', 'running')
+                OR c.created_at < now() - interval '{REPLICA_TIMEOUT}')
+         RETURNING c.job_id"
+    ))
+    .fetch_all(db)
+    .await?;
+
+    let mut job_ids: Vec<Uuid> = expired.iter().map(|(id,)| *id).collect();
+    job_ids.sort_unstable();
+    job_ids.dedup();
+    for job_id in job_ids {
+        completion::settle_job(db, job_id).await?;
+    }
+
+    Ok(expired.len() as u64)
+}
@@ -7,6 +7,7 @@ use poise::serenity_prelude::{ConnectionStage, ShardManager};
 use sqlx::PgPool;
 
 use crate::utils::filter_refresh::FilterMap;
+use crate::utils::nonce::NonceStore;
 
 pub struct ServerState {
     pub db: PgPool,
@@ -15,6 +16,12 @@ pub struct ServerState {
     pub prometheus: PrometheusHandle,
     pub shard_manager: OnceLock<Arc<ShardManager>>,
     pub last_scheduler_tick: AtomicU64,
+    /// Fraction of untrusted chunks re-run on a second node
+    pub verify_fraction: f64,
+    /// Nonces of recently signed requests from registered nodes
+    pub nonces: NonceStore,
+    /// Nonces of recently signed register requests
+    pub register_nonces: NonceStore,
 }
 
 impl ServerState {
@@ -4,11 +4,17 @@ use crate::cron::CronJob;
 use crate::state::ServerState;
 
 pub const CHUNKS_ASSIGNED: &str = "sentinel_chunks_assigned_total";
+pub const CHUNKS_FAILED: &str = "sentinel_chunks_failed_total";
 pub const CHUNKS_PENDING: &str = "sentinel_chunks_pending";
 pub const CHUNKS_RECLAIMED: &str = "sentinel_chunks_reclaimed_total";
 pub const CHUNKS_RUNNING: &str = "sentinel_chunks_running";
+pub const CHUNKS_VERIFIED: &str = "sentinel_chunks_verified_total";
+pub const CHUNK_MISMATCHES: &str = "sentinel_chunk_mismatches_total";
+pub const JOBS_FAILED: &str = "sentinel_jobs_failed_total";
 pub const NODES_ONLINE: &str = "sentinel_nodes_online";
 pub const NODES_MARKED_OFFLINE: &str = "sentinel_nodes_marked_offline_total";
+pub const NODES_QUARANTINED: &str = "sentinel_nodes_quarantined_total";
+pub const REPLICAS_EXPIRED: &str = "sentinel_replicas_expired_total";
 pub const STALE_DATA_CLEANUPS: &str = "sentinel_stale_data_cleanups_total";
 pub const UPTIME_SECONDS: &str = "sentinel_uptime_seconds";
 
@@ -19,8 +25,14 @@ pub fn init() {
 
     for name in [
         CHUNKS_ASSIGNED,
+        CHUNKS_FAILED,
         CHUNKS_RECLAIMED,
+        CHUNKS_VERIFIED,
+        CHUNK_MISMATCHES,
+        JOBS_FAILED,
         NODES_MARKED_OFFLINE,
+        NODES_QUARANTINED,
+        REPLICAS_EXPIRED,
         STALE_DATA_CLEANUPS,
     ] {
         metrics::counter!(name).absolute(0);
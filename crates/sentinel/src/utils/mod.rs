@@ -4,4 +4,5 @@ pub mod discord;
 pub mod embed;
 pub mod filter_refresh;
 pub mod meta;
+pub mod nonce;
 pub mod sys;
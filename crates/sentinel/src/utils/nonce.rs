@@ -0,0 +1,119 @@
+//! Seen-nonce store for replay protection of signed node requests.
+//!
+//! A signed request is valid while its timestamp is within the allowed clock
+//! skew, so each nonce only has to be remembered until its timestamp falls out
+//! of that window. Each key may hold at most `per_key` live nonces, so one
+//! busy or hostile node can't crowd out the others; past that its requests
+//! are rejected rather than forgetting nonces that could still be replayed.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Why a nonce was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonceError {
+    /// Already used by this key within the window
+    Replayed,
+    /// This key has too many live nonces to track another one
+    KeyFull,
+    /// Too many live nonces across all keys to track another one
+    Full,
+}
+
+pub struct NonceStore {
+    window: u64,
+    per_key: usize,
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Nonces currently remembered, by public key
+    seen: HashMap<String, HashSet<String>>,
+    /// Same entries ordered by request timestamp, for expiry
+    by_time: BTreeSet<(u64, String, String)>,
+}
+
+impl NonceStore {
+    /// Remember each nonce until its timestamp is more than `window` seconds
+    /// old, tracking at most `per_key` for any one key.
+    pub fn new(window: u64, per_key: usize) -> Self {
+        Self {
+            window,
+            per_key,
+            capacity: usize::MAX,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Also cap the nonces tracked across all keys.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Record a nonce for a key, failing if it was already used.
+    ///
+    /// `timestamp` is the request's signed timestamp and `now` the current
+    /// time, both in unix seconds.
+    pub fn check(
+        &self,
+        public_key: &str,
+        nonce: &str,
+        timestamp: u64,
+        now: u64,
+    ) -> Result<(), NonceError> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.expire(now.saturating_sub(self.window));
+
+        let held = inner.seen.get(public_key);
+        if held.is_some_and(|nonces| nonces.contains(nonce)) {
+            return Err(NonceError::Replayed);
+        }
+        if held.map_or(0, HashSet::len) >= self.per_key {
+            return Err(NonceError::KeyFull);
+        }
+        if inner.by_time.len() >= self.capacity {
+            return Err(NonceError::Full);
+        }
+
+        inner
+            .by_time
+            .insert((timestamp, public_key.to_string(), nonce.to_string()));
+        inner
+            .seen
+            .entry(public_key.to_string())
+            .or_default()
+            .insert(nonce.to_string());
+        Ok(())
+    }
+
+    /// Number of nonces currently remembered
+    pub fn len(&self) -> usize {
+        self.inner.lock().map(|i| i.by_time.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Inner {
+    /// Forget nonces whose timestamp is before `cutoff`; requests that old
+    /// are rejected by the timestamp check anyway.
+    fn expire(&mut self, cutoff: u64) {
+        while let Some(first) = self.by_time.first() {
+            if first.0 >= cutoff {
+                break;
+            }
+            let (_, key, nonce) = self.by_time.pop_first().unwrap();
+            if let Some(nonces) = self.seen.get_mut(&key) {
+                nonces.remove(&nonce);
+                if nonces.is_empty() {
+                    self.seen.remove(&key);
+                }
+            }
+        }
+    }
+}
@@ -5,7 +5,10 @@ use poise::serenity_prelude::GuildId;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use wowlab_sentinel::scheduler::assign::{is_eligible, JobInfo, NodePermission, OnlineNode};
+use wowlab_sentinel::scheduler::assign::{
+    is_eligible, pick_node, JobInfo, NodePermission, OnlineNode,
+};
+use wowlab_sentinel::scheduler::PendingChunk;
 use wowlab_sentinel::utils::bloom::BloomFilter;
 use wowlab_sentinel::utils::filter_refresh::{FilterMap, GuildFilter};
 
@@ -217,3 +220,75 @@ fn unknown_access_type_non_owner_rejected() {
 
     assert!(!is_eligible(&n, &j, &[], &filters));
 }
+
+// --- Node selection ---
+
+fn chunk(last_failed_node_id: Option<Uuid>) -> PendingChunk {
+    PendingChunk {
+        id: Uuid::from_u128(300),
+        job_id: JOB_A,
+        last_failed_node_id,
+        excluded_user_ids: Vec::new(),
+    }
+}
+
+#[test]
+fn pick_node_avoids_last_failed_node() {
+    let mut nodes = vec![node(NODE_A, USER_A, None), node(NODE_B, USER_A, None)];
+    nodes[1].backlog = 3;
+    let j = job(USER_A, None, None);
+    let filters = empty_filters();
+
+    let picked = pick_node(&mut nodes, &chunk(None), &j, &[], &filters).unwrap();
+    assert_eq!(picked.id, NODE_A);
+
+    let picked = pick_node(&mut nodes, &chunk(Some(NODE_A)), &j, &[], &filters).unwrap();
+    assert_eq!(picked.id, NODE_B);
+}
+
+#[test]
+fn pick_node_falls_back_to_last_failed_node() {
+    let mut nodes = vec![node(NODE_A, USER_A, None)];
+    let j = job(USER_A, None, None);
+    let filters = empty_filters();
+
+    let picked = pick_node(&mut nodes, &chunk(Some(NODE_A)), &j, &[], &filters).unwrap();
+    assert_eq!(picked.id, NODE_A);
+}
+
+#[test]
+fn pick_node_skips_verified_users_nodes() {
+    let mut nodes = vec![node(NODE_A, USER_A, None), node(NODE_B, USER_B, None)];
+    let j = job(USER_A, Some("public"), None);
+    let filters = empty_filters();
+    let replica = PendingChunk {
+        excluded_user_ids: vec![USER_A],
+        ..chunk(None)
+    };
+
+    let picked = pick_node(&mut nodes, &replica, &j, &[], &filters).unwrap();
+    assert_eq!(picked.id, NODE_B);
+
+    let mut only_a = vec![node(NODE_A, USER_A, None)];
+    assert!(pick_node(&mut only_a, &replica, &j, &[], &filters).is_none());
+}
+
+#[test]
+fn pick_node_sends_tiebreak_to_a_third_user() {
+    let user_c = Uuid::from_u128(102);
+    let node_c = Uuid::from_u128(3);
+    let mut nodes = vec![
+        node(NODE_A, USER_A, None),
+        node(NODE_B, USER_B, None),
+        node(node_c, user_c, None),
+    ];
+    let j = job(user_c, Some("public"), None);
+    let filters = empty_filters();
+    let tiebreak = PendingChunk {
+        excluded_user_ids: vec![USER_A, USER_B],
+        ..chunk(None)
+    };
+
+    let picked = pick_node(&mut nodes, &tiebreak, &j, &[], &filters).unwrap();
+    assert_eq!(picked.id, node_c);
+}
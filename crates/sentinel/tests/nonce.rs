@@ -0,0 +1,60 @@
+use wowlab_sentinel::utils::nonce::{NonceError, NonceStore};
+
+const NOW: u64 = 1_800_000_000;
+
+#[test]
+fn rejects_replayed_nonce() {
+    let store = NonceStore::new(300, 100);
+    assert_eq!(store.check("key", "abc", NOW, NOW), Ok(()));
+    assert_eq!(
+        store.check("key", "abc", NOW, NOW + 1),
+        Err(NonceError::Replayed)
+    );
+}
+
+#[test]
+fn nonces_are_per_key() {
+    let store = NonceStore::new(300, 100);
+    assert_eq!(store.check("a", "abc", NOW, NOW), Ok(()));
+    assert_eq!(store.check("b", "abc", NOW, NOW), Ok(()));
+    assert_eq!(store.len(), 2);
+}
+
+#[test]
+fn forgets_nonces_outside_window() {
+    let store = NonceStore::new(300, 100);
+    assert_eq!(store.check("key", "abc", NOW, NOW), Ok(()));
+    assert_eq!(store.check("key", "def", NOW + 301, NOW + 301), Ok(()));
+    assert_eq!(store.len(), 1);
+}
+
+#[test]
+fn rejects_key_over_quota() {
+    let store = NonceStore::new(300, 2);
+    assert_eq!(store.check("key", "a", NOW, NOW), Ok(()));
+    assert_eq!(store.check("key", "b", NOW, NOW), Ok(()));
+    assert_eq!(store.check("key", "c", NOW, NOW), Err(NonceError::KeyFull));
+
+    // Room frees up once the old nonces expire
+    assert_eq!(store.check("key", "c", NOW + 301, NOW + 301), Ok(()));
+}
+
+#[test]
+fn full_key_does_not_block_others() {
+    let store = NonceStore::new(300, 2);
+    assert_eq!(store.check("a", "1", NOW, NOW), Ok(()));
+    assert_eq!(store.check("a", "2", NOW, NOW), Ok(()));
+    assert_eq!(store.check("a", "3", NOW, NOW), Err(NonceError::KeyFull));
+
+    assert_eq!(store.check("b", "1", NOW, NOW), Ok(()));
+    assert_eq!(store.check("b", "2", NOW, NOW), Ok(()));
+}
+
+#[test]
+fn rejects_when_full() {
+    let store = NonceStore::new(300, 2).with_capacity(3);
+    assert_eq!(store.check("a", "1", NOW, NOW), Ok(()));
+    assert_eq!(store.check("a", "2", NOW, NOW), Ok(()));
+    assert_eq!(store.check("b", "1", NOW, NOW), Ok(()));
+    assert_eq!(store.check("c", "1", NOW, NOW), Err(NonceError::Full));
+}
@@ -0,0 +1,71 @@
+use wowlab_common::{ChunkResult, SimulationResult};
+
+use wowlab_sentinel::scheduler::precision::{decide, wave_chunks, Decision, CHUNK_ITERATIONS};
+
+/// A pooled result with the given relative error (95% CI half-width / mean).
+fn result(iterations: u32, relative_error: f64) -> SimulationResult {
+    let mean_dps = 100_000.0;
+    let std_error = relative_error * mean_dps / wowlab_common::Z_95;
+    let mut result = SimulationResult::new();
+    result.merge_chunk(&ChunkResult {
+        iterations,
+        mean_dps,
+        std_dps: std_error * f64::from(iterations).sqrt(),
+        min_dps: 90_000.0,
+        max_dps: 110_000.0,
+    });
+    result
+}
+
+// --- Decisions ---
+
+#[test]
+fn finalizes_once_precise() {
+    let r = result(10_000, 0.0009);
+    assert_eq!(decide(&r, 0.001, 12_000, 2, 1_000_000), Decision::Finalize);
+}
+
+#[test]
+fn waits_for_in_flight_chunks() {
+    let r = result(5_000, 0.004);
+    assert_eq!(decide(&r, 0.001, 10_000, 5, 1_000_000), Decision::Wait);
+}
+
+#[test]
+fn dispatches_estimated_iterations() {
+    // Halving the error takes four times the iterations
+    let r = result(10_000, 0.002);
+    assert_eq!(
+        decide(&r, 0.001, 10_000, 0, 1_000_000),
+        Decision::Dispatch(30_000)
+    );
+}
+
+#[test]
+fn dispatches_at_least_one_chunk() {
+    let r = result(10_000, 0.001_000_1);
+    assert_eq!(
+        decide(&r, 0.001, 10_000, 0, 1_000_000),
+        Decision::Dispatch(CHUNK_ITERATIONS)
+    );
+}
+
+#[test]
+fn respects_iteration_cap() {
+    let r = result(10_000, 0.01);
+    assert_eq!(
+        decide(&r, 0.001, 10_000, 0, 25_000),
+        Decision::Dispatch(15_000)
+    );
+    assert_eq!(decide(&r, 0.001, 25_000, 0, 25_000), Decision::Finalize);
+}
+
+// --- Waves ---
+
+#[test]
+fn wave_continues_seed_offsets() {
+    assert_eq!(
+        wave_chunks(10_000, 2_500),
+        [(1000, 10_000), (1000, 11_000), (500, 12_000)]
+    );
+}
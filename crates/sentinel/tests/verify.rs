@@ -0,0 +1,111 @@
+use uuid::Uuid;
+use wowlab_common::ChunkResult;
+
+use wowlab_sentinel::scheduler::verify::{
+    results_match, should_quarantine, should_verify, tally, updated_reputation, Verdict,
+    LOW_REPUTATION, QUARANTINE_MISMATCHES,
+};
+
+fn result(mean_dps: f64) -> ChunkResult {
+    ChunkResult {
+        iterations: 1000,
+        mean_dps,
+        std_dps: 1500.0,
+        min_dps: mean_dps - 5000.0,
+        max_dps: mean_dps + 5000.0,
+    }
+}
+
+// --- Sampling ---
+
+#[test]
+fn own_jobs_are_never_verified() {
+    assert!(!should_verify(true, 0.0, Uuid::from_u128(1), 1.0));
+}
+
+#[test]
+fn low_reputation_nodes_are_always_verified() {
+    assert!(should_verify(
+        false,
+        LOW_REPUTATION - 0.01,
+        Uuid::from_u128(999_999),
+        0.0
+    ));
+}
+
+#[test]
+fn fraction_selects_chunks() {
+    assert!(should_verify(false, 1.0, Uuid::from_u128(10_000), 0.05));
+    assert!(!should_verify(false, 1.0, Uuid::from_u128(60_000), 0.05));
+    assert!(!should_verify(false, 1.0, Uuid::from_u128(0), 0.0));
+}
+
+// --- Comparison ---
+
+#[test]
+fn identical_results_match() {
+    assert!(results_match(&result(50_000.0), &result(50_000.0)));
+}
+
+#[test]
+fn different_results_mismatch() {
+    assert!(!results_match(&result(50_000.0), &result(50_001.0)));
+
+    let mut fewer = result(50_000.0);
+    fewer.iterations = 999;
+    assert!(!results_match(&result(50_000.0), &fewer));
+}
+
+// --- Votes ---
+
+#[test]
+fn matching_replica_agrees() {
+    assert_eq!(
+        tally(&[result(50_000.0), result(50_000.0)]),
+        Verdict::Agreed
+    );
+}
+
+#[test]
+fn single_disagreement_needs_a_third_run() {
+    assert_eq!(tally(&[result(50_000.0), result(1.0)]), Verdict::Disputed);
+}
+
+#[test]
+fn wrong_replica_loses_the_vote() {
+    // An honest original, a bad replica and an honest tiebreak
+    let runs = [result(50_000.0), result(1.0), result(50_000.0)];
+    assert_eq!(tally(&runs), Verdict::Outvoted(1));
+}
+
+#[test]
+fn wrong_original_loses_the_vote() {
+    let runs = [result(1.0), result(50_000.0), result(50_000.0)];
+    assert_eq!(tally(&runs), Verdict::Outvoted(0));
+}
+
+#[test]
+fn three_way_disagreement_is_inconclusive() {
+    let runs = [result(1.0), result(2.0), result(50_000.0)];
+    assert_eq!(tally(&runs), Verdict::Inconclusive);
+}
+
+// --- Reputation ---
+
+#[test]
+fn reputation_recovers_slowly_and_drops_fast() {
+    assert_eq!(updated_reputation(1.0, true), 1.0);
+    assert!((updated_reputation(0.5, true) - 0.55).abs() < 1e-12);
+    assert_eq!(updated_reputation(1.0, false), 0.5);
+}
+
+#[test]
+fn repeated_mismatches_quarantine() {
+    let mut reputation = 1.0;
+    for _ in 0..QUARANTINE_MISMATCHES {
+        reputation = updated_reputation(reputation, false);
+    }
+    assert!(should_quarantine(reputation, QUARANTINE_MISMATCHES));
+    assert!(!should_quarantine(reputation, QUARANTINE_MISMATCHES - 1));
+    assert!(!should_quarantine(1.0, QUARANTINE_MISMATCHES));
+}
@@ -1,7 +1,7 @@
 const corsHeaders = {
   "Access-Control-Allow-Origin": "*",
   "Access-Control-Allow-Headers":
-    "authorization, x-client-info, apikey, content-type, x-node-key, x-node-sig, x-node-ts",
+    "authorization, x-client-info, apikey, content-type, x-node-key, x-node-sig, x-node-ts, x-node-nonce",
 };
 
 export function json(data: unknown, status = 200): Response {
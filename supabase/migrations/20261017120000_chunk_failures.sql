@@ -0,0 +1,12 @@
+-- Track failed attempts on chunks so nodes can report errors immediately
+-- instead of leaving chunks to go stale.
+
+ALTER TABLE "public"."jobs_chunks"
+    ADD COLUMN "attempts" integer DEFAULT 0 NOT NULL,
+    ADD COLUMN "config_failures" integer DEFAULT 0 NOT NULL,
+    ADD COLUMN "last_error_class" "text",
+    ADD COLUMN "last_error" "text",
+    ADD COLUMN "last_failed_node_id" "uuid";
+
+ALTER TABLE ONLY "public"."jobs_chunks"
+    ADD CONSTRAINT "jobs_chunks_last_failed_node_id_fkey" FOREIGN KEY ("last_failed_node_id") REFERENCES "public"."nodes"("id") ON DELETE SET NULL;
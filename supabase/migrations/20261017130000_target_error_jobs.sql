@@ -0,0 +1,78 @@
+-- Target-error jobs: instead of a fixed iteration count, the sentinel
+-- dispatches chunks in waves until the 95% confidence interval of the mean
+-- DPS is within "target_error" (a fraction of the mean). "total_iterations"
+-- is the iteration cap for these jobs.
+
+ALTER TABLE "public"."jobs"
+    ADD COLUMN "target_error" double precision;
+
+ALTER TABLE ONLY "public"."jobs"
+    ADD CONSTRAINT "jobs_target_error_check" CHECK ((("target_error" IS NULL) OR ("target_error" > (0)::double precision)));
+
+
+CREATE OR REPLACE FUNCTION "public"."create_target_error_job"("p_config_hash" "text", "p_target_error" double precision, "p_max_iterations" integer, "p_access_type" "text" DEFAULT 'private'::"text", "p_discord_server_id" "text" DEFAULT NULL::"text") RETURNS "jsonb"
+    LANGUAGE "plpgsql" SECURITY DEFINER
+    SET "search_path" TO 'public'
+    AS $$
+DECLARE
+  v_user_id uuid;
+  v_job_id uuid;
+BEGIN
+  -- Get authenticated user
+  v_user_id := auth.uid();
+  IF v_user_id IS NULL THEN
+    RAISE EXCEPTION 'Not authenticated';
+  END IF;
+
+  -- Validate config exists
+  IF NOT EXISTS (SELECT 1 FROM jobs_configs WHERE hash = p_config_hash) THEN
+    RAISE EXCEPTION 'Config not found: %', p_config_hash;
+  END IF;
+
+  IF p_target_error IS NULL OR p_target_error <= 0 THEN
+    RAISE EXCEPTION 'Target error must be positive';
+  END IF;
+
+  IF p_max_iterations IS NULL OR p_max_iterations <= 0 THEN
+    RAISE EXCEPTION 'Max iterations must be positive';
+  END IF;
+
+  -- Create job; the sentinel creates its chunks
+  INSERT INTO jobs (user_id, config_hash, total_iterations, target_error, status, access_type, discord_server_id)
+  VALUES (v_user_id, p_config_hash, p_max_iterations, p_target_error, 'pending', p_access_type, p_discord_server_id)
+  RETURNING id INTO v_job_id;
+
+  -- Update config last_used_at
+  UPDATE jobs_configs SET last_used_at = now() WHERE hash = p_config_hash;
+
+  RETURN jsonb_build_object(
+    'jobId', v_job_id
+  );
+END;
+$$;
+
+
+ALTER FUNCTION "public"."create_target_error_job"("p_config_hash" "text", "p_target_error" double precision, "p_max_iterations" integer, "p_access_type" "text", "p_discord_server_id" "text") OWNER TO "postgres";
+
+
+-- Wake the scheduler so it creates the first wave of chunks
+CREATE OR REPLACE FUNCTION "public"."notify_target_error_job"() RETURNS "trigger"
+    LANGUAGE "plpgsql"
+    SET "search_path" TO 'public'
+    AS $$
+BEGIN
+  PERFORM pg_notify('pending_chunk', NEW.id::text);
+  RETURN NEW;
+END;
+$$;
+
+
+ALTER FUNCTION "public"."notify_target_error_job"() OWNER TO "postgres";
+
+
+CREATE OR REPLACE TRIGGER "trg_target_error_job" AFTER INSERT ON "public"."jobs" FOR EACH ROW WHEN (("new"."target_error" IS NOT NULL)) EXECUTE FUNCTION "public"."notify_target_error_job"();
+
+
+GRANT ALL ON FUNCTION "public"."create_target_error_job"("p_config_hash" "text", "p_target_error" double precision, "p_max_iterations" integer, "p_access_type" "text", "p_discord_server_id" "text") TO "anon";
+GRANT ALL ON FUNCTION "public"."create_target_error_job"("p_config_hash" "text", "p_target_error" double precision, "p_max_iterations" integer, "p_access_type" "text", "p_discord_server_id" "text") TO "authenticated";
+GRANT ALL ON FUNCTION "public"."create_target_error_job"("p_config_hash" "text", "p_target_error" double precision, "p_max_iterations" integer, "p_access_type" "text", "p_discord_server_id" "text") TO "service_role";
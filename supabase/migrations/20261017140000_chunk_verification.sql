@@ -0,0 +1,18 @@
+-- Spot-check verification: the sentinel re-runs a fraction of chunks from
+-- untrusted nodes on a second node and compares the results. Nodes keep a
+-- reputation score and are quarantined after repeated mismatches.
+
+ALTER TABLE "public"."jobs_chunks"
+    ADD COLUMN "verifies_chunk_id" "uuid";
+
+ALTER TABLE ONLY "public"."jobs_chunks"
+    ADD CONSTRAINT "jobs_chunks_verifies_chunk_id_fkey" FOREIGN KEY ("verifies_chunk_id") REFERENCES "public"."jobs_chunks"("id") ON DELETE CASCADE;
+
+CREATE INDEX "idx_jobs_chunks_verifies_chunk_id" ON "public"."jobs_chunks" USING "btree" ("verifies_chunk_id") WHERE ("verifies_chunk_id" IS NOT NULL);
+
+
+ALTER TABLE "public"."nodes"
+    ADD COLUMN "reputation" double precision DEFAULT 1.0 NOT NULL,
+    ADD COLUMN "verified_chunks" integer DEFAULT 0 NOT NULL,
+    ADD COLUMN "mismatched_chunks" integer DEFAULT 0 NOT NULL,
+    ADD COLUMN "quarantined_at" timestamp with time zone;